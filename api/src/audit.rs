@@ -0,0 +1,178 @@
+use actix_web::{dev::Payload, FromRequest, HttpMessage, HttpRequest};
+use chrono::Utc;
+use entity::audit_log::{self, AuditAction};
+use sea_orm::{ActiveModelTrait, ConnectionTrait, Set};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::future::{ready, Ready};
+
+use crate::error::ApiError;
+use crate::events::{ChangeEvent, PendingEvents};
+use crate::middlewares::jwt::Claims;
+use crate::middlewares::request_id::RequestId;
+use crate::tenant::Tenant;
+use crate::webhooks;
+
+/// Who is making the current request, recorded with every change it makes.
+#[derive(Clone, Debug, Default)]
+pub struct AuditContext {
+    pub actor: Option<String>,
+    /// Company whose data is being changed.
+    pub tenant_id: Option<String>,
+    pub request_id: Option<String>,
+    pub ip: Option<String>,
+    /// Where changes wait to be streamed until the request succeeds.
+    pub events: Option<PendingEvents>,
+}
+
+impl AuditContext {
+    /// Same context acting as `actor`, for requests made before a login.
+    pub fn with_actor(mut self, actor: &str) -> Self {
+        self.actor = Some(actor.to_string());
+        self
+    }
+
+    /// Same context working in `tenant_id`, for companies being set up.
+    pub fn with_tenant(mut self, tenant_id: &str) -> Self {
+        self.tenant_id = Some(tenant_id.to_string());
+        self
+    }
+
+    /// Appends one entry to the audit log and queues the webhooks subscribed
+    /// to the change. Pass the connection the change was made on so both are
+    /// committed or rolled back with it. The change is also streamed to
+    /// `/v1/events` once the request succeeds.
+    pub async fn record<C, T>(
+        &self,
+        db: &C,
+        entity: &str,
+        entity_id: &str,
+        action: AuditAction,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), ApiError>
+    where
+        C: ConnectionTrait,
+        T: Serialize,
+    {
+        let event = webhooks::event_name(entity, &action);
+        let stream_action = action.clone();
+        let now = Utc::now().naive_utc();
+        let (changed_before, changed_after) = diff(to_json(before)?, to_json(after)?);
+
+        audit_log::ActiveModel {
+            id: Set(uuid::Uuid::new_v4().to_string()),
+            tenant_id: Set(self.tenant_id.clone().unwrap_or_default()),
+            actor: Set(self.actor.clone()),
+            entity: Set(entity.to_string()),
+            entity_id: Set(entity_id.to_string()),
+            action: Set(action),
+            before: Set(changed_before),
+            after: Set(changed_after),
+            request_id: Set(self.request_id.clone()),
+            ip: Set(self.ip.clone()),
+            created_at: Set(now),
+        }
+        .insert(db)
+        .await?;
+
+        let (Some(tenant_id), Some(data)) = (&self.tenant_id, after.or(before)) else {
+            return Ok(());
+        };
+        webhooks::enqueue(db, &Tenant::new(tenant_id.as_str()), &event, data).await?;
+        if let Some(events) = &self.events {
+            events.push(ChangeEvent {
+                tenant_id: tenant_id.clone(),
+                entity: entity.to_string(),
+                id: entity_id.to_string(),
+                action: stream_action,
+                data: to_json(Some(data))?.unwrap_or_default(),
+                occurred_at: now,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl FromRequest for AuditContext {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let extensions = req.extensions();
+        let claims = extensions.get::<Claims>();
+        ready(Ok(AuditContext {
+            actor: claims.map(|claims| claims.sub.clone()),
+            tenant_id: claims.map(|claims| claims.tenant_id.clone()),
+            request_id: extensions.get::<RequestId>().map(|id| id.0.clone()),
+            ip: req
+                .connection_info()
+                .realip_remote_addr()
+                .map(str::to_string),
+            events: extensions.get::<PendingEvents>().cloned(),
+        }))
+    }
+}
+
+fn to_json<T: Serialize>(value: Option<&T>) -> Result<Option<Value>, ApiError> {
+    value
+        .map(serde_json::to_value)
+        .transpose()
+        .map_err(|_| ApiError::InternalServerError)
+}
+
+/// Keeps only the fields whose values differ between the two versions.
+fn diff(before: Option<Value>, after: Option<Value>) -> (Option<Value>, Option<Value>) {
+    let (Some(Value::Object(before)), Some(Value::Object(after))) = (&before, &after) else {
+        return (before, after);
+    };
+
+    let mut changed_before = Map::new();
+    let mut changed_after = Map::new();
+    for (field, old) in before {
+        let new = after.get(field).unwrap_or(&Value::Null);
+        if old != new {
+            changed_before.insert(field.clone(), old.clone());
+            changed_after.insert(field.clone(), new.clone());
+        }
+    }
+    for (field, new) in after {
+        if !before.contains_key(field) {
+            changed_before.insert(field.clone(), Value::Null);
+            changed_after.insert(field.clone(), new.clone());
+        }
+    }
+
+    (
+        Some(Value::Object(changed_before)),
+        Some(Value::Object(changed_after)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diff_keeps_changed_fields_only() {
+        let (before, after) = diff(
+            Some(json!({"id": "1", "price": 10.0, "name": "Bolt", "version": 1})),
+            Some(json!({"id": "1", "price": 12.5, "name": "Bolt", "version": 2})),
+        );
+
+        assert_eq!(before, Some(json!({"price": 10.0, "version": 1})));
+        assert_eq!(after, Some(json!({"price": 12.5, "version": 2})));
+    }
+
+    #[test]
+    fn diff_keeps_whole_record_on_create_and_delete() {
+        let record = json!({"id": "1", "name": "Bolt"});
+
+        assert_eq!(
+            diff(None, Some(record.clone())),
+            (None, Some(record.clone()))
+        );
+        assert_eq!(diff(Some(record.clone()), None), (Some(record), None));
+    }
+}
@@ -0,0 +1,281 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use async_trait::async_trait;
+use entity::audit_log::AuditAction;
+use sea_orm::{DatabaseConnection, DatabaseTransaction, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::shared::concurrency::Precondition;
+use crate::tenant::Tenant;
+
+/// Largest number of operations accepted in one batch request.
+pub const MAX_BATCH_OPERATIONS: usize = 10_000;
+/// Request body limit for batch endpoints.
+pub const MAX_BATCH_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    /// Every operation succeeds or nothing is written.
+    #[default]
+    Atomic,
+    /// Successful operations are kept, failed ones are reported.
+    BestEffort,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchOperation<C, U> {
+    Create {
+        data: C,
+    },
+    /// `version` works like `If-Match`: an operation without it fails with
+    /// 428.
+    Update {
+        id: String,
+        version: Option<i32>,
+        data: U,
+    },
+    Delete {
+        id: String,
+        version: Option<i32>,
+    },
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct BatchRequest<C, U> {
+    #[serde(default)]
+    pub mode: BatchMode,
+    pub operations: Vec<BatchOperation<C, U>>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct OperationResult {
+    /// Position of the operation in the request.
+    pub index: usize,
+    pub id: Option<String>,
+    /// HTTP status the operation would have returned on its own endpoint.
+    pub status: u16,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct BatchResponse {
+    pub mode: BatchMode,
+    /// Whether any change was committed.
+    pub applied: bool,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<OperationResult>,
+}
+
+/// Single-record operations of a resource, run inside the batch transaction.
+#[async_trait]
+pub trait BatchResource {
+    /// Name of the resource in the audit log.
+    const ENTITY: &'static str;
+
+    type Create: Validate + Send + Sync;
+    type Update: Validate + Send + Sync;
+    type Model: Serialize + Send + Sync;
+
+    /// Loads and locks the current record, kept as the audit `before`.
+    async fn find(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        id: &str,
+    ) -> Result<Option<Self::Model>, ApiError>;
+    async fn create(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        data: &Self::Create,
+    ) -> Result<Self::Model, ApiError>;
+    async fn update(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        id: &str,
+        precondition: &Precondition,
+        data: &Self::Update,
+    ) -> Result<Self::Model, ApiError>;
+    async fn delete(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        id: &str,
+        precondition: &Precondition,
+    ) -> Result<(), ApiError>;
+    fn id(model: &Self::Model) -> String;
+}
+
+/// Outcome of a batch: the report plus the records to push to search.
+pub struct BatchOutcome<M> {
+    pub response: BatchResponse,
+    pub upserted: Vec<M>,
+    pub deleted: Vec<String>,
+}
+
+/// 200 when the batch was committed, 422 when nothing was written.
+pub fn batch_response(response: BatchResponse) -> HttpResponse {
+    if response.applied {
+        HttpResponse::Ok().json(response)
+    } else {
+        HttpResponse::UnprocessableEntity().json(response)
+    }
+}
+
+/// Runs every operation in its own savepoint of one transaction. In atomic
+/// mode a single failure rolls the whole batch back.
+pub async fn run_batch<R: BatchResource>(
+    db: &DatabaseConnection,
+    tenant: &Tenant,
+    audit: &AuditContext,
+    request: BatchRequest<R::Create, R::Update>,
+) -> Result<BatchOutcome<R::Model>, ApiError> {
+    if request.operations.is_empty() {
+        return Err(ApiError::ValidationError(
+            "At least one operation is required".to_string(),
+        ));
+    }
+    if request.operations.len() > MAX_BATCH_OPERATIONS {
+        return Err(ApiError::ValidationError(format!(
+            "A batch cannot have more than {} operations",
+            MAX_BATCH_OPERATIONS
+        )));
+    }
+
+    let txn = db.begin().await?;
+    let mut results = Vec::with_capacity(request.operations.len());
+    let mut upserted = Vec::new();
+    let mut deleted = Vec::new();
+
+    for (index, operation) in request.operations.iter().enumerate() {
+        let savepoint = txn.begin().await?;
+        let outcome = run_operation::<R>(&savepoint, tenant, audit, operation).await;
+
+        let result = match outcome {
+            Ok(Some(model)) => {
+                let id = R::id(&model);
+                upserted.push(model);
+                Ok(id)
+            }
+            Ok(None) => {
+                let id = operation_id(operation).unwrap_or_default();
+                deleted.push(id.clone());
+                Ok(id)
+            }
+            Err(err) => Err(err),
+        };
+
+        match result {
+            Ok(id) => {
+                savepoint.commit().await?;
+                results.push(OperationResult {
+                    index,
+                    id: Some(id),
+                    status: StatusCode::OK.as_u16(),
+                    error: None,
+                });
+            }
+            Err(err) => {
+                savepoint.rollback().await?;
+                results.push(OperationResult {
+                    index,
+                    id: operation_id(operation),
+                    status: err.status_code().as_u16(),
+                    error: Some(err.to_string()),
+                });
+            }
+        }
+    }
+
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    let applied = failed < results.len() && (failed == 0 || request.mode == BatchMode::BestEffort);
+    if applied {
+        txn.commit().await?;
+    } else {
+        txn.rollback().await?;
+        upserted.clear();
+        deleted.clear();
+    }
+
+    Ok(BatchOutcome {
+        response: BatchResponse {
+            mode: request.mode,
+            applied,
+            succeeded: results.len() - failed,
+            failed,
+            results,
+        },
+        upserted,
+        deleted,
+    })
+}
+
+/// Applies one operation and records it in the audit log. `None` means the
+/// record was deleted.
+async fn run_operation<R: BatchResource>(
+    txn: &DatabaseTransaction,
+    tenant: &Tenant,
+    audit: &AuditContext,
+    operation: &BatchOperation<R::Create, R::Update>,
+) -> Result<Option<R::Model>, ApiError> {
+    match operation {
+        BatchOperation::Create { data } => {
+            data.validate()?;
+            let model = R::create(txn, tenant, data).await?;
+            audit
+                .record(
+                    txn,
+                    R::ENTITY,
+                    &R::id(&model),
+                    AuditAction::Create,
+                    None,
+                    Some(&model),
+                )
+                .await?;
+            Ok(Some(model))
+        }
+        BatchOperation::Update { id, version, data } => {
+            let precondition = Precondition::from_version(*version)?;
+            data.validate()?;
+            let before = R::find(txn, tenant, id).await?;
+            let model = R::update(txn, tenant, id, &precondition, data).await?;
+            audit
+                .record(
+                    txn,
+                    R::ENTITY,
+                    id,
+                    AuditAction::Update,
+                    before.as_ref(),
+                    Some(&model),
+                )
+                .await?;
+            Ok(Some(model))
+        }
+        BatchOperation::Delete { id, version } => {
+            let precondition = Precondition::from_version(*version)?;
+            let before = R::find(txn, tenant, id).await?;
+            R::delete(txn, tenant, id, &precondition).await?;
+            audit
+                .record(
+                    txn,
+                    R::ENTITY,
+                    id,
+                    AuditAction::Delete,
+                    before.as_ref(),
+                    None,
+                )
+                .await?;
+            Ok(None)
+        }
+    }
+}
+
+fn operation_id<C, U>(operation: &BatchOperation<C, U>) -> Option<String> {
+    match operation {
+        BatchOperation::Create { .. } => None,
+        BatchOperation::Update { id, .. } | BatchOperation::Delete { id, .. } => Some(id.clone()),
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use sea_orm::{ColumnTrait, ConnectionTrait, QueryFilter};
+
+use crate::error::ApiError;
+use crate::tenant::Tenant;
+use entity::holiday;
+
+/// Company holidays between two dates, both included.
+pub async fn holidays<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<HashSet<NaiveDate>, ApiError> {
+    Ok(tenant
+        .find::<holiday::Entity>()
+        .filter(holiday::Column::Date.between(start, end))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|holiday| holiday.date)
+        .collect())
+}
+
+/// Weekdays that are not holidays.
+pub fn is_working_day(date: NaiveDate, holidays: &HashSet<NaiveDate>) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !holidays.contains(&date)
+}
+
+/// Working days from `start` to `end`, both included.
+pub fn working_days(start: NaiveDate, end: NaiveDate, holidays: &HashSet<NaiveDate>) -> u32 {
+    start
+        .iter_days()
+        .take_while(|date| *date <= end)
+        .filter(|date| is_working_day(*date, holidays))
+        .count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        // June 2025 starts on a Sunday
+        NaiveDate::from_ymd_opt(2025, 6, day).unwrap()
+    }
+
+    #[test]
+    fn weekends_are_not_counted() {
+        let none = HashSet::new();
+        assert_eq!(working_days(date(2), date(6), &none), 5);
+        assert_eq!(working_days(date(1), date(14), &none), 10);
+        assert_eq!(working_days(date(7), date(8), &none), 0);
+        assert_eq!(working_days(date(6), date(2), &none), 0);
+    }
+
+    #[test]
+    fn holidays_are_not_counted() {
+        let holidays = HashSet::from([date(4), date(7)]);
+        assert_eq!(working_days(date(2), date(6), &holidays), 4);
+        assert!(!is_working_day(date(4), &holidays));
+        assert!(is_working_day(date(5), &holidays));
+    }
+}
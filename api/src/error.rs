@@ -1,22 +1,49 @@
-use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use actix_web::{
+    error::{JsonPayloadError, QueryPayloadError},
+    http::StatusCode,
+    web, HttpRequest, HttpResponse, ResponseError,
+};
 use sea_orm::DbErr;
-use serde_json::json;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use thiserror::Error;
+use utoipa::ToSchema;
+
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
 
 #[derive(Error, Debug)]
 pub enum ApiError {
     #[error("Validation error: {0}")]
     ValidationError(String),
 
+    #[error("Validation error: {0}")]
+    InvalidFields(FieldErrors),
+
     #[error("Not found: {0}")]
     NotFound(String),
 
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    #[error("Unprocessable entity: {0}")]
+    UnprocessableEntity(String),
+
+    #[error("Precondition failed: {0}")]
+    PreconditionFailed(String),
+
+    #[error("Precondition required: {0}")]
+    PreconditionRequired(String),
+
     #[error("Database error")]
     DatabaseError(#[from] DbErr),
 
@@ -27,28 +54,247 @@ pub enum ApiError {
     InternalServerError,
 }
 
+impl ApiError {
+    /// Stable identifier used to build `type`, and its title.
+    fn kind(&self) -> (&'static str, &'static str) {
+        match self {
+            ApiError::ValidationError(_) | ApiError::InvalidFields(_) => {
+                ("validation-error", "Validation error")
+            }
+            ApiError::NotFound(_) => ("not-found", "Resource not found"),
+            ApiError::Unauthorized(_) => ("unauthorized", "Authentication required"),
+            ApiError::Forbidden(_) => ("forbidden", "Permission denied"),
+            ApiError::Conflict(_) => ("conflict", "Conflict"),
+            ApiError::PayloadTooLarge(_) => ("payload-too-large", "Payload too large"),
+            ApiError::UnprocessableEntity(_) => ("unprocessable-entity", "Unprocessable request"),
+            ApiError::PreconditionFailed(_) => ("precondition-failed", "Precondition failed"),
+            ApiError::PreconditionRequired(_) => ("precondition-required", "Precondition required"),
+            ApiError::DatabaseError(_)
+            | ApiError::SearchError(_)
+            | ApiError::InternalServerError => ("internal-server-error", "Internal server error"),
+        }
+    }
+
+    fn detail(&self) -> String {
+        match self {
+            ApiError::ValidationError(message)
+            | ApiError::NotFound(message)
+            | ApiError::Unauthorized(message)
+            | ApiError::Forbidden(message)
+            | ApiError::Conflict(message)
+            | ApiError::PayloadTooLarge(message)
+            | ApiError::UnprocessableEntity(message)
+            | ApiError::PreconditionFailed(message)
+            | ApiError::PreconditionRequired(message) => message.clone(),
+            ApiError::InvalidFields(_) => "One or more fields are invalid".to_string(),
+            // Internal failures keep their details in the logs
+            _ => self.to_string(),
+        }
+    }
+
+    pub fn to_problem(&self) -> Problem {
+        let (slug, title) = self.kind();
+        Problem {
+            problem_type: format!("/problems/{}", slug),
+            title: title.to_string(),
+            status: self.status_code().as_u16(),
+            detail: self.detail(),
+            instance: None,
+            request_id: None,
+            errors: match self {
+                ApiError::InvalidFields(errors) => Some(errors.clone()),
+                _ => None,
+            },
+        }
+    }
+}
+
 impl ResponseError for ApiError {
     fn status_code(&self) -> StatusCode {
         match self {
             ApiError::ValidationError(_) => StatusCode::BAD_REQUEST,
+            ApiError::InvalidFields(_) => StatusCode::BAD_REQUEST,
             ApiError::NotFound(_) => StatusCode::NOT_FOUND,
             ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
             ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            ApiError::PreconditionRequired(_) => StatusCode::PRECONDITION_REQUIRED,
             ApiError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::SearchError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
+    /// `instance` and `request_id` are filled in by `RequestIdMiddleware`,
+    /// which knows the request this error belongs to.
     fn error_response(&self) -> HttpResponse {
-        let status_code = self.status_code();
-        let error_message = self.to_string();
+        HttpResponse::build(self.status_code())
+            .content_type(PROBLEM_CONTENT_TYPE)
+            .json(self.to_problem())
+    }
+}
+
+/// RFC 7807 problem details, the body of every error response.
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    /// Path of the request that failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Same value as the `X-Request-Id` response header.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Messages per invalid field, present on validation failures.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<BTreeMap<String, Vec<String>>>)]
+    pub errors: Option<FieldErrors>,
+}
+
+/// Every invalid field of a request with its messages, keyed by field name.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct FieldErrors(BTreeMap<String, Vec<String>>);
+
+impl FieldErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.0
+            .entry(field.to_string())
+            .or_default()
+            .push(message.into());
+    }
+
+    /// Records the message of a failed single-field check.
+    pub fn check(&mut self, field: &str, result: Result<(), ApiError>) {
+        match result {
+            Ok(()) => {}
+            Err(ApiError::ValidationError(message)) => self.add(field, message),
+            Err(other) => self.add(field, other.to_string()),
+        }
+    }
 
-        HttpResponse::build(status_code).json(json!({
-            "error": {
-                "code": status_code.as_u16(),
-                "message": error_message
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, field: &str) -> Option<&Vec<String>> {
+        self.0.get(field)
+    }
+
+    /// `Ok` when nothing was recorded, otherwise `InvalidFields`.
+    pub fn into_result(self) -> Result<(), ApiError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::InvalidFields(self))
+        }
+    }
+}
+
+impl fmt::Display for FieldErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<String> = self
+            .0
+            .iter()
+            .flat_map(|(field, messages)| messages.iter().map(move |m| format!("{field}: {m}")))
+            .collect();
+        write!(f, "{}", messages.join("; "))
+    }
+}
+
+impl From<validator::ValidationErrors> for ApiError {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        let mut fields = FieldErrors::new();
+        for (field, field_errors) in errors.field_errors() {
+            for error in field_errors {
+                let message = match &error.message {
+                    Some(message) => message.to_string(),
+                    None => format!("Invalid value ({})", error.code),
+                };
+                fields.add(&field, message);
             }
-        }))
+        }
+        ApiError::InvalidFields(fields)
+    }
+}
+
+/// JSON extractor settings that report malformed bodies as problems.
+pub fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default().error_handler(json_error)
+}
+
+/// Query extractor settings that report bad parameters as problems.
+pub fn query_config() -> web::QueryConfig {
+    web::QueryConfig::default().error_handler(query_error)
+}
+
+fn json_error(err: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    let api_error = match &err {
+        JsonPayloadError::OverflowKnownLength { .. } | JsonPayloadError::Overflow { .. } => {
+            ApiError::PayloadTooLarge(err.to_string())
+        }
+        JsonPayloadError::ContentType => {
+            ApiError::ValidationError("Content type must be application/json".to_string())
+        }
+        JsonPayloadError::Deserialize(e) => {
+            ApiError::ValidationError(format!("Malformed JSON body: {}", e))
+        }
+        _ => ApiError::ValidationError(err.to_string()),
+    };
+    api_error.into()
+}
+
+fn query_error(err: QueryPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    ApiError::ValidationError(err.to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_errors_collect_every_field() {
+        let mut errors = FieldErrors::new();
+        errors.check("name", Ok(()));
+        errors.check(
+            "price",
+            Err(ApiError::ValidationError(
+                "Price cannot be negative".to_string(),
+            )),
+        );
+        errors.add("quantity", "Quantity cannot be negative");
+        errors.add("quantity", "Tracked items must be created empty");
+
+        let problem = match errors.into_result() {
+            Err(err) => err.to_problem(),
+            Ok(()) => panic!("expected invalid fields"),
+        };
+        assert_eq!(problem.status, 400);
+        assert_eq!(problem.problem_type, "/problems/validation-error");
+
+        let errors = problem.errors.unwrap();
+        assert!(errors.get("name").is_none());
+        assert_eq!(errors.get("price").unwrap().len(), 1);
+        assert_eq!(errors.get("quantity").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn problem_serializes_without_empty_members() {
+        let problem = ApiError::NotFound("Item not found".to_string()).to_problem();
+        let body = serde_json::to_value(&problem).unwrap();
+
+        assert_eq!(body["type"], "/problems/not-found");
+        assert_eq!(body["detail"], "Item not found");
+        assert!(body.get("errors").is_none());
+        assert!(body.get("instance").is_none());
     }
 }
@@ -0,0 +1,204 @@
+//! Live feed of entity changes behind `GET /v1/events`.
+//!
+//! Changes recorded in the audit log are collected while a request runs and
+//! published by `EventsMiddleware` once it answered with a success, which
+//! handlers only do after committing. The bus keeps the latest events in
+//! memory so clients can resume with `Last-Event-ID`; each instance only
+//! sees the changes it made itself.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use chrono::NaiveDateTime;
+use entity::audit_log::AuditAction;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::watch;
+use utoipa::ToSchema;
+
+/// Entities whose changes are streamed.
+pub const STREAMED_ENTITIES: &[&str] = &["employee", "inventory", "order"];
+
+/// Events kept for clients that reconnect.
+pub const DEFAULT_BUFFER_SIZE: usize = 1000;
+
+/// One change, as sent in the `data` of an event.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct ChangeEvent {
+    #[serde(skip)]
+    pub tenant_id: String,
+    pub entity: String,
+    /// Id of the changed record.
+    pub id: String,
+    pub action: AuditAction,
+    /// The record after the change, or before it for deletes.
+    #[schema(value_type = Object)]
+    pub data: Value,
+    pub occurred_at: NaiveDateTime,
+}
+
+/// Changes made by the current request, waiting for it to succeed.
+#[derive(Clone, Debug, Default)]
+pub struct PendingEvents(Arc<Mutex<Vec<ChangeEvent>>>);
+
+impl PendingEvents {
+    pub fn push(&self, event: ChangeEvent) {
+        if STREAMED_ENTITIES.contains(&event.entity.as_str()) {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    pub fn take(&self) -> Vec<ChangeEvent> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+/// A published change with its position in the bus.
+#[derive(Debug)]
+pub struct Published {
+    pub seq: u64,
+    pub event: ChangeEvent,
+}
+
+struct Buffer {
+    events: VecDeque<Arc<Published>>,
+    latest: u64,
+}
+
+pub struct EventBus {
+    /// Tells ids of this process from those of an earlier one, whose events
+    /// are gone.
+    epoch: String,
+    capacity: usize,
+    buffer: Mutex<Buffer>,
+    latest: watch::Sender<u64>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUFFER_SIZE)
+    }
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        EventBus {
+            epoch: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            capacity: capacity.max(1),
+            buffer: Mutex::new(Buffer {
+                events: VecDeque::new(),
+                latest: 0,
+            }),
+            latest: watch::Sender::new(0),
+        }
+    }
+
+    pub fn publish(&self, events: Vec<ChangeEvent>) {
+        if events.is_empty() {
+            return;
+        }
+        let mut buffer = self.buffer.lock().unwrap();
+        for event in events {
+            buffer.latest += 1;
+            let seq = buffer.latest;
+            buffer.events.push_back(Arc::new(Published { seq, event }));
+            if buffer.events.len() > self.capacity {
+                buffer.events.pop_front();
+            }
+        }
+        self.latest.send_replace(buffer.latest);
+    }
+
+    /// Sequence number of the last published event.
+    pub fn latest(&self) -> u64 {
+        self.buffer.lock().unwrap().latest
+    }
+
+    /// Notified whenever events are published.
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.latest.subscribe()
+    }
+
+    /// Events published after `seq`, or `None` when some of them were
+    /// already dropped from the buffer.
+    pub fn since(&self, seq: u64) -> Option<Vec<Arc<Published>>> {
+        let buffer = self.buffer.lock().unwrap();
+        if seq > buffer.latest {
+            return None;
+        }
+        let oldest = buffer.events.front().map_or(buffer.latest + 1, |e| e.seq);
+        if seq + 1 < oldest {
+            return None;
+        }
+        Some(
+            buffer
+                .events
+                .iter()
+                .filter(|e| e.seq > seq)
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Value of the SSE `id` field for the event at `seq`.
+    pub fn event_id(&self, seq: u64) -> String {
+        format!("{}-{}", self.epoch, seq)
+    }
+
+    /// Sequence number behind an id handed out by this bus.
+    pub fn parse_event_id(&self, id: &str) -> Option<u64> {
+        let (epoch, seq) = id.split_once('-')?;
+        if epoch != self.epoch {
+            return None;
+        }
+        seq.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(entity: &str) -> ChangeEvent {
+        ChangeEvent {
+            tenant_id: "company1".to_string(),
+            entity: entity.to_string(),
+            id: "1".to_string(),
+            action: AuditAction::Create,
+            data: Value::Null,
+            occurred_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn resumes_from_buffered_events_only() {
+        let bus = EventBus::new(2);
+        bus.publish(vec![change("order"), change("order"), change("order")]);
+        assert_eq!(bus.latest(), 3);
+
+        let seqs = |events: Vec<Arc<Published>>| events.iter().map(|e| e.seq).collect::<Vec<_>>();
+        assert_eq!(seqs(bus.since(1).unwrap()), vec![2, 3]);
+        assert_eq!(seqs(bus.since(3).unwrap()), Vec::<u64>::new());
+        // Event 1 was dropped, and 4 does not exist yet
+        assert!(bus.since(0).is_none());
+        assert!(bus.since(4).is_none());
+    }
+
+    #[test]
+    fn event_ids_belong_to_one_bus() {
+        let bus = EventBus::default();
+        let id = bus.event_id(42);
+        assert_eq!(bus.parse_event_id(&id), Some(42));
+        assert_eq!(EventBus::default().parse_event_id(&id), None);
+        assert_eq!(bus.parse_event_id("42"), None);
+    }
+
+    #[test]
+    fn only_streamed_entities_are_kept() {
+        let pending = PendingEvents::default();
+        pending.push(change("inventory"));
+        pending.push(change("webhook"));
+        assert_eq!(pending.take().len(), 1);
+        assert!(pending.take().is_empty());
+    }
+}
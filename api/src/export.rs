@@ -0,0 +1,508 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use actix_web::{http::header, web::Bytes, HttpResponse};
+use futures_util::stream;
+use sea_orm::{
+    DatabaseConnection, EntityTrait, Iterable, PrimaryKeyToColumn, QueryOrder, QuerySelect, Select,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use zip::write::{SimpleFileOptions, StreamWriter};
+use zip::ZipWriter;
+
+use crate::error::ApiError;
+
+/// Rows fetched from the database per round trip.
+pub const CHUNK_SIZE: u64 = 500;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+    Jsonl,
+}
+
+impl ExportFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+            ExportFormat::Jsonl => "application/x-ndjson",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+pub enum Cell {
+    Text(String),
+    Number(f64),
+    Empty,
+}
+
+impl From<String> for Cell {
+    fn from(value: String) -> Self {
+        Cell::Text(value)
+    }
+}
+
+impl From<Option<String>> for Cell {
+    fn from(value: Option<String>) -> Self {
+        value.map_or(Cell::Empty, Cell::Text)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(value: f64) -> Self {
+        Cell::Number(value)
+    }
+}
+
+impl From<i32> for Cell {
+    fn from(value: i32) -> Self {
+        Cell::Number(value.into())
+    }
+}
+
+/// A record that can be written as a spreadsheet row. JSON lines use the
+/// record's `Serialize` implementation instead.
+pub trait ExportRow: Serialize {
+    fn headers() -> &'static [&'static str];
+    fn cells(&self) -> Vec<Cell>;
+}
+
+/// Streams every row matched by `select` in the requested format, reading
+/// `CHUNK_SIZE` rows at a time so the response is never held in memory.
+pub fn stream_export<E>(
+    db: DatabaseConnection,
+    select: Select<E>,
+    format: ExportFormat,
+    name: &str,
+) -> HttpResponse
+where
+    E: EntityTrait,
+    E::Model: ExportRow + 'static,
+{
+    let state = ExportState {
+        db,
+        select: in_total_order(select),
+        offset: 0,
+        encoder: Encoder::new(format),
+        started: false,
+        done: false,
+    };
+
+    let body = stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+        let chunk = state.next_chunk().await;
+        if let Err(err) = &chunk {
+            log::error!("Export failed: {:?}", err);
+            state.done = true;
+        }
+        Some((chunk, state))
+    });
+
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.{}\"", name, format.extension()),
+        ))
+        .streaming(body)
+}
+
+/// Breaks ties in the select's ordering by primary key, so rows with equal
+/// sort keys keep their place between chunks and none is skipped or repeated.
+fn in_total_order<E: EntityTrait>(select: Select<E>) -> Select<E> {
+    E::PrimaryKey::iter().fold(select, |select, key| select.order_by_asc(key.into_column()))
+}
+
+struct ExportState<E: EntityTrait> {
+    db: DatabaseConnection,
+    select: Select<E>,
+    offset: u64,
+    encoder: Encoder,
+    started: bool,
+    done: bool,
+}
+
+impl<E> ExportState<E>
+where
+    E: EntityTrait,
+    E::Model: ExportRow,
+{
+    async fn next_chunk(&mut self) -> Result<Bytes, ApiError> {
+        if !self.started {
+            self.started = true;
+            return self.encoder.begin(E::Model::headers());
+        }
+
+        let rows = self
+            .select
+            .clone()
+            .offset(self.offset)
+            .limit(CHUNK_SIZE)
+            .all(&self.db)
+            .await?;
+
+        if rows.is_empty() {
+            self.done = true;
+            return self.encoder.finish();
+        }
+        self.offset += rows.len() as u64;
+
+        self.encoder.rows(&rows)
+    }
+}
+
+enum Encoder {
+    Csv,
+    Jsonl,
+    Xlsx(Box<XlsxStream>),
+}
+
+impl Encoder {
+    fn new(format: ExportFormat) -> Self {
+        match format {
+            ExportFormat::Csv => Encoder::Csv,
+            ExportFormat::Jsonl => Encoder::Jsonl,
+            ExportFormat::Xlsx => Encoder::Xlsx(Box::new(XlsxStream::new())),
+        }
+    }
+
+    fn begin(&mut self, headers: &[&str]) -> Result<Bytes, ApiError> {
+        match self {
+            Encoder::Csv => {
+                let mut writer = csv::Writer::from_writer(Vec::new());
+                writer.write_record(headers).map_err(internal)?;
+                csv_bytes(writer)
+            }
+            Encoder::Jsonl => Ok(Bytes::new()),
+            Encoder::Xlsx(xlsx) => xlsx.begin(headers).map_err(internal),
+        }
+    }
+
+    fn rows<T: ExportRow>(&mut self, rows: &[T]) -> Result<Bytes, ApiError> {
+        match self {
+            Encoder::Csv => {
+                let mut writer = csv::Writer::from_writer(Vec::new());
+                for row in rows {
+                    let record: Vec<String> = row
+                        .cells()
+                        .into_iter()
+                        .map(|cell| match cell {
+                            Cell::Text(text) => text,
+                            Cell::Number(number) => number.to_string(),
+                            Cell::Empty => String::new(),
+                        })
+                        .collect();
+                    writer.write_record(&record).map_err(internal)?;
+                }
+                csv_bytes(writer)
+            }
+            Encoder::Jsonl => {
+                let mut buffer = Vec::new();
+                for row in rows {
+                    serde_json::to_writer(&mut buffer, row).map_err(internal)?;
+                    buffer.push(b'\n');
+                }
+                Ok(Bytes::from(buffer))
+            }
+            Encoder::Xlsx(xlsx) => {
+                for row in rows {
+                    xlsx.write_row(&row.cells()).map_err(internal)?;
+                }
+                Ok(xlsx.take())
+            }
+        }
+    }
+
+    fn finish(&mut self) -> Result<Bytes, ApiError> {
+        match self {
+            Encoder::Csv | Encoder::Jsonl => Ok(Bytes::new()),
+            Encoder::Xlsx(xlsx) => xlsx.finish().map_err(internal),
+        }
+    }
+}
+
+fn csv_bytes(writer: csv::Writer<Vec<u8>>) -> Result<Bytes, ApiError> {
+    writer.into_inner().map(Bytes::from).map_err(internal)
+}
+
+fn internal<T: std::fmt::Debug>(err: T) -> ApiError {
+    log::error!("Export encoding failed: {:?}", err);
+    ApiError::InternalServerError
+}
+
+/// Write target shared with the zip writer so compressed output can be
+/// drained after every chunk.
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Minimal single-sheet XLSX writer on top of a streaming zip archive.
+struct XlsxStream {
+    zip: Option<ZipWriter<StreamWriter<SharedBuffer>>>,
+    buffer: SharedBuffer,
+    next_row: u32,
+}
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#;
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const WORKBOOK: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Export" sheetId="1" r:id="rId1"/></sheets></workbook>"#;
+
+const WORKBOOK_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#;
+
+const SHEET_START: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#;
+
+const SHEET_END: &str = "</sheetData></worksheet>";
+
+impl XlsxStream {
+    fn new() -> Self {
+        let buffer = SharedBuffer::default();
+        Self {
+            zip: Some(ZipWriter::new_stream(buffer.clone())),
+            buffer,
+            next_row: 1,
+        }
+    }
+
+    fn zip(&mut self) -> io::Result<&mut ZipWriter<StreamWriter<SharedBuffer>>> {
+        self.zip
+            .as_mut()
+            .ok_or_else(|| io::Error::other("workbook already finished"))
+    }
+
+    fn begin(&mut self, headers: &[&str]) -> io::Result<Bytes> {
+        let options = SimpleFileOptions::default();
+        let zip = self.zip()?;
+        for (name, content) in [
+            ("[Content_Types].xml", CONTENT_TYPES),
+            ("_rels/.rels", ROOT_RELS),
+            ("xl/workbook.xml", WORKBOOK),
+            ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS),
+        ] {
+            zip.start_file(name, options)?;
+            zip.write_all(content.as_bytes())?;
+        }
+        zip.start_file("xl/worksheets/sheet1.xml", options)?;
+        zip.write_all(SHEET_START.as_bytes())?;
+
+        let header_cells: Vec<Cell> = headers
+            .iter()
+            .map(|header| Cell::Text(header.to_string()))
+            .collect();
+        self.write_row(&header_cells)?;
+
+        Ok(self.take())
+    }
+
+    fn write_row(&mut self, cells: &[Cell]) -> io::Result<()> {
+        let row = self.next_row;
+        self.next_row += 1;
+
+        let mut xml = format!(r#"<row r="{}">"#, row);
+        for (index, cell) in cells.iter().enumerate() {
+            let reference = format!("{}{}", column_name(index), row);
+            match cell {
+                Cell::Text(text) => xml.push_str(&format!(
+                    r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                    reference,
+                    escape_xml(text)
+                )),
+                Cell::Number(number) => {
+                    xml.push_str(&format!(r#"<c r="{}"><v>{}</v></c>"#, reference, number))
+                }
+                Cell::Empty => {}
+            }
+        }
+        xml.push_str("</row>");
+
+        self.zip()?.write_all(xml.as_bytes())
+    }
+
+    fn finish(&mut self) -> io::Result<Bytes> {
+        let mut zip = self
+            .zip
+            .take()
+            .ok_or_else(|| io::Error::other("workbook already finished"))?;
+        zip.write_all(SHEET_END.as_bytes())?;
+        zip.finish()?;
+
+        Ok(self.take())
+    }
+
+    /// Drains whatever the zip writer has produced so far.
+    fn take(&self) -> Bytes {
+        Bytes::from(std::mem::take(&mut *self.buffer.0.borrow_mut()))
+    }
+}
+
+fn column_name(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            // Control characters other than tab and newlines are invalid in XML
+            '\t' | '\n' | '\r' => escaped.push(ch),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::{Data, Reader, Xlsx};
+    use std::io::Cursor;
+
+    #[derive(Serialize)]
+    struct Line {
+        name: String,
+        amount: f64,
+    }
+
+    impl ExportRow for Line {
+        fn headers() -> &'static [&'static str] {
+            &["name", "amount"]
+        }
+
+        fn cells(&self) -> Vec<Cell> {
+            vec![self.name.clone().into(), self.amount.into()]
+        }
+    }
+
+    fn encode(format: ExportFormat, chunks: &[Vec<Line>]) -> Vec<u8> {
+        let mut encoder = Encoder::new(format);
+        let mut output = encoder.begin(Line::headers()).unwrap().to_vec();
+        for chunk in chunks {
+            output.extend_from_slice(&encoder.rows(chunk).unwrap());
+        }
+        output.extend_from_slice(&encoder.finish().unwrap());
+        output
+    }
+
+    fn lines() -> Vec<Vec<Line>> {
+        vec![
+            vec![Line {
+                name: "Bolts & <nuts>".to_string(),
+                amount: 1.5,
+            }],
+            vec![Line {
+                name: "Washer, flat".to_string(),
+                amount: 2.0,
+            }],
+        ]
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let output = String::from_utf8(encode(ExportFormat::Csv, &lines())).unwrap();
+        assert_eq!(
+            output,
+            "name,amount\nBolts & <nuts>,1.5\n\"Washer, flat\",2\n"
+        );
+    }
+
+    #[test]
+    fn test_jsonl_export() {
+        let output = String::from_utf8(encode(ExportFormat::Jsonl, &lines())).unwrap();
+        let rows: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1]["name"], "Washer, flat");
+    }
+
+    #[test]
+    fn test_xlsx_export_is_readable() {
+        let output = encode(ExportFormat::Xlsx, &lines());
+        let mut workbook = Xlsx::new(Cursor::new(output)).unwrap();
+        let range = workbook.worksheet_range_at(0).unwrap().unwrap();
+
+        assert_eq!(range.height(), 3);
+        assert_eq!(range.get((0, 0)), Some(&Data::String("name".to_string())));
+        assert_eq!(
+            range.get((1, 0)),
+            Some(&Data::String("Bolts & <nuts>".to_string()))
+        );
+        assert_eq!(range.get((2, 1)), Some(&Data::Float(2.0)));
+    }
+
+    #[test]
+    fn test_chunks_are_ordered_by_id_last() {
+        use crate::tenant::Tenant;
+        use entity::inventory;
+        use sea_orm::{DbBackend, QueryTrait};
+
+        let select = Tenant::new("company-a")
+            .find_active::<inventory::Entity>()
+            .order_by_asc(inventory::Column::Name);
+        let sql = in_total_order(select).build(DbBackend::MySql).to_string();
+        assert!(sql.ends_with("ORDER BY `inventory`.`name` ASC, `inventory`.`id` ASC"));
+    }
+
+    #[test]
+    fn test_column_name() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(27), "AB");
+    }
+}
@@ -0,0 +1,81 @@
+use actix_web::{dev::Payload, web, FromRequest, HttpMessage, HttpRequest};
+use entity::user;
+use futures_util::future::LocalBoxFuture;
+use sea_orm::EntityTrait;
+use serde::de::DeserializeOwned;
+use std::ops::Deref;
+use validator::Validate;
+
+use crate::error::ApiError;
+use crate::middlewares::jwt::Claims;
+use crate::v1::company::services as company_services;
+
+/// JSON body that has passed its `#[validate]` rules. Invalid bodies are
+/// rejected with every failing field listed.
+pub struct ValidatedJson<T>(pub T);
+
+impl<T> ValidatedJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for ValidatedJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> FromRequest for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate + 'static,
+{
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let json = web::Json::<T>::from_request(req, payload);
+
+        Box::pin(async move {
+            let value = json.await?.into_inner();
+            value.validate().map_err(ApiError::from)?;
+            Ok(ValidatedJson(value))
+        })
+    }
+}
+
+/// The authenticated user, who must administer the company they work in.
+/// The role is read from the database so revoking it takes effect
+/// immediately.
+pub struct Admin(pub user::Model);
+
+impl FromRequest for Admin {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let claims = req.extensions().get::<Claims>().cloned();
+        let data = req.app_data::<web::Data<config::app::AppState>>().cloned();
+
+        Box::pin(async move {
+            let claims = claims
+                .ok_or_else(|| ApiError::Unauthorized("Authentication required".to_string()))?;
+            let data = data.ok_or(ApiError::InternalServerError)?;
+            let forbidden = || ApiError::Forbidden("Only administrators can do this".to_string());
+
+            if !company_services::is_admin(&data.db, &claims.tenant_id, &claims.sub).await? {
+                return Err(forbidden().into());
+            }
+            // Users are global, not company data
+            #[allow(clippy::disallowed_methods)]
+            let user = user::Entity::find_by_id(claims.sub)
+                .one(&data.db)
+                .await
+                .map_err(ApiError::from)?
+                .ok_or_else(forbidden)?;
+            Ok(Admin(user))
+        })
+    }
+}
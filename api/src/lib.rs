@@ -1,5 +1,17 @@
+pub mod audit;
+pub mod batch;
+pub mod calendar;
 pub mod error;
+pub mod events;
+pub mod export;
+pub mod extractors;
 pub mod middlewares;
 pub mod openapi;
+pub mod pagination;
+pub mod pdf;
+pub mod reindex;
 pub mod shared;
+pub mod soft_delete;
+pub mod tenant;
 pub mod v1;
+pub mod webhooks;
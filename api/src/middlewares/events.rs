@@ -0,0 +1,78 @@
+use crate::events::{EventBus, PendingEvents};
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error, HttpMessage,
+};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Publishes the changes a request recorded to the `EventBus` once it
+/// succeeded. Failed requests rolled their changes back, so theirs are
+/// dropped.
+#[derive(Clone, Default)]
+pub struct EventsMiddleware;
+
+impl EventsMiddleware {
+    pub fn new() -> Self {
+        EventsMiddleware
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for EventsMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = EventsMiddlewareService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(EventsMiddlewareService {
+            service: Arc::new(service),
+        }))
+    }
+}
+
+pub struct EventsMiddlewareService<S> {
+    service: Arc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for EventsMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let Some(bus) = req.app_data::<web::Data<EventBus>>().cloned() else {
+                return service.call(req).await;
+            };
+            let pending = PendingEvents::default();
+            req.extensions_mut().insert(pending.clone());
+
+            let res = service.call(req).await?;
+            if res.status().is_success() {
+                bus.publish(pending.take());
+            }
+            Ok(res)
+        })
+    }
+}
@@ -0,0 +1,340 @@
+// Idempotency keys belong to the caller, not to a company
+#![allow(clippy::disallowed_methods)]
+
+use crate::error::ApiError;
+use crate::middlewares::jwt::Claims;
+use crate::shared::db_utils::is_duplicate;
+use actix_web::{
+    body::{self, BoxBody, MessageBody},
+    dev::{Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{header, Method, StatusCode},
+    web, Error, HttpMessage, HttpResponse,
+};
+use chrono::{Duration, Utc};
+use entity::idempotency_key;
+use futures_util::{future::LocalBoxFuture, StreamExt};
+use log::warn;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::future::{ready, Ready};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+/// Set on responses that were replayed from an earlier attempt.
+pub const IDEMPOTENT_REPLAY_HEADER: &str = "Idempotent-Replayed";
+
+const MAX_KEY_LENGTH: usize = 255;
+/// Largest request body buffered for fingerprinting, matching the biggest
+/// upload any API route accepts.
+const MAX_BODY_BYTES: usize = 20 * 1024 * 1024;
+/// How long a key stays reserved while its request runs. A retry after that
+/// takes the key over, in case the request never finished or released it.
+const PENDING_LEASE_SECONDS: i64 = 300;
+/// Headers kept with a response, besides its Content-Type, so a replay
+/// points to the same record and version.
+const REPLAYED_HEADERS: [header::HeaderName; 2] = [header::ETAG, header::LOCATION];
+
+/// Makes POST requests carrying an `Idempotency-Key` header safe to retry.
+/// Must sit inside `JwtMiddleware` so keys are scoped to the caller.
+#[derive(Clone, Default)]
+pub struct IdempotencyMiddleware;
+
+impl IdempotencyMiddleware {
+    pub fn new() -> Self {
+        IdempotencyMiddleware
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for IdempotencyMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = IdempotencyMiddlewareService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IdempotencyMiddlewareService {
+            service: Arc::new(service),
+        }))
+    }
+}
+
+pub struct IdempotencyMiddlewareService<S> {
+    service: Arc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for IdempotencyMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let key = match req.headers().get(IDEMPOTENCY_KEY_HEADER) {
+                Some(key) if req.method() == Method::POST => key.to_str().unwrap_or("").to_string(),
+                _ => return Ok(service.call(req).await?.map_into_boxed_body()),
+            };
+            if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+                return Err(ApiError::ValidationError(format!(
+                    "{} must be between 1 and {} characters",
+                    IDEMPOTENCY_KEY_HEADER, MAX_KEY_LENGTH
+                ))
+                .into());
+            }
+
+            let data = req
+                .app_data::<web::Data<config::app::AppState>>()
+                .cloned()
+                .ok_or(ApiError::InternalServerError)?;
+            let user_id = req
+                .extensions()
+                .get::<Claims>()
+                .map(|claims| claims.sub.clone())
+                .unwrap_or_default();
+
+            // Buffer the body to fingerprint it, then hand it back to the handler
+            let body = read_body(&mut req).await?;
+            let fingerprint = fingerprint(&req, &body);
+            req.set_payload(Payload::from(body));
+
+            let window = Duration::seconds(data.idempotency_window_seconds as i64);
+            let lease = Duration::seconds(PENDING_LEASE_SECONDS).min(window);
+            let record = match reserve(&data.db, &user_id, &key, &fingerprint, lease).await? {
+                Reservation::Reserved(record) => record,
+                Reservation::Replay(response) => {
+                    return Ok(req.into_response(response));
+                }
+            };
+            // Released unless the response gets stored, also when the
+            // handler fails or the client goes away mid-request
+            let guard = ReservationGuard {
+                db: data.db.clone(),
+                id: Some(record.id.clone()),
+            };
+
+            let res = service.call(req).await?;
+            let (req, res) = res.into_parts();
+            let (res, body) = res.into_parts();
+            let body = body::to_bytes(body)
+                .await
+                .map_err(|_| ApiError::InternalServerError)?;
+
+            // Server errors are not remembered so the client can try again
+            if res.status().is_server_error() {
+                guard.release().await?;
+            } else {
+                let content_type = res
+                    .headers()
+                    .get(header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
+                let headers: Map<String, Value> = REPLAYED_HEADERS
+                    .iter()
+                    .filter_map(|name| {
+                        let value = res.headers().get(name)?.to_str().ok()?;
+                        Some((name.to_string(), Value::from(value)))
+                    })
+                    .collect();
+                let mut active: idempotency_key::ActiveModel = record.into();
+                active.response_status = Set(Some(res.status().as_u16() as i16));
+                active.response_content_type = Set(content_type);
+                active.response_headers =
+                    Set((!headers.is_empty()).then_some(Value::Object(headers)));
+                active.response_body = Set(Some(body.to_vec()));
+                active.expires_at = Set(Utc::now().naive_utc() + window);
+                match active.update(&data.db).await {
+                    Ok(_) => guard.keep(),
+                    Err(e) => {
+                        warn!("Failed to store idempotent response: {}", e);
+                        guard.release().await?;
+                    }
+                }
+            }
+
+            let res = res.set_body(body).map_into_boxed_body();
+            Ok(ServiceResponse::new(req, res))
+        })
+    }
+}
+
+/// A reserved key whose response is not stored yet. Dropping it deletes the
+/// reservation so retries are not refused as still in progress.
+struct ReservationGuard {
+    db: DatabaseConnection,
+    id: Option<String>,
+}
+
+impl ReservationGuard {
+    /// The response was stored; the key now answers retries.
+    fn keep(mut self) {
+        self.id = None;
+    }
+
+    /// Deletes the reservation right away so a retry can run.
+    async fn release(mut self) -> Result<(), ApiError> {
+        if let Some(id) = self.id.take() {
+            idempotency_key::Entity::delete_by_id(id)
+                .exec(&self.db)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ReservationGuard {
+    fn drop(&mut self) {
+        let Some(id) = self.id.take() else {
+            return;
+        };
+        let db = self.db.clone();
+        actix_web::rt::spawn(async move {
+            if let Err(e) = idempotency_key::Entity::delete_by_id(id).exec(&db).await {
+                warn!("Failed to release idempotency key: {}", e);
+            }
+        });
+    }
+}
+
+enum Reservation {
+    /// The key is new; the request should run and its response be stored.
+    Reserved(idempotency_key::Model),
+    /// The key was used before; answer with this instead of running again.
+    Replay(HttpResponse),
+}
+
+async fn read_body(req: &mut ServiceRequest) -> Result<web::Bytes, ApiError> {
+    let mut payload = req.take_payload();
+    let mut body = web::BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk
+            .map_err(|_| ApiError::ValidationError("Could not read request body".to_string()))?;
+        if body.len() + chunk.len() > MAX_BODY_BYTES {
+            return Err(ApiError::PayloadTooLarge(
+                "Request body is too large".to_string(),
+            ));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body.freeze())
+}
+
+fn fingerprint(req: &ServiceRequest, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    // A key reused after switching companies is a different request
+    if let Some(claims) = req.extensions().get::<Claims>() {
+        hasher.update(&claims.tenant_id);
+        hasher.update(b"\n");
+    }
+    hasher.update(req.method().as_str());
+    hasher.update(b" ");
+    hasher.update(req.uri().to_string());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+async fn reserve(
+    db: &DatabaseConnection,
+    user_id: &str,
+    key: &str,
+    fingerprint: &str,
+    lease: Duration,
+) -> Result<Reservation, ApiError> {
+    let now = Utc::now().naive_utc();
+
+    // Two attempts: the second one runs after clearing an expired record,
+    // which includes a reservation whose lease ran out
+    for _ in 0..2 {
+        let record = idempotency_key::ActiveModel {
+            id: Set(uuid::Uuid::new_v4().to_string()),
+            user_id: Set(user_id.to_string()),
+            key: Set(key.to_string()),
+            fingerprint: Set(fingerprint.to_string()),
+            response_status: Set(None),
+            response_content_type: Set(None),
+            response_headers: Set(None),
+            response_body: Set(None),
+            created_at: Set(now),
+            expires_at: Set(now + lease),
+        };
+
+        match record.insert(db).await {
+            Ok(record) => return Ok(Reservation::Reserved(record)),
+            Err(e) if is_duplicate(&e) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let Some(existing) = idempotency_key::Entity::find()
+            .filter(idempotency_key::Column::UserId.eq(user_id))
+            .filter(idempotency_key::Column::Key.eq(key))
+            .one(db)
+            .await?
+        else {
+            // Deleted in the meantime, try to claim it again
+            continue;
+        };
+
+        if existing.expires_at <= now {
+            idempotency_key::Entity::delete_by_id(existing.id)
+                .exec(db)
+                .await?;
+            continue;
+        }
+        return replay(existing, fingerprint).map(Reservation::Replay);
+    }
+
+    Err(ApiError::Conflict(format!(
+        "{} is being reused concurrently",
+        IDEMPOTENCY_KEY_HEADER
+    )))
+}
+
+fn replay(existing: idempotency_key::Model, fingerprint: &str) -> Result<HttpResponse, ApiError> {
+    if existing.fingerprint != fingerprint {
+        return Err(ApiError::UnprocessableEntity(format!(
+            "{} was already used with a different request",
+            IDEMPOTENCY_KEY_HEADER
+        )));
+    }
+
+    let status = existing
+        .response_status
+        .and_then(|status| StatusCode::from_u16(status as u16).ok())
+        .ok_or_else(|| {
+            ApiError::Conflict(
+                "A request with this Idempotency-Key is still in progress".to_string(),
+            )
+        })?;
+
+    let mut response = HttpResponse::build(status);
+    response.insert_header((IDEMPOTENT_REPLAY_HEADER, "true"));
+    if let Some(content_type) = existing.response_content_type {
+        response.insert_header((header::CONTENT_TYPE, content_type));
+    }
+    if let Some(Value::Object(headers)) = existing.response_headers {
+        for (name, value) in headers {
+            if let Value::String(value) = value {
+                response.insert_header((name, value));
+            }
+        }
+    }
+    Ok(response.body(existing.response_body.unwrap_or_default()))
+}
@@ -1,4 +1,5 @@
 use crate::error::ApiError;
+use crate::v1::company::services as company_services;
 use actix_web::{
     dev::{Service, ServiceRequest, ServiceResponse, Transform},
     Error, HttpMessage,
@@ -15,6 +16,8 @@ use utoipa::ToSchema;
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Claims {
     pub sub: String,
+    /// Company the token works in, see `POST /v1/auth/switch-company`.
+    pub tenant_id: String,
     pub exp: usize,
 }
 
@@ -73,7 +76,11 @@ where
         let token_prefix = self.token_prefix.clone();
 
         Box::pin(async move {
-            match Self::verify_token(&req, &token_prefix) {
+            let verified = match Self::verify_token(&req, &token_prefix) {
+                Ok(claims) => Self::check_membership(&req, claims).await,
+                Err(e) => Err(e),
+            };
+            match verified {
                 Ok(claims) => {
                     req.extensions_mut().insert(claims);
                     service.call(req).await
@@ -95,6 +102,20 @@ impl<S> JwtMiddlewareService<S> {
             .and_then(|s| s.strip_prefix(token_prefix).map(|s| s.trim()))
     }
 
+    /// Tokens stop working once the membership they were issued for is
+    /// disabled, e.g. when the user's employee was let go.
+    async fn check_membership(req: &ServiceRequest, claims: Claims) -> Result<Claims, ApiError> {
+        let data = req
+            .app_data::<actix_web::web::Data<config::app::AppState>>()
+            .ok_or(ApiError::InternalServerError)?;
+        if company_services::is_disabled_member(&data.db, &claims.tenant_id, &claims.sub).await? {
+            return Err(ApiError::Unauthorized(
+                "This login has been disabled in this company".to_string(),
+            ));
+        }
+        Ok(claims)
+    }
+
     fn verify_token(req: &ServiceRequest, token_prefix: &str) -> Result<Claims, ApiError> {
         let data = req
             .app_data::<actix_web::web::Data<config::app::AppState>>()
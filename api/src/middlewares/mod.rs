@@ -1 +1,4 @@
+pub mod events;
+pub mod idempotency;
 pub mod jwt;
+pub mod request_id;
@@ -0,0 +1,136 @@
+use crate::error::{Problem, PROBLEM_CONTENT_TYPE};
+use actix_web::{
+    body::{self, BoxBody, MessageBody},
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{self, HeaderName, HeaderValue},
+    Error, HttpMessage,
+};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// Id of the current request, available from request extensions.
+#[derive(Clone, Debug)]
+pub struct RequestId(pub String);
+
+/// Tags every request with an id, echoed in `X-Request-Id`, and completes
+/// problem responses with the request path and id.
+#[derive(Clone, Default)]
+pub struct RequestIdMiddleware;
+
+impl RequestIdMiddleware {
+    pub fn new() -> Self {
+        RequestIdMiddleware
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequestIdMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestIdMiddlewareService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdMiddlewareService {
+            service: Arc::new(service),
+        }))
+    }
+}
+
+pub struct RequestIdMiddlewareService<S> {
+    service: Arc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            // Keep the caller's id so logs can be correlated across services
+            let request_id = req
+                .headers()
+                .get(REQUEST_ID_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LENGTH)
+                .map(str::to_string)
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+            req.extensions_mut().insert(RequestId(request_id.clone()));
+
+            // Errors raised by inner middlewares are rendered here so they
+            // get the same treatment as handler errors
+            let http_req = req.request().clone();
+            let res = match service.call(req).await {
+                Ok(res) => res.map_into_boxed_body(),
+                Err(err) => ServiceResponse::from_err(err, http_req),
+            };
+
+            let mut res = if is_problem(&res) {
+                complete_problem(res, &request_id).await?
+            } else {
+                res
+            };
+
+            if let Ok(value) = HeaderValue::from_str(&request_id) {
+                res.headers_mut()
+                    .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+            }
+            Ok(res)
+        })
+    }
+}
+
+fn is_problem(res: &ServiceResponse<BoxBody>) -> bool {
+    res.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with(PROBLEM_CONTENT_TYPE))
+}
+
+async fn complete_problem(
+    res: ServiceResponse<BoxBody>,
+    request_id: &str,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let (req, res) = res.into_parts();
+    let (res, body) = res.into_parts();
+    let body = body::to_bytes(body).await?;
+
+    let body = match serde_json::from_slice::<Problem>(&body) {
+        Ok(mut problem) => {
+            problem
+                .instance
+                .get_or_insert_with(|| req.path().to_string());
+            problem.request_id = Some(request_id.to_string());
+            serde_json::to_vec(&problem).map(Into::into).unwrap_or(body)
+        }
+        Err(_) => body,
+    };
+
+    Ok(ServiceResponse::new(
+        req,
+        res.set_body(body).map_into_boxed_body(),
+    ))
+}
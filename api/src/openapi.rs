@@ -1,5 +1,9 @@
+use actix_web::{web, HttpResponse};
+use utoipa::openapi::{Content, Ref};
 use utoipa::{Modify, OpenApi};
 
+use crate::error::PROBLEM_CONTENT_TYPE;
+
 pub struct SecurityAddon;
 
 impl Modify for SecurityAddon {
@@ -17,6 +21,38 @@ impl Modify for SecurityAddon {
     }
 }
 
+/// Documents every 4xx/5xx response without a body as a `Problem`.
+pub struct ProblemResponses;
+
+impl Modify for ProblemResponses {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for path_item in openapi.paths.paths.values_mut() {
+            let operations = [
+                path_item.get.as_mut(),
+                path_item.put.as_mut(),
+                path_item.post.as_mut(),
+                path_item.delete.as_mut(),
+                path_item.patch.as_mut(),
+            ];
+            for operation in operations.into_iter().flatten() {
+                for (status, response) in operation.responses.responses.iter_mut() {
+                    let utoipa::openapi::RefOr::T(response) = response else {
+                        continue;
+                    };
+                    if (status.starts_with('4') || status.starts_with('5'))
+                        && response.content.is_empty()
+                    {
+                        response.content.insert(
+                            PROBLEM_CONTENT_TYPE.to_string(),
+                            Content::new(Some(Ref::from_schema_name("Problem"))),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
@@ -29,6 +65,176 @@ impl Modify for SecurityAddon {
         crate::v1::inventory::handlers::delete_item,
         crate::v1::employee::handlers::create_employee,
         crate::v1::order::handlers::create_order,
+        crate::v1::stock::handlers::receive_stock,
+        crate::v1::stock::handlers::ship_stock,
+        crate::v1::stock::handlers::suggest_pick,
+        crate::v1::stock::handlers::get_item_lots,
+        crate::v1::traceability::handlers::trace_lot,
+        crate::v1::stock_count::handlers::create_count,
+        crate::v1::stock_count::handlers::get_all_counts,
+        crate::v1::stock_count::handlers::get_count_by_id,
+        crate::v1::stock_count::handlers::submit_counts,
+        crate::v1::stock_count::handlers::get_variances,
+        crate::v1::stock_count::handlers::approve_count,
+        crate::v1::stock_count::handlers::cancel_count,
+        crate::v1::customer::handlers::create_customer,
+        crate::v1::customer::handlers::search_customers,
+        crate::v1::customer::handlers::get_all_customers,
+        crate::v1::customer::handlers::get_customer_by_id,
+        crate::v1::customer::handlers::update_customer,
+        crate::v1::customer::handlers::delete_customer,
+        crate::v1::import::handlers::import,
+        crate::v1::inventory::handlers::export_items,
+        crate::v1::employee::handlers::export_employees,
+        crate::v1::order::handlers::export_orders,
+        crate::v1::inventory::handlers::batch_items,
+        crate::v1::employee::handlers::batch_employees,
+        crate::v1::order::handlers::batch_orders,
+        crate::v1::employee::handlers::get_all_employees,
+        crate::v1::employee::handlers::get_employee_by_id,
+        crate::v1::employee::handlers::update_employee,
+        crate::v1::employee::handlers::delete_employee,
+        crate::v1::order::handlers::get_all_orders,
+        crate::v1::order::handlers::get_order_by_id,
+        crate::v1::order::handlers::get_order_lines,
+        crate::v1::order::handlers::update_order,
+        crate::v1::order::handlers::delete_order,
+        crate::v1::auth::handlers::register,
+        crate::v1::auth::handlers::login,
+        crate::v1::auth::handlers::refresh,
+        crate::v1::auth::handlers::me,
+        crate::v1::auth::handlers::logout,
+        crate::v1::auth::handlers::logout_all,
+        crate::v1::audit::handlers::get_audit_log,
+        crate::v1::inventory::handlers::restore_item,
+        crate::v1::inventory::handlers::purge_item,
+        crate::v1::employee::handlers::restore_employee,
+        crate::v1::employee::handlers::purge_employee,
+        crate::v1::order::handlers::approve_discount,
+        crate::v1::order::handlers::restore_order,
+        crate::v1::order::handlers::purge_order,
+        crate::v1::customer::handlers::restore_customer,
+        crate::v1::customer::handlers::purge_customer,
+        crate::v1::auth::handlers::switch_company,
+        crate::v1::company::handlers::get_companies,
+        crate::v1::company::handlers::create_company,
+        crate::v1::company::handlers::add_member,
+        crate::v1::webhook::handlers::get_webhooks,
+        crate::v1::webhook::handlers::create_webhook,
+        crate::v1::webhook::handlers::get_webhook_by_id,
+        crate::v1::webhook::handlers::update_webhook,
+        crate::v1::webhook::handlers::delete_webhook,
+        crate::v1::webhook::handlers::get_deliveries,
+        crate::v1::webhook::handlers::redeliver,
+        crate::v1::events::handlers::stream_events,
+        crate::v1::employee::handlers::search_employees,
+        crate::v1::employee::handlers::get_reports,
+        crate::v1::department::handlers::get_departments,
+        crate::v1::department::handlers::create_department,
+        crate::v1::department::handlers::get_headcount,
+        crate::v1::department::handlers::get_department_by_id,
+        crate::v1::department::handlers::update_department,
+        crate::v1::department::handlers::delete_department,
+        crate::v1::position::handlers::get_positions,
+        crate::v1::position::handlers::create_position,
+        crate::v1::position::handlers::get_position_by_id,
+        crate::v1::position::handlers::update_position,
+        crate::v1::position::handlers::delete_position,
+        crate::v1::auth::handlers::accept_invite,
+        crate::v1::employee::handlers::invite_employee,
+        crate::v1::employee::handlers::get_history,
+        crate::v1::employee::handlers::add_history_record,
+        crate::v1::employee::handlers::get_employees_as_of,
+        crate::v1::leave::handlers::get_leave_types,
+        crate::v1::leave::handlers::create_leave_type,
+        crate::v1::leave::handlers::update_leave_type,
+        crate::v1::leave::handlers::delete_leave_type,
+        crate::v1::leave::handlers::get_holidays,
+        crate::v1::leave::handlers::create_holiday,
+        crate::v1::leave::handlers::delete_holiday,
+        crate::v1::leave::handlers::get_balances,
+        crate::v1::leave::handlers::get_requests,
+        crate::v1::leave::handlers::submit_request,
+        crate::v1::leave::handlers::get_request_by_id,
+        crate::v1::leave::handlers::approve_request,
+        crate::v1::leave::handlers::reject_request,
+        crate::v1::leave::handlers::cancel_request,
+        crate::v1::project::handlers::get_projects,
+        crate::v1::project::handlers::create_project,
+        crate::v1::project::handlers::get_project_by_id,
+        crate::v1::project::handlers::update_project,
+        crate::v1::project::handlers::delete_project,
+        crate::v1::attendance::handlers::clock_in,
+        crate::v1::attendance::handlers::clock_out,
+        crate::v1::attendance::handlers::get_attendance,
+        crate::v1::attendance::handlers::get_report,
+        crate::v1::attendance::handlers::get_overtime_policy,
+        crate::v1::attendance::handlers::update_overtime_policy,
+        crate::v1::timesheet::handlers::get_timesheets,
+        crate::v1::timesheet::handlers::get_timesheet_by_id,
+        crate::v1::timesheet::handlers::add_entry,
+        crate::v1::timesheet::handlers::delete_entry,
+        crate::v1::timesheet::handlers::submit_timesheet,
+        crate::v1::timesheet::handlers::approve_timesheet,
+        crate::v1::timesheet::handlers::reject_timesheet,
+        crate::v1::payroll::handlers::get_components,
+        crate::v1::payroll::handlers::create_component,
+        crate::v1::payroll::handlers::update_component,
+        crate::v1::payroll::handlers::delete_component,
+        crate::v1::payroll::handlers::get_runs,
+        crate::v1::payroll::handlers::create_run,
+        crate::v1::payroll::handlers::get_run_by_id,
+        crate::v1::payroll::handlers::recalculate_run,
+        crate::v1::payroll::handlers::approve_run,
+        crate::v1::payroll::handlers::post_run,
+        crate::v1::payroll::handlers::delete_run,
+        crate::v1::payroll::handlers::get_journal,
+        crate::v1::payroll::handlers::get_payslips,
+        crate::v1::payroll::handlers::get_payslip_by_id,
+        crate::v1::payroll::handlers::get_payslip_pdf,
+        crate::v1::expense::handlers::get_categories,
+        crate::v1::expense::handlers::create_category,
+        crate::v1::expense::handlers::update_category,
+        crate::v1::expense::handlers::delete_category,
+        crate::v1::expense::handlers::get_claims,
+        crate::v1::expense::handlers::export_claims,
+        crate::v1::expense::handlers::create_claim,
+        crate::v1::expense::handlers::get_claim_by_id,
+        crate::v1::expense::handlers::delete_claim,
+        crate::v1::expense::handlers::add_line,
+        crate::v1::expense::handlers::delete_line,
+        crate::v1::expense::handlers::upload_receipt,
+        crate::v1::expense::handlers::get_receipt,
+        crate::v1::expense::handlers::submit_claim,
+        crate::v1::expense::handlers::approve_claim,
+        crate::v1::expense::handlers::reject_claim,
+        crate::v1::expense::handlers::reimburse_claim,
+        crate::v1::attachment::handlers::get_attachments,
+        crate::v1::attachment::handlers::upload_attachment,
+        crate::v1::attachment::handlers::get_attachment_by_id,
+        crate::v1::attachment::handlers::delete_attachment,
+        crate::v1::attachment::handlers::download_attachment,
+        crate::v1::attachment::handlers::download_thumbnail,
+        crate::v1::quotation::handlers::get_quotations,
+        crate::v1::quotation::handlers::create_quotation,
+        crate::v1::quotation::handlers::get_quotation_by_id,
+        crate::v1::quotation::handlers::update_quotation,
+        crate::v1::quotation::handlers::delete_quotation,
+        crate::v1::quotation::handlers::send_quotation,
+        crate::v1::quotation::handlers::accept_quotation,
+        crate::v1::quotation::handlers::reject_quotation,
+        crate::v1::quotation::handlers::revise_quotation,
+        crate::v1::quotation::handlers::get_revisions,
+        crate::v1::quotation::handlers::convert_quotation,
+        crate::v1::quotation::handlers::get_quotation_pdf,
+        crate::v1::pricing::handlers::get_price_lists,
+        crate::v1::pricing::handlers::create_price_list,
+        crate::v1::pricing::handlers::get_price_list_by_id,
+        crate::v1::pricing::handlers::update_price_list,
+        crate::v1::pricing::handlers::delete_price_list,
+        crate::v1::pricing::handlers::get_discount_policy,
+        crate::v1::pricing::handlers::update_discount_policy,
+        crate::v1::pricing::handlers::quote,
     ),
     components(
         schemas(
@@ -39,12 +245,273 @@ impl Modify for SecurityAddon {
             crate::v1::employee::models::Employee,
             crate::v1::employee::models::CreateEmployee,
             crate::v1::order::models::Order,
+            crate::v1::order::models::OrderLine,
             crate::v1::order::models::CreateOrder,
+            crate::v1::stock::models::StockLot,
+            crate::v1::stock::models::StockMovement,
+            crate::v1::stock::models::CreateReceipt,
+            crate::v1::stock::models::ReceiptLot,
+            crate::v1::stock::models::CreateShipment,
+            crate::v1::stock::models::ShipmentLot,
+            crate::v1::stock::models::PickList,
+            crate::v1::stock::models::PickLine,
+            crate::v1::traceability::models::LotTrace,
+            crate::v1::stock_count::models::StockCount,
+            crate::v1::stock_count::models::StockCountLine,
+            crate::v1::stock_count::models::StockCountLineView,
+            crate::v1::stock_count::models::StockCountDetail,
+            crate::v1::stock_count::models::CreateStockCount,
+            crate::v1::stock_count::models::SubmitCounts,
+            crate::v1::stock_count::models::CountedLine,
+            crate::v1::customer::models::Customer,
+            crate::v1::customer::models::CreateCustomer,
+            crate::v1::customer::models::UpdateCustomer,
+            crate::v1::import::models::ImportEntity,
+            crate::v1::import::models::ImportReport,
+            crate::v1::import::models::RowError,
+            crate::export::ExportFormat,
+            crate::batch::BatchMode,
+            crate::batch::BatchResponse,
+            crate::batch::OperationResult,
+            crate::error::Problem,
+            crate::v1::employee::models::UpdateEmployee,
+            crate::v1::order::models::UpdateOrder,
+            crate::v1::auth::models::RegisterRequest,
+            crate::v1::auth::models::LoginRequest,
+            crate::v1::auth::models::RefreshRequest,
+            crate::v1::auth::models::TokenResponse,
+            crate::middlewares::jwt::Claims,
+            crate::v1::audit::models::AuditEntry,
+            entity::audit_log::AuditAction,
+            crate::pagination::Page<crate::v1::audit::models::AuditEntry>,
+            crate::v1::auth::models::SwitchCompanyRequest,
+            crate::v1::company::models::Company,
+            crate::v1::company::models::CompanyMember,
+            crate::v1::company::models::CreateCompany,
+            crate::v1::company::models::AddMember,
+            entity::company_user::CompanyRole,
+            crate::v1::webhook::models::Webhook,
+            crate::v1::webhook::models::WebhookDelivery,
+            crate::v1::webhook::models::CreateWebhook,
+            crate::v1::webhook::models::UpdateWebhook,
+            entity::webhook_delivery::DeliveryStatus,
+            crate::pagination::Page<crate::v1::webhook::models::WebhookDelivery>,
+            crate::events::ChangeEvent,
+            crate::v1::employee::models::EmployeeDocument,
+            crate::v1::employee::models::ReportNode,
+            crate::v1::department::models::Department,
+            crate::v1::department::models::CreateDepartment,
+            crate::v1::department::models::UpdateDepartment,
+            crate::v1::department::models::Headcount,
+            crate::v1::position::models::Position,
+            crate::v1::position::models::CreatePosition,
+            crate::v1::position::models::UpdatePosition,
+            crate::v1::auth::models::AcceptInviteRequest,
+            crate::v1::auth::models::Profile,
+            crate::v1::auth::models::EmployeeProfile,
+            crate::v1::employee::models::Invite,
+            crate::v1::employee::models::EmploymentRecord,
+            crate::v1::employee::models::CreateEmploymentRecord,
+            crate::v1::employee::models::EmploymentState,
+            crate::v1::employee::models::HistoryEntry,
+            crate::v1::employee::models::EmploymentHistory,
+            crate::v1::employee::models::EmployeeAsOf,
+            entity::employment_record::EmploymentEvent,
+            crate::v1::leave::models::LeaveType,
+            crate::v1::leave::models::CreateLeaveType,
+            crate::v1::leave::models::UpdateLeaveType,
+            crate::v1::leave::models::Holiday,
+            crate::v1::leave::models::CreateHoliday,
+            crate::v1::leave::models::LeaveRequest,
+            crate::v1::leave::models::SubmitLeave,
+            crate::v1::leave::models::DecideLeave,
+            crate::v1::leave::models::LeaveBalance,
+            entity::leave_type::Accrual,
+            entity::leave_request::LeaveStatus,
+            crate::v1::project::models::Project,
+            crate::v1::project::models::CreateProject,
+            crate::v1::project::models::UpdateProject,
+            crate::v1::attendance::models::Attendance,
+            crate::v1::attendance::models::ClockRequest,
+            crate::v1::attendance::models::OvertimePolicy,
+            crate::v1::attendance::models::UpdateOvertimePolicy,
+            crate::v1::attendance::models::AttendanceReport,
+            crate::v1::attendance::models::ProjectHours,
+            crate::v1::timesheet::models::Timesheet,
+            crate::v1::timesheet::models::TimesheetEntry,
+            crate::v1::timesheet::models::TimesheetWithEntries,
+            crate::v1::timesheet::models::CreateEntry,
+            crate::v1::timesheet::models::DecideTimesheet,
+            entity::project::ProjectKind,
+            entity::timesheet::TimesheetStatus,
+            crate::v1::payroll::models::PayrollComponent,
+            crate::v1::payroll::models::CreateComponent,
+            crate::v1::payroll::models::UpdateComponent,
+            crate::v1::payroll::models::Bracket,
+            crate::v1::payroll::models::PayrollRun,
+            crate::v1::payroll::models::CreateRun,
+            crate::v1::payroll::models::PostRun,
+            crate::v1::payroll::models::PayrollRunWithPayslips,
+            crate::v1::payroll::models::Payslip,
+            crate::v1::payroll::models::PayslipLine,
+            crate::v1::payroll::models::PayslipWithLines,
+            crate::v1::payroll::models::JournalEntry,
+            entity::payroll_component::ComponentKind,
+            entity::payroll_component::ComponentBase,
+            entity::payroll_component::ComponentQuantity,
+            entity::payroll_run::PayrollStatus,
+            crate::v1::expense::models::ExpenseCategory,
+            crate::v1::expense::models::CreateCategory,
+            crate::v1::expense::models::UpdateCategory,
+            crate::v1::expense::models::ExpenseClaim,
+            crate::v1::expense::models::CreateClaim,
+            crate::v1::expense::models::ClaimWithLines,
+            crate::v1::expense::models::ExpenseLine,
+            crate::v1::expense::models::AddLine,
+            crate::v1::expense::models::DecideClaim,
+            crate::v1::expense::models::ReimburseClaim,
+            entity::expense_claim::ClaimStatus,
+            entity::expense_claim::ReimbursementMethod,
+            crate::v1::attachment::models::Attachment,
+            crate::v1::attachment::models::AttachmentWithUrls,
+            crate::v1::attachment::models::UploadAttachment,
+            entity::attachment::AttachedTo,
+            crate::v1::quotation::models::Quotation,
+            crate::v1::quotation::models::QuotationLine,
+            crate::v1::quotation::models::QuotationRevision,
+            crate::v1::quotation::models::QuotationWithLines,
+            crate::v1::quotation::models::QuoteLineInput,
+            crate::v1::quotation::models::CreateQuotation,
+            crate::v1::quotation::models::UpdateQuotation,
+            crate::v1::quotation::models::DecideQuotation,
+            crate::v1::quotation::models::ConvertedQuotation,
+            entity::quotation::QuoteStatus,
+            crate::v1::pricing::models::PriceList,
+            crate::v1::pricing::models::PriceListItem,
+            crate::v1::pricing::models::PriceBreak,
+            crate::v1::pricing::models::CreatePriceList,
+            crate::v1::pricing::models::UpdatePriceList,
+            crate::v1::pricing::models::PriceListWithItems,
+            crate::v1::pricing::models::DiscountPolicy,
+            crate::v1::pricing::models::UpdateDiscountPolicy,
+            crate::v1::pricing::models::Discount,
+            crate::v1::pricing::models::PricingLineInput,
+            crate::v1::pricing::models::PricingRequest,
+            crate::v1::pricing::models::PricedItem,
+            crate::v1::pricing::models::PricingQuote,
+            entity::order::DiscountKind,
+            entity::order::DiscountStatus,
         )
     ),
-    modifiers(&SecurityAddon),
+    modifiers(&SecurityAddon, &ProblemResponses),
     tags(
-        (name = "inventory", description = "Inventory management endpoints.")
+        (name = "inventory", description = "Inventory management endpoints."),
+        (name = "stock", description = "Stock movement, lot and serial tracking endpoints."),
+        (name = "customer", description = "Customer management endpoints."),
+        (name = "import", description = "Bulk CSV/XLSX import endpoints."),
+        (name = "auth", description = "Registration, login and token endpoints."),
+        (name = "employee", description = "Employee management endpoints."),
+        (name = "order", description = "Order management endpoints."),
+        (name = "audit", description = "Append-only log of every change made through the API."),
+        (name = "company", description = "Companies (tenants) and their members"),
+        (name = "webhook", description = "Event subscriptions for external systems"),
+        (name = "events", description = "Live stream of changes"),
+        (name = "department", description = "Departments and headcount."),
+        (name = "position", description = "Job positions within departments."),
+        (name = "leave", description = "Leave types, holidays, balances and requests"),
+        (name = "project", description = "Projects and cost centers time is booked on"),
+        (name = "attendance", description = "Clocking in and out, overtime and attendance reports"),
+        (name = "timesheet", description = "Weekly timesheets and their approval"),
+        (name = "payroll", description = "Salary components, payroll runs, payslips and their journal entries"),
+        (name = "expense", description = "Expense categories, claims with receipts, approvals and reimbursement"),
+        (name = "attachment", description = "Files attached to orders, inventory items and employees, downloaded through signed links"),
+        (name = "quotation", description = "Sales quotations, their revisions and conversion into orders"),
+        (name = "pricing", description = "Price lists, discount limits and customer prices")
     )
 )]
 pub struct ApiDoc;
+
+pub async fn openapi_json() -> HttpResponse {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/openapi.json", web::get().to(openapi_json));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use std::{fs, path::Path};
+
+    /// Reads every `(path, method)` registered by the v1 `routes.rs` files.
+    fn registered_routes() -> Vec<(String, String)> {
+        let token = Regex::new(
+            r#"web::scope\("([^"]*)"\)|web::resource\("([^"]*)"\)|\.route\(\s*(?:"([^"]*)",\s*)?web::(\w+)\(\)"#,
+        )
+        .unwrap();
+        let v1 = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/v1");
+
+        let mut routes = Vec::new();
+        for module in fs::read_dir(v1).unwrap() {
+            let file = module.unwrap().path().join("routes.rs");
+            let Ok(source) = fs::read_to_string(&file) else {
+                continue;
+            };
+
+            let (mut scope, mut resource) = (String::new(), String::new());
+            for caps in token.captures_iter(&source) {
+                if let Some(path) = caps.get(1) {
+                    scope = path.as_str().to_string();
+                } else if let Some(path) = caps.get(2) {
+                    resource = path.as_str().to_string();
+                } else {
+                    let path = caps.get(3).map_or(resource.as_str(), |p| p.as_str());
+                    routes.push((format!("{scope}{path}"), caps[4].to_string()));
+                }
+            }
+        }
+        routes
+    }
+
+    #[test]
+    fn every_route_is_documented() {
+        let doc = ApiDoc::openapi();
+        let routes = registered_routes();
+        assert!(!routes.is_empty());
+
+        let missing: Vec<String> = routes
+            .into_iter()
+            .filter(|(path, method)| {
+                let Some(item) = doc.paths.paths.get(path) else {
+                    return true;
+                };
+                let operation = match method.as_str() {
+                    "get" => &item.get,
+                    "post" => &item.post,
+                    "put" => &item.put,
+                    "delete" => &item.delete,
+                    "patch" => &item.patch,
+                    _ => return true,
+                };
+                operation.is_none()
+            })
+            .map(|(path, method)| format!("{} {}", method.to_uppercase(), path))
+            .collect();
+
+        assert!(missing.is_empty(), "undocumented routes: {:?}", missing);
+    }
+
+    #[test]
+    fn error_responses_use_problem_details() {
+        let doc = ApiDoc::openapi();
+        let item = &doc.paths.paths["/v1/inventory/{id}"];
+        let utoipa::openapi::RefOr::T(not_found) =
+            &item.get.as_ref().unwrap().responses.responses["404"]
+        else {
+            panic!("expected an inline response");
+        };
+        assert!(not_found.content.contains_key(PROBLEM_CONTENT_TYPE));
+    }
+}
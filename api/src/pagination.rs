@@ -0,0 +1,64 @@
+use sea_orm::{ConnectionTrait, EntityTrait, FromQueryResult, PaginatorTrait, Select};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::error::ApiError;
+
+pub const DEFAULT_PER_PAGE: u64 = 50;
+pub const MAX_PER_PAGE: u64 = 200;
+
+/// `page` and `per_page` query parameters. Pages start at 1.
+#[derive(Deserialize, IntoParams)]
+pub struct PageQuery {
+    #[param(minimum = 1)]
+    pub page: Option<u64>,
+    #[param(minimum = 1, maximum = 200)]
+    pub per_page: Option<u64>,
+}
+
+impl PageQuery {
+    pub fn page(&self) -> u64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn per_page(&self) -> u64 {
+        self.per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: u64,
+    pub per_page: u64,
+    /// Number of matching records across all pages.
+    pub total: u64,
+    pub total_pages: u64,
+}
+
+/// Runs `select` for the requested page, counting every match.
+pub async fn paginate<E, C>(
+    db: &C,
+    select: Select<E>,
+    query: &PageQuery,
+) -> Result<Page<E::Model>, ApiError>
+where
+    E: EntityTrait,
+    E::Model: FromQueryResult + Send + Sync,
+    C: ConnectionTrait,
+{
+    let (page, per_page) = (query.page(), query.per_page());
+    let paginator = select.paginate(db, per_page);
+    let counts = paginator.num_items_and_pages().await?;
+    let items = paginator.fetch_page(page - 1).await?;
+
+    Ok(Page {
+        items,
+        page,
+        per_page,
+        total: counts.number_of_items,
+        total_pages: counts.number_of_pages,
+    })
+}
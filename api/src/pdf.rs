@@ -0,0 +1,275 @@
+//! A small writer for text-only PDF documents such as payslips and
+//! quotations. Text is set in Courier so columns can be lined up with
+//! spaces.
+
+use std::fmt::Write;
+
+const PAGE_WIDTH: u32 = 595;
+const PAGE_HEIGHT: u32 = 842;
+const MARGIN: u32 = 56;
+const FONT_SIZE: u32 = 10;
+const LEADING: u32 = 14;
+/// Characters that fit on a line between the margins.
+pub const LINE_WIDTH: usize = ((PAGE_WIDTH - 2 * MARGIN) * 10 / (FONT_SIZE * 6)) as usize;
+
+struct Line {
+    text: String,
+    bold: bool,
+}
+
+#[derive(Default)]
+pub struct TextDocument {
+    lines: Vec<Line>,
+}
+
+impl TextDocument {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn line(&mut self, text: impl Into<String>) -> &mut Self {
+        self.lines.push(Line {
+            text: text.into(),
+            bold: false,
+        });
+        self
+    }
+
+    pub fn bold(&mut self, text: impl Into<String>) -> &mut Self {
+        self.lines.push(Line {
+            text: text.into(),
+            bold: true,
+        });
+        self
+    }
+
+    pub fn blank(&mut self) -> &mut Self {
+        self.line("")
+    }
+
+    /// A label on the left and a value against the right margin.
+    pub fn row(&mut self, label: &str, value: &str) -> &mut Self {
+        let width = LINE_WIDTH.saturating_sub(value.chars().count() + 1);
+        let label: String = label.chars().take(width).collect();
+        self.line(format!("{label:<width$} {value}"))
+    }
+
+    /// Free text, wrapped between words to fit the lines. Line breaks in the
+    /// text are kept.
+    pub fn paragraph(&mut self, text: &str) -> &mut Self {
+        for source in text.lines() {
+            let mut line = String::new();
+            for word in source.split_whitespace() {
+                let needed = line.chars().count() + word.chars().count() + 1;
+                if !line.is_empty() && needed > LINE_WIDTH {
+                    self.line(std::mem::take(&mut line));
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+            }
+            self.line(line);
+        }
+        self
+    }
+
+    pub fn rule(&mut self) -> &mut Self {
+        self.line("-".repeat(LINE_WIDTH))
+    }
+
+    /// The PDF file, with as many A4 pages as the lines need.
+    pub fn render(&self) -> Vec<u8> {
+        let per_page = ((PAGE_HEIGHT - 2 * MARGIN) / LEADING) as usize;
+        let pages: Vec<&[Line]> = if self.lines.is_empty() {
+            vec![&[]]
+        } else {
+            self.lines.chunks(per_page).collect()
+        };
+
+        // 1 catalog, 2 page tree, 3 and 4 fonts, then a page and its content
+        // stream for every page
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                (0..pages.len())
+                    .map(|i| format!("{} 0 R", 5 + 2 * i))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                pages.len()
+            ),
+            font("Courier"),
+            font("Courier-Bold"),
+        ];
+        for (i, lines) in pages.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                6 + 2 * i
+            ));
+            let content = content(lines);
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{content}\nendstream",
+                content.len()
+            ));
+        }
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            let _ = write!(pdf, "{} 0 obj\n{object}\nendobj\n", i + 1);
+        }
+        let xref = pdf.len();
+        let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(pdf, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            pdf,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        );
+        pdf.into_bytes()
+    }
+}
+
+fn font(name: &str) -> String {
+    format!("<< /Type /Font /Subtype /Type1 /BaseFont /{name} /Encoding /WinAnsiEncoding >>")
+}
+
+fn content(lines: &[Line]) -> String {
+    let mut stream = format!(
+        "BT\n{LEADING} TL\n{MARGIN} {} Td\n",
+        PAGE_HEIGHT - MARGIN - FONT_SIZE
+    );
+    for line in lines {
+        let font = if line.bold { "F2" } else { "F1" };
+        let _ = writeln!(
+            stream,
+            "/{font} {FONT_SIZE} Tf ({}) Tj T*",
+            escape(&line.text)
+        );
+    }
+    stream.push_str("ET");
+    stream
+}
+
+/// Escapes a string literal. Characters the standard fonts cannot show are
+/// replaced, keeping the content stream ASCII.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
+
+/// An amount with thousands separators and two decimals, e.g. `1,250,000.00`.
+pub fn amount(value: f64) -> String {
+    let fixed = format!("{:.2}", value.abs());
+    let (whole, cents) = fixed.split_once('.').unwrap_or((&fixed, "00"));
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    let sign = if value < 0.0 && fixed != "0.00" {
+        "-"
+    } else {
+        ""
+    };
+    format!("{sign}{grouped}.{cents}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cross_references_point_at_objects() {
+        let mut document = TextDocument::new();
+        document.bold("Payslip").row("Net pay", "1,000.00");
+        let pdf = String::from_utf8(document.render()).unwrap();
+
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        let xref: usize = pdf
+            .rsplit("startxref\n")
+            .next()
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(pdf[xref..].starts_with("xref\n"));
+        let offsets: Vec<usize> = pdf[xref..]
+            .lines()
+            .filter(|line| line.ends_with(" n "))
+            .map(|line| line[..10].parse().unwrap())
+            .collect();
+        assert_eq!(offsets.len(), 6);
+        for (i, offset) in offsets.iter().enumerate() {
+            assert!(pdf[*offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+    }
+
+    #[test]
+    fn long_documents_break_into_pages() {
+        let mut document = TextDocument::new();
+        for i in 0..100 {
+            document.line(format!("Line {i}"));
+        }
+        let pdf = String::from_utf8(document.render()).unwrap();
+        assert!(pdf.contains("/Count 2"));
+        assert_eq!(pdf.matches("/Type /Page ").count(), 2);
+    }
+
+    #[test]
+    fn text_is_escaped() {
+        assert_eq!(escape(r"(a) \ b"), r"\(a\) \\ b");
+        assert_eq!(escape("Café"), "Caf?");
+    }
+
+    #[test]
+    fn rows_are_right_aligned() {
+        let mut document = TextDocument::new();
+        document.row("Basic salary", "12.00");
+        assert_eq!(document.lines[0].text.len(), LINE_WIDTH);
+        assert!(document.lines[0].text.ends_with(" 12.00"));
+    }
+
+    #[test]
+    fn paragraphs_wrap_between_words() {
+        let mut document = TextDocument::new();
+        document.paragraph(&format!("{}\nPrices exclude tax.", "word ".repeat(30)));
+        let texts: Vec<&str> = document.lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts.len(), 3);
+        assert!(texts[0].len() <= LINE_WIDTH);
+        assert!(texts[0].ends_with("word"));
+        assert_eq!(
+            texts[0].split(' ').count() + texts[1].split(' ').count(),
+            30
+        );
+        assert_eq!(texts[2], "Prices exclude tax.");
+    }
+
+    #[test]
+    fn amounts_are_grouped() {
+        assert_eq!(amount(0.0), "0.00");
+        assert_eq!(amount(999.5), "999.50");
+        assert_eq!(amount(1250000.0), "1,250,000.00");
+        assert_eq!(amount(-1234.567), "-1,234.57");
+    }
+}
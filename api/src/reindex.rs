@@ -0,0 +1,81 @@
+//! Rebuilding search indexes from the database.
+
+use config::app::AppState;
+use entity::{customer, employee, inventory};
+use sea_orm::{EntityTrait, PaginatorTrait, QueryOrder, Select};
+use search::meilisearch::{delete_documents_without_tenant, has_documents_without_tenant};
+use serde::Serialize;
+
+use crate::error::ApiError;
+use crate::tenant::Tenant;
+use crate::v1::company::services as company_services;
+use crate::v1::employee::services as employee_services;
+
+/// Rows indexed per request to Meilisearch.
+const BATCH_SIZE: u64 = 1000;
+
+/// Rebuilds the index when it still holds documents from before data
+/// belonged to a company, which no company's search would find. Returns
+/// whether it did.
+pub async fn reindex_documents_without_tenant(
+    data: &AppState,
+    index_name: &str,
+) -> Result<bool, ApiError> {
+    if !has_documents_without_tenant(&data.meilisearch, index_name).await? {
+        return Ok(false);
+    }
+    reindex(data, index_name).await?;
+    // Whatever is left belongs to rows deleted since
+    delete_documents_without_tenant(&data.meilisearch, index_name).await?;
+    Ok(true)
+}
+
+/// Indexes every company's rows again, e.g. after an upgrade changed what
+/// the documents hold. Documents are replaced by id.
+pub async fn reindex(data: &AppState, index_name: &str) -> Result<(), ApiError> {
+    for company in company_services::all_companies(&data.db).await? {
+        let tenant = Tenant::new(company.id);
+        match index_name {
+            "inventory" => {
+                let items = tenant
+                    .find_active::<inventory::Entity>()
+                    .order_by_asc(inventory::Column::Id);
+                add_in_batches(data, index_name, items).await?;
+            }
+            "customer" => {
+                let customers = tenant
+                    .find_active::<customer::Entity>()
+                    .order_by_asc(customer::Column::Id);
+                add_in_batches(data, index_name, customers).await?;
+            }
+            "employee" => {
+                let mut pages = tenant
+                    .find_active::<employee::Entity>()
+                    .order_by_asc(employee::Column::Id)
+                    .paginate(&data.db, BATCH_SIZE);
+                while let Some(employees) = pages.fetch_and_next().await? {
+                    employee_services::index_employees(data, &tenant, employees).await?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+async fn add_in_batches<E>(
+    data: &AppState,
+    index_name: &str,
+    select: Select<E>,
+) -> Result<(), ApiError>
+where
+    E: EntityTrait,
+    E::Model: Serialize + Sync,
+{
+    let index = data.meilisearch.index(index_name);
+    let mut pages = select.paginate(&data.db, BATCH_SIZE);
+    while let Some(documents) = pages.fetch_and_next().await? {
+        index.add_documents(&documents, Some("id")).await?;
+    }
+    Ok(())
+}
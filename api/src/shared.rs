@@ -1,45 +1,132 @@
-use crate::error::ApiError;
+/// Common utilities for database errors
+pub mod db_utils {
+    use crate::error::ApiError;
+    use crate::tenant::Tenant;
+    use entity::tenant::TenantOwned;
+    use sea_orm::{ConnectionTrait, DbErr, PrimaryKeyTrait, QuerySelect};
+
+    pub fn is_duplicate(db_err: &DbErr) -> bool {
+        db_err.to_string().contains("Duplicate entry")
+    }
+
+    /// Turns a unique key violation into a `Conflict` with the given message.
+    pub fn conflict_on_duplicate(db_err: DbErr, message: &str) -> ApiError {
+        if is_duplicate(&db_err) {
+            return ApiError::Conflict(message.to_string());
+        }
+        ApiError::DatabaseError(db_err)
+    }
 
-/// Generic validation trait
-pub trait Validatable {
-    fn validate(&self) -> Result<(), ApiError>;
+    /// Loads a record of the tenant by id and locks it until the
+    /// transaction ends.
+    pub async fn find_locked<E, C>(
+        db: &C,
+        tenant: &Tenant,
+        id: &str,
+    ) -> Result<Option<E::Model>, ApiError>
+    where
+        E: TenantOwned,
+        <E::PrimaryKey as PrimaryKeyTrait>::ValueType: From<String>,
+        C: ConnectionTrait,
+    {
+        Ok(tenant.find_by_id::<E>(id).lock_exclusive().one(db).await?)
+    }
 }
 
-/// Common validation utilities
-pub mod validation {
+/// ETag and conditional request helpers for versioned entities
+pub mod concurrency {
+    use actix_web::http::header::{ETag, EntityTag, Header, IfMatch, IfNoneMatch};
+    use actix_web::{HttpRequest, HttpResponse};
+
     use crate::error::ApiError;
 
-    pub fn validate_non_negative(value: i32, field: &str) -> Result<(), ApiError> {
-        if value < 0 {
-            Err(ApiError::ValidationError(format!(
-                "{} cannot be negative",
-                field
-            )))
-        } else {
-            Ok(())
+    /// Versions a write is allowed to replace, taken from `If-Match`.
+    pub enum Precondition {
+        Any,
+        Versions(Vec<i32>),
+    }
+
+    impl Precondition {
+        /// Writes must say which version they replace, so `If-Match` is required.
+        pub fn from_request(req: &HttpRequest) -> Result<Self, ApiError> {
+            match IfMatch::parse(req) {
+                Ok(IfMatch::Any) => Ok(Precondition::Any),
+                Ok(IfMatch::Items(tags)) if !tags.is_empty() => Ok(Precondition::Versions(
+                    // If-Match uses the strong comparison, weak tags never match
+                    tags.iter()
+                        .filter(|tag| !tag.weak)
+                        .filter_map(|tag| tag.tag().parse().ok())
+                        .collect(),
+                )),
+                _ => Err(ApiError::PreconditionRequired(
+                    "If-Match header with the current ETag is required".to_string(),
+                )),
+            }
+        }
+
+        pub fn check(&self, version: i32) -> Result<(), ApiError> {
+            match self {
+                Precondition::Versions(versions) if !versions.contains(&version) => {
+                    Err(ApiError::PreconditionFailed(
+                        "The record was changed by someone else, reload it and try again"
+                            .to_string(),
+                    ))
+                }
+                _ => Ok(()),
+            }
         }
     }
 
-    pub fn validate_non_negative_float(value: f64, field: &str) -> Result<(), ApiError> {
-        if value < 0.0 {
-            Err(ApiError::ValidationError(format!(
-                "{} cannot be negative",
-                field
-            )))
-        } else {
-            Ok(())
+    impl Precondition {
+        /// The `version` of a batch operation stands in for `If-Match`, and
+        /// is just as required.
+        pub fn from_version(version: Option<i32>) -> Result<Self, ApiError> {
+            version
+                .map(|version| Precondition::Versions(vec![version]))
+                .ok_or_else(|| {
+                    ApiError::PreconditionRequired(
+                        "The version of the record being replaced is required".to_string(),
+                    )
+                })
         }
     }
 
-    pub fn validate_email(email: &str) -> Result<(), ApiError> {
-        if !email.contains('@') {
-            Err(ApiError::ValidationError(
-                "Invalid email format".to_string(),
-            ))
-        } else {
-            Ok(())
+    pub fn etag(version: i32) -> ETag {
+        ETag(EntityTag::new_strong(version.to_string()))
+    }
+
+    /// Whether `If-None-Match` already names the current version.
+    pub fn is_not_modified(req: &HttpRequest, version: i32) -> bool {
+        let current = EntityTag::new_strong(version.to_string());
+        match IfNoneMatch::parse(req) {
+            Ok(IfNoneMatch::Any) => true,
+            Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&current)),
+            Err(_) => false,
         }
     }
+
+    pub fn not_modified(version: i32) -> HttpResponse {
+        HttpResponse::NotModified()
+            .insert_header(etag(version))
+            .finish()
+    }
+}
+
+/// Deserializer for update fields that can be cleared: a missing field
+/// leaves the value alone (`None`), `null` clears it (`Some(None)`).
+///
+/// Use with `#[serde(default, deserialize_with = "nullable")]`.
+pub fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::Deserialize<'de>,
+{
+    serde::Deserialize::deserialize(deserializer).map(Some)
+}
+
+/// Rounds an amount of money, or hours, to two decimals.
+pub fn round(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
 }
 
 /// Common utilities for entity operations
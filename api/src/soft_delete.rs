@@ -0,0 +1,102 @@
+use entity::audit_log::AuditAction;
+use entity::soft_delete::SoftDelete;
+use entity::tenant::TenantOwned;
+use sea_orm::{
+    ActiveModelBehavior, ActiveModelTrait, ConnectionTrait, IntoActiveModel, PrimaryKeyTrait,
+    QuerySelect,
+};
+use serde::Serialize;
+
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::tenant::Tenant;
+
+/// Loads and locks a deleted record. Live records cannot be restored or
+/// purged, which is reported as a conflict.
+async fn find_deleted<E, C>(db: &C, tenant: &Tenant, id: &str) -> Result<E::Model, ApiError>
+where
+    E: SoftDelete + TenantOwned,
+    <E::PrimaryKey as PrimaryKeyTrait>::ValueType: From<String>,
+    C: ConnectionTrait,
+{
+    if let Some(model) = tenant
+        .find_deleted_by_id::<E>(id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+    {
+        return Ok(model);
+    }
+
+    match tenant.find_active_by_id::<E>(id).one(db).await? {
+        Some(_) => Err(ApiError::Conflict(format!(
+            "Record with id {} is not deleted",
+            id
+        ))),
+        None => Err(ApiError::NotFound(format!(
+            "Record with id {} not found",
+            id
+        ))),
+    }
+}
+
+/// Clears `deleted_at` so the record shows up again.
+pub async fn restore<E, C>(
+    db: &C,
+    tenant: &Tenant,
+    audit: &AuditContext,
+    entity: &str,
+    id: &str,
+) -> Result<E::Model, ApiError>
+where
+    E: SoftDelete + TenantOwned,
+    E::Model: IntoActiveModel<E::ActiveModel> + Serialize,
+    E::ActiveModel: ActiveModelBehavior + Send,
+    <E::PrimaryKey as PrimaryKeyTrait>::ValueType: From<String>,
+    C: ConnectionTrait,
+{
+    let deleted = find_deleted::<E, C>(db, tenant, id).await?;
+
+    let mut active = deleted.clone().into_active_model();
+    active.set(
+        E::deleted_at(),
+        Option::<chrono::NaiveDateTime>::None.into(),
+    );
+    let restored = active.update(db).await?;
+
+    audit
+        .record(
+            db,
+            entity,
+            id,
+            AuditAction::Restore,
+            Some(&deleted),
+            Some(&restored),
+        )
+        .await?;
+    Ok(restored)
+}
+
+/// Removes a deleted record for good, together with the rows that cascade
+/// from it.
+pub async fn purge<E, C>(
+    db: &C,
+    tenant: &Tenant,
+    audit: &AuditContext,
+    entity: &str,
+    id: &str,
+) -> Result<(), ApiError>
+where
+    E: SoftDelete + TenantOwned,
+    E::Model: IntoActiveModel<E::ActiveModel> + Serialize,
+    E::ActiveModel: ActiveModelBehavior + Send,
+    <E::PrimaryKey as PrimaryKeyTrait>::ValueType: From<String>,
+    C: ConnectionTrait,
+{
+    let deleted = find_deleted::<E, C>(db, tenant, id).await?;
+    deleted.clone().into_active_model().delete(db).await?;
+
+    audit
+        .record(db, entity, id, AuditAction::Purge, Some(&deleted), None)
+        .await
+}
@@ -0,0 +1,175 @@
+use actix_web::{dev::Payload, FromRequest, HttpMessage, HttpRequest};
+use entity::soft_delete::SoftDelete;
+use entity::tenant::TenantOwned;
+use futures_util::future::{ready, Ready};
+use meilisearch_sdk::indexes::Index;
+use sea_orm::{ColumnTrait, DeleteMany, PrimaryKeyTrait, QueryFilter, Select, UpdateMany};
+use serde::de::DeserializeOwned;
+
+use crate::error::ApiError;
+use crate::middlewares::jwt::Claims;
+
+/// The company the caller is working in, taken from their token.
+///
+/// Company data is only reachable through this scope: the unscoped
+/// `EntityTrait` queries and `Index::search` are disallowed by clippy, so a
+/// handler cannot read or change another company's rows by forgetting a
+/// filter.
+#[derive(Clone, Debug)]
+pub struct Tenant {
+    id: String,
+    search_filter: String,
+}
+
+// The scope itself is the one place allowed to call the unscoped queries
+#[allow(clippy::disallowed_methods)]
+impl Tenant {
+    pub fn new(id: impl Into<String>) -> Self {
+        let id = id.into();
+        let search_filter = format!("tenant_id = {}", serde_json::Value::from(id.as_str()));
+        Tenant { id, search_filter }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn find<E: TenantOwned>(&self) -> Select<E> {
+        E::find().filter(E::tenant_id().eq(&self.id))
+    }
+
+    pub fn find_by_id<E>(&self, id: &str) -> Select<E>
+    where
+        E: TenantOwned,
+        <E::PrimaryKey as PrimaryKeyTrait>::ValueType: From<String>,
+    {
+        E::find_by_id(id.to_string()).filter(E::tenant_id().eq(&self.id))
+    }
+
+    /// Rows that have not been soft-deleted.
+    pub fn find_active<E: TenantOwned + SoftDelete>(&self) -> Select<E> {
+        self.find::<E>().filter(E::deleted_at().is_null())
+    }
+
+    pub fn find_active_by_id<E>(&self, id: &str) -> Select<E>
+    where
+        E: TenantOwned + SoftDelete,
+        <E::PrimaryKey as PrimaryKeyTrait>::ValueType: From<String>,
+    {
+        self.find_by_id::<E>(id).filter(E::deleted_at().is_null())
+    }
+
+    pub fn find_deleted_by_id<E>(&self, id: &str) -> Select<E>
+    where
+        E: TenantOwned + SoftDelete,
+        <E::PrimaryKey as PrimaryKeyTrait>::ValueType: From<String>,
+    {
+        self.find_by_id::<E>(id)
+            .filter(E::deleted_at().is_not_null())
+    }
+
+    pub fn update_many<E: TenantOwned>(&self) -> UpdateMany<E> {
+        E::update_many().filter(E::tenant_id().eq(&self.id))
+    }
+
+    pub fn delete_many<E: TenantOwned>(&self) -> DeleteMany<E> {
+        E::delete_many().filter(E::tenant_id().eq(&self.id))
+    }
+
+    /// Full-text search limited to this company's documents.
+    pub async fn search<T>(
+        &self,
+        index: &Index,
+        query: &str,
+    ) -> Result<Vec<T>, meilisearch_sdk::errors::Error>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        self.search_where(index, query, &[]).await
+    }
+
+    /// Like `search`, further narrowed to documents whose attributes equal
+    /// the given values. The attributes must be filterable on the index.
+    pub async fn search_where<T>(
+        &self,
+        index: &Index,
+        query: &str,
+        filters: &[(&str, &str)],
+    ) -> Result<Vec<T>, meilisearch_sdk::errors::Error>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let filter = self.filter_with(filters);
+        let results = index
+            .search()
+            .with_query(query)
+            .with_filter(&filter)
+            .execute::<T>()
+            .await?;
+        Ok(results.hits.into_iter().map(|hit| hit.result).collect())
+    }
+
+    fn filter_with(&self, filters: &[(&str, &str)]) -> String {
+        filters
+            .iter()
+            .fold(self.search_filter.clone(), |filter, (attribute, value)| {
+                format!(
+                    "{filter} AND {attribute} = {}",
+                    serde_json::Value::from(*value)
+                )
+            })
+    }
+}
+
+impl FromRequest for Tenant {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let tenant = req
+            .extensions()
+            .get::<Claims>()
+            .map(|claims| Tenant::new(claims.tenant_id.clone()))
+            .ok_or_else(|| ApiError::Unauthorized("Authentication required".to_string()).into());
+        ready(tenant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use entity::inventory;
+    use sea_orm::{DbBackend, QueryTrait};
+
+    #[test]
+    fn queries_are_limited_to_the_tenant() {
+        let tenant = Tenant::new("company-a");
+        let sql = tenant
+            .find_active_by_id::<inventory::Entity>("item-1")
+            .build(DbBackend::MySql)
+            .to_string();
+
+        assert!(sql.contains("`inventory`.`tenant_id` = 'company-a'"));
+        assert!(sql.contains("`inventory`.`id` = 'item-1'"));
+        assert!(sql.contains("`inventory`.`deleted_at` IS NULL"));
+
+        let sql = tenant
+            .update_many::<inventory::Entity>()
+            .build(DbBackend::MySql)
+            .to_string();
+        assert!(sql.contains("WHERE `inventory`.`tenant_id` = 'company-a'"));
+    }
+
+    #[test]
+    fn search_filter_quotes_the_tenant() {
+        let tenant = Tenant::new(r#"a" OR tenant_id = "b"#);
+        assert_eq!(
+            tenant.search_filter,
+            r#"tenant_id = "a\" OR tenant_id = \"b""#
+        );
+        assert_eq!(
+            Tenant::new("a").filter_with(&[("department_id", r#"d" OR 1"#)]),
+            r#"tenant_id = "a" AND department_id = "d\" OR 1""#
+        );
+    }
+}
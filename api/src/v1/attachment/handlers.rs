@@ -0,0 +1,407 @@
+use actix_multipart::{Multipart, MultipartError};
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use chrono::Utc;
+use config::file_storage::FileStorage;
+use futures_util::StreamExt;
+use sea_orm::{ConnectionTrait, TransactionTrait};
+use serde_json::json;
+
+use super::models::{
+    AttachmentWithUrls, SignedQuery, Upload, UploadAttachment, ATTACHMENT_TYPES,
+    MAX_ATTACHMENT_BYTES,
+};
+use super::services::{self, storage_error, DOWNLOAD, THUMBNAIL};
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::middlewares::jwt::Claims;
+use crate::tenant::Tenant;
+use crate::v1::auth::models::Caller;
+use crate::v1::auth::services as auth_services;
+use entity::attachment::AttachedTo;
+use entity::audit_log::AuditAction;
+use entity::{employee, inventory, order};
+
+/// Orders and inventory items are open to the whole company; an employee's
+/// documents only to them and their manager.
+async fn check_record<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    caller: &Caller,
+    attached_to: AttachedTo,
+    id: &str,
+) -> Result<(), ApiError> {
+    match attached_to {
+        AttachedTo::Order => {
+            tenant
+                .find_active_by_id::<order::Entity>(id)
+                .one(db)
+                .await?
+                .ok_or_else(|| ApiError::NotFound("Order not found".to_string()))?;
+        }
+        AttachedTo::Inventory => {
+            tenant
+                .find_active_by_id::<inventory::Entity>(id)
+                .one(db)
+                .await?
+                .ok_or_else(|| ApiError::NotFound("Inventory item not found".to_string()))?;
+        }
+        AttachedTo::Employee => {
+            let employee = tenant
+                .find_active_by_id::<employee::Entity>(id)
+                .one(db)
+                .await?
+                .ok_or_else(|| ApiError::NotFound("Employee not found".to_string()))?;
+            if !caller.is(&employee.id) && !caller.manages(&employee) {
+                return Err(ApiError::Forbidden(
+                    "Only the employee or their manager can see their documents".to_string(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn base_url(req: &HttpRequest) -> String {
+    let info = req.connection_info();
+    format!("{}://{}", info.scheme(), info.host())
+}
+
+fn invalid_form(err: MultipartError) -> ApiError {
+    ApiError::ValidationError(format!("Could not read the form: {err}"))
+}
+
+/// The `file` field of the form, checked against the size and type limits.
+async fn read_upload(mut form: Multipart) -> Result<Upload, ApiError> {
+    while let Some(field) = form.next().await {
+        let mut field = field.map_err(invalid_form)?;
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let content_type = field
+            .content_type()
+            .map(|mime| mime.essence_str().to_ascii_lowercase())
+            .unwrap_or_default();
+        if !ATTACHMENT_TYPES.contains(&content_type.as_str()) {
+            return Err(ApiError::ValidationError(format!(
+                "Files of type '{content_type}' cannot be attached"
+            )));
+        }
+        // Some browsers send the whole path of the file
+        let file_name = field
+            .content_disposition()
+            .and_then(|disposition| disposition.get_filename())
+            .and_then(|name| name.rsplit(['/', '\\']).next())
+            .map(|name| name.trim().chars().take(255).collect::<String>())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "attachment".to_string());
+
+        let mut content = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(invalid_form)?;
+            if content.len() + chunk.len() > MAX_ATTACHMENT_BYTES {
+                return Err(ApiError::PayloadTooLarge(
+                    "Files can be up to 15 MB".to_string(),
+                ));
+            }
+            content.extend_from_slice(&chunk);
+        }
+        if content.is_empty() {
+            return Err(ApiError::ValidationError("The file is empty".to_string()));
+        }
+        return Ok(Upload {
+            file_name,
+            content_type,
+            content,
+        });
+    }
+    Err(ApiError::ValidationError(
+        "Send the file in a form field named 'file'".to_string(),
+    ))
+}
+
+/// List the files attached to a record
+#[utoipa::path(
+    get,
+    path = "/v1/{entity}/{id}/attachments",
+    tag = "attachment",
+    params(
+        ("entity" = AttachedTo, Path, description = "Kind of record"),
+        ("id" = String, Path, description = "Record ID")
+    ),
+    responses(
+        (status = 200, description = "Attachments with download links", body = Vec<AttachmentWithUrls>),
+        (status = 403, description = "Not allowed to see the record's documents"),
+        (status = 404, description = "Record not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_attachments(
+    req: HttpRequest,
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    claims: web::ReqData<Claims>,
+    path: web::Path<(AttachedTo, String)>,
+) -> Result<HttpResponse, ApiError> {
+    let (attached_to, id) = path.into_inner();
+    let caller = auth_services::caller(&data.db, &tenant, &claims).await?;
+    check_record(&data.db, &tenant, &caller, attached_to, &id).await?;
+
+    let base_url = base_url(&req);
+    let now = Utc::now();
+    let attachments: Vec<AttachmentWithUrls> =
+        services::attachments(&data.db, &tenant, attached_to, &id)
+            .await?
+            .into_iter()
+            .map(|attachment| services::with_urls(&base_url, &data.jwt_secret, attachment, now))
+            .collect();
+    Ok(HttpResponse::Ok().json(attachments))
+}
+
+/// Attach a file to a record, uploaded as the `file` field of a form
+#[utoipa::path(
+    post,
+    path = "/v1/{entity}/{id}/attachments",
+    tag = "attachment",
+    params(
+        ("entity" = AttachedTo, Path, description = "Kind of record"),
+        ("id" = String, Path, description = "Record ID")
+    ),
+    request_body(content = UploadAttachment, content_type = "multipart/form-data", description = "PDF, image, text, CSV, Word or Excel file up to 15 MB"),
+    responses(
+        (status = 201, description = "File attached", body = AttachmentWithUrls),
+        (status = 200, description = "The record already had the file", body = AttachmentWithUrls),
+        (status = 400, description = "Missing, empty or unsupported file"),
+        (status = 403, description = "Not allowed to see the record's documents"),
+        (status = 404, description = "Record not found"),
+        (status = 413, description = "File too large"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_attachment(
+    req: HttpRequest,
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    storage: web::Data<dyn FileStorage>,
+    path: web::Path<(AttachedTo, String)>,
+    form: Multipart,
+) -> Result<HttpResponse, ApiError> {
+    let (attached_to, id) = path.into_inner();
+    let upload = read_upload(form).await?;
+
+    let txn = data.db.begin().await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    check_record(&txn, &tenant, &caller, attached_to, &id).await?;
+    let (attachment, created) = services::upload(
+        &txn,
+        storage.as_ref(),
+        &tenant,
+        &caller.user_id,
+        attached_to,
+        &id,
+        upload,
+    )
+    .await?;
+    if created {
+        audit
+            .record(
+                &txn,
+                "attachment",
+                &attachment.id,
+                AuditAction::Create,
+                None,
+                Some(&attachment),
+            )
+            .await?;
+    }
+    txn.commit().await?;
+
+    let attachment = services::with_urls(&base_url(&req), &data.jwt_secret, attachment, Utc::now());
+    if created {
+        Ok(HttpResponse::Created().json(attachment))
+    } else {
+        Ok(HttpResponse::Ok().json(attachment))
+    }
+}
+
+/// Get an attachment with fresh download links
+#[utoipa::path(
+    get,
+    path = "/v1/{entity}/{id}/attachments/{attachment_id}",
+    tag = "attachment",
+    params(
+        ("entity" = AttachedTo, Path, description = "Kind of record"),
+        ("id" = String, Path, description = "Record ID"),
+        ("attachment_id" = String, Path, description = "Attachment ID")
+    ),
+    responses(
+        (status = 200, description = "Attachment found", body = AttachmentWithUrls),
+        (status = 403, description = "Not allowed to see the record's documents"),
+        (status = 404, description = "Record or attachment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_attachment_by_id(
+    req: HttpRequest,
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    claims: web::ReqData<Claims>,
+    path: web::Path<(AttachedTo, String, String)>,
+) -> Result<HttpResponse, ApiError> {
+    let (attached_to, id, attachment_id) = path.into_inner();
+    let caller = auth_services::caller(&data.db, &tenant, &claims).await?;
+    check_record(&data.db, &tenant, &caller, attached_to, &id).await?;
+    let attachment =
+        services::find_attachment(&data.db, &tenant, attached_to, &id, &attachment_id).await?;
+
+    Ok(HttpResponse::Ok().json(services::with_urls(
+        &base_url(&req),
+        &data.jwt_secret,
+        attachment,
+        Utc::now(),
+    )))
+}
+
+/// Remove a file from a record
+#[utoipa::path(
+    delete,
+    path = "/v1/{entity}/{id}/attachments/{attachment_id}",
+    tag = "attachment",
+    params(
+        ("entity" = AttachedTo, Path, description = "Kind of record"),
+        ("id" = String, Path, description = "Record ID"),
+        ("attachment_id" = String, Path, description = "Attachment ID")
+    ),
+    responses(
+        (status = 200, description = "Attachment deleted"),
+        (status = 403, description = "Not allowed to see the record's documents"),
+        (status = 404, description = "Record or attachment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_attachment(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    storage: web::Data<dyn FileStorage>,
+    path: web::Path<(AttachedTo, String, String)>,
+) -> Result<HttpResponse, ApiError> {
+    let (attached_to, id, attachment_id) = path.into_inner();
+    let txn = data.db.begin().await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    check_record(&txn, &tenant, &caller, attached_to, &id).await?;
+    let attachment =
+        services::find_attachment(&txn, &tenant, attached_to, &id, &attachment_id).await?;
+
+    let unused = services::delete(&txn, &tenant, attachment.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "attachment",
+            &attachment.id,
+            AuditAction::Delete,
+            Some(&attachment),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+    // A file left behind only costs space
+    for key in unused {
+        if let Err(err) = storage.delete(&key).await {
+            log::warn!("Could not delete attachment file {}: {:?}", key, err);
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Attachment deleted successfully"})))
+}
+
+/// Download an attached file through a signed link
+#[utoipa::path(
+    get,
+    path = "/v1/attachment/{id}/download",
+    tag = "attachment",
+    params(
+        ("id" = String, Path, description = "Attachment ID"),
+        SignedQuery
+    ),
+    responses(
+        (status = 200, description = "The file as uploaded", body = Vec<u8>),
+        (status = 403, description = "Invalid or expired link"),
+        (status = 404, description = "Attachment not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn download_attachment(
+    data: web::Data<config::app::AppState>,
+    storage: web::Data<dyn FileStorage>,
+    id: web::Path<String>,
+    query: web::Query<SignedQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let attachment =
+        services::find_signed(&data.db, &data.jwt_secret, &id, DOWNLOAD, &query).await?;
+    let content = storage
+        .get(&attachment.storage_key)
+        .await
+        .map_err(storage_error)?
+        .ok_or_else(|| ApiError::NotFound("Attachment not found".to_string()))?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(attachment.content_type)
+        .insert_header(header::ContentDisposition::attachment(attachment.file_name))
+        .insert_header((header::CACHE_CONTROL, "private, max-age=300"))
+        .body(content))
+}
+
+/// Download the thumbnail of an attached image through a signed link
+#[utoipa::path(
+    get,
+    path = "/v1/attachment/{id}/thumbnail",
+    tag = "attachment",
+    params(
+        ("id" = String, Path, description = "Attachment ID"),
+        SignedQuery
+    ),
+    responses(
+        (status = 200, description = "PNG thumbnail", content_type = "image/png", body = Vec<u8>),
+        (status = 403, description = "Invalid or expired link"),
+        (status = 404, description = "Attachment or thumbnail not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn download_thumbnail(
+    data: web::Data<config::app::AppState>,
+    storage: web::Data<dyn FileStorage>,
+    id: web::Path<String>,
+    query: web::Query<SignedQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let attachment =
+        services::find_signed(&data.db, &data.jwt_secret, &id, THUMBNAIL, &query).await?;
+    let not_found = || ApiError::NotFound("Thumbnail not found".to_string());
+    let key = attachment.thumbnail_key.ok_or_else(not_found)?;
+    let content = storage
+        .get(&key)
+        .await
+        .map_err(storage_error)?
+        .ok_or_else(not_found)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("image/png")
+        .insert_header((header::CACHE_CONTROL, "private, max-age=300"))
+        .body(content))
+}
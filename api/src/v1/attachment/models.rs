@@ -0,0 +1,60 @@
+use chrono::NaiveDateTime;
+use entity::attachment;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+pub type Attachment = attachment::Model;
+
+/// Largest file accepted, leaving room for the multipart framing within the
+/// request body limit.
+pub const MAX_ATTACHMENT_BYTES: usize = 15 * 1024 * 1024;
+
+/// Content types a file can be attached as.
+pub const ATTACHMENT_TYPES: &[&str] = &[
+    "application/pdf",
+    "image/jpeg",
+    "image/png",
+    "image/webp",
+    "text/plain",
+    "text/csv",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+];
+
+/// Images get a thumbnail fitting in a square this many pixels wide.
+pub const THUMBNAIL_SIZE: u32 = 256;
+
+/// How long a download link works.
+pub const URL_LIFETIME_SECONDS: i64 = 15 * 60;
+
+/// Multipart form an attachment is uploaded as.
+#[derive(ToSchema)]
+pub struct UploadAttachment {
+    /// The file, with its name and content type.
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
+/// A file read from an upload.
+pub struct Upload {
+    pub file_name: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+/// An attachment with links to download it, valid until `urls_expire_at`.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AttachmentWithUrls {
+    #[serde(flatten)]
+    pub attachment: Attachment,
+    pub url: String,
+    pub thumbnail_url: Option<String>,
+    pub urls_expire_at: NaiveDateTime,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct SignedQuery {
+    /// Unix time the link stops working.
+    pub expires: i64,
+    pub signature: String,
+}
@@ -0,0 +1,37 @@
+use super::handlers;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+/// Must be configured before the scopes of the records files are attached
+/// to, which would otherwise take their `/attachments` paths.
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/{entity}/{id}/attachments")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .route("", web::get().to(handlers::get_attachments))
+            .route("", web::post().to(handlers::upload_attachment))
+            .route(
+                "/{attachment_id}",
+                web::get().to(handlers::get_attachment_by_id),
+            )
+            .route(
+                "/{attachment_id}",
+                web::delete().to(handlers::delete_attachment),
+            ),
+    );
+    // Signed links work without a token
+    cfg.service(
+        web::scope("/v1/attachment")
+            .route(
+                "/{id}/download",
+                web::get().to(handlers::download_attachment),
+            )
+            .route(
+                "/{id}/thumbnail",
+                web::get().to(handlers::download_thumbnail),
+            ),
+    );
+}
@@ -0,0 +1,235 @@
+use std::io::Cursor;
+
+use actix_web::web;
+use chrono::{DateTime, Utc};
+use config::file_storage::FileStorage;
+use hmac::{Hmac, Mac};
+use image::ImageFormat;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, ModelTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::models::{
+    Attachment, AttachmentWithUrls, SignedQuery, Upload, THUMBNAIL_SIZE, URL_LIFETIME_SECONDS,
+};
+use crate::error::ApiError;
+use crate::shared::db_utils::conflict_on_duplicate;
+use crate::tenant::Tenant;
+use entity::attachment::{self, AttachedTo};
+
+/// Path segment of the link to the file itself.
+pub const DOWNLOAD: &str = "download";
+/// Path segment of the link to an image's thumbnail.
+pub const THUMBNAIL: &str = "thumbnail";
+
+pub fn storage_error(err: std::io::Error) -> ApiError {
+    log::error!("File storage failed: {:?}", err);
+    ApiError::InternalServerError
+}
+
+pub async fn attachments<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    attached_to: AttachedTo,
+    entity_id: &str,
+) -> Result<Vec<Attachment>, ApiError> {
+    Ok(tenant
+        .find::<attachment::Entity>()
+        .filter(attachment::Column::EntityType.eq(attached_to))
+        .filter(attachment::Column::EntityId.eq(entity_id))
+        .order_by_asc(attachment::Column::CreatedAt)
+        .order_by_asc(attachment::Column::Id)
+        .all(db)
+        .await?)
+}
+
+pub async fn find_attachment<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    attached_to: AttachedTo,
+    entity_id: &str,
+    id: &str,
+) -> Result<Attachment, ApiError> {
+    tenant
+        .find_by_id::<attachment::Entity>(id)
+        .filter(attachment::Column::EntityType.eq(attached_to))
+        .filter(attachment::Column::EntityId.eq(entity_id))
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Attachment not found".to_string()))
+}
+
+/// A PNG no larger than `THUMBNAIL_SIZE` either way.
+fn thumbnail(content: &[u8]) -> Result<Vec<u8>, image::ImageError> {
+    let image = image::load_from_memory(content)?.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+    let mut png = Vec::new();
+    image.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+    Ok(png)
+}
+
+/// Attaches `upload` to the record, storing its content unless the company
+/// already has a file with the same checksum. Uploading a file the record
+/// already has returns that attachment instead, with `false`.
+pub async fn upload<C: ConnectionTrait>(
+    db: &C,
+    storage: &dyn FileStorage,
+    tenant: &Tenant,
+    uploaded_by: &str,
+    attached_to: AttachedTo,
+    entity_id: &str,
+    upload: Upload,
+) -> Result<(Attachment, bool), ApiError> {
+    let checksum = hex::encode(Sha256::digest(&upload.content));
+    // Locked, the range included when there are none, so a delete cannot
+    // remove the stored file while it is shared again, nor a second upload
+    // store it alongside
+    let same_file = tenant
+        .find::<attachment::Entity>()
+        .filter(attachment::Column::Checksum.eq(&checksum))
+        .lock_exclusive()
+        .all(db)
+        .await?;
+    if let Some(existing) = same_file
+        .iter()
+        .find(|stored| stored.entity_type == attached_to && stored.entity_id == entity_id)
+    {
+        return Ok((existing.clone(), false));
+    }
+
+    let (storage_key, thumbnail_key) = match same_file.into_iter().next() {
+        Some(stored) => (stored.storage_key, stored.thumbnail_key),
+        None => {
+            let storage_key = format!("{}/attachments/{checksum}", tenant.id());
+            let thumbnail_key = if upload.content_type.starts_with("image/") {
+                let content = upload.content.clone();
+                let png = web::block(move || thumbnail(&content))
+                    .await
+                    .map_err(|_| ApiError::InternalServerError)?
+                    .map_err(|_| {
+                        ApiError::ValidationError("The file is not a readable image".to_string())
+                    })?;
+                let key = format!("{storage_key}.thumbnail.png");
+                storage.put(&key, &png).await.map_err(storage_error)?;
+                Some(key)
+            } else {
+                None
+            };
+            storage
+                .put(&storage_key, &upload.content)
+                .await
+                .map_err(storage_error)?;
+            (storage_key, thumbnail_key)
+        }
+    };
+
+    let attachment = attachment::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        entity_type: Set(attached_to),
+        entity_id: Set(entity_id.to_string()),
+        file_name: Set(upload.file_name),
+        content_type: Set(upload.content_type),
+        size: Set(upload.content.len() as i32),
+        checksum: Set(checksum),
+        storage_key: Set(storage_key),
+        thumbnail_key: Set(thumbnail_key),
+        uploaded_by: Set(uploaded_by.to_string()),
+        created_at: Set(Utc::now().naive_utc()),
+    }
+    .insert(db)
+    .await
+    .map_err(|e| conflict_on_duplicate(e, "The file is already attached"))?;
+    Ok((attachment, true))
+}
+
+/// Removes the attachment, returning the storage keys no other attachment of
+/// the company uses any more. They are for the caller to delete once the
+/// removal is committed.
+pub async fn delete<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    attachment: Attachment,
+) -> Result<Vec<String>, ApiError> {
+    // Locked so an upload cannot start sharing the file being let go of
+    let same_file = tenant
+        .find::<attachment::Entity>()
+        .filter(attachment::Column::Checksum.eq(&attachment.checksum))
+        .lock_exclusive()
+        .all(db)
+        .await?;
+    let still_used = same_file.iter().any(|other| other.id != attachment.id);
+    let keys = [
+        Some(attachment.storage_key.clone()),
+        attachment.thumbnail_key.clone(),
+    ];
+    attachment.delete(db).await?;
+
+    if still_used {
+        return Ok(Vec::new());
+    }
+    Ok(keys.into_iter().flatten().collect())
+}
+
+fn signature(secret: &str, id: &str, variant: &str, expires: i64) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("attachment:{id}:{variant}:{expires}").as_bytes());
+    mac
+}
+
+/// Links to the attachment and its thumbnail under `base_url`, working
+/// without a token until they expire.
+pub fn with_urls(
+    base_url: &str,
+    secret: &str,
+    attachment: Attachment,
+    now: DateTime<Utc>,
+) -> AttachmentWithUrls {
+    let expires = now.timestamp() + URL_LIFETIME_SECONDS;
+    let url = |variant: &str| {
+        let signature = signature(secret, &attachment.id, variant, expires);
+        format!(
+            "{base_url}/v1/attachment/{}/{variant}?expires={expires}&signature={}",
+            attachment.id,
+            hex::encode(signature.finalize().into_bytes())
+        )
+    };
+    let file_url = url(DOWNLOAD);
+    let thumbnail_url = attachment.thumbnail_key.as_ref().map(|_| url(THUMBNAIL));
+    AttachmentWithUrls {
+        url: file_url,
+        thumbnail_url,
+        urls_expire_at: DateTime::from_timestamp(expires, 0)
+            .unwrap_or(now)
+            .naive_utc(),
+        attachment,
+    }
+}
+
+/// The attachment a signed link points at. Links carry no token, so the
+/// signature stands in for the company scope.
+#[allow(clippy::disallowed_methods)]
+pub async fn find_signed<C: ConnectionTrait>(
+    db: &C,
+    secret: &str,
+    id: &str,
+    variant: &str,
+    query: &SignedQuery,
+) -> Result<Attachment, ApiError> {
+    let invalid = || ApiError::Forbidden("The link is invalid".to_string());
+    let given = hex::decode(&query.signature).map_err(|_| invalid())?;
+    signature(secret, id, variant, query.expires)
+        .verify_slice(&given)
+        .map_err(|_| invalid())?;
+    if query.expires < Utc::now().timestamp() {
+        return Err(ApiError::Forbidden("The link has expired".to_string()));
+    }
+
+    attachment::Entity::find_by_id(id.to_string())
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Attachment not found".to_string()))
+}
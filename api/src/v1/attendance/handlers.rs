@@ -0,0 +1,235 @@
+use actix_web::{web, HttpResponse};
+use sea_orm::{ConnectionTrait, QuerySelect, TransactionTrait};
+
+use super::models::{
+    Attendance, AttendanceFilter, AttendanceReport, ClockRequest, OvertimePolicy, ReportQuery,
+    UpdateOvertimePolicy,
+};
+use super::services;
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::extractors::{Admin, ValidatedJson};
+use crate::middlewares::jwt::Claims;
+use crate::tenant::Tenant;
+use crate::v1::auth::services as auth_services;
+use crate::v1::employee::models::Employee;
+use entity::audit_log::AuditAction;
+use entity::employee;
+
+/// The locked employee record of the caller.
+async fn linked_employee<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    claims: &Claims,
+) -> Result<Employee, ApiError> {
+    let caller = auth_services::caller(db, tenant, claims).await?;
+    let employee = caller.employee.ok_or_else(|| {
+        ApiError::Forbidden("This login is not linked to an employee".to_string())
+    })?;
+    tenant
+        .find_active_by_id::<employee::Entity>(&employee.id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Employee not found".to_string()))
+}
+
+/// Clock in as the employee linked to your login
+#[utoipa::path(
+    post,
+    path = "/v1/attendance/clock-in",
+    tag = "attendance",
+    request_body = ClockRequest,
+    responses(
+        (status = 201, description = "Clocked in", body = Attendance),
+        (status = 403, description = "The login is not linked to an employee"),
+        (status = 409, description = "Already clocked in"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn clock_in(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    request: ValidatedJson<ClockRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let employee = linked_employee(&txn, &tenant, &claims).await?;
+    let attendance =
+        services::clock_in(&txn, &tenant, &employee, request.into_inner().note).await?;
+    audit
+        .record(
+            &txn,
+            "attendance",
+            &attendance.id,
+            AuditAction::Create,
+            None,
+            Some(&attendance),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Created().json(attendance))
+}
+
+/// Clock out as the employee linked to your login
+#[utoipa::path(
+    post,
+    path = "/v1/attendance/clock-out",
+    tag = "attendance",
+    request_body = ClockRequest,
+    responses(
+        (status = 200, description = "Clocked out", body = Attendance),
+        (status = 403, description = "The login is not linked to an employee"),
+        (status = 409, description = "Not clocked in"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn clock_out(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    request: ValidatedJson<ClockRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let employee = linked_employee(&txn, &tenant, &claims).await?;
+    let (before, attendance) =
+        services::clock_out(&txn, &tenant, &employee, request.into_inner().note).await?;
+    audit
+        .record(
+            &txn,
+            "attendance",
+            &attendance.id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&attendance),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(attendance))
+}
+
+/// List attendance, latest first
+#[utoipa::path(
+    get,
+    path = "/v1/attendance",
+    tag = "attendance",
+    params(
+        AttendanceFilter
+    ),
+    responses(
+        (status = 200, description = "List of attendance", body = Vec<Attendance>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_attendance(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<AttendanceFilter>,
+) -> Result<HttpResponse, ApiError> {
+    let attendance = filter.select(&tenant).all(&data.db).await?;
+    Ok(HttpResponse::Ok().json(attendance))
+}
+
+/// Attendance, regular hours and overtime per employee over a period
+#[utoipa::path(
+    get,
+    path = "/v1/attendance/report",
+    tag = "attendance",
+    params(
+        ReportQuery
+    ),
+    responses(
+        (status = 200, description = "One row per active employee", body = Vec<AttendanceReport>),
+        (status = 400, description = "Invalid period"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_report(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    query: web::Query<ReportQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let report = services::report(&data.db, &tenant, &query).await?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// How overtime is counted on timesheets
+#[utoipa::path(
+    get,
+    path = "/v1/attendance/overtime-policy",
+    tag = "attendance",
+    responses(
+        (status = 200, description = "Overtime policy of the company", body = OvertimePolicy),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_overtime_policy(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+) -> Result<HttpResponse, ApiError> {
+    let policy = services::overtime_policy(&data.db, &tenant).await?;
+    Ok(HttpResponse::Ok().json(policy))
+}
+
+/// Change how overtime is counted, for timesheets submitted from now on
+#[utoipa::path(
+    put,
+    path = "/v1/attendance/overtime-policy",
+    tag = "attendance",
+    request_body = UpdateOvertimePolicy,
+    responses(
+        (status = 200, description = "Overtime policy updated", body = OvertimePolicy),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_overtime_policy(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    update: ValidatedJson<UpdateOvertimePolicy>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let (before, policy) = services::set_overtime_policy(&txn, &tenant, &update).await?;
+    let action = match before {
+        Some(_) => AuditAction::Update,
+        None => AuditAction::Create,
+    };
+    audit
+        .record(
+            &txn,
+            "overtime_policy",
+            tenant.id(),
+            action,
+            before.as_ref(),
+            Some(&policy),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(policy))
+}
@@ -0,0 +1,93 @@
+use chrono::{Duration, NaiveDate};
+use entity::{attendance, overtime_policy};
+use sea_orm::{ColumnTrait, QueryFilter, QueryOrder, Select};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::tenant::Tenant;
+
+pub type Attendance = attendance::Model;
+pub type OvertimePolicy = overtime_policy::Model;
+
+/// Sent to clock in or out, `{}` without a note.
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct ClockRequest {
+    #[validate(length(max = 1000, message = "Note must be at most 1000 characters"))]
+    #[schema(max_length = 1000)]
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct AttendanceFilter {
+    pub employee_id: Option<String>,
+    /// Only attendance clocked in on or after this date
+    pub from: Option<NaiveDate>,
+    /// Only attendance clocked in on or before this date
+    pub to: Option<NaiveDate>,
+}
+
+impl AttendanceFilter {
+    pub fn select(&self, tenant: &Tenant) -> Select<attendance::Entity> {
+        let mut query = tenant
+            .find::<attendance::Entity>()
+            .order_by_desc(attendance::Column::ClockIn)
+            .order_by_asc(attendance::Column::Id);
+        if let Some(employee_id) = &self.employee_id {
+            query = query.filter(attendance::Column::EmployeeId.eq(employee_id));
+        }
+        if let Some(from) = self.from {
+            query =
+                query.filter(attendance::Column::ClockIn.gte(from.and_time(Default::default())));
+        }
+        if let Some(to) = self.to {
+            let next_day = (to + Duration::days(1)).and_time(Default::default());
+            query = query.filter(attendance::Column::ClockIn.lt(next_day));
+        }
+        query
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateOvertimePolicy {
+    #[validate(range(min = 1.0, max = 24.0, message = "Daily hours must be 1 to 24"))]
+    pub daily_hours: f64,
+    #[validate(range(min = 1.0, max = 168.0, message = "Weekly hours must be 1 to 168"))]
+    pub weekly_hours: f64,
+    pub non_working_days: bool,
+    #[validate(range(min = 1.0, max = 5.0, message = "Multiplier must be 1 to 5"))]
+    pub multiplier: f64,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ReportQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Department ID, only its employees are reported
+    pub department: Option<String>,
+}
+
+/// What an employee worked over a period.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AttendanceReport {
+    pub employee_id: String,
+    pub name: String,
+    pub department_id: Option<String>,
+    /// Days the employee clocked in on.
+    pub days_present: u32,
+    /// Hours between clocking in and out.
+    pub clocked_hours: f64,
+    /// Regular hours on approved timesheets of weeks starting in the period.
+    pub regular_hours: f64,
+    pub overtime_hours: f64,
+    /// The approved hours by project or cost center.
+    pub projects: Vec<ProjectHours>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ProjectHours {
+    pub project_id: String,
+    pub code: String,
+    pub name: String,
+    pub hours: f64,
+}
@@ -0,0 +1,25 @@
+use super::handlers;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/attendance")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .route("", web::get().to(handlers::get_attendance))
+            .route("/clock-in", web::post().to(handlers::clock_in))
+            .route("/clock-out", web::post().to(handlers::clock_out))
+            .route("/report", web::get().to(handlers::get_report))
+            .route(
+                "/overtime-policy",
+                web::get().to(handlers::get_overtime_policy),
+            )
+            .route(
+                "/overtime-policy",
+                web::put().to(handlers::update_overtime_policy),
+            ),
+    );
+}
@@ -0,0 +1,209 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, IntoActiveModel, QueryFilter, QueryOrder, Set,
+};
+use uuid::Uuid;
+
+use super::models::{
+    Attendance, AttendanceFilter, AttendanceReport, OvertimePolicy, ProjectHours, ReportQuery,
+    UpdateOvertimePolicy,
+};
+use crate::error::{ApiError, FieldErrors};
+use crate::shared::round;
+use crate::tenant::Tenant;
+use crate::v1::employee::models::Employee;
+use entity::timesheet::TimesheetStatus;
+use entity::{attendance, employee, overtime_policy, project, timesheet, timesheet_entry};
+
+async fn open_attendance<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee_id: &str,
+) -> Result<Option<Attendance>, ApiError> {
+    Ok(tenant
+        .find::<attendance::Entity>()
+        .filter(attendance::Column::EmployeeId.eq(employee_id))
+        .filter(attendance::Column::ClockOut.is_null())
+        .one(db)
+        .await?)
+}
+
+/// Starts attendance for a locked employee who is not clocked in yet.
+pub async fn clock_in<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee: &Employee,
+    note: Option<String>,
+) -> Result<Attendance, ApiError> {
+    if let Some(open) = open_attendance(db, tenant, &employee.id).await? {
+        return Err(ApiError::Conflict(format!(
+            "Already clocked in since {}",
+            open.clock_in
+        )));
+    }
+
+    Ok(attendance::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        employee_id: Set(employee.id.clone()),
+        clock_in: Set(Utc::now().naive_utc()),
+        clock_out: Set(None),
+        note: Set(note),
+    }
+    .insert(db)
+    .await?)
+}
+
+/// Ends the attendance of a locked employee. A note replaces the one given
+/// when clocking in. Returns the attendance as it was and as it is now.
+pub async fn clock_out<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee: &Employee,
+    note: Option<String>,
+) -> Result<(Attendance, Attendance), ApiError> {
+    let open = open_attendance(db, tenant, &employee.id)
+        .await?
+        .ok_or_else(|| ApiError::Conflict("Not clocked in".to_string()))?;
+
+    let mut active = open.clone().into_active_model();
+    active.clock_out = Set(Some(Utc::now().naive_utc()));
+    if note.is_some() {
+        active.note = Set(note);
+    }
+    Ok((open, active.update(db).await?))
+}
+
+/// The overtime policy of the company, or the default when it has none.
+pub async fn overtime_policy<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+) -> Result<OvertimePolicy, ApiError> {
+    Ok(tenant
+        .find::<overtime_policy::Entity>()
+        .one(db)
+        .await?
+        .unwrap_or_else(|| OvertimePolicy::default_for(tenant.id())))
+}
+
+/// Replaces the overtime policy, used for timesheets submitted from now
+/// on. Returns the policy as it was, if the company had one.
+pub async fn set_overtime_policy<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    data: &UpdateOvertimePolicy,
+) -> Result<(Option<OvertimePolicy>, OvertimePolicy), ApiError> {
+    let existing = tenant.find::<overtime_policy::Entity>().one(db).await?;
+    let policy = overtime_policy::ActiveModel {
+        tenant_id: Set(tenant.id().to_string()),
+        daily_hours: Set(data.daily_hours),
+        weekly_hours: Set(data.weekly_hours),
+        non_working_days: Set(data.non_working_days),
+        multiplier: Set(data.multiplier),
+        updated_at: Set(Utc::now().naive_utc()),
+    };
+    let policy = if existing.is_some() {
+        policy.update(db).await?
+    } else {
+        policy.insert(db).await?
+    };
+    Ok((existing, policy))
+}
+
+/// Attendance and approved time of the active employees over a period,
+/// optionally of one department.
+pub async fn report<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    query: &ReportQuery,
+) -> Result<Vec<AttendanceReport>, ApiError> {
+    if query.to < query.from {
+        let mut errors = FieldErrors::new();
+        errors.add("to", "The period cannot end before it starts");
+        return Err(ApiError::InvalidFields(errors));
+    }
+
+    let mut employees = tenant
+        .find_active::<employee::Entity>()
+        .order_by_asc(employee::Column::Name);
+    if let Some(department_id) = &query.department {
+        employees = employees.filter(employee::Column::DepartmentId.eq(department_id));
+    }
+    let employees = employees.all(db).await?;
+    let employee_ids: Vec<&str> = employees.iter().map(|e| e.id.as_str()).collect();
+
+    let sessions = AttendanceFilter {
+        employee_id: None,
+        from: Some(query.from),
+        to: Some(query.to),
+    }
+    .select(tenant)
+    .filter(attendance::Column::EmployeeId.is_in(employee_ids.clone()))
+    .all(db)
+    .await?;
+
+    let timesheets = tenant
+        .find::<timesheet::Entity>()
+        .filter(timesheet::Column::EmployeeId.is_in(employee_ids))
+        .filter(timesheet::Column::Status.eq(TimesheetStatus::Approved))
+        .filter(timesheet::Column::WeekStart.between(query.from, query.to))
+        .all(db)
+        .await?;
+    let entries = tenant
+        .find::<timesheet_entry::Entity>()
+        .filter(timesheet_entry::Column::TimesheetId.is_in(timesheets.iter().map(|t| &t.id)))
+        .all(db)
+        .await?;
+    let projects: HashMap<String, project::Model> = tenant
+        .find::<project::Entity>()
+        .filter(project::Column::Id.is_in(entries.iter().map(|e| &e.project_id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|project| (project.id.clone(), project))
+        .collect();
+    let employee_of: HashMap<&str, &str> = timesheets
+        .iter()
+        .map(|t| (t.id.as_str(), t.employee_id.as_str()))
+        .collect();
+
+    Ok(employees
+        .iter()
+        .map(|employee| {
+            let own_sessions = sessions.iter().filter(|s| s.employee_id == employee.id);
+            let days: HashSet<_> = own_sessions.clone().map(|s| s.clock_in.date()).collect();
+            let own_timesheets = timesheets.iter().filter(|t| t.employee_id == employee.id);
+
+            let mut by_project: BTreeMap<&str, f64> = BTreeMap::new();
+            for entry in entries
+                .iter()
+                .filter(|e| employee_of.get(e.timesheet_id.as_str()) == Some(&employee.id.as_str()))
+            {
+                *by_project.entry(entry.project_id.as_str()).or_insert(0.0) += entry.hours;
+            }
+
+            AttendanceReport {
+                employee_id: employee.id.clone(),
+                name: employee.name.clone(),
+                department_id: employee.department_id.clone(),
+                days_present: days.len() as u32,
+                clocked_hours: round(own_sessions.map(|s| s.hours()).sum()),
+                regular_hours: round(own_timesheets.clone().map(|t| t.regular_hours).sum()),
+                overtime_hours: round(own_timesheets.map(|t| t.overtime_hours).sum()),
+                projects: by_project
+                    .into_iter()
+                    .filter_map(|(id, hours)| {
+                        projects.get(id).map(|project| ProjectHours {
+                            project_id: project.id.clone(),
+                            code: project.code.clone(),
+                            name: project.name.clone(),
+                            hours: round(hours),
+                        })
+                    })
+                    .collect(),
+            }
+        })
+        .collect())
+}
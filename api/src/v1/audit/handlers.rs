@@ -0,0 +1,31 @@
+use actix_web::{web, HttpResponse};
+
+use super::models::{AuditEntry, AuditFilter};
+use crate::error::ApiError;
+use crate::pagination::{paginate, Page, PageQuery};
+use crate::tenant::Tenant;
+
+/// List audit log entries, newest first
+#[utoipa::path(
+    get,
+    path = "/v1/audit",
+    tag = "audit",
+    params(AuditFilter, PageQuery),
+    responses(
+        (status = 200, description = "One page of audit entries", body = Page<AuditEntry>),
+        (status = 400, description = "Invalid query parameters"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_audit_log(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<AuditFilter>,
+    page: web::Query<PageQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let entries = paginate(&data.db, filter.select(&tenant), &page).await?;
+    Ok(HttpResponse::Ok().json(entries))
+}
@@ -0,0 +1,33 @@
+use entity::audit_log;
+use sea_orm::{ColumnTrait, QueryFilter, QueryOrder, Select};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::tenant::Tenant;
+
+pub type AuditEntry = audit_log::Model;
+
+#[derive(Deserialize, IntoParams)]
+pub struct AuditFilter {
+    /// Kind of record, e.g. `inventory` or `order`.
+    pub entity: Option<String>,
+    /// Id of the record.
+    pub id: Option<String>,
+}
+
+impl AuditFilter {
+    /// Matching entries, newest first.
+    pub fn select(&self, tenant: &Tenant) -> Select<audit_log::Entity> {
+        let mut query = tenant
+            .find::<audit_log::Entity>()
+            .order_by_desc(audit_log::Column::CreatedAt)
+            .order_by_desc(audit_log::Column::Id);
+        if let Some(entity) = &self.entity {
+            query = query.filter(audit_log::Column::Entity.eq(entity));
+        }
+        if let Some(id) = &self.id {
+            query = query.filter(audit_log::Column::EntityId.eq(id));
+        }
+        query
+    }
+}
@@ -0,0 +1,13 @@
+use super::handlers;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+/// Read-only on purpose: audit entries are never changed or removed.
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/audit")
+            .wrap(jwt_middleware)
+            .route("", web::get().to(handlers::get_audit_log)),
+    );
+}
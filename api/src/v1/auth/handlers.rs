@@ -1,18 +1,30 @@
+// Users are global: they choose a tenant rather than belong to one
+#![allow(clippy::disallowed_methods)]
+
+use crate::audit::AuditContext;
 use crate::error::ApiError;
+use crate::extractors::ValidatedJson;
 use crate::middlewares::jwt::Claims;
-use crate::v1::auth::models::{LoginRequest, RefreshRequest, RegisterRequest, TokenResponse};
+use crate::tenant::Tenant;
+use crate::v1::auth::models::{
+    AcceptInviteRequest, LoginRequest, Profile, RefreshRequest, RegisterRequest,
+    SwitchCompanyRequest, TokenResponse,
+};
+use crate::v1::auth::services;
+use crate::v1::company::services as company_services;
 use actix_web::{web, HttpResponse};
 use bcrypt::{hash, verify};
 use config::app::AppState;
+use entity::audit_log::AuditAction;
 use entity::user::{self, Entity as User};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
-use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, TransactionTrait};
 use serde_json::json;
-use uuid::Uuid;
 
 #[utoipa::path(
     post,
     path = "/v1/auth/register",
+    tag = "auth",
     request_body = RegisterRequest,
     responses(
         (status = 201, description = "User registered successfully"),
@@ -23,28 +35,31 @@ use uuid::Uuid;
 )]
 pub async fn register(
     data: web::Data<AppState>,
-    req: web::Json<RegisterRequest>,
+    audit: AuditContext,
+    req: ValidatedJson<RegisterRequest>,
 ) -> Result<HttpResponse, ApiError> {
-    let db = &data.db;
     let hashed_password =
         hash(&req.password, data.bcrypt_cost).map_err(|_| ApiError::InternalServerError)?;
 
-    let new_user = user::ActiveModel {
-        id: sea_orm::ActiveValue::Set(Uuid::new_v4().to_string()),
-        username: sea_orm::ActiveValue::Set(req.username.clone()),
-        password: sea_orm::ActiveValue::Set(hashed_password),
-        created_at: sea_orm::ActiveValue::Set(chrono::Utc::now()),
-        updated_at: sea_orm::ActiveValue::Set(chrono::Utc::now()),
-    };
+    let txn = data.db.begin().await?;
+    let inserted_user = services::create_user(&txn, &req.username, hashed_password, None).await?;
+    let company_name = req.company.as_deref().unwrap_or(&req.username);
+    let company = company_services::create_company(&txn, company_name, &inserted_user.id).await?;
 
-    User::insert(new_user).exec(db).await.map_err(|db_err| {
-        if let DbErr::Exec(ref err_msg) = db_err {
-            if err_msg.to_string().contains("Duplicate entry") {
-                return ApiError::Conflict("User already exists".to_string());
-            }
-        }
-        ApiError::DatabaseError(db_err)
-    })?;
+    // Nobody is logged in yet, the new user registered themselves
+    audit
+        .with_actor(&inserted_user.id)
+        .with_tenant(&company.id)
+        .record(
+            &txn,
+            "user",
+            &inserted_user.id,
+            AuditAction::Create,
+            None,
+            Some(&inserted_user),
+        )
+        .await?;
+    txn.commit().await?;
 
     Ok(HttpResponse::Created().finish())
 }
@@ -52,6 +67,7 @@ pub async fn register(
 #[utoipa::path(
     post,
     path = "/v1/auth/login",
+    tag = "auth",
     request_body = LoginRequest,
     responses(
         (status = 200, description = "Login successful", body = TokenResponse),
@@ -88,47 +104,84 @@ pub async fn login(
         ));
     }
 
-    let exp = chrono::Utc::now()
-        .checked_add_signed(chrono::Duration::seconds(
-            data.jwt_expires_in_seconds as i64,
-        ))
-        .ok_or(ApiError::InternalServerError)?
-        .timestamp();
-
-    let claims = Claims {
-        sub: user.id.clone(),
-        exp: exp as usize,
-    };
-
-    let token = encode(
-        &Header::new(data.jwt_algorithm),
-        &claims,
-        &EncodingKey::from_secret(data.jwt_secret.as_ref()),
-    )
-    .map_err(|_| ApiError::InternalServerError)?;
+    // Memberships are disabled where the user's employee was let go
+    let tenant_id = company_services::default_company(db, &user.id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::Unauthorized("This login is not active in any company".to_string())
+        })?;
 
-    Ok(HttpResponse::Ok().json(TokenResponse { token }))
+    Ok(HttpResponse::Ok().json(issue_token(&data, &user.id, &tenant_id)?))
 }
 
 #[utoipa::path(
     get,
     path = "/v1/auth/me",
+    tag = "auth",
     security(
-        ("bearer_auth" = [])
+        ("bearerAuth" = [])
     ),
     responses(
-        (status = 200, description = "Authenticated user data", body = Claims)
+        (status = 200, description = "The user with their employee record", body = Profile)
+    )
+)]
+pub async fn me(
+    data: web::Data<AppState>,
+    tenant: Tenant,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, ApiError> {
+    let profile = services::profile(&data.db, &tenant, claims.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(profile))
+}
+
+/// Create the login an employee was invited to
+#[utoipa::path(
+    post,
+    path = "/v1/auth/accept-invite",
+    tag = "auth",
+    request_body = AcceptInviteRequest,
+    responses(
+        (status = 200, description = "Login created, signed in to the employee's company", body = TokenResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Invitation invalid, used or expired"),
+        (status = 409, description = "Username taken or employee already has a login"),
+        (status = 500, description = "Internal Server Error")
     )
 )]
-pub async fn me(claims: web::ReqData<Claims>) -> Result<HttpResponse, ApiError> {
-    Ok(HttpResponse::Ok().json(claims.into_inner()))
+pub async fn accept_invite(
+    data: web::Data<AppState>,
+    audit: AuditContext,
+    req: ValidatedJson<AcceptInviteRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let hashed_password =
+        hash(&req.password, data.bcrypt_cost).map_err(|_| ApiError::InternalServerError)?;
+
+    let txn = data.db.begin().await?;
+    let (user, tenant_id) =
+        services::accept_invite(&txn, &req.token, &req.username, hashed_password).await?;
+    audit
+        .with_actor(&user.id)
+        .with_tenant(&tenant_id)
+        .record(
+            &txn,
+            "user",
+            &user.id,
+            AuditAction::Create,
+            None,
+            Some(&user),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(issue_token(&data, &user.id, &tenant_id)?))
 }
 
 #[utoipa::path(
     post,
     path = "/v1/auth/logout",
+    tag = "auth",
     security(
-        ("bearer_auth" = [])
+        ("bearerAuth" = [])
     ),
     responses(
         (status = 200, description = "Logout successful"),
@@ -147,8 +200,9 @@ pub async fn logout(
 #[utoipa::path(
     post,
     path = "/v1/auth/logout-all",
+    tag = "auth",
     security(
-        ("bearer_auth" = [])
+        ("bearerAuth" = [])
     ),
     responses(
         (status = 200, description = "All sessions logged out successfully"),
@@ -166,6 +220,7 @@ pub async fn logout_all(
 #[utoipa::path(
     post,
     path = "/v1/auth/refresh",
+    tag = "auth",
     request_body = RefreshRequest,
     responses(
         (status = 200, description = "Token refreshed successfully", body = TokenResponse),
@@ -195,7 +250,46 @@ pub async fn refresh(
         .await?
         .ok_or_else(|| ApiError::Unauthorized("User not found".to_string()))?;
 
-    // Generate new token
+    // The token keeps its company as long as the user still works in it
+    if !company_services::is_member(&data.db, &claims.tenant_id, &user.id).await? {
+        return Err(ApiError::Unauthorized(
+            "User no longer belongs to this company".to_string(),
+        ));
+    }
+
+    Ok(HttpResponse::Ok().json(issue_token(&data, &user.id, &claims.tenant_id)?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/auth/switch-company",
+    tag = "auth",
+    request_body = SwitchCompanyRequest,
+    security(
+        ("bearerAuth" = [])
+    ),
+    responses(
+        (status = 200, description = "Token for the other company", body = TokenResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Caller does not belong to the company")
+    )
+)]
+pub async fn switch_company(
+    data: web::Data<AppState>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<SwitchCompanyRequest>,
+) -> Result<HttpResponse, ApiError> {
+    if !company_services::is_member(&data.db, &req.company_id, &claims.sub).await? {
+        return Err(ApiError::Forbidden(
+            "You do not belong to this company".to_string(),
+        ));
+    }
+
+    Ok(HttpResponse::Ok().json(issue_token(&data, &claims.sub, &req.company_id)?))
+}
+
+/// Signs a token for `user_id` working in `tenant_id`.
+fn issue_token(data: &AppState, user_id: &str, tenant_id: &str) -> Result<TokenResponse, ApiError> {
     let exp = chrono::Utc::now()
         .checked_add_signed(chrono::Duration::seconds(
             data.jwt_expires_in_seconds as i64,
@@ -203,17 +297,18 @@ pub async fn refresh(
         .ok_or(ApiError::InternalServerError)?
         .timestamp();
 
-    let new_claims = Claims {
-        sub: user.id.clone(),
+    let claims = Claims {
+        sub: user_id.to_string(),
+        tenant_id: tenant_id.to_string(),
         exp: exp as usize,
     };
 
-    let new_token = encode(
+    let token = encode(
         &Header::new(data.jwt_algorithm),
-        &new_claims,
+        &claims,
         &EncodingKey::from_secret(data.jwt_secret.as_ref()),
     )
     .map_err(|_| ApiError::InternalServerError)?;
 
-    Ok(HttpResponse::Ok().json(TokenResponse { token: new_token }))
+    Ok(TokenResponse { token })
 }
@@ -1,5 +1,11 @@
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::middlewares::jwt::Claims;
+use crate::v1::department::models::Department;
+use crate::v1::employee::models::Employee;
+use crate::v1::position::models::Position;
 
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct LoginRequest {
@@ -7,10 +13,19 @@ pub struct LoginRequest {
     pub password: String,
 }
 
-#[derive(Serialize, Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
 pub struct RegisterRequest {
+    #[validate(length(min = 3, max = 255, message = "Username must be 3 to 255 characters"))]
+    #[schema(min_length = 3, max_length = 255)]
     pub username: String,
+    /// bcrypt only uses the first 72 bytes.
+    #[validate(length(min = 8, max = 72, message = "Password must be 8 to 72 characters"))]
+    #[schema(min_length = 8, max_length = 72)]
     pub password: String,
+    /// Name of the company created for the new user, defaults to the username.
+    #[validate(length(min = 1, max = 255, message = "Company must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub company: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -22,3 +37,76 @@ pub struct TokenResponse {
 pub struct RefreshRequest {
     pub refresh_token: String,
 }
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SwitchCompanyRequest {
+    pub company_id: String,
+}
+
+/// Granted to users marked as administrators.
+pub const ROLE_ADMIN: &str = "admin";
+/// Granted to users linked to an employee of the current company.
+pub const ROLE_EMPLOYEE: &str = "employee";
+/// Granted to employees others report to.
+pub const ROLE_MANAGER: &str = "manager";
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct AcceptInviteRequest {
+    /// Token from the invitation.
+    pub token: String,
+    #[validate(length(min = 3, max = 255, message = "Username must be 3 to 255 characters"))]
+    #[schema(min_length = 3, max_length = 255)]
+    pub username: String,
+    #[validate(length(min = 8, max = 72, message = "Password must be 8 to 72 characters"))]
+    #[schema(min_length = 8, max_length = 72)]
+    pub password: String,
+}
+
+/// The signed-in user as returned by `GET /v1/auth/me`.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct Profile {
+    #[serde(flatten)]
+    pub claims: Claims,
+    pub username: String,
+    /// Whether the user administers the current company.
+    pub is_admin: bool,
+    /// Employee record of the user in the current company, if linked.
+    pub employee: Option<EmployeeProfile>,
+    /// What the user may do: `admin`, `employee` and `manager`.
+    pub roles: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct EmployeeProfile {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    /// Job role, as recorded on the employee.
+    pub role: String,
+    pub department: Option<Department>,
+    pub position: Option<Position>,
+    pub manager_id: Option<String>,
+}
+
+/// The signed-in user with their employee record in the current company,
+/// for workflows where managers decide on what their reports ask for.
+pub struct Caller {
+    pub user_id: String,
+    pub is_admin: bool,
+    pub employee: Option<Employee>,
+}
+
+impl Caller {
+    pub fn is(&self, employee_id: &str) -> bool {
+        self.employee.as_ref().is_some_and(|e| e.id == employee_id)
+    }
+
+    /// Administrators act for every manager.
+    pub fn manages(&self, employee: &Employee) -> bool {
+        self.is_admin
+            || self
+                .employee
+                .as_ref()
+                .is_some_and(|e| employee.manager_id.as_deref() == Some(e.id.as_str()))
+    }
+}
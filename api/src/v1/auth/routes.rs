@@ -9,6 +9,7 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
             .route("/register", web::post().to(handlers::register))
             .route("/login", web::post().to(handlers::login))
             .route("/refresh", web::post().to(handlers::refresh))
+            .route("/accept-invite", web::post().to(handlers::accept_invite))
             .service(
                 web::resource("/me")
                     .wrap(jwt_middleware.clone())
@@ -19,6 +20,11 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
                     .wrap(jwt_middleware.clone())
                     .route(web::post().to(handlers::logout)),
             )
+            .service(
+                web::resource("/switch-company")
+                    .wrap(jwt_middleware.clone())
+                    .route(web::post().to(handlers::switch_company)),
+            )
             .service(
                 web::resource("/logout-all")
                     .wrap(jwt_middleware)
@@ -0,0 +1,258 @@
+// Users are global: they choose a tenant rather than belong to one
+#![allow(clippy::disallowed_methods)]
+
+use chrono::{Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, PaginatorTrait,
+    QueryFilter, QuerySelect, Set,
+};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::models::{Caller, EmployeeProfile, Profile, ROLE_ADMIN, ROLE_EMPLOYEE, ROLE_MANAGER};
+use crate::error::ApiError;
+use crate::middlewares::jwt::Claims;
+use crate::shared::db_utils::conflict_on_duplicate;
+use crate::tenant::Tenant;
+use crate::v1::company::services as company_services;
+use crate::v1::employee::models::Employee;
+use entity::company_user::CompanyRole;
+use entity::user::{self, Entity as User};
+use entity::{department, employee, position, user_invite};
+
+/// How long an invitation can be accepted.
+pub const INVITE_VALIDITY_DAYS: i64 = 7;
+
+pub async fn create_user<C: ConnectionTrait>(
+    db: &C,
+    username: &str,
+    password_hash: String,
+    employee_id: Option<String>,
+) -> Result<user::Model, ApiError> {
+    let now = Utc::now();
+    user::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        username: Set(username.to_string()),
+        password: Set(password_hash),
+        employee_id: Set(employee_id),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(db)
+    .await
+    .map_err(|e| conflict_on_duplicate(e, "User already exists"))
+}
+
+/// The login of an employee, if they have one.
+pub async fn login_of<C: ConnectionTrait>(
+    db: &C,
+    employee_id: &str,
+) -> Result<Option<user::Model>, ApiError> {
+    Ok(User::find()
+        .filter(user::Column::EmployeeId.eq(employee_id))
+        .one(db)
+        .await?)
+}
+
+/// Disables the login of a deleted employee in their company, or enables it
+/// again once they are restored. The login keeps working in the user's
+/// other companies.
+pub async fn set_login_active<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee_id: &str,
+    active: bool,
+) -> Result<(), ApiError> {
+    if let Some(login) = login_of(db, employee_id).await? {
+        company_services::set_member_active(db, tenant.id(), &login.id, active).await?;
+    }
+    Ok(())
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Invites an employee without a login to create one. Earlier invitations
+/// of the employee that were not accepted stop working. Returns the
+/// invitation with the token to hand to the employee, which is not stored.
+pub async fn create_invite<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee: &Employee,
+    invited_by: &str,
+) -> Result<(user_invite::Model, String), ApiError> {
+    if login_of(db, &employee.id).await?.is_some() {
+        return Err(ApiError::Conflict(
+            "Employee already has a login".to_string(),
+        ));
+    }
+
+    tenant
+        .delete_many::<user_invite::Entity>()
+        .filter(user_invite::Column::EmployeeId.eq(&employee.id))
+        .filter(user_invite::Column::AcceptedAt.is_null())
+        .exec(db)
+        .await?;
+
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let now = Utc::now().naive_utc();
+    let invite = user_invite::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        employee_id: Set(employee.id.clone()),
+        token_hash: Set(hash_token(&token)),
+        invited_by: Set(invited_by.to_string()),
+        expires_at: Set(now + Duration::days(INVITE_VALIDITY_DAYS)),
+        accepted_at: Set(None),
+        created_at: Set(now),
+    }
+    .insert(db)
+    .await?;
+
+    Ok((invite, token))
+}
+
+/// Creates the login an invitation was sent for and adds it to the
+/// company of the employee. Returns the new user and that company.
+pub async fn accept_invite<C: ConnectionTrait>(
+    db: &C,
+    token: &str,
+    username: &str,
+    password_hash: String,
+) -> Result<(user::Model, String), ApiError> {
+    let invalid = || ApiError::Unauthorized("The invitation is invalid or has expired".to_string());
+
+    // The token is the only proof of which company the caller joins
+    let invite = user_invite::Entity::find()
+        .filter(user_invite::Column::TokenHash.eq(hash_token(token)))
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or_else(invalid)?;
+    if invite.accepted_at.is_some() || invite.expires_at < Utc::now().naive_utc() {
+        return Err(invalid());
+    }
+
+    let tenant = Tenant::new(invite.tenant_id.clone());
+    let employee = tenant
+        .find_active_by_id::<employee::Entity>(&invite.employee_id)
+        .one(db)
+        .await?
+        .ok_or_else(invalid)?;
+    if login_of(db, &employee.id).await?.is_some() {
+        return Err(ApiError::Conflict(
+            "Employee already has a login".to_string(),
+        ));
+    }
+
+    let user = create_user(db, username, password_hash, Some(employee.id)).await?;
+    company_services::add_member(db, tenant.id(), &user.id, CompanyRole::Member).await?;
+
+    let mut invite = invite.into_active_model();
+    invite.accepted_at = Set(Some(Utc::now().naive_utc()));
+    invite.update(db).await?;
+
+    Ok((user, tenant.id().to_string()))
+}
+
+async fn user_and_employee<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    claims: &Claims,
+) -> Result<(user::Model, Option<Employee>), ApiError> {
+    let user = User::find_by_id(&claims.sub)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::Unauthorized("User not found".to_string()))?;
+
+    // A login belongs to one employee, shown only in that employee's company
+    let employee = match &user.employee_id {
+        Some(id) => {
+            tenant
+                .find_active_by_id::<employee::Entity>(id)
+                .one(db)
+                .await?
+        }
+        None => None,
+    };
+    Ok((user, employee))
+}
+
+/// Who is calling, for deciding what they may approve.
+pub async fn caller<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    claims: &Claims,
+) -> Result<Caller, ApiError> {
+    let (user, employee) = user_and_employee(db, tenant, claims).await?;
+    let is_admin = company_services::is_admin(db, tenant.id(), &user.id).await?;
+    Ok(Caller {
+        user_id: user.id,
+        is_admin,
+        employee,
+    })
+}
+
+/// The caller with their employee record in the company they work in.
+pub async fn profile<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    claims: Claims,
+) -> Result<Profile, ApiError> {
+    let (user, employee) = user_and_employee(db, tenant, &claims).await?;
+    let is_admin = company_services::is_admin(db, tenant.id(), &user.id).await?;
+
+    let mut roles = Vec::new();
+    if is_admin {
+        roles.push(ROLE_ADMIN.to_string());
+    }
+    let employee = match employee {
+        Some(employee) => {
+            roles.push(ROLE_EMPLOYEE.to_string());
+            let reports = tenant
+                .find_active::<employee::Entity>()
+                .filter(employee::Column::ManagerId.eq(&employee.id))
+                .count(db)
+                .await?;
+            if reports > 0 {
+                roles.push(ROLE_MANAGER.to_string());
+            }
+            Some(employee_profile(db, tenant, employee).await?)
+        }
+        None => None,
+    };
+
+    Ok(Profile {
+        claims,
+        username: user.username,
+        is_admin,
+        employee,
+        roles,
+    })
+}
+
+async fn employee_profile<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee: Employee,
+) -> Result<EmployeeProfile, ApiError> {
+    let department = match &employee.department_id {
+        Some(id) => tenant.find_by_id::<department::Entity>(id).one(db).await?,
+        None => None,
+    };
+    let position = match &employee.position_id {
+        Some(id) => tenant.find_by_id::<position::Entity>(id).one(db).await?,
+        None => None,
+    };
+
+    Ok(EmployeeProfile {
+        id: employee.id,
+        name: employee.name,
+        email: employee.email,
+        role: employee.role,
+        department,
+        position,
+        manager_id: employee.manager_id,
+    })
+}
@@ -0,0 +1,136 @@
+use actix_web::{web, HttpResponse};
+use sea_orm::{EntityTrait, TransactionTrait};
+
+use super::models::{AddMember, Company, CompanyMember, CreateCompany};
+use super::services;
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::extractors::ValidatedJson;
+use crate::middlewares::jwt::Claims;
+use entity::audit_log::AuditAction;
+use entity::user;
+
+/// List the companies the caller belongs to
+#[utoipa::path(
+    get,
+    path = "/v1/company",
+    tag = "company",
+    responses(
+        (status = 200, description = "Companies of the caller", body = Vec<Company>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_companies(
+    data: web::Data<config::app::AppState>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, ApiError> {
+    let companies = services::companies_of(&data.db, &claims.sub).await?;
+    Ok(HttpResponse::Ok().json(companies))
+}
+
+/// Create a company with the caller as its first member and administrator
+#[utoipa::path(
+    post,
+    path = "/v1/company",
+    tag = "company",
+    request_body = CreateCompany,
+    responses(
+        (status = 200, description = "Company created", body = Company),
+        (status = 400, description = "Validation error"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_company(
+    data: web::Data<config::app::AppState>,
+    claims: web::ReqData<Claims>,
+    audit: AuditContext,
+    company: ValidatedJson<CreateCompany>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let company = services::create_company(&txn, &company.name, &claims.sub).await?;
+    audit
+        .with_tenant(&company.id)
+        .record(
+            &txn,
+            "company",
+            &company.id,
+            AuditAction::Create,
+            None,
+            Some(&company),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(company))
+}
+
+/// Add a user to a company the caller administers
+#[utoipa::path(
+    post,
+    path = "/v1/company/{id}/members",
+    tag = "company",
+    params(
+        ("id" = String, Path, description = "Company ID")
+    ),
+    request_body = AddMember,
+    responses(
+        (status = 200, description = "Member added", body = CompanyMember),
+        (status = 403, description = "The caller does not administer the company"),
+        (status = 404, description = "Company or user not found"),
+        (status = 409, description = "User is already a member"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn add_member(
+    data: web::Data<config::app::AppState>,
+    claims: web::ReqData<Claims>,
+    audit: AuditContext,
+    id: web::Path<String>,
+    member: web::Json<AddMember>,
+) -> Result<HttpResponse, ApiError> {
+    let company_id = id.into_inner();
+
+    let txn = data.db.begin().await?;
+    // Companies of others are reported as missing rather than forbidden
+    if !services::is_member(&txn, &company_id, &claims.sub).await? {
+        return Err(ApiError::NotFound("Company not found".to_string()));
+    }
+    if !services::is_admin(&txn, &company_id, &claims.sub).await? {
+        return Err(ApiError::Forbidden(
+            "Only administrators can add members".to_string(),
+        ));
+    }
+    // Users are global, not company data
+    #[allow(clippy::disallowed_methods)]
+    let user = user::Entity::find_by_id(member.user_id.clone())
+        .one(&txn)
+        .await?;
+    if user.is_none() {
+        return Err(ApiError::NotFound("User not found".to_string()));
+    }
+
+    let membership = services::add_member(&txn, &company_id, &member.user_id, member.role).await?;
+    audit
+        .with_tenant(&company_id)
+        .record(
+            &txn,
+            "company_member",
+            &member.user_id,
+            AuditAction::Create,
+            None,
+            Some(&membership),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(membership))
+}
@@ -0,0 +1,23 @@
+use entity::company_user::CompanyRole;
+use entity::{company, company_user};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+pub type Company = company::Model;
+pub type CompanyMember = company_user::Model;
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateCompany {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AddMember {
+    pub user_id: String,
+    /// Defaults to `member`.
+    #[serde(default)]
+    pub role: CompanyRole,
+}
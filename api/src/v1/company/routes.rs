@@ -0,0 +1,16 @@
+use super::handlers;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/company")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .route("", web::get().to(handlers::get_companies))
+            .route("", web::post().to(handlers::create_company))
+            .route("/{id}/members", web::post().to(handlers::add_member)),
+    );
+}
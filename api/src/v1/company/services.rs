@@ -0,0 +1,156 @@
+// Companies and memberships decide which tenant a caller may work in, so
+// they are global rather than scoped by one
+#![allow(clippy::disallowed_methods)]
+
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, RelationTrait, Set,
+};
+
+use crate::error::ApiError;
+use crate::shared::db_utils::conflict_on_duplicate;
+use entity::company_user::CompanyRole;
+use entity::{company, company_user};
+
+/// Creates a company with `owner_id` as its first member and administrator.
+pub async fn create_company<C: ConnectionTrait>(
+    db: &C,
+    name: &str,
+    owner_id: &str,
+) -> Result<company::Model, ApiError> {
+    let company = company::ActiveModel {
+        id: Set(uuid::Uuid::new_v4().to_string()),
+        name: Set(name.to_string()),
+        created_at: Set(Utc::now().naive_utc()),
+    }
+    .insert(db)
+    .await?;
+
+    add_member(db, &company.id, owner_id, CompanyRole::Admin).await?;
+    Ok(company)
+}
+
+pub async fn add_member<C: ConnectionTrait>(
+    db: &C,
+    company_id: &str,
+    user_id: &str,
+    role: CompanyRole,
+) -> Result<company_user::Model, ApiError> {
+    company_user::ActiveModel {
+        company_id: Set(company_id.to_string()),
+        user_id: Set(user_id.to_string()),
+        active: Set(true),
+        role: Set(role),
+        created_at: Set(Utc::now().naive_utc()),
+    }
+    .insert(db)
+    .await
+    .map_err(|e| conflict_on_duplicate(e, "User is already a member of this company"))
+}
+
+/// Whether the user may work in the company: they belong to it and their
+/// membership was not disabled.
+pub async fn is_member<C: ConnectionTrait>(
+    db: &C,
+    company_id: &str,
+    user_id: &str,
+) -> Result<bool, ApiError> {
+    let membership =
+        company_user::Entity::find_by_id((company_id.to_string(), user_id.to_string()))
+            .one(db)
+            .await?;
+    Ok(membership.is_some_and(|membership| membership.active))
+}
+
+/// Whether the user administers the company, through an active membership.
+pub async fn is_admin<C: ConnectionTrait>(
+    db: &C,
+    company_id: &str,
+    user_id: &str,
+) -> Result<bool, ApiError> {
+    let membership =
+        company_user::Entity::find_by_id((company_id.to_string(), user_id.to_string()))
+            .one(db)
+            .await?;
+    Ok(membership
+        .is_some_and(|membership| membership.active && membership.role == CompanyRole::Admin))
+}
+
+/// Whether the user's membership of the company was disabled. Tokens
+/// issued for it stop working.
+pub async fn is_disabled_member<C: ConnectionTrait>(
+    db: &C,
+    company_id: &str,
+    user_id: &str,
+) -> Result<bool, ApiError> {
+    let membership =
+        company_user::Entity::find_by_id((company_id.to_string(), user_id.to_string()))
+            .one(db)
+            .await?;
+    Ok(membership.is_some_and(|membership| !membership.active))
+}
+
+/// Enables or disables the user's membership of the company, if they have
+/// one.
+pub async fn set_member_active<C: ConnectionTrait>(
+    db: &C,
+    company_id: &str,
+    user_id: &str,
+    active: bool,
+) -> Result<(), ApiError> {
+    company_user::Entity::update_many()
+        .col_expr(company_user::Column::Active, Expr::value(active))
+        .filter(company_user::Column::CompanyId.eq(company_id))
+        .filter(company_user::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Company a user works in after logging in: the active membership they
+/// joined first.
+pub async fn default_company<C: ConnectionTrait>(
+    db: &C,
+    user_id: &str,
+) -> Result<Option<String>, ApiError> {
+    let membership = company_user::Entity::find()
+        .filter(company_user::Column::UserId.eq(user_id))
+        .filter(company_user::Column::Active.eq(true))
+        .order_by_asc(company_user::Column::CreatedAt)
+        .order_by_asc(company_user::Column::CompanyId)
+        .one(db)
+        .await?;
+    Ok(membership.map(|membership| membership.company_id))
+}
+
+pub async fn companies_of<C: ConnectionTrait>(
+    db: &C,
+    user_id: &str,
+) -> Result<Vec<company::Model>, ApiError> {
+    Ok(company::Entity::find()
+        .join(
+            sea_orm::JoinType::InnerJoin,
+            company::Relation::CompanyUser.def(),
+        )
+        .filter(company_user::Column::UserId.eq(user_id))
+        .filter(company_user::Column::Active.eq(true))
+        .order_by_asc(company::Column::Name)
+        .all(db)
+        .await?)
+}
+
+pub async fn find_company<C: ConnectionTrait>(
+    db: &C,
+    id: &str,
+) -> Result<Option<company::Model>, ApiError> {
+    Ok(company::Entity::find_by_id(id.to_string()).one(db).await?)
+}
+
+pub async fn all_companies<C: ConnectionTrait>(db: &C) -> Result<Vec<company::Model>, ApiError> {
+    Ok(company::Entity::find()
+        .order_by_asc(company::Column::Id)
+        .all(db)
+        .await?)
+}
@@ -0,0 +1,392 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, QueryOrder, QuerySelect, Set, TransactionTrait};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use super::models::{CreateCustomer, Customer, UpdateCustomer};
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::extractors::{Admin, ValidatedJson};
+use crate::shared::concurrency::{self, Precondition};
+use crate::shared::db_utils::conflict_on_duplicate;
+use crate::soft_delete;
+use crate::tenant::Tenant;
+use entity::audit_log::AuditAction;
+use entity::customer;
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    q: Option<String>,
+}
+
+/// Create a new customer
+#[utoipa::path(
+    post,
+    path = "/v1/customer",
+    tag = "customer",
+    request_body = CreateCustomer,
+    responses(
+        (status = 200, description = "Customer created successfully", body = Customer),
+        (status = 400, description = "Validation error"),
+        (status = 409, description = "Email already in use"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_customer(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    customer: ValidatedJson<CreateCustomer>,
+) -> Result<HttpResponse, ApiError> {
+    let new_customer = customer::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        name: Set(customer.name.clone()),
+        email: Set(customer.email.clone()),
+        phone: Set(customer.phone.clone()),
+        address: Set(customer.address.clone()),
+        customer_group: Set(customer.customer_group.clone()),
+        created_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    let txn = data.db.begin().await?;
+    let inserted_customer = new_customer
+        .insert(&txn)
+        .await
+        .map_err(|e| conflict_on_duplicate(e, "Email already in use"))?;
+    audit
+        .record(
+            &txn,
+            "customer",
+            &inserted_customer.id,
+            AuditAction::Create,
+            None,
+            Some(&inserted_customer),
+        )
+        .await?;
+    txn.commit().await?;
+
+    let index = data.meilisearch.index("customer");
+    index
+        .add_documents(&[&inserted_customer], Some("id"))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(inserted_customer))
+}
+
+/// Search customers
+#[utoipa::path(
+    get,
+    path = "/v1/customer/search",
+    tag = "customer",
+    params(
+        ("q" = String, Query, description = "Search query for customers")
+    ),
+    responses(
+        (status = 200, description = "Search results", body = Vec<Customer>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn search_customers(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    query: web::Query<SearchQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let q = query.q.as_deref().unwrap_or("");
+
+    let index = data.meilisearch.index("customer");
+    let hits = tenant.search::<Customer>(&index, q).await?;
+
+    Ok(HttpResponse::Ok().json(hits))
+}
+
+/// Get all customers
+#[utoipa::path(
+    get,
+    path = "/v1/customer",
+    tag = "customer",
+    responses(
+        (status = 200, description = "List of customers", body = Vec<Customer>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_all_customers(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+) -> Result<HttpResponse, ApiError> {
+    let customers = tenant
+        .find_active::<customer::Entity>()
+        .order_by_asc(customer::Column::Name)
+        .all(&data.db)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(customers))
+}
+
+/// Get customer by ID
+#[utoipa::path(
+    get,
+    path = "/v1/customer/{id}",
+    tag = "customer",
+    params(
+        ("id" = String, Path, description = "Customer ID"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag from an earlier response")
+    ),
+    responses(
+        (status = 200, description = "Customer found", body = Customer),
+        (status = 304, description = "Customer unchanged since the given ETag"),
+        (status = 404, description = "Customer not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_customer_by_id(
+    req: HttpRequest,
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let customer = tenant
+        .find_active_by_id::<customer::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Customer not found".to_string()))?;
+
+    if concurrency::is_not_modified(&req, customer.version) {
+        return Ok(concurrency::not_modified(customer.version));
+    }
+
+    Ok(HttpResponse::Ok()
+        .insert_header(concurrency::etag(customer.version))
+        .json(customer))
+}
+
+/// Update customer
+#[utoipa::path(
+    put,
+    path = "/v1/customer/{id}",
+    tag = "customer",
+    params(
+        ("id" = String, Path, description = "Customer ID"),
+        ("If-Match" = String, Header, description = "ETag of the version being replaced")
+    ),
+    request_body = UpdateCustomer,
+    responses(
+        (status = 200, description = "Customer updated successfully", body = Customer),
+        (status = 400, description = "Validation error"),
+        (status = 404, description = "Customer not found"),
+        (status = 409, description = "Email already in use"),
+        (status = 412, description = "Customer was changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_customer(
+    req: HttpRequest,
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+    customer: ValidatedJson<UpdateCustomer>,
+) -> Result<HttpResponse, ApiError> {
+    let precondition = Precondition::from_request(&req)?;
+
+    let txn = data.db.begin().await?;
+    let existing_customer = tenant
+        .find_active_by_id::<customer::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Customer not found".to_string()))?;
+    precondition.check(existing_customer.version)?;
+
+    let mut customer_model: customer::ActiveModel = existing_customer.clone().into();
+
+    if let Some(name) = &customer.name {
+        customer_model.name = Set(name.clone());
+    }
+    if let Some(email) = &customer.email {
+        customer_model.email = Set(email.clone());
+    }
+    if let Some(phone) = &customer.phone {
+        customer_model.phone = Set(Some(phone.clone()));
+    }
+    if let Some(address) = &customer.address {
+        customer_model.address = Set(Some(address.clone()));
+    }
+    if let Some(customer_group) = &customer.customer_group {
+        customer_model.customer_group = Set(Some(customer_group.clone()));
+    }
+
+    let updated_customer = customer_model
+        .update(&txn)
+        .await
+        .map_err(|e| conflict_on_duplicate(e, "Email already in use"))?;
+    audit
+        .record(
+            &txn,
+            "customer",
+            &updated_customer.id,
+            AuditAction::Update,
+            Some(&existing_customer),
+            Some(&updated_customer),
+        )
+        .await?;
+    txn.commit().await?;
+
+    let index = data.meilisearch.index("customer");
+    index
+        .add_documents(&[&updated_customer], Some("id"))
+        .await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(concurrency::etag(updated_customer.version))
+        .json(updated_customer))
+}
+
+/// Delete customer
+#[utoipa::path(
+    delete,
+    path = "/v1/customer/{id}",
+    tag = "customer",
+    params(
+        ("id" = String, Path, description = "Customer ID"),
+        ("If-Match" = String, Header, description = "ETag of the version being deleted")
+    ),
+    responses(
+        (status = 200, description = "Customer deleted successfully"),
+        (status = 404, description = "Customer not found"),
+        (status = 412, description = "Customer was changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_customer(
+    req: HttpRequest,
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let precondition = Precondition::from_request(&req)?;
+
+    let txn = data.db.begin().await?;
+    let customer = tenant
+        .find_active_by_id::<customer::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Customer not found".to_string()))?;
+    precondition.check(customer.version)?;
+
+    let customer_id = customer.id.clone();
+    let mut customer_active: customer::ActiveModel = customer.clone().into();
+    customer_active.deleted_at = Set(Some(Utc::now().naive_utc()));
+    customer_active.update(&txn).await?;
+    audit
+        .record(
+            &txn,
+            "customer",
+            &customer_id,
+            AuditAction::Delete,
+            Some(&customer),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+
+    let index = data.meilisearch.index("customer");
+    index.delete_document(&customer_id).await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Customer deleted successfully"})))
+}
+
+/// Restore a deleted customer
+#[utoipa::path(
+    post,
+    path = "/v1/customer/{id}/restore",
+    tag = "customer",
+    params(
+        ("id" = String, Path, description = "Customer ID")
+    ),
+    responses(
+        (status = 200, description = "Customer restored", body = Customer),
+        (status = 404, description = "Customer not found"),
+        (status = 409, description = "Customer is not deleted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn restore_customer(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let customer =
+        soft_delete::restore::<customer::Entity, _>(&txn, &tenant, &audit, "customer", &id).await?;
+    txn.commit().await?;
+
+    let index = data.meilisearch.index("customer");
+    index.add_documents(&[&customer], Some("id")).await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(concurrency::etag(customer.version))
+        .json(customer))
+}
+
+/// Permanently remove a deleted customer, administrators only
+#[utoipa::path(
+    delete,
+    path = "/v1/customer/{id}/purge",
+    tag = "customer",
+    params(
+        ("id" = String, Path, description = "Customer ID")
+    ),
+    responses(
+        (status = 200, description = "Customer purged"),
+        (status = 403, description = "Caller is not an administrator"),
+        (status = 404, description = "Customer not found"),
+        (status = 409, description = "Customer must be deleted first"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn purge_customer(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    _admin: Admin,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    soft_delete::purge::<customer::Entity, _>(&txn, &tenant, &audit, "customer", &id).await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Customer purged successfully"})))
+}
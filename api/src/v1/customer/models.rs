@@ -0,0 +1,50 @@
+use entity::customer;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+pub type Customer = customer::Model;
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateCustomer {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: String,
+    #[validate(email(message = "Invalid email format"))]
+    #[schema(format = Email)]
+    pub email: String,
+    #[validate(length(max = 50, message = "Phone cannot be longer than 50 characters"))]
+    #[schema(max_length = 50)]
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    /// Group whose price lists the customer buys at, e.g. `wholesale`.
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Customer group must be 1 to 50 characters"
+    ))]
+    #[schema(min_length = 1, max_length = 50)]
+    pub customer_group: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateCustomer {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: Option<String>,
+    #[validate(email(message = "Invalid email format"))]
+    #[schema(format = Email)]
+    pub email: Option<String>,
+    #[validate(length(max = 50, message = "Phone cannot be longer than 50 characters"))]
+    #[schema(max_length = 50)]
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    /// Group whose price lists the customer buys at, e.g. `wholesale`.
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Customer group must be 1 to 50 characters"
+    ))]
+    #[schema(min_length = 1, max_length = 50)]
+    pub customer_group: Option<String>,
+}
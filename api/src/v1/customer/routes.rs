@@ -0,0 +1,21 @@
+use super::handlers;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/customer")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .route("", web::get().to(handlers::get_all_customers))
+            .route("", web::post().to(handlers::create_customer))
+            .route("/search", web::get().to(handlers::search_customers))
+            .route("/{id}", web::get().to(handlers::get_customer_by_id))
+            .route("/{id}", web::put().to(handlers::update_customer))
+            .route("/{id}", web::delete().to(handlers::delete_customer))
+            .route("/{id}/restore", web::post().to(handlers::restore_customer))
+            .route("/{id}/purge", web::delete().to(handlers::purge_customer)),
+    );
+}
@@ -0,0 +1,239 @@
+use actix_web::{web, HttpResponse};
+use sea_orm::{ColumnTrait, QueryOrder, QuerySelect, TransactionTrait};
+use serde_json::json;
+
+use super::models::{CreateDepartment, Department, Headcount, UpdateDepartment};
+use super::services;
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::extractors::ValidatedJson;
+use crate::tenant::Tenant;
+use crate::v1::employee::services::reindex_employees;
+use entity::audit_log::AuditAction;
+use entity::{department, employee};
+
+/// List departments
+#[utoipa::path(
+    get,
+    path = "/v1/department",
+    tag = "department",
+    responses(
+        (status = 200, description = "List of departments", body = Vec<Department>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_departments(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+) -> Result<HttpResponse, ApiError> {
+    let departments = tenant
+        .find::<department::Entity>()
+        .order_by_asc(department::Column::Name)
+        .all(&data.db)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(departments))
+}
+
+/// Create a department
+#[utoipa::path(
+    post,
+    path = "/v1/department",
+    tag = "department",
+    request_body = CreateDepartment,
+    responses(
+        (status = 200, description = "Department created", body = Department),
+        (status = 400, description = "Validation error"),
+        (status = 409, description = "Name already in use"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_department(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    department: ValidatedJson<CreateDepartment>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let department = services::create_department(&txn, &tenant, &department).await?;
+    audit
+        .record(
+            &txn,
+            "department",
+            &department.id,
+            AuditAction::Create,
+            None,
+            Some(&department),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(department))
+}
+
+/// Number of active employees in each department
+#[utoipa::path(
+    get,
+    path = "/v1/department/headcount",
+    tag = "department",
+    responses(
+        (status = 200, description = "Headcount per department", body = Vec<Headcount>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_headcount(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+) -> Result<HttpResponse, ApiError> {
+    let headcount = services::headcount(&data.db, &tenant).await?;
+    Ok(HttpResponse::Ok().json(headcount))
+}
+
+/// Get department by ID
+#[utoipa::path(
+    get,
+    path = "/v1/department/{id}",
+    tag = "department",
+    params(
+        ("id" = String, Path, description = "Department ID")
+    ),
+    responses(
+        (status = 200, description = "Department found", body = Department),
+        (status = 404, description = "Department not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_department_by_id(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let department = tenant
+        .find_by_id::<department::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Department not found".to_string()))?;
+
+    Ok(HttpResponse::Ok().json(department))
+}
+
+/// Rename a department
+#[utoipa::path(
+    put,
+    path = "/v1/department/{id}",
+    tag = "department",
+    params(
+        ("id" = String, Path, description = "Department ID")
+    ),
+    request_body = UpdateDepartment,
+    responses(
+        (status = 200, description = "Department updated", body = Department),
+        (status = 400, description = "Validation error"),
+        (status = 404, description = "Department not found"),
+        (status = 409, description = "Name already in use"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_department(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+    update: ValidatedJson<UpdateDepartment>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let department = tenant
+        .find_by_id::<department::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Department not found".to_string()))?;
+
+    let updated = services::update_department(&txn, department.clone(), &update).await?;
+    audit
+        .record(
+            &txn,
+            "department",
+            &updated.id,
+            AuditAction::Update,
+            Some(&department),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    // Employees are found by the name of their department
+    if updated.name != department.name {
+        reindex_employees(
+            &data,
+            &tenant,
+            employee::Column::DepartmentId.eq(&updated.id),
+        )
+        .await?;
+    }
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Delete a department without employees or positions
+#[utoipa::path(
+    delete,
+    path = "/v1/department/{id}",
+    tag = "department",
+    params(
+        ("id" = String, Path, description = "Department ID")
+    ),
+    responses(
+        (status = 200, description = "Department deleted"),
+        (status = 404, description = "Department not found"),
+        (status = 409, description = "Department still has employees or positions"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_department(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let department = tenant
+        .find_by_id::<department::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Department not found".to_string()))?;
+
+    services::delete_department(&txn, &tenant, department.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "department",
+            &department.id,
+            AuditAction::Delete,
+            Some(&department),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Department deleted successfully"})))
+}
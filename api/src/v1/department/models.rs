@@ -0,0 +1,29 @@
+use entity::department;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+pub type Department = department::Model;
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateDepartment {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateDepartment {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: Option<String>,
+}
+
+/// Active employees of one department. Employees without a department are
+/// counted under a `null` department.
+#[derive(Serialize, Deserialize, ToSchema, Debug, PartialEq)]
+pub struct Headcount {
+    pub department_id: Option<String>,
+    pub name: Option<String>,
+    pub headcount: i64,
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, IntoActiveModel, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use uuid::Uuid;
+
+use super::models::{CreateDepartment, Department, Headcount, UpdateDepartment};
+use crate::error::ApiError;
+use crate::shared::db_utils::conflict_on_duplicate;
+use crate::tenant::Tenant;
+use crate::v1::employee::services as employee_services;
+use entity::{department, employee, position};
+
+const DUPLICATE_NAME: &str = "A department with this name already exists";
+
+pub async fn create_department<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    data: &CreateDepartment,
+) -> Result<Department, ApiError> {
+    department::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        name: Set(data.name.clone()),
+        created_at: Set(Utc::now().naive_utc()),
+    }
+    .insert(db)
+    .await
+    .map_err(|e| conflict_on_duplicate(e, DUPLICATE_NAME))
+}
+
+pub async fn update_department<C: ConnectionTrait>(
+    db: &C,
+    department: Department,
+    data: &UpdateDepartment,
+) -> Result<Department, ApiError> {
+    let mut active = department.into_active_model();
+    if let Some(name) = &data.name {
+        active.name = Set(name.clone());
+    }
+    active
+        .update(db)
+        .await
+        .map_err(|e| conflict_on_duplicate(e, DUPLICATE_NAME))
+}
+
+/// Departments still referenced by employees or positions are kept, deleted
+/// employees included since they can be restored.
+pub async fn delete_department<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    department: Department,
+) -> Result<(), ApiError> {
+    let employees = tenant
+        .find::<employee::Entity>()
+        .filter(employee::Column::DepartmentId.eq(&department.id))
+        .count(db)
+        .await?;
+    if employees > 0 {
+        return Err(ApiError::Conflict(
+            "The department still has employees, move them first".to_string(),
+        ));
+    }
+    let positions = tenant
+        .find::<position::Entity>()
+        .filter(position::Column::DepartmentId.eq(&department.id))
+        .count(db)
+        .await?;
+    if positions > 0 {
+        return Err(ApiError::Conflict(
+            "The department still has positions, move or delete them first".to_string(),
+        ));
+    }
+
+    department.into_active_model().delete(db).await?;
+    Ok(())
+}
+
+/// Employees currently employed per department, every department listed by
+/// name and employees without one last. Employees without any history are
+/// taken to be employed.
+pub async fn headcount<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+) -> Result<Vec<Headcount>, ApiError> {
+    let not_employed: Vec<String> =
+        employee_services::states_as_of(db, tenant, Utc::now().date_naive())
+            .await?
+            .into_iter()
+            .filter(|(_, state)| !state.employed)
+            .map(|(id, _)| id)
+            .collect();
+    let mut counts: HashMap<Option<String>, i64> = tenant
+        .find_active::<employee::Entity>()
+        .filter(employee::Column::Id.is_not_in(not_employed))
+        .select_only()
+        .column(employee::Column::DepartmentId)
+        .column_as(employee::Column::Id.count(), "headcount")
+        .group_by(employee::Column::DepartmentId)
+        .into_tuple::<(Option<String>, i64)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+    let departments = tenant
+        .find::<department::Entity>()
+        .order_by_asc(department::Column::Name)
+        .all(db)
+        .await?;
+
+    let mut headcount: Vec<Headcount> = departments
+        .into_iter()
+        .map(|department| Headcount {
+            headcount: counts.remove(&Some(department.id.clone())).unwrap_or(0),
+            department_id: Some(department.id),
+            name: Some(department.name),
+        })
+        .collect();
+    if let Some(unassigned) = counts.remove(&None) {
+        headcount.push(Headcount {
+            department_id: None,
+            name: None,
+            headcount: unassigned,
+        });
+    }
+    Ok(headcount)
+}
+
+/// Whether the department exists in the company, for records pointing at it.
+pub async fn exists<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    id: &str,
+) -> Result<bool, ApiError> {
+    Ok(tenant
+        .find_by_id::<department::Entity>(id)
+        .count(db)
+        .await?
+        > 0)
+}
@@ -1,19 +1,35 @@
-use actix_web::{web, HttpResponse};
-use sea_orm::{ActiveModelTrait, EntityTrait, QueryOrder, Set};
-use uuid::Uuid;
+use actix_web::{web, HttpRequest, HttpResponse};
+use sea_orm::{QuerySelect, TransactionTrait};
 
-use super::models::{CreateEmployee, Employee, UpdateEmployee};
+use super::models::{
+    AsOfQuery, CreateEmployee, CreateEmploymentRecord, Employee, EmployeeAsOf, EmployeeDocument,
+    EmployeeFilter, EmploymentHistory, EmploymentRecord, EmploymentState, HistoryEntry,
+    HistoryQuery, Invite, ReportNode, SearchQuery, UpdateEmployee,
+};
+use super::services::{self, index_employees, EmployeeBatch, INDEX};
+use crate::audit::AuditContext;
+use crate::batch::{batch_response, run_batch, BatchRequest, BatchResponse};
 use crate::error::ApiError;
+use crate::export::{stream_export, ExportFormat, ExportQuery};
+use crate::extractors::{Admin, ValidatedJson};
+use crate::middlewares::jwt::Claims;
+use crate::shared::concurrency::{self, Precondition};
+use crate::shared::db_utils::find_locked;
+use crate::soft_delete;
+use crate::tenant::Tenant;
+use crate::v1::auth::services as auth_services;
+use entity::audit_log::AuditAction;
 use entity::employee;
 use serde_json::json;
 
 #[utoipa::path(
     post,
     path = "/v1/employee",
+    tag = "employee",
     request_body = CreateEmployee,
     responses(
         (status = 201, description = "Employee created successfully", body = Employee),
-        (status = 400, description = "Validation error"),
+        (status = 400, description = "Validation error or unknown department, position or manager"),
         (status = 500, description = "Internal server error")
     ),
     security(
@@ -22,31 +38,36 @@ use serde_json::json;
 )]
 pub async fn create_employee(
     data: web::Data<config::app::AppState>,
-    employee: web::Json<CreateEmployee>,
+    tenant: Tenant,
+    audit: AuditContext,
+    employee: ValidatedJson<CreateEmployee>,
 ) -> Result<HttpResponse, ApiError> {
-    if !employee.email.contains('@') {
-        return Err(ApiError::ValidationError(
-            "Invalid email format".to_string(),
-        ));
-    }
-
-    let new_uuid = Uuid::new_v4();
-    let new_employee = employee::ActiveModel {
-        id: Set(new_uuid.to_string()),
-        name: Set(employee.name.clone()),
-        role: Set(employee.role.clone()),
-        email: Set(employee.email.clone()),
-    };
-
-    let inserted_employee = new_employee.insert(&data.db).await?;
+    let txn = data.db.begin().await?;
+    let inserted_employee = services::create_employee(&txn, &tenant, &employee).await?;
+    audit
+        .record(
+            &txn,
+            "employee",
+            &inserted_employee.id,
+            AuditAction::Create,
+            None,
+            Some(&inserted_employee),
+        )
+        .await?;
+    txn.commit().await?;
 
-    let employee_response: Employee = inserted_employee;
-    Ok(HttpResponse::Ok().json(employee_response))
+    index_employees(&data, &tenant, vec![inserted_employee.clone()]).await?;
+    Ok(HttpResponse::Ok().json(inserted_employee))
 }
 
 #[utoipa::path(
     get,
     path = "/v1/employee",
+    tag = "employee",
+    params(
+        ("role" = Option<String>, Query, description = "Filter by role"),
+        ("department_id" = Option<String>, Query, description = "Filter by department")
+    ),
     responses(
         (status = 200, description = "List of employees", body = Vec<Employee>),
         (status = 500, description = "Internal server error")
@@ -57,22 +78,61 @@ pub async fn create_employee(
 )]
 pub async fn get_all_employees(
     data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<EmployeeFilter>,
 ) -> Result<HttpResponse, ApiError> {
-    let employees = employee::Entity::find()
-        .order_by_asc(entity::employee::Column::Name)
-        .all(&data.db)
-        .await?;
+    let employees = filter.select(&tenant).all(&data.db).await?;
 
     let employee_responses: Vec<Employee> = employees.into_iter().map(|e| e.into()).collect();
 
     Ok(HttpResponse::Ok().json(employee_responses))
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/employee/export",
+    tag = "employee",
+    params(
+        ("format" = Option<ExportFormat>, Query, description = "csv (default), xlsx or jsonl"),
+        ("role" = Option<String>, Query, description = "Filter by role"),
+        ("department_id" = Option<String>, Query, description = "Filter by department")
+    ),
+    responses(
+        (status = 200, description = "Export file", content(
+            (String = "text/csv"),
+            (String = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+            (String = "application/x-ndjson")
+        )),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn export_employees(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<EmployeeFilter>,
+    query: web::Query<ExportQuery>,
+) -> HttpResponse {
+    stream_export(
+        data.db.clone(),
+        filter.select(&tenant),
+        query.format,
+        "employees",
+    )
+}
+
 #[utoipa::path(
     get,
     path = "/v1/employee/{id}",
+    tag = "employee",
+    params(
+        ("If-None-Match" = Option<String>, Header, description = "ETag from an earlier response")
+    ),
     responses(
         (status = 200, description = "Employee found", body = Employee),
+        (status = 304, description = "Employee unchanged since the given ETag"),
         (status = 404, description = "Employee not found"),
         (status = 500, description = "Internal server error")
     ),
@@ -81,26 +141,41 @@ pub async fn get_all_employees(
     )
 )]
 pub async fn get_employee_by_id(
+    req: HttpRequest,
     data: web::Data<config::app::AppState>,
+    tenant: Tenant,
     id: web::Path<String>,
 ) -> Result<HttpResponse, ApiError> {
-    let employee = employee::Entity::find_by_id(id.into_inner())
+    let employee = tenant
+        .find_active_by_id::<employee::Entity>(&id)
         .one(&data.db)
         .await?
         .ok_or(ApiError::NotFound("Employee not found".to_string()))?;
 
+    if concurrency::is_not_modified(&req, employee.version) {
+        return Ok(concurrency::not_modified(employee.version));
+    }
+
     let employee_response: Employee = employee.into();
-    Ok(HttpResponse::Ok().json(employee_response))
+    Ok(HttpResponse::Ok()
+        .insert_header(concurrency::etag(employee_response.version))
+        .json(employee_response))
 }
 
 #[utoipa::path(
     put,
     path = "/v1/employee/{id}",
+    tag = "employee",
+    params(
+        ("If-Match" = String, Header, description = "ETag of the version being replaced")
+    ),
     request_body = UpdateEmployee,
     responses(
         (status = 200, description = "Employee updated successfully", body = Employee),
-        (status = 400, description = "Validation error"),
+        (status = 400, description = "Validation error, unknown reference or manager cycle"),
         (status = 404, description = "Employee not found"),
+        (status = 412, description = "Employee was changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
         (status = 500, description = "Internal server error")
     ),
     security(
@@ -108,44 +183,331 @@ pub async fn get_employee_by_id(
     )
 )]
 pub async fn update_employee(
+    req: HttpRequest,
     data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
     id: web::Path<String>,
-    employee: web::Json<UpdateEmployee>,
+    employee: ValidatedJson<UpdateEmployee>,
 ) -> Result<HttpResponse, ApiError> {
+    let precondition = Precondition::from_request(&req)?;
     let employee_id = id.into_inner();
 
-    let existing_employee = employee::Entity::find_by_id(&employee_id)
+    let txn = data.db.begin().await?;
+    let before = find_locked::<employee::Entity, _>(&txn, &tenant, &employee_id).await?;
+    let updated_employee =
+        services::update_employee(&txn, &tenant, &employee_id, &precondition, &employee).await?;
+    audit
+        .record(
+            &txn,
+            "employee",
+            &employee_id,
+            AuditAction::Update,
+            before.as_ref(),
+            Some(&updated_employee),
+        )
+        .await?;
+    txn.commit().await?;
+
+    index_employees(&data, &tenant, vec![updated_employee.clone()]).await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(concurrency::etag(updated_employee.version))
+        .json(updated_employee))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/employee/{id}",
+    tag = "employee",
+    params(
+        ("If-Match" = String, Header, description = "ETag of the version being deleted")
+    ),
+    responses(
+        (status = 200, description = "Employee deleted and their login disabled"),
+        (status = 404, description = "Employee not found"),
+        (status = 409, description = "Employee still has people reporting to them"),
+        (status = 412, description = "Employee was changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_employee(
+    req: HttpRequest,
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let precondition = Precondition::from_request(&req)?;
+    let employee_id = id.into_inner();
+
+    let txn = data.db.begin().await?;
+    let before = find_locked::<employee::Entity, _>(&txn, &tenant, &employee_id).await?;
+    services::delete_employee(&txn, &tenant, &employee_id, &precondition).await?;
+    audit
+        .record(
+            &txn,
+            "employee",
+            &employee_id,
+            AuditAction::Delete,
+            before.as_ref(),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+
+    data.meilisearch
+        .index(INDEX)
+        .delete_document(&employee_id)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Employee deleted successfully"})))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/employee/batch",
+    tag = "employee",
+    request_body = BatchRequest<CreateEmployee, UpdateEmployee>,
+    responses(
+        (status = 200, description = "Batch processed", body = BatchResponse),
+        (status = 400, description = "Empty or oversized batch"),
+        (status = 422, description = "Atomic batch rolled back", body = BatchResponse),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn batch_employees(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    batch: web::Json<BatchRequest<CreateEmployee, UpdateEmployee>>,
+) -> Result<HttpResponse, ApiError> {
+    let outcome = run_batch::<EmployeeBatch>(&data.db, &tenant, &audit, batch.into_inner()).await?;
+
+    index_employees(&data, &tenant, outcome.upserted).await?;
+    if !outcome.deleted.is_empty() {
+        data.meilisearch
+            .index(INDEX)
+            .delete_documents(&outcome.deleted)
+            .await?;
+    }
+
+    Ok(batch_response(outcome.response))
+}
+
+/// Restore a deleted employee
+#[utoipa::path(
+    post,
+    path = "/v1/employee/{id}/restore",
+    tag = "employee",
+    params(
+        ("id" = String, Path, description = "Employee ID")
+    ),
+    responses(
+        (status = 200, description = "Employee restored, with their login enabled again", body = Employee),
+        (status = 404, description = "Employee not found"),
+        (status = 409, description = "Employee is not deleted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn restore_employee(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let employee =
+        soft_delete::restore::<employee::Entity, _>(&txn, &tenant, &audit, "employee", &id).await?;
+    services::sync_login(&txn, &tenant, &employee).await?;
+    txn.commit().await?;
+
+    index_employees(&data, &tenant, vec![employee.clone()]).await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(concurrency::etag(employee.version))
+        .json(employee))
+}
+
+/// Permanently remove a deleted employee, administrators only
+#[utoipa::path(
+    delete,
+    path = "/v1/employee/{id}/purge",
+    tag = "employee",
+    params(
+        ("id" = String, Path, description = "Employee ID")
+    ),
+    responses(
+        (status = 200, description = "Employee purged"),
+        (status = 403, description = "Caller is not an administrator"),
+        (status = 404, description = "Employee not found"),
+        (status = 409, description = "Employee must be deleted first or has payslips or expense claims"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn purge_employee(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    _admin: Admin,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    services::check_purge(&txn, &tenant, &id).await?;
+    soft_delete::purge::<employee::Entity, _>(&txn, &tenant, &audit, "employee", &id).await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Employee purged successfully"})))
+}
+
+/// Search employees by name, email, role, department or position
+#[utoipa::path(
+    get,
+    path = "/v1/employee/search",
+    tag = "employee",
+    params(
+        ("q" = String, Query, description = "Search query for employees"),
+        ("department_id" = Option<String>, Query, description = "Only employees of this department")
+    ),
+    responses(
+        (status = 200, description = "Search results", body = Vec<EmployeeDocument>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn search_employees(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    query: web::Query<SearchQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let q = query.q.as_deref().unwrap_or("");
+    let mut filters = Vec::new();
+    if let Some(department_id) = &query.department_id {
+        filters.push(("department_id", department_id.as_str()));
+    }
+
+    let index = data.meilisearch.index(INDEX);
+    let hits = tenant
+        .search_where::<EmployeeDocument>(&index, q, &filters)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(hits))
+}
+
+/// Org chart below an employee: everyone reporting to them, directly or not
+#[utoipa::path(
+    get,
+    path = "/v1/employee/{id}/reports",
+    tag = "employee",
+    params(
+        ("id" = String, Path, description = "Employee ID")
+    ),
+    responses(
+        (status = 200, description = "The employee with their reports, nested", body = ReportNode),
+        (status = 404, description = "Employee not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_reports(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let employee = tenant
+        .find_active_by_id::<employee::Entity>(&id)
         .one(&data.db)
         .await?
         .ok_or(ApiError::NotFound("Employee not found".to_string()))?;
 
-    let mut employee_model: employee::ActiveModel = existing_employee.into();
+    let tree = services::reports(&data.db, &tenant, employee).await?;
+    Ok(HttpResponse::Ok().json(tree))
+}
 
-    if let Some(name) = &employee.name {
-        employee_model.name = Set(name.clone());
-    }
-    if let Some(role) = &employee.role {
-        employee_model.role = Set(role.clone());
-    }
-    if let Some(email) = &employee.email {
-        if !email.contains('@') {
-            return Err(ApiError::ValidationError(
-                "Invalid email format".to_string(),
-            ));
-        }
-        employee_model.email = Set(email.clone());
-    }
+/// Invite an employee to create their login
+#[utoipa::path(
+    post,
+    path = "/v1/employee/{id}/invite",
+    tag = "employee",
+    params(
+        ("id" = String, Path, description = "Employee ID")
+    ),
+    responses(
+        (status = 201, description = "Invitation created", body = Invite),
+        (status = 404, description = "Employee not found"),
+        (status = 409, description = "Employee already has a login"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn invite_employee(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let employee = tenant
+        .find_active_by_id::<employee::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or(ApiError::NotFound("Employee not found".to_string()))?;
 
-    let updated_employee = employee_model.update(&data.db).await?;
-    let employee_response: Employee = updated_employee.into();
-    Ok(HttpResponse::Ok().json(employee_response))
+    let (invite, token) =
+        auth_services::create_invite(&txn, &tenant, &employee, &claims.sub).await?;
+    audit
+        .record(
+            &txn,
+            "user_invite",
+            &invite.id,
+            AuditAction::Create,
+            None,
+            Some(&invite),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Created().json(Invite {
+        id: invite.id,
+        employee_id: invite.employee_id,
+        token,
+        expires_at: invite.expires_at,
+    }))
 }
 
+/// Employment timeline of an employee: hires, transfers, salary changes and
+/// terminations
 #[utoipa::path(
-    delete,
-    path = "/v1/employee/{id}",
+    get,
+    path = "/v1/employee/{id}/history",
+    tag = "employee",
+    params(
+        ("id" = String, Path, description = "Employee ID"),
+        HistoryQuery
+    ),
     responses(
-        (status = 200, description = "Employee deleted successfully"),
+        (status = 200, description = "Records with the state each led to", body = EmploymentHistory),
         (status = 404, description = "Employee not found"),
         (status = 500, description = "Internal server error")
     ),
@@ -153,16 +515,130 @@ pub async fn update_employee(
         ("bearerAuth" = [])
     )
 )]
-pub async fn delete_employee(
+pub async fn get_history(
     data: web::Data<config::app::AppState>,
+    tenant: Tenant,
     id: web::Path<String>,
+    query: web::Query<HistoryQuery>,
 ) -> Result<HttpResponse, ApiError> {
-    let employee = employee::Entity::find_by_id(id.into_inner())
+    let employee = tenant
+        .find_active_by_id::<employee::Entity>(&id)
         .one(&data.db)
         .await?
         .ok_or(ApiError::NotFound("Employee not found".to_string()))?;
 
-    let employee_active: employee::ActiveModel = employee.into();
-    employee_active.delete(&data.db).await?;
-    Ok(HttpResponse::Ok().json(json!({"message": "Employee deleted successfully"})))
+    let records = services::timeline(&data.db, &tenant, &employee.id).await?;
+    let as_of = query
+        .as_of
+        .unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let state = EmploymentState::as_of(&records, as_of);
+
+    let mut current = EmploymentState::default();
+    let entries = records
+        .into_iter()
+        .map(|record| {
+            let _ = current.apply(&record);
+            HistoryEntry {
+                record,
+                state: current.clone(),
+            }
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(EmploymentHistory {
+        employee_id: employee.id,
+        as_of,
+        state,
+        entries,
+    }))
+}
+
+/// Record a hire, transfer, salary change or termination
+#[utoipa::path(
+    post,
+    path = "/v1/employee/{id}/history",
+    tag = "employee",
+    params(
+        ("id" = String, Path, description = "Employee ID")
+    ),
+    request_body = CreateEmploymentRecord,
+    responses(
+        (status = 201, description = "Record added", body = EmploymentRecord),
+        (status = 400, description = "Validation error or the record does not fit the timeline"),
+        (status = 404, description = "Employee not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn add_history_record(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+    record: ValidatedJson<CreateEmploymentRecord>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let employee = tenant
+        .find_active_by_id::<employee::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or(ApiError::NotFound("Employee not found".to_string()))?;
+
+    let (record, updated) = services::record_employment(&txn, &tenant, &employee, &record).await?;
+    audit
+        .record(
+            &txn,
+            "employment_record",
+            &record.id,
+            AuditAction::Create,
+            None,
+            Some(&record),
+        )
+        .await?;
+    if let Some(updated) = &updated {
+        audit
+            .record(
+                &txn,
+                "employee",
+                &updated.id,
+                AuditAction::Update,
+                Some(&employee),
+                Some(updated),
+            )
+            .await?;
+    }
+    txn.commit().await?;
+
+    if let Some(updated) = updated {
+        index_employees(&data, &tenant, vec![updated]).await?;
+    }
+
+    Ok(HttpResponse::Created().json(record))
+}
+
+/// Employees employed on a date, e.g. who was in a department on March 1
+#[utoipa::path(
+    get,
+    path = "/v1/employee/as-of",
+    tag = "employee",
+    params(AsOfQuery),
+    responses(
+        (status = 200, description = "Employees with where they stood on the date", body = Vec<EmployeeAsOf>),
+        (status = 400, description = "Missing or invalid date"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_employees_as_of(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    query: web::Query<AsOfQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let employees = services::employed_as_of(&data.db, &tenant, &query).await?;
+    Ok(HttpResponse::Ok().json(employees))
 }
@@ -1,19 +1,296 @@
+use chrono::{NaiveDate, NaiveDateTime};
 use entity::employee;
+use entity::employment_record::{self, EmploymentEvent};
+use sea_orm::{ColumnTrait, QueryFilter, QueryOrder, Select};
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::export::{Cell, ExportRow};
+use crate::shared::nullable;
+use crate::tenant::Tenant;
 
 pub type Employee = employee::Model;
+pub type EmploymentRecord = employment_record::Model;
 
-#[derive(Serialize, Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
 pub struct CreateEmployee {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
     pub name: String,
+    #[validate(length(min = 1, max = 255, message = "Role must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
     pub role: String,
+    #[validate(email(message = "Invalid email format"))]
+    #[schema(format = Email)]
     pub email: String,
+    pub department_id: Option<String>,
+    pub position_id: Option<String>,
+    /// Employee this one reports to.
+    pub manager_id: Option<String>,
+    /// Records the hire effective on this date, starting the history.
+    pub hire_date: Option<NaiveDate>,
+    /// Salary at hire, only recorded along with `hire_date`.
+    #[validate(range(min = 0.0, message = "Salary cannot be negative"))]
+    pub salary: Option<f64>,
 }
 
-#[derive(Serialize, Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
 pub struct UpdateEmployee {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
     pub name: Option<String>,
+    #[validate(length(min = 1, max = 255, message = "Role must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
     pub role: Option<String>,
+    #[validate(email(message = "Invalid email format"))]
+    #[schema(format = Email)]
     pub email: Option<String>,
+    /// `null` removes the employee from their department, and likewise for
+    /// the position and manager.
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable)]
+    pub department_id: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable)]
+    pub position_id: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable)]
+    pub manager_id: Option<Option<String>>,
+}
+
+/// An employee as indexed for search, with the names of their department
+/// and position.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct EmployeeDocument {
+    #[serde(flatten)]
+    pub employee: Employee,
+    pub department: Option<String>,
+    pub position: Option<String>,
+}
+
+/// An employee with everyone reporting to them, directly or not.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ReportNode {
+    #[serde(flatten)]
+    pub employee: Employee,
+    #[schema(no_recursion)]
+    pub reports: Vec<ReportNode>,
+}
+
+/// An invitation for an employee to create their login.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct Invite {
+    pub id: String,
+    pub employee_id: String,
+    /// Hand this to the employee for `POST /v1/auth/accept-invite`. It is
+    /// not shown again.
+    pub token: String,
+    pub expires_at: NaiveDateTime,
+}
+
+/// A hire, transfer, salary change or termination. Fields left out keep
+/// their earlier value.
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateEmploymentRecord {
+    pub kind: EmploymentEvent,
+    pub effective_date: NaiveDate,
+    pub department_id: Option<String>,
+    pub position_id: Option<String>,
+    pub manager_id: Option<String>,
+    #[validate(length(min = 1, max = 255, message = "Role must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub role: Option<String>,
+    #[validate(range(min = 0.0, message = "Salary cannot be negative"))]
+    pub salary: Option<f64>,
+    /// Required for terminations.
+    #[validate(length(min = 1, max = 1000, message = "Reason must be 1 to 1000 characters"))]
+    #[schema(min_length = 1, max_length = 1000)]
+    pub reason: Option<String>,
+}
+
+/// Where an employee stood on a given date.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct EmploymentState {
+    pub employed: bool,
+    /// Start of the current or last employment.
+    pub hire_date: Option<NaiveDate>,
+    pub termination_date: Option<NaiveDate>,
+    pub termination_reason: Option<String>,
+    pub department_id: Option<String>,
+    pub position_id: Option<String>,
+    pub manager_id: Option<String>,
+    pub role: Option<String>,
+    pub salary: Option<f64>,
+}
+
+impl EmploymentState {
+    /// Applies the next record, failing when it does not fit the state,
+    /// e.g. a transfer before the hire.
+    pub fn apply(&mut self, record: &EmploymentRecord) -> Result<(), String> {
+        match record.kind {
+            EmploymentEvent::Hire if self.employed => {
+                return Err(format!(
+                    "The employee is already employed on {}",
+                    record.effective_date
+                ));
+            }
+            EmploymentEvent::Hire => {
+                self.employed = true;
+                self.hire_date = Some(record.effective_date);
+                self.termination_date = None;
+                self.termination_reason = None;
+            }
+            _ if !self.employed => {
+                return Err(format!(
+                    "The employee is not employed on {}",
+                    record.effective_date
+                ));
+            }
+            EmploymentEvent::Termination => {
+                self.employed = false;
+                self.termination_date = Some(record.effective_date);
+                self.termination_reason = record.reason.clone();
+            }
+            EmploymentEvent::Transfer | EmploymentEvent::SalaryChange => {}
+        }
+
+        let set = |field: &mut Option<String>, value: &Option<String>| {
+            if value.is_some() {
+                field.clone_from(value);
+            }
+        };
+        set(&mut self.department_id, &record.department_id);
+        set(&mut self.position_id, &record.position_id);
+        set(&mut self.manager_id, &record.manager_id);
+        set(&mut self.role, &record.role);
+        if record.salary.is_some() {
+            self.salary = record.salary;
+        }
+        Ok(())
+    }
+
+    /// State at the end of `date`, from records in timeline order.
+    pub fn as_of<'a>(
+        records: impl IntoIterator<Item = &'a EmploymentRecord>,
+        date: NaiveDate,
+    ) -> Self {
+        let mut state = EmploymentState::default();
+        for record in records {
+            if record.effective_date > date {
+                break;
+            }
+            // Stored timelines were checked when written
+            let _ = state.apply(record);
+        }
+        state
+    }
+}
+
+/// A record with the state it led to.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct HistoryEntry {
+    #[serde(flatten)]
+    pub record: EmploymentRecord,
+    pub state: EmploymentState,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct EmploymentHistory {
+    pub employee_id: String,
+    /// State on the `as_of` date, today by default.
+    pub as_of: NaiveDate,
+    pub state: EmploymentState,
+    /// Every record, oldest first.
+    pub entries: Vec<HistoryEntry>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct HistoryQuery {
+    /// Date to report the state on, today by default
+    pub as_of: Option<NaiveDate>,
+}
+
+/// Filters for the employees employed on a date, applied to where they
+/// stood on that date.
+#[derive(Deserialize, IntoParams)]
+pub struct AsOfQuery {
+    /// Date to look at
+    pub date: NaiveDate,
+    pub department_id: Option<String>,
+    pub position_id: Option<String>,
+    pub role: Option<String>,
+}
+
+impl AsOfQuery {
+    pub fn matches(&self, state: &EmploymentState) -> bool {
+        let matches =
+            |filter: &Option<String>, value: &Option<String>| filter.is_none() || filter == value;
+        state.employed
+            && matches(&self.department_id, &state.department_id)
+            && matches(&self.position_id, &state.position_id)
+            && matches(&self.role, &state.role)
+    }
+}
+
+/// An employee with where they stood on the requested date.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct EmployeeAsOf {
+    #[serde(flatten)]
+    pub employee: Employee,
+    pub employment: EmploymentState,
+}
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    pub q: Option<String>,
+    pub department_id: Option<String>,
+}
+
+/// Filters shared by the list and export endpoints.
+#[derive(Deserialize)]
+pub struct EmployeeFilter {
+    pub role: Option<String>,
+    pub department_id: Option<String>,
+}
+
+impl EmployeeFilter {
+    pub fn select(&self, tenant: &Tenant) -> Select<employee::Entity> {
+        let mut query = tenant
+            .find_active::<employee::Entity>()
+            .order_by_asc(employee::Column::Name);
+        if let Some(role) = &self.role {
+            query = query.filter(employee::Column::Role.eq(role));
+        }
+        if let Some(department_id) = &self.department_id {
+            query = query.filter(employee::Column::DepartmentId.eq(department_id));
+        }
+        query
+    }
+}
+
+impl ExportRow for Employee {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "id",
+            "name",
+            "role",
+            "email",
+            "department_id",
+            "position_id",
+            "manager_id",
+        ]
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        vec![
+            self.id.clone().into(),
+            self.name.clone().into(),
+            self.role.clone().into(),
+            self.email.clone().into(),
+            self.department_id.clone().into(),
+            self.position_id.clone().into(),
+            self.manager_id.clone().into(),
+        ]
+    }
 }
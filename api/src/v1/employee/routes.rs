@@ -1,4 +1,7 @@
 use super::handlers;
+use crate::batch::MAX_BATCH_BYTES;
+use crate::error::json_config;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
 use crate::middlewares::jwt::JwtMiddleware;
 use actix_web::web;
 
@@ -6,11 +9,29 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
     let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
     cfg.service(
         web::scope("/v1/employee")
+            .wrap(IdempotencyMiddleware::new())
             .wrap(jwt_middleware)
             .route("", web::get().to(handlers::get_all_employees))
             .route("", web::post().to(handlers::create_employee))
+            .route("/export", web::get().to(handlers::export_employees))
+            .route("/search", web::get().to(handlers::search_employees))
+            .route("/as-of", web::get().to(handlers::get_employees_as_of))
+            .service(
+                web::resource("/batch")
+                    .app_data(json_config().limit(MAX_BATCH_BYTES))
+                    .route(web::post().to(handlers::batch_employees)),
+            )
             .route("/{id}", web::get().to(handlers::get_employee_by_id))
             .route("/{id}", web::put().to(handlers::update_employee))
-            .route("/{id}", web::delete().to(handlers::delete_employee)),
+            .route("/{id}", web::delete().to(handlers::delete_employee))
+            .route("/{id}/reports", web::get().to(handlers::get_reports))
+            .route("/{id}/invite", web::post().to(handlers::invite_employee))
+            .route("/{id}/history", web::get().to(handlers::get_history))
+            .route(
+                "/{id}/history",
+                web::post().to(handlers::add_history_record),
+            )
+            .route("/{id}/restore", web::post().to(handlers::restore_employee))
+            .route("/{id}/purge", web::delete().to(handlers::purge_employee)),
     );
 }
@@ -0,0 +1,875 @@
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, Utc};
+use log::warn;
+use sea_orm::sea_query::SimpleExpr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DatabaseTransaction,
+    EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use super::models::{
+    AsOfQuery, CreateEmployee, CreateEmploymentRecord, Employee, EmployeeAsOf, EmployeeDocument,
+    EmploymentRecord, EmploymentState, ReportNode, UpdateEmployee,
+};
+use crate::audit::AuditContext;
+use crate::batch::BatchResource;
+use crate::error::{ApiError, FieldErrors};
+use crate::shared::concurrency::Precondition;
+use crate::shared::db_utils::find_locked;
+use crate::tenant::Tenant;
+use crate::v1::auth::models::Caller;
+use crate::v1::auth::services as auth_services;
+use entity::audit_log::AuditAction;
+use entity::employment_record::{self, EmploymentEvent};
+use entity::{department, employee, expense_claim, payslip, position};
+
+/// Search index of employee documents.
+pub const INDEX: &str = "employee";
+
+/// How often employment records made ahead of time are applied.
+const EMPLOYMENT_SYNC_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// How far back the employment sync looks, so it catches up after downtime.
+const EMPLOYMENT_SYNC_LOOKBACK_DAYS: i64 = 7;
+
+/// Where an employee sits in the organisation.
+struct Placement<'a> {
+    department_id: Option<&'a str>,
+    position_id: Option<&'a str>,
+    manager_id: Option<&'a str>,
+}
+
+/// Checks that the department, position and manager exist in the company
+/// and that giving `employee_id` this manager does not make the chain of
+/// managers loop back to them.
+async fn check_placement<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee_id: &str,
+    placement: &Placement<'_>,
+) -> Result<(), ApiError> {
+    let mut errors = FieldErrors::new();
+
+    if let Some(id) = placement.department_id {
+        let found = tenant
+            .find_by_id::<department::Entity>(id)
+            .count(db)
+            .await?;
+        if found == 0 {
+            errors.add("department_id", "Department not found");
+        }
+    }
+    if let Some(id) = placement.position_id {
+        match tenant.find_by_id::<position::Entity>(id).one(db).await? {
+            None => errors.add("position_id", "Position not found"),
+            Some(position) => {
+                if let (Some(expected), Some(actual)) =
+                    (position.department_id.as_deref(), placement.department_id)
+                {
+                    if expected != actual {
+                        errors.add("position_id", "Position belongs to another department");
+                    }
+                }
+            }
+        }
+    }
+    if let Some(id) = placement.manager_id {
+        match tenant
+            .find_active_by_id::<employee::Entity>(id)
+            .one(db)
+            .await?
+        {
+            None => errors.add("manager_id", "Manager not found"),
+            Some(manager) => {
+                if reports_up_to(db, tenant, manager, employee_id).await? {
+                    errors.add(
+                        "manager_id",
+                        "Employee cannot report to themselves or to someone reporting to them",
+                    );
+                }
+            }
+        }
+    }
+
+    errors.into_result()
+}
+
+/// Whether `employee_id` is `manager` or one of their managers. The chain
+/// is read with shared locks, so a concurrent change closing the same loop
+/// from the other end has to wait for this transaction.
+async fn reports_up_to<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    manager: Employee,
+    employee_id: &str,
+) -> Result<bool, ApiError> {
+    let mut seen = HashSet::new();
+    let mut current = Some(manager);
+    while let Some(employee) = current {
+        if employee.id == employee_id {
+            return Ok(true);
+        }
+        // A loop that is already stored does not run through this employee
+        if !seen.insert(employee.id.clone()) {
+            return Ok(false);
+        }
+        current = match employee.manager_id {
+            Some(manager_id) => {
+                tenant
+                    .find_by_id::<employee::Entity>(&manager_id)
+                    .lock_shared()
+                    .one(db)
+                    .await?
+            }
+            None => None,
+        };
+    }
+    Ok(false)
+}
+
+pub async fn create_employee<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee: &CreateEmployee,
+) -> Result<Employee, ApiError> {
+    let id = Uuid::new_v4().to_string();
+    let placement = Placement {
+        department_id: employee.department_id.as_deref(),
+        position_id: employee.position_id.as_deref(),
+        manager_id: employee.manager_id.as_deref(),
+    };
+    check_placement(db, tenant, &id, &placement).await?;
+
+    let new_employee = employee::ActiveModel {
+        id: Set(id),
+        tenant_id: Set(tenant.id().to_string()),
+        name: Set(employee.name.clone()),
+        role: Set(employee.role.clone()),
+        email: Set(employee.email.clone()),
+        department_id: Set(employee.department_id.clone()),
+        position_id: Set(employee.position_id.clone()),
+        manager_id: Set(employee.manager_id.clone()),
+        ..Default::default()
+    };
+    let inserted = new_employee.insert(db).await?;
+
+    if let Some(hire_date) = employee.hire_date {
+        let hire = EmploymentRecord {
+            kind: EmploymentEvent::Hire,
+            effective_date: hire_date,
+            department_id: inserted.department_id.clone(),
+            position_id: inserted.position_id.clone(),
+            manager_id: inserted.manager_id.clone(),
+            role: Some(inserted.role.clone()),
+            salary: employee.salary,
+            ..new_record(tenant, &inserted.id)
+        };
+        insert_record(db, hire).await?;
+    }
+
+    Ok(inserted)
+}
+
+pub async fn update_employee<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee_id: &str,
+    precondition: &Precondition,
+    employee: &UpdateEmployee,
+) -> Result<Employee, ApiError> {
+    let existing_employee = tenant
+        .find_active_by_id::<employee::Entity>(employee_id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or(ApiError::NotFound("Employee not found".to_string()))?;
+    precondition.check(existing_employee.version)?;
+
+    if employee.department_id.is_some()
+        || employee.position_id.is_some()
+        || employee.manager_id.is_some()
+    {
+        let placement = Placement {
+            department_id: pick(&employee.department_id, &existing_employee.department_id),
+            position_id: pick(&employee.position_id, &existing_employee.position_id),
+            manager_id: pick(&employee.manager_id, &existing_employee.manager_id),
+        };
+        check_placement(db, tenant, employee_id, &placement).await?;
+    }
+
+    let before = existing_employee.clone();
+    let mut employee_model: employee::ActiveModel = existing_employee.into();
+
+    if let Some(name) = &employee.name {
+        employee_model.name = Set(name.clone());
+    }
+    if let Some(role) = &employee.role {
+        employee_model.role = Set(role.clone());
+    }
+    if let Some(email) = &employee.email {
+        employee_model.email = Set(email.clone());
+    }
+    if let Some(department_id) = &employee.department_id {
+        employee_model.department_id = Set(department_id.clone());
+    }
+    if let Some(position_id) = &employee.position_id {
+        employee_model.position_id = Set(position_id.clone());
+    }
+    if let Some(manager_id) = &employee.manager_id {
+        employee_model.manager_id = Set(manager_id.clone());
+    }
+
+    let updated = employee_model.update(db).await?;
+    record_transfer(db, tenant, &before, &updated).await?;
+    Ok(updated)
+}
+
+/// Keeps the history of an employee who is employed today in step with
+/// edits to their department, position, manager or role, as a transfer
+/// effective today. Cleared fields are not recorded, a record only sets
+/// values.
+async fn record_transfer<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    before: &Employee,
+    after: &Employee,
+) -> Result<(), ApiError> {
+    let changed = |old: &Option<String>, new: &Option<String>| {
+        if old != new {
+            new.clone()
+        } else {
+            None
+        }
+    };
+    let transfer = EmploymentRecord {
+        kind: EmploymentEvent::Transfer,
+        department_id: changed(&before.department_id, &after.department_id),
+        position_id: changed(&before.position_id, &after.position_id),
+        manager_id: changed(&before.manager_id, &after.manager_id),
+        role: changed(&Some(before.role.clone()), &Some(after.role.clone())),
+        ..new_record(tenant, &after.id)
+    };
+    if transfer.department_id.is_none()
+        && transfer.position_id.is_none()
+        && transfer.manager_id.is_none()
+        && transfer.role.is_none()
+    {
+        return Ok(());
+    }
+
+    let records = timeline(db, tenant, &after.id).await?;
+    if !EmploymentState::as_of(&records, transfer.effective_date).employed {
+        return Ok(());
+    }
+    insert_record(db, transfer).await?;
+    Ok(())
+}
+
+/// A record for the employee effective today, to fill in.
+fn new_record(tenant: &Tenant, employee_id: &str) -> EmploymentRecord {
+    let now = Utc::now().naive_utc();
+    EmploymentRecord {
+        id: Uuid::new_v4().to_string(),
+        tenant_id: tenant.id().to_string(),
+        employee_id: employee_id.to_string(),
+        kind: EmploymentEvent::Transfer,
+        effective_date: now.date(),
+        department_id: None,
+        position_id: None,
+        manager_id: None,
+        role: None,
+        salary: None,
+        reason: None,
+        created_at: now,
+    }
+}
+
+async fn insert_record<C: ConnectionTrait>(
+    db: &C,
+    record: EmploymentRecord,
+) -> Result<EmploymentRecord, ApiError> {
+    Ok(record.into_active_model().reset_all().insert(db).await?)
+}
+
+/// Records of an employee, oldest first.
+pub async fn timeline<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee_id: &str,
+) -> Result<Vec<EmploymentRecord>, ApiError> {
+    Ok(tenant
+        .find::<employment_record::Entity>()
+        .filter(employment_record::Column::EmployeeId.eq(employee_id))
+        .order_by_asc(employment_record::Column::EffectiveDate)
+        .order_by_asc(employment_record::Column::CreatedAt)
+        .order_by_asc(employment_record::Column::Id)
+        .all(db)
+        .await?)
+}
+
+/// Adds a record to the history of a locked employee once the whole
+/// timeline still holds together with it, so a backdated termination cannot
+/// precede a later transfer. What is effective by today is copied onto the
+/// employee, who is returned when that changed them, and a hire or
+/// termination enables or disables their login.
+pub async fn record_employment<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee: &Employee,
+    data: &CreateEmploymentRecord,
+) -> Result<(EmploymentRecord, Option<Employee>), ApiError> {
+    let mut errors = FieldErrors::new();
+    match data.kind {
+        EmploymentEvent::Transfer
+            if data.department_id.is_none()
+                && data.position_id.is_none()
+                && data.manager_id.is_none()
+                && data.role.is_none() =>
+        {
+            errors.add(
+                "kind",
+                "A transfer must set a department, position, manager or role",
+            );
+        }
+        EmploymentEvent::SalaryChange if data.salary.is_none() => {
+            errors.add("salary", "A salary change must set the salary");
+        }
+        EmploymentEvent::Termination if data.reason.is_none() => {
+            errors.add("reason", "A termination must give a reason");
+        }
+        _ => {}
+    }
+    errors.into_result()?;
+
+    let placement = Placement {
+        department_id: data.department_id.as_deref(),
+        position_id: data.position_id.as_deref(),
+        manager_id: data.manager_id.as_deref(),
+    };
+    check_placement(db, tenant, &employee.id, &placement).await?;
+
+    let record = EmploymentRecord {
+        kind: data.kind,
+        effective_date: data.effective_date,
+        department_id: data.department_id.clone(),
+        position_id: data.position_id.clone(),
+        manager_id: data.manager_id.clone(),
+        role: data.role.clone(),
+        salary: data.salary,
+        reason: data.reason.clone(),
+        ..new_record(tenant, &employee.id)
+    };
+
+    // Created last, the record goes after the others of its date
+    let mut records = timeline(db, tenant, &employee.id).await?;
+    let at = records.partition_point(|r| r.effective_date <= record.effective_date);
+    records.insert(at, record.clone());
+    let mut state = EmploymentState::default();
+    for entry in &records {
+        if let Err(message) = state.apply(entry) {
+            let mut errors = FieldErrors::new();
+            errors.add("effective_date", message);
+            return Err(ApiError::InvalidFields(errors));
+        }
+    }
+
+    let record = insert_record(db, record).await?;
+
+    let today = Utc::now().date_naive();
+    if record.effective_date > today {
+        return Ok((record, None));
+    }
+    if matches!(
+        record.kind,
+        EmploymentEvent::Hire | EmploymentEvent::Termination
+    ) {
+        sync_login(db, tenant, employee).await?;
+    }
+    let updated = apply_employment(db, employee, &records, today).await?;
+    Ok((record, updated))
+}
+
+/// Copies where the employee stands on `today` by their history onto the
+/// employee. Returns the employee when that changed anything.
+async fn apply_employment<C: ConnectionTrait>(
+    db: &C,
+    employee: &Employee,
+    records: &[EmploymentRecord],
+    today: NaiveDate,
+) -> Result<Option<Employee>, ApiError> {
+    let current = EmploymentState::as_of(records, today);
+    let mut active: employee::ActiveModel = employee.clone().into();
+    let mut changed = false;
+    let mut sync = |field: &mut sea_orm::ActiveValue<Option<String>>,
+                    old: &Option<String>,
+                    new: &Option<String>| {
+        if new.is_some() && new != old {
+            *field = Set(new.clone());
+            changed = true;
+        }
+    };
+    sync(
+        &mut active.department_id,
+        &employee.department_id,
+        &current.department_id,
+    );
+    sync(
+        &mut active.position_id,
+        &employee.position_id,
+        &current.position_id,
+    );
+    sync(
+        &mut active.manager_id,
+        &employee.manager_id,
+        &current.manager_id,
+    );
+    if let Some(role) = current.role.filter(|role| role != &employee.role) {
+        active.role = Set(role);
+        changed = true;
+    }
+    if !changed {
+        return Ok(None);
+    }
+    Ok(Some(active.update(db).await?))
+}
+
+/// Enables the employee's login in their company while they are employed,
+/// and disables it once they are terminated or deleted. Employees without
+/// any history are taken to be employed.
+pub async fn sync_login<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee: &Employee,
+) -> Result<(), ApiError> {
+    let records = timeline(db, tenant, &employee.id).await?;
+    let employed =
+        records.is_empty() || EmploymentState::as_of(&records, Utc::now().date_naive()).employed;
+    let active = employed && employee.deleted_at.is_none();
+    auth_services::set_login_active(db, tenant, &employee.id, active).await
+}
+
+/// Starts the task that applies employment records made ahead of time as
+/// they take effect: it moves the employee and disables or enables their
+/// login.
+pub fn spawn_employment_sync(data: config::app::AppState) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(EMPLOYMENT_SYNC_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = sync_recent_employment(&data).await {
+                warn!("Employment sync failed: {}", e);
+            }
+        }
+    })
+}
+
+// The sync goes through the employees of every company
+#[allow(clippy::disallowed_methods)]
+async fn sync_recent_employment(data: &config::app::AppState) -> Result<(), ApiError> {
+    let today = Utc::now().date_naive();
+    let records = employment_record::Entity::find()
+        .filter(
+            employment_record::Column::EffectiveDate
+                .between(today - Duration::days(EMPLOYMENT_SYNC_LOOKBACK_DAYS), today),
+        )
+        .all(&data.db)
+        .await?;
+    // Whether a hire or termination took effect, which the login follows
+    let mut employees: HashMap<(String, String), bool> = HashMap::new();
+    for record in records {
+        let login = matches!(
+            record.kind,
+            EmploymentEvent::Hire | EmploymentEvent::Termination
+        );
+        *employees
+            .entry((record.tenant_id, record.employee_id))
+            .or_default() |= login;
+    }
+    for ((tenant_id, employee_id), login) in employees {
+        let tenant = Tenant::new(tenant_id);
+        if let Some(updated) = sync_employment(&data.db, &tenant, &employee_id, login).await? {
+            index_employees(data, &tenant, vec![updated]).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Applies the employee's history as of today, returning the employee when
+/// they were moved.
+async fn sync_employment(
+    db: &DatabaseConnection,
+    tenant: &Tenant,
+    employee_id: &str,
+    login: bool,
+) -> Result<Option<Employee>, ApiError> {
+    let txn = db.begin().await?;
+    let employee = tenant
+        .find_by_id::<employee::Entity>(employee_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?;
+    let Some(employee) = employee else {
+        return Ok(None);
+    };
+    if login {
+        sync_login(&txn, tenant, &employee).await?;
+    }
+    let records = timeline(&txn, tenant, &employee.id).await?;
+    let updated = match employee.deleted_at {
+        Some(_) => None,
+        None => apply_employment(&txn, &employee, &records, Utc::now().date_naive()).await?,
+    };
+    if let Some(updated) = &updated {
+        AuditContext::default()
+            .with_tenant(tenant.id())
+            .record(
+                &txn,
+                "employee",
+                &updated.id,
+                AuditAction::Update,
+                Some(&employee),
+                Some(updated),
+            )
+            .await?;
+    }
+    txn.commit().await?;
+    Ok(updated)
+}
+
+/// Employees employed on `query.date` who match the filters there, with
+/// where they stood. Employees without any history are not known to have
+/// been employed and are left out.
+pub async fn employed_as_of<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    query: &AsOfQuery,
+) -> Result<Vec<EmployeeAsOf>, ApiError> {
+    let states: HashMap<String, EmploymentState> = states_as_of(db, tenant, query.date)
+        .await?
+        .into_iter()
+        .filter(|(_, state)| query.matches(state))
+        .collect();
+
+    let employees = tenant
+        .find_active::<employee::Entity>()
+        .filter(employee::Column::Id.is_in(states.keys().cloned()))
+        .order_by_asc(employee::Column::Name)
+        .all(db)
+        .await?;
+
+    Ok(employees
+        .into_iter()
+        .filter_map(|employee| {
+            let employment = states.get(&employee.id)?.clone();
+            Some(EmployeeAsOf {
+                employee,
+                employment,
+            })
+        })
+        .collect())
+}
+
+/// Where each employee with any history stood on `date`, by employee id.
+pub async fn states_as_of<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    date: NaiveDate,
+) -> Result<HashMap<String, EmploymentState>, ApiError> {
+    let records = tenant
+        .find::<employment_record::Entity>()
+        .filter(employment_record::Column::EffectiveDate.lte(date))
+        .order_by_asc(employment_record::Column::EffectiveDate)
+        .order_by_asc(employment_record::Column::CreatedAt)
+        .order_by_asc(employment_record::Column::Id)
+        .all(db)
+        .await?;
+
+    let mut by_employee: HashMap<String, Vec<EmploymentRecord>> = HashMap::new();
+    for record in records {
+        by_employee
+            .entry(record.employee_id.clone())
+            .or_default()
+            .push(record);
+    }
+    Ok(by_employee
+        .into_iter()
+        .map(|(id, records)| (id, EmploymentState::as_of(&records, date)))
+        .collect())
+}
+
+/// The employee asked for, or the caller's own when none is given.
+pub async fn employee_for<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    caller: &Caller,
+    employee_id: Option<&str>,
+    lock: bool,
+) -> Result<Employee, ApiError> {
+    let Some(id) = employee_id.or(caller.employee.as_ref().map(|e| e.id.as_str())) else {
+        let mut errors = FieldErrors::new();
+        errors.add(
+            "employee_id",
+            "Required when the login is not linked to an employee",
+        );
+        return Err(ApiError::InvalidFields(errors));
+    };
+    let mut query = tenant.find_active_by_id::<employee::Entity>(id);
+    if lock {
+        query = query.lock_exclusive();
+    }
+    query
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Employee not found".to_string()))
+}
+
+/// The new value of a clearable field, or the current one when not given.
+fn pick<'a>(update: &'a Option<Option<String>>, current: &'a Option<String>) -> Option<&'a str> {
+    match update {
+        Some(value) => value.as_deref(),
+        None => current.as_deref(),
+    }
+}
+
+/// Employees who were paid or claimed expenses are kept for good, so their
+/// payslips and claims stay with the payroll runs and journal entries they
+/// belong to.
+pub async fn check_purge<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee_id: &str,
+) -> Result<(), ApiError> {
+    let paid = tenant
+        .find::<payslip::Entity>()
+        .filter(payslip::Column::EmployeeId.eq(employee_id))
+        .one(db)
+        .await?
+        .is_some();
+    if paid {
+        return Err(ApiError::Conflict(
+            "The employee has payslips and cannot be purged".to_string(),
+        ));
+    }
+    let claimed = tenant
+        .find::<expense_claim::Entity>()
+        .filter(expense_claim::Column::EmployeeId.eq(employee_id))
+        .one(db)
+        .await?
+        .is_some();
+    if claimed {
+        return Err(ApiError::Conflict(
+            "The employee has expense claims and cannot be purged".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn delete_employee<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee_id: &str,
+    precondition: &Precondition,
+) -> Result<(), ApiError> {
+    let employee = tenant
+        .find_active_by_id::<employee::Entity>(employee_id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or(ApiError::NotFound("Employee not found".to_string()))?;
+    precondition.check(employee.version)?;
+
+    let reports = tenant
+        .find_active::<employee::Entity>()
+        .filter(employee::Column::ManagerId.eq(employee_id))
+        .count(db)
+        .await?;
+    if reports > 0 {
+        return Err(ApiError::Conflict(
+            "Employee still has people reporting to them, reassign them first".to_string(),
+        ));
+    }
+
+    let mut employee_active: employee::ActiveModel = employee.into();
+    employee_active.deleted_at = Set(Some(Utc::now().naive_utc()));
+    employee_active.update(db).await?;
+    auth_services::set_login_active(db, tenant, employee_id, false).await?;
+
+    Ok(())
+}
+
+/// Everyone reporting to `root`, directly or through their managers, read
+/// one level of the organisation at a time.
+pub async fn reports<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    root: Employee,
+) -> Result<ReportNode, ApiError> {
+    let mut seen = HashSet::from([root.id.clone()]);
+    let mut by_manager: HashMap<String, Vec<Employee>> = HashMap::new();
+    let mut level = vec![root.id.clone()];
+
+    while !level.is_empty() {
+        let reports = tenant
+            .find_active::<employee::Entity>()
+            .filter(employee::Column::ManagerId.is_in(level))
+            .order_by_asc(employee::Column::Name)
+            .all(db)
+            .await?;
+        level = Vec::new();
+        for report in reports {
+            if !seen.insert(report.id.clone()) {
+                continue;
+            }
+            level.push(report.id.clone());
+            let manager_id = report.manager_id.clone().unwrap_or_default();
+            by_manager.entry(manager_id).or_default().push(report);
+        }
+    }
+
+    Ok(report_tree(root, &mut by_manager))
+}
+
+fn report_tree(employee: Employee, by_manager: &mut HashMap<String, Vec<Employee>>) -> ReportNode {
+    let reports = by_manager
+        .remove(&employee.id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|report| report_tree(report, by_manager))
+        .collect();
+    ReportNode { employee, reports }
+}
+
+/// Search documents for the employees, with their department and position
+/// names looked up.
+pub async fn documents<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employees: Vec<Employee>,
+) -> Result<Vec<EmployeeDocument>, ApiError> {
+    let department_ids: HashSet<&String> = employees
+        .iter()
+        .filter_map(|e| e.department_id.as_ref())
+        .collect();
+    let departments: HashMap<String, String> = tenant
+        .find::<department::Entity>()
+        .filter(department::Column::Id.is_in(department_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|d| (d.id, d.name))
+        .collect();
+
+    let position_ids: HashSet<&String> = employees
+        .iter()
+        .filter_map(|e| e.position_id.as_ref())
+        .collect();
+    let positions: HashMap<String, String> = tenant
+        .find::<position::Entity>()
+        .filter(position::Column::Id.is_in(position_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|p| (p.id, p.title))
+        .collect();
+
+    Ok(employees
+        .into_iter()
+        .map(|employee| EmployeeDocument {
+            department: employee
+                .department_id
+                .as_ref()
+                .and_then(|id| departments.get(id).cloned()),
+            position: employee
+                .position_id
+                .as_ref()
+                .and_then(|id| positions.get(id).cloned()),
+            employee,
+        })
+        .collect())
+}
+
+/// Adds or replaces the employees in the search index.
+pub async fn index_employees(
+    data: &config::app::AppState,
+    tenant: &Tenant,
+    employees: Vec<Employee>,
+) -> Result<(), ApiError> {
+    if employees.is_empty() {
+        return Ok(());
+    }
+    let documents = documents(&data.db, tenant, employees).await?;
+    data.meilisearch
+        .index(INDEX)
+        .add_documents(&documents, Some("id"))
+        .await?;
+    Ok(())
+}
+
+/// Indexes the active employees matching `condition` again, e.g. after the
+/// department they are in was renamed.
+pub async fn reindex_employees(
+    data: &config::app::AppState,
+    tenant: &Tenant,
+    condition: SimpleExpr,
+) -> Result<(), ApiError> {
+    let employees = tenant
+        .find_active::<employee::Entity>()
+        .filter(condition)
+        .all(&data.db)
+        .await?;
+    index_employees(data, tenant, employees).await
+}
+
+pub struct EmployeeBatch;
+
+#[async_trait]
+impl BatchResource for EmployeeBatch {
+    const ENTITY: &'static str = "employee";
+
+    type Create = CreateEmployee;
+    type Update = UpdateEmployee;
+    type Model = Employee;
+
+    async fn find(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        id: &str,
+    ) -> Result<Option<Employee>, ApiError> {
+        find_locked::<employee::Entity, _>(txn, tenant, id).await
+    }
+
+    async fn create(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        data: &CreateEmployee,
+    ) -> Result<Employee, ApiError> {
+        create_employee(txn, tenant, data).await
+    }
+
+    async fn update(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        id: &str,
+        precondition: &Precondition,
+        data: &UpdateEmployee,
+    ) -> Result<Employee, ApiError> {
+        update_employee(txn, tenant, id, precondition, data).await
+    }
+
+    async fn delete(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        id: &str,
+        precondition: &Precondition,
+    ) -> Result<(), ApiError> {
+        delete_employee(txn, tenant, id, precondition).await
+    }
+
+    fn id(model: &Employee) -> String {
+        model.id.clone()
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::{HashSet, VecDeque};
+use std::convert::Infallible;
+use std::time::Duration;
+
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use futures_util::stream;
+use sea_orm::{ColumnTrait, QueryFilter, QuerySelect};
+use serde_json::Value;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+use super::models::EventsQuery;
+use crate::error::ApiError;
+use crate::events::{ChangeEvent, EventBus, Published};
+use crate::middlewares::jwt::Claims;
+use crate::tenant::Tenant;
+use crate::v1::auth::services as auth_services;
+use crate::v1::company::services as company_services;
+use crate::webhooks::event_name;
+use entity::employee;
+
+pub const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+
+/// Comment sent on quiet streams so proxies keep the connection open.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+/// Reconnection delay suggested to clients, in milliseconds.
+const RETRY_MS: u64 = 3000;
+/// How often a stream checks that the caller still works in the company,
+/// and whom they manage.
+const RECHECK: Duration = Duration::from_secs(30);
+
+/// Stream changes to employees, inventory and orders as Server-Sent Events
+///
+/// Each event is named like `inventory.updated` and carries the change in
+/// its data. Reconnect with `Last-Event-ID` to receive what was missed; a
+/// `reset` event means some changes are no longer available and the client
+/// should reload. Records about an employee are only sent to them, their
+/// manager and administrators. The stream ends when the token expires or
+/// the caller's membership of the company is disabled.
+#[utoipa::path(
+    get,
+    path = "/v1/events",
+    tag = "events",
+    params(
+        EventsQuery,
+        ("Last-Event-ID" = Option<String>, Header, description = "Id of the last event received, to resume after it")
+    ),
+    responses(
+        (status = 200, description = "Stream of changes", content_type = "text/event-stream", body = ChangeEvent),
+        (status = 400, description = "Unknown event type"),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn stream_events(
+    req: HttpRequest,
+    data: web::Data<config::app::AppState>,
+    bus: web::Data<EventBus>,
+    tenant: Tenant,
+    claims: web::ReqData<Claims>,
+    query: web::Query<EventsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let entities = query.entities()?;
+    let claims = claims.into_inner();
+    let viewer = Viewer::load(&data, &tenant, &claims)
+        .await?
+        .ok_or_else(|| ApiError::Unauthorized("Not a member of this company".to_string()))?;
+    // Subscribe before reading the buffer so nothing published in between is missed
+    let receiver = bus.subscribe();
+
+    let last_event_id = req
+        .headers()
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|v| v.to_str().ok());
+    let resume_from = last_event_id
+        .and_then(|id| bus.parse_event_id(id))
+        .filter(|seq| bus.since(*seq).is_some());
+
+    let mut feed = Feed {
+        last: resume_from.unwrap_or_else(|| bus.latest()),
+        bus,
+        receiver,
+        entities,
+        queue: VecDeque::new(),
+        expires_at: expiry(claims.exp),
+        data,
+        tenant,
+        claims,
+        viewer,
+        recheck_at: Instant::now() + RECHECK,
+    };
+    feed.queue.push_back(format!("retry: {RETRY_MS}\n\n"));
+    if last_event_id.is_some() && resume_from.is_none() {
+        feed.queue.push_back(feed.reset());
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        // Stops nginx from holding events back
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(stream::unfold(feed, Feed::next)))
+}
+
+/// State of one client's stream.
+struct Feed {
+    bus: web::Data<EventBus>,
+    receiver: watch::Receiver<u64>,
+    entities: Vec<String>,
+    /// Sequence number of the last event looked at.
+    last: u64,
+    queue: VecDeque<String>,
+    expires_at: Instant,
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    claims: Claims,
+    viewer: Viewer,
+    recheck_at: Instant,
+}
+
+impl Feed {
+    async fn next(mut self) -> Option<(Result<web::Bytes, Infallible>, Self)> {
+        loop {
+            if let Some(frame) = self.queue.pop_front() {
+                return Some((Ok(web::Bytes::from(frame)), self));
+            }
+            if Instant::now() >= self.recheck_at {
+                match Viewer::load(&self.data, &self.tenant, &self.claims).await {
+                    Ok(Some(viewer)) => self.viewer = viewer,
+                    // Let go of the company, or could not tell
+                    _ => return None,
+                }
+                self.recheck_at = Instant::now() + RECHECK;
+            }
+
+            match self.bus.since(self.last) {
+                Some(events) => {
+                    for published in events {
+                        self.last = published.seq;
+                        if self.wants(&published.event) {
+                            self.queue.push_back(self.frame(&published));
+                        }
+                    }
+                }
+                // Fell so far behind that events were dropped
+                None => {
+                    self.last = self.bus.latest();
+                    self.queue.push_back(self.reset());
+                }
+            }
+            if !self.queue.is_empty() {
+                continue;
+            }
+
+            tokio::select! {
+                changed = self.receiver.changed() => {
+                    if changed.is_err() {
+                        return None;
+                    }
+                }
+                _ = tokio::time::sleep(KEEP_ALIVE) => {
+                    self.queue.push_back(": keep-alive\n\n".to_string());
+                }
+                _ = tokio::time::sleep_until(self.expires_at) => return None,
+                _ = tokio::time::sleep_until(self.recheck_at) => {}
+            }
+        }
+    }
+
+    /// Companies only see their own changes, and people only the records
+    /// they may read.
+    fn wants(&self, event: &ChangeEvent) -> bool {
+        event.tenant_id == self.tenant.id()
+            && self.entities.contains(&event.entity)
+            && self.viewer.may_see(event)
+    }
+
+    fn frame(&self, published: &Published) -> String {
+        let data = serde_json::to_string(&published.event).unwrap_or_default();
+        format!(
+            "id: {}\nevent: {}\ndata: {}\n\n",
+            self.bus.event_id(published.seq),
+            event_name(&published.event.entity, &published.event.action),
+            data
+        )
+    }
+
+    fn reset(&self) -> String {
+        format!(
+            "id: {}\nevent: reset\ndata: {{}}\n\n",
+            self.bus.event_id(self.last)
+        )
+    }
+}
+
+/// Who is watching a stream, for the visibility rules of the REST
+/// endpoints.
+struct Viewer {
+    is_admin: bool,
+    employee_id: Option<String>,
+    /// Employees who report to the viewer.
+    reports: HashSet<String>,
+}
+
+impl Viewer {
+    /// The caller as they stand now, or `None` once they no longer work in
+    /// the company.
+    async fn load(
+        data: &config::app::AppState,
+        tenant: &Tenant,
+        claims: &Claims,
+    ) -> Result<Option<Viewer>, ApiError> {
+        if !company_services::is_member(&data.db, tenant.id(), &claims.sub).await? {
+            return Ok(None);
+        }
+        let caller = auth_services::caller(&data.db, tenant, claims).await?;
+        let employee_id = caller.employee.map(|employee| employee.id);
+        let reports = match &employee_id {
+            Some(id) => tenant
+                .find_active::<employee::Entity>()
+                .select_only()
+                .column(employee::Column::Id)
+                .filter(employee::Column::ManagerId.eq(id))
+                .into_tuple::<String>()
+                .all(&data.db)
+                .await?
+                .into_iter()
+                .collect(),
+            None => HashSet::new(),
+        };
+        Ok(Some(Viewer {
+            is_admin: caller.is_admin,
+            employee_id,
+            reports,
+        }))
+    }
+
+    /// Records about one employee, the employee itself included, are seen
+    /// by them, their manager and administrators. Everything else is seen by
+    /// everyone in the company.
+    fn may_see(&self, event: &ChangeEvent) -> bool {
+        let field = |name: &str| event.data.get(name).and_then(Value::as_str);
+        let (owner, manager) = match event.entity.as_str() {
+            "employee" => (Some(event.id.as_str()), field("manager_id")),
+            _ => (field("employee_id"), None),
+        };
+        let Some(owner) = owner else {
+            return true;
+        };
+        let me = self.employee_id.as_deref();
+        self.is_admin
+            || me == Some(owner)
+            || (me.is_some() && manager == me)
+            || self.reports.contains(owner)
+    }
+}
+
+/// When a token expiring at `exp` (Unix seconds) runs out.
+fn expiry(exp: usize) -> Instant {
+    let now = chrono::Utc::now().timestamp().max(0) as usize;
+    Instant::now() + Duration::from_secs(exp.saturating_sub(now) as u64)
+}
@@ -0,0 +1,36 @@
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::error::ApiError;
+use crate::events::STREAMED_ENTITIES;
+
+#[derive(Deserialize, IntoParams)]
+pub struct EventsQuery {
+    /// Comma-separated entities to receive, e.g. `inventory,order`. All of
+    /// `employee`, `inventory` and `order` when left out.
+    pub types: Option<String>,
+}
+
+impl EventsQuery {
+    pub fn entities(&self) -> Result<Vec<String>, ApiError> {
+        let Some(types) = &self.types else {
+            return Ok(STREAMED_ENTITIES.iter().map(|e| e.to_string()).collect());
+        };
+        types
+            .split(',')
+            .map(str::trim)
+            .filter(|entity| !entity.is_empty())
+            .map(|entity| {
+                if STREAMED_ENTITIES.contains(&entity) {
+                    Ok(entity.to_string())
+                } else {
+                    Err(ApiError::ValidationError(format!(
+                        "Unknown event type '{}', expected one of {}",
+                        entity,
+                        STREAMED_ENTITIES.join(", ")
+                    )))
+                }
+            })
+            .collect()
+    }
+}
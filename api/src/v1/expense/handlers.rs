@@ -0,0 +1,924 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use config::file_storage::FileStorage;
+use sea_orm::{ConnectionTrait, QueryOrder, QuerySelect, TransactionTrait};
+use serde_json::json;
+
+use super::models::{
+    AddLine, ClaimFilter, ClaimWithLines, CreateCategory, CreateClaim, DecideClaim,
+    ExpenseCategory, ExpenseClaim, ExpenseLine, ReimburseClaim, UpdateCategory, RECEIPT_TYPES,
+};
+use super::services;
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::export::{stream_export, ExportFormat, ExportQuery};
+use crate::extractors::{Admin, ValidatedJson};
+use crate::middlewares::jwt::Claims;
+use crate::tenant::Tenant;
+use crate::v1::attachment::services::storage_error;
+use crate::v1::auth::models::Caller;
+use crate::v1::auth::services as auth_services;
+use crate::v1::employee::models::Employee;
+use crate::v1::employee::services as employee_services;
+use entity::audit_log::AuditAction;
+use entity::expense_claim::{self, ClaimStatus};
+use entity::{employee, expense_category, expense_line};
+
+/// List expense categories
+#[utoipa::path(
+    get,
+    path = "/v1/expense/category",
+    tag = "expense",
+    responses(
+        (status = 200, description = "List of expense categories", body = Vec<ExpenseCategory>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_categories(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+) -> Result<HttpResponse, ApiError> {
+    let categories = tenant
+        .find::<expense_category::Entity>()
+        .order_by_asc(expense_category::Column::Name)
+        .all(&data.db)
+        .await?;
+    Ok(HttpResponse::Ok().json(categories))
+}
+
+/// Create an expense category
+#[utoipa::path(
+    post,
+    path = "/v1/expense/category",
+    tag = "expense",
+    request_body = CreateCategory,
+    responses(
+        (status = 200, description = "Expense category created", body = ExpenseCategory),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not an administrator"),
+        (status = 409, description = "Name already in use"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_category(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    category: ValidatedJson<CreateCategory>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let category = services::create_category(&txn, &tenant, &category).await?;
+    audit
+        .record(
+            &txn,
+            "expense_category",
+            &category.id,
+            AuditAction::Create,
+            None,
+            Some(&category),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(category))
+}
+
+async fn find_category<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    id: &str,
+) -> Result<ExpenseCategory, ApiError> {
+    tenant
+        .find_by_id::<expense_category::Entity>(id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Expense category not found".to_string()))
+}
+
+/// Update an expense category
+#[utoipa::path(
+    put,
+    path = "/v1/expense/category/{id}",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense category ID")
+    ),
+    request_body = UpdateCategory,
+    responses(
+        (status = 200, description = "Expense category updated", body = ExpenseCategory),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Expense category not found"),
+        (status = 409, description = "Name already in use"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_category(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    id: web::Path<String>,
+    update: ValidatedJson<UpdateCategory>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let category = find_category(&txn, &tenant, &id).await?;
+    let updated = services::update_category(&txn, category.clone(), &update).await?;
+    audit
+        .record(
+            &txn,
+            "expense_category",
+            &updated.id,
+            AuditAction::Update,
+            Some(&category),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Delete an expense category nothing has been claimed in
+#[utoipa::path(
+    delete,
+    path = "/v1/expense/category/{id}",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense category ID")
+    ),
+    responses(
+        (status = 200, description = "Expense category deleted"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Expense category not found"),
+        (status = 409, description = "Expenses have been claimed in the category"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_category(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let category = find_category(&txn, &tenant, &id).await?;
+    services::delete_category(&txn, &tenant, category.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "expense_category",
+            &category.id,
+            AuditAction::Delete,
+            Some(&category),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Expense category deleted successfully"})))
+}
+
+/// List expense claims, latest first
+#[utoipa::path(
+    get,
+    path = "/v1/expense/claim",
+    tag = "expense",
+    params(
+        ClaimFilter
+    ),
+    responses(
+        (status = 200, description = "List of expense claims", body = Vec<ExpenseClaim>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_claims(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<ClaimFilter>,
+) -> Result<HttpResponse, ApiError> {
+    let claims = filter.select(&tenant).all(&data.db).await?;
+    Ok(HttpResponse::Ok().json(claims))
+}
+
+/// Export expense claims, e.g. the approved ones still to be paid
+#[utoipa::path(
+    get,
+    path = "/v1/expense/export",
+    tag = "expense",
+    params(
+        ("format" = Option<ExportFormat>, Query, description = "csv (default), xlsx or jsonl"),
+        ClaimFilter
+    ),
+    responses(
+        (status = 200, description = "Export file", content(
+            (String = "text/csv"),
+            (String = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+            (String = "application/x-ndjson")
+        )),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn export_claims(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    _admin: Admin,
+    filter: web::Query<ClaimFilter>,
+    query: web::Query<ExportQuery>,
+) -> HttpResponse {
+    stream_export(
+        data.db.clone(),
+        filter.select(&tenant),
+        query.format,
+        "expense_claims",
+    )
+}
+
+/// Claims are made and changed by the employee or their manager.
+fn check_claimant(caller: &Caller, employee: &Employee) -> Result<(), ApiError> {
+    if caller.is(&employee.id) || caller.manages(employee) {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(
+            "Only the employee or their manager can change their expense claims".to_string(),
+        ))
+    }
+}
+
+/// Start an expense claim for yourself or one of your reports
+#[utoipa::path(
+    post,
+    path = "/v1/expense/claim",
+    tag = "expense",
+    request_body = CreateClaim,
+    responses(
+        (status = 201, description = "Expense claim started", body = ExpenseClaim),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not the employee or their manager"),
+        (status = 404, description = "Employee not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_claim(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    claim: ValidatedJson<CreateClaim>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    let employee = employee_services::employee_for(
+        &txn,
+        &tenant,
+        &caller,
+        claim.employee_id.as_deref(),
+        false,
+    )
+    .await?;
+    check_claimant(&caller, &employee)?;
+
+    let claim = services::create_claim(&txn, &tenant, &employee, &claim).await?;
+    audit
+        .record(
+            &txn,
+            "expense_claim",
+            &claim.id,
+            AuditAction::Create,
+            None,
+            Some(&claim),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Created().json(claim))
+}
+
+/// Get an expense claim with its lines
+#[utoipa::path(
+    get,
+    path = "/v1/expense/claim/{id}",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense claim ID")
+    ),
+    responses(
+        (status = 200, description = "Expense claim found", body = ClaimWithLines),
+        (status = 404, description = "Expense claim not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_claim_by_id(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let claim = tenant
+        .find_by_id::<expense_claim::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Expense claim not found".to_string()))?;
+    let lines = services::lines(&data.db, &tenant, &claim.id).await?;
+
+    Ok(HttpResponse::Ok().json(ClaimWithLines { claim, lines }))
+}
+
+/// A locked claim with the employee it is for.
+async fn find_claim<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    id: &str,
+) -> Result<(ExpenseClaim, Employee), ApiError> {
+    let claim = tenant
+        .find_by_id::<expense_claim::Entity>(id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Expense claim not found".to_string()))?;
+    let employee = tenant
+        .find_by_id::<employee::Entity>(&claim.employee_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Employee not found".to_string()))?;
+    Ok((claim, employee))
+}
+
+async fn find_line<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    claim: &ExpenseClaim,
+    id: &str,
+) -> Result<ExpenseLine, ApiError> {
+    tenant
+        .find_by_id::<expense_line::Entity>(id)
+        .one(db)
+        .await?
+        .filter(|line| line.claim_id == claim.id)
+        .ok_or_else(|| ApiError::NotFound("Expense line not found".to_string()))
+}
+
+/// Removes receipts once the lines they belong to are gone; a file left
+/// behind only costs space.
+async fn delete_receipts(storage: &dyn FileStorage, lines: &[ExpenseLine]) {
+    for key in lines.iter().filter_map(|line| line.receipt_key.as_deref()) {
+        if let Err(err) = storage.delete(key).await {
+            log::warn!("Could not delete receipt {}: {:?}", key, err);
+        }
+    }
+}
+
+/// Delete an expense claim that was not submitted
+#[utoipa::path(
+    delete,
+    path = "/v1/expense/claim/{id}",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense claim ID")
+    ),
+    responses(
+        (status = 200, description = "Expense claim deleted"),
+        (status = 403, description = "Not the employee or their manager"),
+        (status = 404, description = "Expense claim not found"),
+        (status = 409, description = "The claim was already submitted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_claim(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    storage: web::Data<dyn FileStorage>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let (claim, employee) = find_claim(&txn, &tenant, &id).await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    check_claimant(&caller, &employee)?;
+    if !claim.status.is_editable() {
+        return Err(ApiError::Conflict(
+            "The claim was already submitted".to_string(),
+        ));
+    }
+
+    let lines = services::lines(&txn, &tenant, &claim.id).await?;
+    sea_orm::ModelTrait::delete(claim.clone(), &txn).await?;
+    audit
+        .record(
+            &txn,
+            "expense_claim",
+            &claim.id,
+            AuditAction::Delete,
+            Some(&claim),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+    delete_receipts(storage.as_ref(), &lines).await;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Expense claim deleted successfully"})))
+}
+
+/// Add an expense to a claim
+#[utoipa::path(
+    post,
+    path = "/v1/expense/claim/{id}/line",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense claim ID")
+    ),
+    request_body = AddLine,
+    responses(
+        (status = 201, description = "Expense added", body = ExpenseLine),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not the employee or their manager"),
+        (status = 404, description = "Expense claim not found"),
+        (status = 409, description = "The claim was already submitted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn add_line(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+    line: ValidatedJson<AddLine>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let (claim, employee) = find_claim(&txn, &tenant, &id).await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    check_claimant(&caller, &employee)?;
+
+    let (updated, line) = services::add_line(&txn, &tenant, claim.clone(), &line).await?;
+    audit
+        .record(
+            &txn,
+            "expense_claim",
+            &updated.id,
+            AuditAction::Update,
+            Some(&claim),
+            Some(&updated),
+        )
+        .await?;
+    audit
+        .record(
+            &txn,
+            "expense_line",
+            &line.id,
+            AuditAction::Create,
+            None,
+            Some(&line),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Created().json(line))
+}
+
+/// Remove an expense and its receipt from a claim
+#[utoipa::path(
+    delete,
+    path = "/v1/expense/claim/{id}/line/{line_id}",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense claim ID"),
+        ("line_id" = String, Path, description = "Expense line ID")
+    ),
+    responses(
+        (status = 200, description = "Expense removed", body = ExpenseClaim),
+        (status = 403, description = "Not the employee or their manager"),
+        (status = 404, description = "Expense not found"),
+        (status = 409, description = "The claim was already submitted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_line(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    storage: web::Data<dyn FileStorage>,
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, line_id) = path.into_inner();
+    let txn = data.db.begin().await?;
+    let (claim, employee) = find_claim(&txn, &tenant, &id).await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    check_claimant(&caller, &employee)?;
+    let line = find_line(&txn, &tenant, &claim, &line_id).await?;
+
+    let updated = services::delete_line(&txn, &tenant, claim.clone(), line.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "expense_line",
+            &line.id,
+            AuditAction::Delete,
+            Some(&line),
+            None,
+        )
+        .await?;
+    audit
+        .record(
+            &txn,
+            "expense_claim",
+            &updated.id,
+            AuditAction::Update,
+            Some(&claim),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+    delete_receipts(storage.as_ref(), &[line]).await;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Upload the receipt of an expense as the request body, replacing any
+/// earlier one
+#[utoipa::path(
+    put,
+    path = "/v1/expense/claim/{id}/line/{line_id}/receipt",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense claim ID"),
+        ("line_id" = String, Path, description = "Expense line ID")
+    ),
+    request_body(content = Vec<u8>, content_type = "application/pdf", description = "PDF, JPEG, PNG or WebP, up to 10 MB"),
+    responses(
+        (status = 200, description = "Receipt stored", body = ExpenseLine),
+        (status = 400, description = "Empty file or unsupported type"),
+        (status = 403, description = "Not the employee or their manager"),
+        (status = 404, description = "Expense not found"),
+        (status = 409, description = "The claim was already submitted"),
+        (status = 413, description = "File too large"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_receipt(
+    req: HttpRequest,
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    storage: web::Data<dyn FileStorage>,
+    path: web::Path<(String, String)>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    if !RECEIPT_TYPES.contains(&content_type.as_str()) {
+        return Err(ApiError::ValidationError(
+            "Receipts must be PDF, JPEG, PNG or WebP files".to_string(),
+        ));
+    }
+    if body.is_empty() {
+        return Err(ApiError::ValidationError("The file is empty".to_string()));
+    }
+
+    let (id, line_id) = path.into_inner();
+    let txn = data.db.begin().await?;
+    let (claim, employee) = find_claim(&txn, &tenant, &id).await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    check_claimant(&caller, &employee)?;
+    let line = find_line(&txn, &tenant, &claim, &line_id).await?;
+    if !claim.status.is_editable() {
+        return Err(ApiError::Conflict(
+            "The claim was already submitted".to_string(),
+        ));
+    }
+
+    let key = services::receipt_key(&tenant, &line);
+    storage.put(&key, &body).await.map_err(storage_error)?;
+    let updated =
+        services::attach_receipt(&txn, claim, line.clone(), key, &content_type, body.len()).await?;
+    audit
+        .record(
+            &txn,
+            "expense_line",
+            &updated.id,
+            AuditAction::Update,
+            Some(&line),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Download the receipt of an expense
+#[utoipa::path(
+    get,
+    path = "/v1/expense/claim/{id}/line/{line_id}/receipt",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense claim ID"),
+        ("line_id" = String, Path, description = "Expense line ID")
+    ),
+    responses(
+        (status = 200, description = "The receipt as uploaded", body = Vec<u8>),
+        (status = 403, description = "Not the employee or their manager"),
+        (status = 404, description = "Expense or receipt not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_receipt(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    claims: web::ReqData<Claims>,
+    storage: web::Data<dyn FileStorage>,
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, line_id) = path.into_inner();
+    let (claim, employee) = find_claim(&data.db, &tenant, &id).await?;
+    let caller = auth_services::caller(&data.db, &tenant, &claims).await?;
+    check_claimant(&caller, &employee)?;
+    let line = find_line(&data.db, &tenant, &claim, &line_id).await?;
+
+    let not_found = || ApiError::NotFound("Receipt not found".to_string());
+    let key = line.receipt_key.as_deref().ok_or_else(not_found)?;
+    let content = storage
+        .get(key)
+        .await
+        .map_err(storage_error)?
+        .ok_or_else(not_found)?;
+    Ok(HttpResponse::Ok()
+        .content_type(
+            line.receipt_type
+                .as_deref()
+                .unwrap_or("application/octet-stream"),
+        )
+        .insert_header((header::CONTENT_DISPOSITION, "inline"))
+        .body(content))
+}
+
+/// Submit a claim to the employee's manager
+#[utoipa::path(
+    post,
+    path = "/v1/expense/claim/{id}/submit",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense claim ID")
+    ),
+    responses(
+        (status = 200, description = "Expense claim submitted", body = ExpenseClaim),
+        (status = 400, description = "No expenses, or expenses without a receipt"),
+        (status = 403, description = "Not the employee or their manager"),
+        (status = 404, description = "Expense claim not found"),
+        (status = 409, description = "The claim was already submitted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn submit_claim(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let (claim, employee) = find_claim(&txn, &tenant, &id).await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    check_claimant(&caller, &employee)?;
+
+    let updated = services::submit_claim(&txn, &tenant, claim.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "expense_claim",
+            &updated.id,
+            AuditAction::Update,
+            Some(&claim),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// The manager decides on a submitted claim, finance on one the manager
+/// approved; nobody decides on their own.
+fn check_decider(
+    caller: &Caller,
+    claim: &ExpenseClaim,
+    employee: &Employee,
+) -> Result<(), ApiError> {
+    let allowed = match claim.status {
+        ClaimStatus::Submitted => caller.manages(employee),
+        ClaimStatus::ManagerApproved => caller.is_admin,
+        // Left for the services to refuse
+        _ => true,
+    };
+    if !allowed || caller.is(&employee.id) {
+        return Err(ApiError::Forbidden(
+            "Claims are decided by the employee's manager and then by finance".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn decide(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+    decision: DecideClaim,
+    approve: bool,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let (claim, employee) = find_claim(&txn, &tenant, &id).await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    check_decider(&caller, &claim, &employee)?;
+
+    let updated = if approve {
+        services::approve_claim(&txn, claim.clone(), &caller.user_id, decision.note).await?
+    } else {
+        services::reject_claim(&txn, claim.clone(), &caller.user_id, decision.note).await?
+    };
+    audit
+        .record(
+            &txn,
+            "expense_claim",
+            &updated.id,
+            AuditAction::Update,
+            Some(&claim),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Approve a claim as the employee's manager, or as finance after them
+#[utoipa::path(
+    post,
+    path = "/v1/expense/claim/{id}/approve",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense claim ID")
+    ),
+    request_body = DecideClaim,
+    responses(
+        (status = 200, description = "Expense claim approved", body = ExpenseClaim),
+        (status = 403, description = "Not the approver of this step"),
+        (status = 404, description = "Expense claim not found"),
+        (status = 409, description = "The claim is not waiting for approval"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn approve_claim(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+    decision: ValidatedJson<DecideClaim>,
+) -> Result<HttpResponse, ApiError> {
+    decide(data, tenant, audit, claims, id, decision.into_inner(), true).await
+}
+
+/// Reject a claim, sending it back to the employee to change
+#[utoipa::path(
+    post,
+    path = "/v1/expense/claim/{id}/reject",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense claim ID")
+    ),
+    request_body = DecideClaim,
+    responses(
+        (status = 200, description = "Expense claim rejected", body = ExpenseClaim),
+        (status = 403, description = "Not the approver of this step"),
+        (status = 404, description = "Expense claim not found"),
+        (status = 409, description = "The claim is not waiting for approval"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn reject_claim(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+    decision: ValidatedJson<DecideClaim>,
+) -> Result<HttpResponse, ApiError> {
+    decide(
+        data,
+        tenant,
+        audit,
+        claims,
+        id,
+        decision.into_inner(),
+        false,
+    )
+    .await
+}
+
+/// Pay an approved claim back by payment, or on the next payroll run
+#[utoipa::path(
+    post,
+    path = "/v1/expense/claim/{id}/reimburse",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense claim ID")
+    ),
+    request_body = ReimburseClaim,
+    responses(
+        (status = 200, description = "Reimbursement recorded", body = ExpenseClaim),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Expense claim not found"),
+        (status = 409, description = "The claim is not approved or is already being paid back"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn reimburse_claim(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    id: web::Path<String>,
+    reimbursement: ValidatedJson<ReimburseClaim>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let (claim, _) = find_claim(&txn, &tenant, &id).await?;
+    let updated = services::reimburse_claim(&txn, claim.clone(), &reimbursement).await?;
+    audit
+        .record(
+            &txn,
+            "expense_claim",
+            &updated.id,
+            AuditAction::Update,
+            Some(&claim),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
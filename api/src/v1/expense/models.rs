@@ -0,0 +1,168 @@
+use chrono::NaiveDate;
+use entity::expense_claim::{self, ClaimStatus, ReimbursementMethod};
+use entity::{expense_category, expense_line};
+use sea_orm::{ColumnTrait, QueryFilter, QueryOrder, Select};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::export::{Cell, ExportRow};
+use crate::tenant::Tenant;
+
+pub type ExpenseCategory = expense_category::Model;
+pub type ExpenseClaim = expense_claim::Model;
+pub type ExpenseLine = expense_line::Model;
+
+/// Largest receipt accepted.
+pub const MAX_RECEIPT_BYTES: usize = 10 * 1024 * 1024;
+
+/// Content types a receipt can be uploaded as.
+pub const RECEIPT_TYPES: &[&str] = &["application/pdf", "image/jpeg", "image/png", "image/webp"];
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateCategory {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: String,
+    #[validate(length(min = 1, max = 50, message = "Account must be 1 to 50 characters"))]
+    #[schema(min_length = 1, max_length = 50)]
+    pub account: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateCategory {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: Option<String>,
+    #[validate(length(min = 1, max = 50, message = "Account must be 1 to 50 characters"))]
+    #[schema(min_length = 1, max_length = 50)]
+    pub account: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateClaim {
+    /// Employee paid back, the caller's own employee record by default.
+    pub employee_id: Option<String>,
+    #[validate(length(min = 1, max = 255, message = "Title must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub title: String,
+    #[validate(length(equal = 3, message = "Currency must be a 3-letter code"))]
+    #[schema(min_length = 3, max_length = 3, example = "IDR")]
+    pub currency: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct AddLine {
+    pub category_id: String,
+    pub expense_date: NaiveDate,
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Description must be 1 to 255 characters"
+    ))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub description: String,
+    #[validate(range(exclusive_min = 0.0, message = "Amount must be more than 0"))]
+    pub amount: f64,
+    /// Currency spent in, the claim's own by default.
+    #[validate(length(equal = 3, message = "Currency must be a 3-letter code"))]
+    #[schema(min_length = 3, max_length = 3)]
+    pub currency: Option<String>,
+    /// Claim currency per unit of `currency`; required when they differ.
+    #[validate(range(exclusive_min = 0.0, message = "Exchange rate must be more than 0"))]
+    pub exchange_rate: Option<f64>,
+}
+
+/// Sent to approve or reject, `{}` without a note.
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct DecideClaim {
+    #[validate(length(max = 1000, message = "Note must be at most 1000 characters"))]
+    #[schema(max_length = 1000)]
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct ReimburseClaim {
+    pub method: ReimbursementMethod,
+    /// Transfer or cheque number of a payment.
+    #[validate(length(max = 255, message = "Reference must be at most 255 characters"))]
+    #[schema(max_length = 255)]
+    pub reference: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ClaimWithLines {
+    #[serde(flatten)]
+    pub claim: ExpenseClaim,
+    pub lines: Vec<ExpenseLine>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ClaimFilter {
+    pub employee_id: Option<String>,
+    pub status: Option<ClaimStatus>,
+    pub method: Option<ReimbursementMethod>,
+}
+
+impl ClaimFilter {
+    pub fn select(&self, tenant: &Tenant) -> Select<expense_claim::Entity> {
+        let mut query = tenant
+            .find::<expense_claim::Entity>()
+            .order_by_desc(expense_claim::Column::CreatedAt)
+            .order_by_asc(expense_claim::Column::Id);
+        if let Some(employee_id) = &self.employee_id {
+            query = query.filter(expense_claim::Column::EmployeeId.eq(employee_id));
+        }
+        if let Some(status) = self.status {
+            query = query.filter(expense_claim::Column::Status.eq(status));
+        }
+        if let Some(method) = self.method {
+            query = query.filter(expense_claim::Column::ReimbursementMethod.eq(method));
+        }
+        query
+    }
+}
+
+impl ExportRow for ExpenseClaim {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "id",
+            "employee_id",
+            "title",
+            "currency",
+            "total",
+            "status",
+            "finance_approved_at",
+            "reimbursement_method",
+            "reimbursement_reference",
+            "reimbursed_at",
+        ]
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        let timestamp = |at: Option<chrono::NaiveDateTime>| {
+            at.map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string())
+                .into()
+        };
+        vec![
+            self.id.clone().into(),
+            self.employee_id.clone().into(),
+            self.title.clone().into(),
+            self.currency.clone().into(),
+            self.total.into(),
+            serde_plain(&self.status).into(),
+            timestamp(self.finance_approved_at),
+            self.reimbursement_method.as_ref().map(serde_plain).into(),
+            self.reimbursement_reference.clone().into(),
+            timestamp(self.reimbursed_at),
+        ]
+    }
+}
+
+/// The name an enum is written with in JSON.
+fn serde_plain<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,50 @@
+use super::handlers;
+use super::models::MAX_RECEIPT_BYTES;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/expense")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .app_data(web::PayloadConfig::new(MAX_RECEIPT_BYTES))
+            .route("/category", web::get().to(handlers::get_categories))
+            .route("/category", web::post().to(handlers::create_category))
+            .route("/category/{id}", web::put().to(handlers::update_category))
+            .route(
+                "/category/{id}",
+                web::delete().to(handlers::delete_category),
+            )
+            .route("/export", web::get().to(handlers::export_claims))
+            .route("/claim", web::get().to(handlers::get_claims))
+            .route("/claim", web::post().to(handlers::create_claim))
+            .route("/claim/{id}", web::get().to(handlers::get_claim_by_id))
+            .route("/claim/{id}", web::delete().to(handlers::delete_claim))
+            .route("/claim/{id}/line", web::post().to(handlers::add_line))
+            .route(
+                "/claim/{id}/line/{line_id}",
+                web::delete().to(handlers::delete_line),
+            )
+            .route(
+                "/claim/{id}/line/{line_id}/receipt",
+                web::put().to(handlers::upload_receipt),
+            )
+            .route(
+                "/claim/{id}/line/{line_id}/receipt",
+                web::get().to(handlers::get_receipt),
+            )
+            .route("/claim/{id}/submit", web::post().to(handlers::submit_claim))
+            .route(
+                "/claim/{id}/approve",
+                web::post().to(handlers::approve_claim),
+            )
+            .route("/claim/{id}/reject", web::post().to(handlers::reject_claim))
+            .route(
+                "/claim/{id}/reimburse",
+                web::post().to(handlers::reimburse_claim),
+            ),
+    );
+}
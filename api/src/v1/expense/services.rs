@@ -0,0 +1,520 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Duration, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, IntoActiveModel,
+    PaginatorTrait, QueryFilter, QueryOrder, Set,
+};
+use uuid::Uuid;
+
+use super::models::{
+    AddLine, CreateCategory, CreateClaim, ExpenseCategory, ExpenseClaim, ExpenseLine,
+    ReimburseClaim, UpdateCategory,
+};
+use crate::error::{ApiError, FieldErrors};
+use crate::shared::db_utils::conflict_on_duplicate;
+use crate::shared::round;
+use crate::tenant::Tenant;
+use crate::v1::employee::models::Employee;
+use crate::v1::payroll::models::PayrollRun;
+use entity::expense_claim::{self, ClaimStatus, ReimbursementMethod};
+use entity::{expense_category, expense_line};
+
+const DUPLICATE_CATEGORY: &str = "An expense category with this name already exists";
+
+pub async fn create_category<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    data: &CreateCategory,
+) -> Result<ExpenseCategory, ApiError> {
+    expense_category::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        name: Set(data.name.clone()),
+        account: Set(data.account.clone()),
+        created_at: Set(Utc::now().naive_utc()),
+    }
+    .insert(db)
+    .await
+    .map_err(|e| conflict_on_duplicate(e, DUPLICATE_CATEGORY))
+}
+
+/// Claims already made keep the category, and are booked to its new
+/// account from then on.
+pub async fn update_category<C: ConnectionTrait>(
+    db: &C,
+    category: ExpenseCategory,
+    data: &UpdateCategory,
+) -> Result<ExpenseCategory, ApiError> {
+    let mut active = category.into_active_model();
+    if let Some(name) = &data.name {
+        active.name = Set(name.clone());
+    }
+    if let Some(account) = &data.account {
+        active.account = Set(account.clone());
+    }
+    active
+        .update(db)
+        .await
+        .map_err(|e| conflict_on_duplicate(e, DUPLICATE_CATEGORY))
+}
+
+pub async fn delete_category<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    category: ExpenseCategory,
+) -> Result<(), ApiError> {
+    let used = tenant
+        .find::<expense_line::Entity>()
+        .filter(expense_line::Column::CategoryId.eq(&category.id))
+        .count(db)
+        .await?;
+    if used > 0 {
+        return Err(ApiError::Conflict(
+            "Expenses have been claimed in this category".to_string(),
+        ));
+    }
+    category.into_active_model().delete(db).await?;
+    Ok(())
+}
+
+pub async fn create_claim<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee: &Employee,
+    data: &CreateClaim,
+) -> Result<ExpenseClaim, ApiError> {
+    let now = Utc::now().naive_utc();
+    Ok(expense_claim::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        employee_id: Set(employee.id.clone()),
+        title: Set(data.title.clone()),
+        currency: Set(data.currency.to_uppercase()),
+        total: Set(0.0),
+        status: Set(ClaimStatus::Draft),
+        submitted_at: Set(None),
+        manager_approved_by: Set(None),
+        manager_approved_at: Set(None),
+        finance_approved_by: Set(None),
+        finance_approved_at: Set(None),
+        rejected_by: Set(None),
+        rejected_at: Set(None),
+        decision_note: Set(None),
+        reimbursement_method: Set(None),
+        reimbursement_reference: Set(None),
+        payroll_run_id: Set(None),
+        reimbursed_at: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(db)
+    .await?)
+}
+
+pub async fn lines<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    claim_id: &str,
+) -> Result<Vec<ExpenseLine>, ApiError> {
+    Ok(tenant
+        .find::<expense_line::Entity>()
+        .filter(expense_line::Column::ClaimId.eq(claim_id))
+        .order_by_asc(expense_line::Column::ExpenseDate)
+        .order_by_asc(expense_line::Column::CreatedAt)
+        .all(db)
+        .await?)
+}
+
+/// Lets a locked claim be changed, putting a rejected one back into draft.
+pub async fn reopen<C: ConnectionTrait>(
+    db: &C,
+    claim: ExpenseClaim,
+) -> Result<ExpenseClaim, ApiError> {
+    match claim.status {
+        ClaimStatus::Draft => Ok(claim),
+        ClaimStatus::Rejected => {
+            let mut active = claim.into_active_model();
+            active.status = Set(ClaimStatus::Draft);
+            active.updated_at = Set(Utc::now().naive_utc());
+            Ok(active.update(db).await?)
+        }
+        _ => Err(ApiError::Conflict(
+            "The claim was already submitted".to_string(),
+        )),
+    }
+}
+
+async fn update_total<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    claim: ExpenseClaim,
+) -> Result<ExpenseClaim, ApiError> {
+    let total: f64 = lines(db, tenant, &claim.id)
+        .await?
+        .iter()
+        .map(|line| line.claim_amount)
+        .sum();
+    let mut active = claim.into_active_model();
+    active.total = Set(round(total));
+    active.updated_at = Set(Utc::now().naive_utc());
+    Ok(active.update(db).await?)
+}
+
+/// Adds a line to a locked claim, returning the claim with its new total.
+pub async fn add_line<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    claim: ExpenseClaim,
+    data: &AddLine,
+) -> Result<(ExpenseClaim, ExpenseLine), ApiError> {
+    let claim = reopen(db, claim).await?;
+
+    let mut errors = FieldErrors::new();
+    let category = tenant
+        .find_by_id::<expense_category::Entity>(&data.category_id)
+        .one(db)
+        .await?;
+    if category.is_none() {
+        errors.add("category_id", "Expense category not found");
+    }
+    let currency = data
+        .currency
+        .as_ref()
+        .map_or_else(|| claim.currency.clone(), |c| c.to_uppercase());
+    let exchange_rate = match data.exchange_rate {
+        None if currency == claim.currency => 1.0,
+        Some(rate) if currency == claim.currency && rate != 1.0 => {
+            errors.add(
+                "exchange_rate",
+                "Lines in the claim's currency have no exchange rate",
+            );
+            rate
+        }
+        Some(rate) => rate,
+        None => {
+            errors.add(
+                "exchange_rate",
+                format!("Give the rate from {} to {}", currency, claim.currency),
+            );
+            1.0
+        }
+    };
+    errors.into_result()?;
+
+    let line = expense_line::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        claim_id: Set(claim.id.clone()),
+        category_id: Set(data.category_id.clone()),
+        expense_date: Set(data.expense_date),
+        description: Set(data.description.clone()),
+        amount: Set(data.amount),
+        currency: Set(currency),
+        exchange_rate: Set(exchange_rate),
+        claim_amount: Set(round(data.amount * exchange_rate)),
+        receipt_key: Set(None),
+        receipt_type: Set(None),
+        receipt_size: Set(None),
+        created_at: Set(Utc::now().naive_utc()),
+    }
+    .insert(db)
+    .await?;
+    let claim = update_total(db, tenant, claim).await?;
+    Ok((claim, line))
+}
+
+/// Removes a line from a locked claim, returning the claim with its new
+/// total. The line's receipt is left for the caller to remove from storage.
+pub async fn delete_line<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    claim: ExpenseClaim,
+    line: ExpenseLine,
+) -> Result<ExpenseClaim, ApiError> {
+    let claim = reopen(db, claim).await?;
+    line.into_active_model().delete(db).await?;
+    update_total(db, tenant, claim).await
+}
+
+/// Storage key of a line's receipt.
+pub fn receipt_key(tenant: &Tenant, line: &ExpenseLine) -> String {
+    format!("{}/receipts/{}", tenant.id(), line.id)
+}
+
+/// Records a receipt stored for a line of a locked claim.
+pub async fn attach_receipt<C: ConnectionTrait>(
+    db: &C,
+    claim: ExpenseClaim,
+    line: ExpenseLine,
+    key: String,
+    content_type: &str,
+    size: usize,
+) -> Result<ExpenseLine, ApiError> {
+    reopen(db, claim).await?;
+    let mut active = line.into_active_model();
+    active.receipt_key = Set(Some(key));
+    active.receipt_type = Set(Some(content_type.to_string()));
+    active.receipt_size = Set(Some(size as i32));
+    Ok(active.update(db).await?)
+}
+
+/// Sends a claim with a receipt on every line to the employee's manager.
+pub async fn submit_claim<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    claim: ExpenseClaim,
+) -> Result<ExpenseClaim, ApiError> {
+    if !claim.status.is_editable() {
+        return Err(ApiError::Conflict(
+            "The claim was already submitted".to_string(),
+        ));
+    }
+    let lines = lines(db, tenant, &claim.id).await?;
+    if lines.is_empty() {
+        return Err(ApiError::ValidationError(
+            "Add at least one expense before submitting".to_string(),
+        ));
+    }
+    let missing = lines
+        .iter()
+        .filter(|line| line.receipt_key.is_none())
+        .count();
+    if missing > 0 {
+        let mut errors = FieldErrors::new();
+        errors.add(
+            "lines",
+            format!("{missing} of the expenses have no receipt"),
+        );
+        return Err(ApiError::InvalidFields(errors));
+    }
+
+    let now = Utc::now().naive_utc();
+    let mut active = claim.into_active_model();
+    active.status = Set(ClaimStatus::Submitted);
+    active.submitted_at = Set(Some(now));
+    active.manager_approved_by = Set(None);
+    active.manager_approved_at = Set(None);
+    active.finance_approved_by = Set(None);
+    active.finance_approved_at = Set(None);
+    active.rejected_by = Set(None);
+    active.rejected_at = Set(None);
+    active.decision_note = Set(None);
+    active.updated_at = Set(now);
+    Ok(active.update(db).await?)
+}
+
+/// Moves a claim one step along the approvals: from the manager to
+/// finance, or from finance to being paid back.
+pub async fn approve_claim<C: ConnectionTrait>(
+    db: &C,
+    claim: ExpenseClaim,
+    user_id: &str,
+    note: Option<String>,
+) -> Result<ExpenseClaim, ApiError> {
+    let now = Utc::now().naive_utc();
+    let status = claim.status;
+    let mut active = claim.into_active_model();
+    match status {
+        ClaimStatus::Submitted => {
+            active.status = Set(ClaimStatus::ManagerApproved);
+            active.manager_approved_by = Set(Some(user_id.to_string()));
+            active.manager_approved_at = Set(Some(now));
+        }
+        ClaimStatus::ManagerApproved => {
+            active.status = Set(ClaimStatus::Approved);
+            active.finance_approved_by = Set(Some(user_id.to_string()));
+            active.finance_approved_at = Set(Some(now));
+        }
+        _ => {
+            return Err(ApiError::Conflict(
+                "Only submitted claims can be approved".to_string(),
+            ))
+        }
+    }
+    active.decision_note = Set(note);
+    active.updated_at = Set(now);
+    Ok(active.update(db).await?)
+}
+
+pub async fn reject_claim<C: ConnectionTrait>(
+    db: &C,
+    claim: ExpenseClaim,
+    user_id: &str,
+    note: Option<String>,
+) -> Result<ExpenseClaim, ApiError> {
+    if !matches!(
+        claim.status,
+        ClaimStatus::Submitted | ClaimStatus::ManagerApproved
+    ) {
+        return Err(ApiError::Conflict(
+            "Only submitted claims can be rejected".to_string(),
+        ));
+    }
+    let now = Utc::now().naive_utc();
+    let mut active = claim.into_active_model();
+    active.status = Set(ClaimStatus::Rejected);
+    active.rejected_by = Set(Some(user_id.to_string()));
+    active.rejected_at = Set(Some(now));
+    active.decision_note = Set(note);
+    active.updated_at = Set(now);
+    Ok(active.update(db).await?)
+}
+
+/// Pays an approved claim back: a payment settles it at once, payroll
+/// leaves it for the next run to pay out.
+pub async fn reimburse_claim<C: ConnectionTrait>(
+    db: &C,
+    claim: ExpenseClaim,
+    data: &ReimburseClaim,
+) -> Result<ExpenseClaim, ApiError> {
+    if claim.status != ClaimStatus::Approved {
+        return Err(ApiError::Conflict(
+            "Only approved claims can be paid back".to_string(),
+        ));
+    }
+    if claim.reimbursement_method.is_some() {
+        return Err(ApiError::Conflict(
+            "The claim is already being paid back through payroll".to_string(),
+        ));
+    }
+    let now = Utc::now().naive_utc();
+    let mut active = claim.into_active_model();
+    active.reimbursement_method = Set(Some(data.method));
+    active.reimbursement_reference = Set(data.reference.clone());
+    if data.method == ReimbursementMethod::Payment {
+        active.status = Set(ClaimStatus::Reimbursed);
+        active.reimbursed_at = Set(Some(now));
+    }
+    active.updated_at = Set(now);
+    Ok(active.update(db).await?)
+}
+
+/// What a payroll run pays an employee back for.
+#[derive(Debug, Default)]
+pub struct PayrollReimbursement {
+    pub claim_ids: Vec<String>,
+    /// Amount per category, with the category's account.
+    pub categories: Vec<(ExpenseCategory, f64)>,
+}
+
+/// Claims left for payroll that were approved by the end of the run's
+/// period, by employee. Claims an earlier calculation of the run took are
+/// released first.
+pub async fn payroll_reimbursements<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    run: &PayrollRun,
+) -> Result<HashMap<String, PayrollReimbursement>, ApiError> {
+    tenant
+        .update_many::<expense_claim::Entity>()
+        .col_expr(
+            expense_claim::Column::PayrollRunId,
+            Expr::value(Option::<String>::None),
+        )
+        .filter(expense_claim::Column::PayrollRunId.eq(&run.id))
+        .exec(db)
+        .await?;
+    let claims = tenant
+        .find::<expense_claim::Entity>()
+        .filter(expense_claim::Column::Status.eq(ClaimStatus::Approved))
+        .filter(expense_claim::Column::ReimbursementMethod.eq(ReimbursementMethod::Payroll))
+        .filter(expense_claim::Column::PayrollRunId.is_null())
+        .filter(
+            expense_claim::Column::FinanceApprovedAt
+                .lt((run.period_end + Duration::days(1)).and_time(Default::default())),
+        )
+        .all(db)
+        .await?;
+    if claims.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let categories: HashMap<String, ExpenseCategory> = tenant
+        .find::<expense_category::Entity>()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|category| (category.id.clone(), category))
+        .collect();
+    let employees: HashMap<String, String> = claims
+        .iter()
+        .map(|claim| (claim.id.clone(), claim.employee_id.clone()))
+        .collect();
+    let lines = tenant
+        .find::<expense_line::Entity>()
+        .filter(expense_line::Column::ClaimId.is_in(employees.keys().cloned()))
+        .all(db)
+        .await?;
+
+    let mut amounts: BTreeMap<(String, String), f64> = BTreeMap::new();
+    for line in lines {
+        *amounts
+            .entry((employees[&line.claim_id].clone(), line.category_id))
+            .or_default() += line.claim_amount;
+    }
+    let mut reimbursements: HashMap<String, PayrollReimbursement> = HashMap::new();
+    for claim in claims {
+        reimbursements
+            .entry(claim.employee_id)
+            .or_default()
+            .claim_ids
+            .push(claim.id);
+    }
+    for ((employee_id, category_id), amount) in amounts {
+        if let (Some(reimbursement), Some(category)) = (
+            reimbursements.get_mut(&employee_id),
+            categories.get(&category_id),
+        ) {
+            reimbursement
+                .categories
+                .push((category.clone(), round(amount)));
+        }
+    }
+    Ok(reimbursements)
+}
+
+/// Marks claims as paid back on a run's payslips.
+pub async fn link_to_run<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    run_id: &str,
+    claim_ids: &[String],
+) -> Result<(), ApiError> {
+    tenant
+        .update_many::<expense_claim::Entity>()
+        .col_expr(expense_claim::Column::PayrollRunId, Expr::value(run_id))
+        .filter(expense_claim::Column::Id.is_in(claim_ids.iter().cloned()))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Settles the claims a run pays back once it is posted.
+pub async fn reimbursed_by_run<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    run: &PayrollRun,
+) -> Result<(), ApiError> {
+    let now = Utc::now().naive_utc();
+    tenant
+        .update_many::<expense_claim::Entity>()
+        .col_expr(
+            expense_claim::Column::Status,
+            Expr::value(ClaimStatus::Reimbursed),
+        )
+        .col_expr(
+            expense_claim::Column::ReimbursementReference,
+            Expr::value(format!(
+                "Payroll {} to {}",
+                run.period_start, run.period_end
+            )),
+        )
+        .col_expr(expense_claim::Column::ReimbursedAt, Expr::value(now))
+        .col_expr(expense_claim::Column::UpdatedAt, Expr::value(now))
+        .filter(expense_claim::Column::PayrollRunId.eq(&run.id))
+        .filter(expense_claim::Column::Status.eq(ClaimStatus::Approved))
+        .exec(db)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,517 @@
+use std::collections::HashSet;
+
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseTransaction, IntoActiveModel, QueryFilter, Set,
+    TransactionTrait,
+};
+use serde::Serialize;
+use uuid::Uuid;
+use validator::{Validate, ValidationErrors};
+
+use super::models::{ImportEntity, ImportFormat, ImportQuery, ImportReport, RowError};
+use super::parser::{self, Row};
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::tenant::Tenant;
+use crate::v1::customer::models::CreateCustomer;
+use crate::v1::employee::models::CreateEmployee;
+use crate::v1::employee::services::index_employees;
+use crate::v1::inventory::models::CreateInventoryItem;
+use crate::v1::stock::services::{post_movement, record_stock_change, MovementDraft};
+use entity::audit_log::AuditAction;
+use entity::inventory::Tracking;
+use entity::stock_movement::MovementType;
+use entity::{customer, employee, inventory};
+
+const XLSX_MIME: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+/// Import inventory items, employees or customers from a CSV or XLSX file.
+/// Rows are upserted by SKU (inventory) or email (employees and customers)
+/// and the batch is only applied when every row is valid.
+#[utoipa::path(
+    post,
+    path = "/v1/import/{entity}",
+    tag = "import",
+    params(
+        ("entity" = ImportEntity, Path, description = "Entity to import"),
+        ("dry_run" = Option<bool>, Query, description = "Validate without applying"),
+        ("format" = Option<String>, Query, description = "csv or xlsx, defaults to the Content-Type")
+    ),
+    request_body(content = String, content_type = "text/csv"),
+    responses(
+        (status = 200, description = "Import report", body = ImportReport),
+        (status = 400, description = "Unreadable file or unsupported entity"),
+        (status = 422, description = "Rows failed validation, nothing was applied", body = ImportReport),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn import(
+    req: HttpRequest,
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    entity: web::Path<String>,
+    query: web::Query<ImportQuery>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    let entity = ImportEntity::parse(&entity.into_inner()).ok_or_else(|| {
+        ApiError::ValidationError(
+            "Entity must be one of inventory, employee or customer".to_string(),
+        )
+    })?;
+    let format = match query.format {
+        Some(format) => format,
+        None => detect_format(&req)?,
+    };
+
+    let rows = parser::parse(format, &body)?;
+    if rows.is_empty() {
+        return Err(ApiError::ValidationError(
+            "The file has no data rows".to_string(),
+        ));
+    }
+
+    let mut report = ImportReport {
+        entity,
+        dry_run: query.dry_run,
+        applied: false,
+        total_rows: rows.len(),
+        created: 0,
+        updated: 0,
+        errors: Vec::new(),
+    };
+
+    // Dry runs are rolled back, so there is nothing to audit or announce
+    let audit = (!report.dry_run).then_some(&audit);
+    let txn = data.db.begin().await?;
+    let ids = match entity {
+        ImportEntity::Inventory => {
+            import_inventory(&txn, &tenant, audit, &rows, &mut report).await?
+        }
+        ImportEntity::Employee => {
+            import_employees(&txn, &tenant, audit, &rows, &mut report).await?
+        }
+        ImportEntity::Customer => {
+            import_customers(&txn, &tenant, audit, &rows, &mut report).await?
+        }
+    };
+
+    if !report.errors.is_empty() {
+        txn.rollback().await?;
+        return Ok(HttpResponse::UnprocessableEntity().json(report));
+    }
+    if report.dry_run {
+        txn.rollback().await?;
+        return Ok(HttpResponse::Ok().json(report));
+    }
+
+    txn.commit().await?;
+    report.applied = true;
+    index_documents(&data, &tenant, entity, ids).await?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+fn detect_format(req: &HttpRequest) -> Result<ImportFormat, ApiError> {
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+
+    if content_type.starts_with(XLSX_MIME) {
+        Ok(ImportFormat::Xlsx)
+    } else if content_type.starts_with("text/csv") || content_type.starts_with("text/plain") {
+        Ok(ImportFormat::Csv)
+    } else {
+        Err(ApiError::ValidationError(
+            "Send the file as text/csv or XLSX, or pass ?format=csv|xlsx".to_string(),
+        ))
+    }
+}
+
+fn check_unique(seen: &mut HashSet<String>, key: &str, field: &str) -> Result<(), String> {
+    if seen.insert(key.to_lowercase()) {
+        Ok(())
+    } else {
+        Err(format!("Duplicate {} '{}' in file", field, key))
+    }
+}
+
+fn validation_message(errors: ValidationErrors) -> String {
+    match ApiError::from(errors) {
+        ApiError::InvalidFields(fields) => fields.to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Audits an imported row, as an update of `before` or else as created.
+async fn record<T: Serialize>(
+    txn: &DatabaseTransaction,
+    audit: Option<&AuditContext>,
+    entity: &str,
+    id: &str,
+    before: Option<&T>,
+    after: &T,
+) -> Result<(), ApiError> {
+    let Some(audit) = audit else {
+        return Ok(());
+    };
+    let action = if before.is_some() {
+        AuditAction::Update
+    } else {
+        AuditAction::Create
+    };
+    audit
+        .record(txn, entity, id, action, before, Some(after))
+        .await
+}
+
+fn inventory_row(row: &Row) -> Result<CreateInventoryItem, String> {
+    let tracking = match row.optional("tracking").as_deref() {
+        None | Some("none") => Tracking::None,
+        Some("lot") => Tracking::Lot,
+        Some("serial") => Tracking::Serial,
+        Some(_) => return Err("Column 'tracking' must be none, lot or serial".to_string()),
+    };
+
+    let item = CreateInventoryItem {
+        sku: row.optional("sku"),
+        name: row.required("name")?,
+        quantity: row.parse_or("quantity", 0)?,
+        price: row.parse("price")?,
+        tracking,
+        location: row.optional("location"),
+        category: row.optional("category"),
+        reorder_point: None,
+    };
+    item.validate().map_err(validation_message)?;
+    if item.tracking != Tracking::None && item.quantity != 0 {
+        return Err(
+            "Tracked items must be imported empty and stocked through receipts".to_string(),
+        );
+    }
+
+    Ok(item)
+}
+
+async fn import_inventory(
+    txn: &DatabaseTransaction,
+    tenant: &Tenant,
+    audit: Option<&AuditContext>,
+    rows: &[Row],
+    report: &mut ImportReport,
+) -> Result<Vec<String>, ApiError> {
+    let mut ids = Vec::new();
+    let mut seen = HashSet::new();
+
+    for row in rows {
+        let item = match inventory_row(row).and_then(|item| {
+            if let Some(sku) = &item.sku {
+                check_unique(&mut seen, sku, "SKU")?;
+            }
+            Ok(item)
+        }) {
+            Ok(item) => item,
+            Err(message) => {
+                report.errors.push(RowError {
+                    row: row.number,
+                    message,
+                });
+                continue;
+            }
+        };
+
+        let existing = match &item.sku {
+            Some(sku) => {
+                tenant
+                    .find_active::<inventory::Entity>()
+                    .filter(inventory::Column::Sku.eq(sku))
+                    .one(txn)
+                    .await?
+            }
+            None => None,
+        };
+
+        let Some(existing) = existing else {
+            let id = Uuid::new_v4().to_string();
+            let inserted = inventory::ActiveModel {
+                id: Set(id.clone()),
+                tenant_id: Set(tenant.id().to_string()),
+                sku: Set(item.sku),
+                name: Set(item.name),
+                quantity: Set(item.quantity),
+                price: Set(item.price),
+                tracking: Set(item.tracking),
+                location: Set(item.location),
+                category: Set(item.category),
+                ..Default::default()
+            }
+            .insert(txn)
+            .await?;
+            record(txn, audit, "inventory", &id, None, &inserted).await?;
+            report.created += 1;
+            ids.push(id);
+            continue;
+        };
+
+        let quantity_change = item.quantity - existing.quantity;
+        if quantity_change != 0 && existing.tracking != Tracking::None {
+            report.errors.push(RowError {
+                row: row.number,
+                message: "Quantity of tracked items can only be changed through stock movements"
+                    .to_string(),
+            });
+            continue;
+        }
+
+        let id = existing.id.clone();
+        let before = existing.clone();
+        let mut active_item = existing.into_active_model();
+        active_item.name = Set(item.name);
+        active_item.price = Set(item.price);
+        if item.location.is_some() {
+            active_item.location = Set(item.location);
+        }
+        if item.category.is_some() {
+            active_item.category = Set(item.category);
+        }
+        let updated = active_item.update(txn).await?;
+
+        if quantity_change == 0 {
+            record(txn, audit, "inventory", &id, Some(&before), &updated).await?;
+        } else {
+            post_movement(
+                txn,
+                tenant,
+                MovementDraft {
+                    inventory_id: id.clone(),
+                    lot_id: None,
+                    movement_type: MovementType::Adjustment,
+                    quantity: quantity_change,
+                    partner_id: None,
+                    reference: None,
+                    reason: Some("import".to_string()),
+                },
+            )
+            .await?;
+            if let Some(audit) = audit {
+                record_stock_change(txn, tenant, audit, &before).await?;
+            }
+        }
+        report.updated += 1;
+        ids.push(id);
+    }
+
+    Ok(ids)
+}
+
+async fn import_employees(
+    txn: &DatabaseTransaction,
+    tenant: &Tenant,
+    audit: Option<&AuditContext>,
+    rows: &[Row],
+    report: &mut ImportReport,
+) -> Result<Vec<String>, ApiError> {
+    let mut ids = Vec::new();
+    let mut seen = HashSet::new();
+
+    for row in rows {
+        let parsed = (|| {
+            let employee = CreateEmployee {
+                name: row.required("name")?,
+                role: row.required("role")?,
+                email: row.required("email")?,
+                department_id: None,
+                position_id: None,
+                manager_id: None,
+                hire_date: None,
+                salary: None,
+            };
+            employee.validate().map_err(validation_message)?;
+            check_unique(&mut seen, &employee.email, "email")?;
+            Ok::<_, String>(employee)
+        })();
+        let employee = match parsed {
+            Ok(employee) => employee,
+            Err(message) => {
+                report.errors.push(RowError {
+                    row: row.number,
+                    message,
+                });
+                continue;
+            }
+        };
+
+        let existing = tenant
+            .find_active::<employee::Entity>()
+            .filter(employee::Column::Email.eq(&employee.email))
+            .one(txn)
+            .await?;
+
+        match existing {
+            Some(existing) => {
+                let id = existing.id.clone();
+                let before = existing.clone();
+                let mut active_employee = existing.into_active_model();
+                active_employee.name = Set(employee.name);
+                active_employee.role = Set(employee.role);
+                let updated = active_employee.update(txn).await?;
+                record(txn, audit, "employee", &id, Some(&before), &updated).await?;
+                report.updated += 1;
+                ids.push(id);
+            }
+            None => {
+                let id = Uuid::new_v4().to_string();
+                let inserted = employee::ActiveModel {
+                    id: Set(id.clone()),
+                    tenant_id: Set(tenant.id().to_string()),
+                    name: Set(employee.name),
+                    role: Set(employee.role),
+                    email: Set(employee.email),
+                    ..Default::default()
+                }
+                .insert(txn)
+                .await?;
+                record(txn, audit, "employee", &id, None, &inserted).await?;
+                report.created += 1;
+                ids.push(id);
+            }
+        }
+    }
+
+    Ok(ids)
+}
+
+async fn import_customers(
+    txn: &DatabaseTransaction,
+    tenant: &Tenant,
+    audit: Option<&AuditContext>,
+    rows: &[Row],
+    report: &mut ImportReport,
+) -> Result<Vec<String>, ApiError> {
+    let mut ids = Vec::new();
+    let mut seen = HashSet::new();
+
+    for row in rows {
+        let parsed = (|| {
+            let customer = CreateCustomer {
+                name: row.required("name")?,
+                email: row.required("email")?,
+                phone: row.optional("phone"),
+                address: row.optional("address"),
+                customer_group: row.optional("customer_group"),
+            };
+            customer.validate().map_err(validation_message)?;
+            check_unique(&mut seen, &customer.email, "email")?;
+            Ok::<_, String>(customer)
+        })();
+        let customer = match parsed {
+            Ok(customer) => customer,
+            Err(message) => {
+                report.errors.push(RowError {
+                    row: row.number,
+                    message,
+                });
+                continue;
+            }
+        };
+
+        let existing = tenant
+            .find_active::<customer::Entity>()
+            .filter(customer::Column::Email.eq(&customer.email))
+            .one(txn)
+            .await?;
+
+        match existing {
+            Some(existing) => {
+                let id = existing.id.clone();
+                let before = existing.clone();
+                let mut active_customer = existing.into_active_model();
+                active_customer.name = Set(customer.name);
+                if customer.phone.is_some() {
+                    active_customer.phone = Set(customer.phone);
+                }
+                if customer.address.is_some() {
+                    active_customer.address = Set(customer.address);
+                }
+                if customer.customer_group.is_some() {
+                    active_customer.customer_group = Set(customer.customer_group);
+                }
+                let updated = active_customer.update(txn).await?;
+                record(txn, audit, "customer", &id, Some(&before), &updated).await?;
+                report.updated += 1;
+                ids.push(id);
+            }
+            None => {
+                let id = Uuid::new_v4().to_string();
+                let inserted = customer::ActiveModel {
+                    id: Set(id.clone()),
+                    tenant_id: Set(tenant.id().to_string()),
+                    name: Set(customer.name),
+                    email: Set(customer.email),
+                    phone: Set(customer.phone),
+                    address: Set(customer.address),
+                    customer_group: Set(customer.customer_group),
+                    created_at: Set(Utc::now().naive_utc()),
+                    ..Default::default()
+                }
+                .insert(txn)
+                .await?;
+                record(txn, audit, "customer", &id, None, &inserted).await?;
+                report.created += 1;
+                ids.push(id);
+            }
+        }
+    }
+
+    Ok(ids)
+}
+
+/// Pushes the imported rows to their search index in a single request.
+async fn index_documents(
+    data: &config::app::AppState,
+    tenant: &Tenant,
+    entity: ImportEntity,
+    ids: Vec<String>,
+) -> Result<(), ApiError> {
+    match entity {
+        ImportEntity::Inventory => {
+            let items = tenant
+                .find::<inventory::Entity>()
+                .filter(inventory::Column::Id.is_in(ids))
+                .all(&data.db)
+                .await?;
+            data.meilisearch
+                .index("inventory")
+                .add_documents(&items, Some("id"))
+                .await?;
+        }
+        ImportEntity::Customer => {
+            let customers = tenant
+                .find::<customer::Entity>()
+                .filter(customer::Column::Id.is_in(ids))
+                .all(&data.db)
+                .await?;
+            data.meilisearch
+                .index("customer")
+                .add_documents(&customers, Some("id"))
+                .await?;
+        }
+        ImportEntity::Employee => {
+            let employees = tenant
+                .find::<employee::Entity>()
+                .filter(employee::Column::Id.is_in(ids))
+                .all(&data.db)
+                .await?;
+            index_employees(data, tenant, employees).await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportEntity {
+    Inventory,
+    Employee,
+    Customer,
+}
+
+impl ImportEntity {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "inventory" => Some(Self::Inventory),
+            "employee" => Some(Self::Employee),
+            "customer" => Some(Self::Customer),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Csv,
+    Xlsx,
+}
+
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Overrides the format detected from the `Content-Type` header.
+    pub format: Option<ImportFormat>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RowError {
+    /// Spreadsheet row number, the header being row 1.
+    pub row: usize,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ImportReport {
+    pub entity: ImportEntity,
+    pub dry_run: bool,
+    /// Whether the batch was committed. A batch with any row error is never applied.
+    pub applied: bool,
+    pub total_rows: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub errors: Vec<RowError>,
+}
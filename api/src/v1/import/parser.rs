@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::str::FromStr;
+
+use calamine::{Data, Reader, Xlsx};
+
+use super::models::ImportFormat;
+use crate::error::ApiError;
+
+/// A data row of an import file with its cells keyed by lowercase header.
+pub struct Row {
+    pub number: usize,
+    cells: HashMap<String, String>,
+}
+
+impl Row {
+    pub fn optional(&self, field: &str) -> Option<String> {
+        self.cells
+            .get(field)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    }
+
+    pub fn required(&self, field: &str) -> Result<String, String> {
+        self.optional(field)
+            .ok_or_else(|| format!("Column '{}' is required", field))
+    }
+
+    pub fn parse<T: FromStr>(&self, field: &str) -> Result<T, String> {
+        self.required(field)?
+            .parse()
+            .map_err(|_| format!("Column '{}' has an invalid value", field))
+    }
+
+    pub fn parse_or<T: FromStr>(&self, field: &str, default: T) -> Result<T, String> {
+        match self.optional(field) {
+            Some(_) => self.parse(field),
+            None => Ok(default),
+        }
+    }
+}
+
+pub fn parse(format: ImportFormat, body: &[u8]) -> Result<Vec<Row>, ApiError> {
+    let records = match format {
+        ImportFormat::Csv => read_csv(body)?,
+        ImportFormat::Xlsx => read_xlsx(body)?,
+    };
+
+    let mut records = records.into_iter();
+    let headers: Vec<String> = records
+        .next()
+        .ok_or_else(|| ApiError::ValidationError("The file is empty".to_string()))?
+        .into_iter()
+        .map(|header| header.trim().to_lowercase())
+        .collect();
+
+    Ok(records
+        .enumerate()
+        .filter(|(_, record)| record.iter().any(|cell| !cell.trim().is_empty()))
+        .map(|(index, record)| Row {
+            number: index + 2,
+            cells: headers.iter().cloned().zip(record).collect(),
+        })
+        .collect())
+}
+
+fn read_csv(body: &[u8]) -> Result<Vec<Vec<String>>, ApiError> {
+    csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(body)
+        .records()
+        .map(|record| {
+            record
+                .map(|record| record.iter().map(str::to_string).collect())
+                .map_err(|e| ApiError::ValidationError(format!("Malformed CSV: {}", e)))
+        })
+        .collect()
+}
+
+fn read_xlsx(body: &[u8]) -> Result<Vec<Vec<String>>, ApiError> {
+    let mut workbook = Xlsx::new(Cursor::new(body))
+        .map_err(|e| ApiError::ValidationError(format!("Malformed XLSX: {}", e)))?;
+
+    let sheet = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| ApiError::ValidationError("The workbook has no sheets".to_string()))?
+        .map_err(|e| ApiError::ValidationError(format!("Malformed XLSX: {}", e)))?;
+
+    Ok(sheet
+        .rows()
+        .map(|row| row.iter().map(cell_to_string).collect())
+        .collect())
+}
+
+fn cell_to_string(cell: &Data) -> String {
+    match cell {
+        // Spreadsheets store every number as a float
+        Data::Float(value) if value.fract() == 0.0 => format!("{}", *value as i64),
+        Data::Empty => String::new(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,18 @@
+use super::handlers;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+/// Largest accepted import file.
+const MAX_IMPORT_BYTES: usize = 20 * 1024 * 1024;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/import")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .app_data(web::PayloadConfig::new(MAX_IMPORT_BYTES))
+            .route("/{entity}", web::post().to(handlers::import)),
+    );
+}
@@ -1,10 +1,20 @@
-use actix_web::{web, HttpResponse};
-use sea_orm::{ActiveModelTrait, EntityTrait, IntoActiveModel, QueryOrder, Set};
+use actix_web::{web, HttpRequest, HttpResponse};
+use sea_orm::TransactionTrait;
 use serde::Deserialize;
 
-use super::models::{CreateInventoryItem, InventoryItem, UpdateInventoryItem};
+use super::models::{CreateInventoryItem, InventoryFilter, InventoryItem, UpdateInventoryItem};
+use super::services::{self, InventoryBatch};
+use crate::audit::AuditContext;
+use crate::batch::{batch_response, run_batch, BatchRequest, BatchResponse};
 use crate::error::ApiError;
-use entity::inventory;
+use crate::export::{stream_export, ExportFormat, ExportQuery};
+use crate::extractors::{Admin, ValidatedJson};
+use crate::shared::concurrency::{self, Precondition};
+use crate::shared::db_utils::find_locked;
+use crate::soft_delete;
+use crate::tenant::Tenant;
+use entity::audit_log::AuditAction;
+use entity::inventory::{self, Tracking};
 
 #[derive(Deserialize)]
 pub struct SearchQuery {
@@ -20,6 +30,7 @@ pub struct SearchQuery {
     responses(
         (status = 200, description = "Item created successfully", body = InventoryItem),
         (status = 400, description = "Validation error"),
+        (status = 409, description = "SKU already in use"),
         (status = 500, description = "Internal server error")
     ),
     security(
@@ -28,37 +39,29 @@ pub struct SearchQuery {
 )]
 pub async fn create_item(
     data: web::Data<config::app::AppState>,
-    item: web::Json<CreateInventoryItem>,
+    tenant: Tenant,
+    audit: AuditContext,
+    item: ValidatedJson<CreateInventoryItem>,
 ) -> Result<HttpResponse, ApiError> {
-    // Validation
-    if item.quantity < 0 {
-        return Err(ApiError::ValidationError(
-            "Quantity cannot be negative".to_string(),
-        ));
-    }
-    if item.price < 0.0 {
-        return Err(ApiError::ValidationError(
-            "Price cannot be negative".to_string(),
-        ));
-    }
-
-    let new_uuid = uuid::Uuid::new_v4();
-    let new_item = inventory::ActiveModel {
-        id: Set(new_uuid.to_string()),
-        name: Set(item.name.clone()),
-        quantity: Set(item.quantity),
-        price: Set(item.price),
-    };
-
-    let inserted_item = new_item.insert(&data.db).await?;
+    let txn = data.db.begin().await?;
+    let inserted_item = services::create_item(&txn, &tenant, &item).await?;
+    audit
+        .record(
+            &txn,
+            "inventory",
+            &inserted_item.id,
+            AuditAction::Create,
+            None,
+            Some(&inserted_item),
+        )
+        .await?;
+    txn.commit().await?;
 
     // Add to Meilisearch for indexing
     let index = data.meilisearch.index("inventory");
-    let item_for_meili: InventoryItem = inserted_item.clone().into();
-
-    index.add_documents(&[&item_for_meili], Some("id")).await?;
+    index.add_documents(&[&inserted_item], Some("id")).await?;
 
-    Ok(HttpResponse::Ok().json(item_for_meili))
+    Ok(HttpResponse::Ok().json(inserted_item))
 }
 
 /// Search inventory items
@@ -79,20 +82,16 @@ pub async fn create_item(
 )]
 pub async fn search_items(
     data: web::Data<config::app::AppState>,
+    tenant: Tenant,
     query: web::Query<SearchQuery>,
 ) -> Result<HttpResponse, ApiError> {
     let q = query.q.as_deref().unwrap_or("");
     log::info!("Searching for: {}", q);
 
     let index = data.meilisearch.index("inventory");
-    let result = index
-        .search()
-        .with_query(q)
-        .execute::<InventoryItem>()
-        .await?;
+    let hits = tenant.search::<InventoryItem>(&index, q).await?;
 
-    log::info!("Search successful, found {} hits", result.hits.len());
-    let hits: Vec<_> = result.hits.into_iter().map(|hit| hit.result).collect();
+    log::info!("Search successful, found {} hits", hits.len());
 
     Ok(HttpResponse::Ok().json(hits))
 }
@@ -102,6 +101,11 @@ pub async fn search_items(
     get,
     path = "/v1/inventory",
     tag = "inventory",
+    params(
+        ("location" = Option<String>, Query, description = "Filter by location"),
+        ("category" = Option<String>, Query, description = "Filter by category"),
+        ("tracking" = Option<Tracking>, Query, description = "Filter by tracking mode")
+    ),
     responses(
         (status = 200, description = "List of inventory items", body = Vec<InventoryItem>),
         (status = 500, description = "Internal server error")
@@ -112,27 +116,65 @@ pub async fn search_items(
 )]
 pub async fn get_all_items(
     data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<InventoryFilter>,
 ) -> Result<HttpResponse, ApiError> {
-    let items = inventory::Entity::find()
-        .order_by_asc(entity::inventory::Column::Name)
-        .all(&data.db)
-        .await?;
+    let items = filter.select(&tenant).all(&data.db).await?;
 
     let item_responses: Vec<InventoryItem> = items.into_iter().map(|item| item.into()).collect();
 
     Ok(HttpResponse::Ok().json(item_responses))
 }
 
+/// Export inventory items as CSV, XLSX or JSON lines
+#[utoipa::path(
+    get,
+    path = "/v1/inventory/export",
+    tag = "inventory",
+    params(
+        ("format" = Option<ExportFormat>, Query, description = "csv (default), xlsx or jsonl"),
+        ("location" = Option<String>, Query, description = "Filter by location"),
+        ("category" = Option<String>, Query, description = "Filter by category"),
+        ("tracking" = Option<Tracking>, Query, description = "Filter by tracking mode")
+    ),
+    responses(
+        (status = 200, description = "Export file", content(
+            (String = "text/csv"),
+            (String = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+            (String = "application/x-ndjson")
+        )),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn export_items(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<InventoryFilter>,
+    query: web::Query<ExportQuery>,
+) -> HttpResponse {
+    stream_export(
+        data.db.clone(),
+        filter.select(&tenant),
+        query.format,
+        "inventory",
+    )
+}
+
 /// Get inventory item by ID
 #[utoipa::path(
     get,
     path = "/v1/inventory/{id}",
     tag = "inventory",
     params(
-        ("id" = String, Path, description = "Item ID")
+        ("id" = String, Path, description = "Item ID"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag from an earlier response")
     ),
     responses(
         (status = 200, description = "Item found", body = InventoryItem),
+        (status = 304, description = "Item unchanged since the given ETag"),
         (status = 404, description = "Item not found"),
         (status = 500, description = "Internal server error")
     ),
@@ -141,16 +183,24 @@ pub async fn get_all_items(
     )
 )]
 pub async fn get_item_by_id(
+    req: HttpRequest,
     data: web::Data<config::app::AppState>,
+    tenant: Tenant,
     id: web::Path<String>,
 ) -> Result<HttpResponse, ApiError> {
-    let item = inventory::Entity::find_by_id(id.into_inner())
+    let item = tenant
+        .find_active_by_id::<inventory::Entity>(&id)
         .one(&data.db)
         .await?
         .ok_or_else(|| ApiError::NotFound("Item not found".to_string()))?;
 
-    let item_response: InventoryItem = item.into();
-    Ok(HttpResponse::Ok().json(item_response))
+    if concurrency::is_not_modified(&req, item.version) {
+        return Ok(concurrency::not_modified(item.version));
+    }
+
+    Ok(HttpResponse::Ok()
+        .insert_header(concurrency::etag(item.version))
+        .json(item))
 }
 
 /// Update inventory item
@@ -159,13 +209,17 @@ pub async fn get_item_by_id(
     path = "/v1/inventory/{id}",
     tag = "inventory",
     params(
-        ("id" = String, Path, description = "Item ID")
+        ("id" = String, Path, description = "Item ID"),
+        ("If-Match" = String, Header, description = "ETag of the version being replaced")
     ),
     request_body = UpdateInventoryItem,
     responses(
         (status = 200, description = "Item updated successfully", body = InventoryItem),
         (status = 400, description = "Validation error"),
         (status = 404, description = "Item not found"),
+        (status = 409, description = "SKU already in use"),
+        (status = 412, description = "Item was changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
         (status = 500, description = "Internal server error")
     ),
     security(
@@ -173,46 +227,37 @@ pub async fn get_item_by_id(
     )
 )]
 pub async fn update_item(
+    req: HttpRequest,
     data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
     id: web::Path<String>,
-    item: web::Json<UpdateInventoryItem>,
+    item: ValidatedJson<UpdateInventoryItem>,
 ) -> Result<HttpResponse, ApiError> {
+    let precondition = Precondition::from_request(&req)?;
     let item_id = id.into_inner();
 
-    let found_item = inventory::Entity::find_by_id(item_id.clone())
-        .one(&data.db)
-        .await?
-        .ok_or_else(|| ApiError::NotFound(format!("Item with id {} not found", item_id)))?;
-
-    let mut active_item = found_item.into_active_model();
-
-    if let Some(name) = &item.name {
-        active_item.name = Set(name.clone());
-    }
-    if let Some(quantity) = item.quantity {
-        if quantity < 0 {
-            return Err(ApiError::ValidationError(
-                "Quantity cannot be negative".to_string(),
-            ));
-        }
-        active_item.quantity = Set(quantity);
-    }
-    if let Some(price) = item.price {
-        if price < 0.0 {
-            return Err(ApiError::ValidationError(
-                "Price cannot be negative".to_string(),
-            ));
-        }
-        active_item.price = Set(price);
-    }
-
-    let updated_item = active_item.update(&data.db).await?;
+    let txn = data.db.begin().await?;
+    let before = find_locked::<inventory::Entity, _>(&txn, &tenant, &item_id).await?;
+    let updated_item = services::update_item(&txn, &tenant, &item_id, &precondition, &item).await?;
+    audit
+        .record(
+            &txn,
+            "inventory",
+            &item_id,
+            AuditAction::Update,
+            before.as_ref(),
+            Some(&updated_item),
+        )
+        .await?;
+    txn.commit().await?;
 
     let index = data.meilisearch.index("inventory");
-    let item_for_meili: InventoryItem = updated_item.clone().into();
-    index.add_documents(&[&item_for_meili], Some("id")).await?;
+    index.add_documents(&[&updated_item], Some("id")).await?;
 
-    Ok(HttpResponse::Ok().json(updated_item))
+    Ok(HttpResponse::Ok()
+        .insert_header(concurrency::etag(updated_item.version))
+        .json(updated_item))
 }
 
 /// Delete inventory item
@@ -221,11 +266,14 @@ pub async fn update_item(
     path = "/v1/inventory/{id}",
     tag = "inventory",
     params(
-        ("id" = String, Path, description = "Item ID")
+        ("id" = String, Path, description = "Item ID"),
+        ("If-Match" = String, Header, description = "ETag of the version being deleted")
     ),
     responses(
         (status = 200, description = "Item deleted successfully"),
         (status = 404, description = "Item not found"),
+        (status = 412, description = "Item was changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
         (status = 500, description = "Internal server error")
     ),
     security(
@@ -233,23 +281,142 @@ pub async fn update_item(
     )
 )]
 pub async fn delete_item(
+    req: HttpRequest,
     data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
     id: web::Path<String>,
 ) -> Result<HttpResponse, ApiError> {
+    let precondition = Precondition::from_request(&req)?;
     let item_id = id.into_inner();
 
-    let found_item = inventory::Entity::find_by_id(item_id.clone())
-        .one(&data.db)
-        .await?
-        .ok_or_else(|| ApiError::NotFound(format!("Item with id {} not found", item_id)))?;
-
-    let item_id_for_meili = found_item.id.clone();
-    let active_item: inventory::ActiveModel = found_item.into();
-    active_item.delete(&data.db).await?;
+    let txn = data.db.begin().await?;
+    let before = find_locked::<inventory::Entity, _>(&txn, &tenant, &item_id).await?;
+    services::delete_item(&txn, &tenant, &item_id, &precondition).await?;
+    audit
+        .record(
+            &txn,
+            "inventory",
+            &item_id,
+            AuditAction::Delete,
+            before.as_ref(),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
 
     // Delete from Meilisearch
     let index = data.meilisearch.index("inventory");
-    index.delete_document(&item_id_for_meili).await?;
+    index.delete_document(&item_id).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Create, update and delete inventory items in one request
+#[utoipa::path(
+    post,
+    path = "/v1/inventory/batch",
+    tag = "inventory",
+    request_body = BatchRequest<CreateInventoryItem, UpdateInventoryItem>,
+    responses(
+        (status = 200, description = "Batch processed", body = BatchResponse),
+        (status = 400, description = "Empty or oversized batch"),
+        (status = 422, description = "Atomic batch rolled back", body = BatchResponse),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn batch_items(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    batch: web::Json<BatchRequest<CreateInventoryItem, UpdateInventoryItem>>,
+) -> Result<HttpResponse, ApiError> {
+    let outcome =
+        run_batch::<InventoryBatch>(&data.db, &tenant, &audit, batch.into_inner()).await?;
+
+    // One indexing call per batch instead of one per item
+    let index = data.meilisearch.index("inventory");
+    if !outcome.upserted.is_empty() {
+        index.add_documents(&outcome.upserted, Some("id")).await?;
+    }
+    if !outcome.deleted.is_empty() {
+        index.delete_documents(&outcome.deleted).await?;
+    }
+
+    Ok(batch_response(outcome.response))
+}
+
+/// Restore a deleted inventory item
+#[utoipa::path(
+    post,
+    path = "/v1/inventory/{id}/restore",
+    tag = "inventory",
+    params(
+        ("id" = String, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 200, description = "Item restored", body = InventoryItem),
+        (status = 404, description = "Item not found"),
+        (status = 409, description = "Item is not deleted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn restore_item(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let item =
+        soft_delete::restore::<inventory::Entity, _>(&txn, &tenant, &audit, "inventory", &id)
+            .await?;
+    txn.commit().await?;
+
+    let index = data.meilisearch.index("inventory");
+    index.add_documents(&[&item], Some("id")).await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(concurrency::etag(item.version))
+        .json(item))
+}
+
+/// Permanently remove a deleted inventory item, administrators only
+#[utoipa::path(
+    delete,
+    path = "/v1/inventory/{id}/purge",
+    tag = "inventory",
+    params(
+        ("id" = String, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 200, description = "Item purged"),
+        (status = 403, description = "Caller is not an administrator"),
+        (status = 404, description = "Item not found"),
+        (status = 409, description = "Item must be deleted first or has stock movements"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn purge_item(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    _admin: Admin,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    services::check_purge(&txn, &tenant, &id).await?;
+    soft_delete::purge::<inventory::Entity, _>(&txn, &tenant, &audit, "inventory", &id).await?;
+    txn.commit().await?;
 
     Ok(HttpResponse::Ok().finish())
 }
@@ -1,18 +1,101 @@
-use entity::inventory;
+use entity::inventory::{self, Tracking};
+use sea_orm::{ActiveEnum, ColumnTrait, QueryFilter, QueryOrder, Select};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::export::{Cell, ExportRow};
+use crate::tenant::Tenant;
 
 pub type InventoryItem = inventory::Model;
 
-#[derive(Serialize, Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
 pub struct CreateInventoryItem {
+    #[validate(length(min = 1, max = 64, message = "SKU must be 1 to 64 characters"))]
+    #[schema(min_length = 1, max_length = 64)]
+    pub sku: Option<String>,
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
     pub name: String,
+    #[validate(range(min = 0, message = "Quantity cannot be negative"))]
+    #[schema(minimum = 0)]
     pub quantity: i32,
+    #[validate(range(min = 0.0, message = "Price cannot be negative"))]
+    #[schema(minimum = 0)]
     pub price: f64,
+    #[serde(default)]
+    pub tracking: Tracking,
+    pub location: Option<String>,
+    pub category: Option<String>,
+    #[validate(range(min = 0, message = "Reorder point cannot be negative"))]
+    #[schema(minimum = 0)]
+    pub reorder_point: Option<i32>,
 }
-#[derive(Serialize, Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
 pub struct UpdateInventoryItem {
+    #[validate(length(min = 1, max = 64, message = "SKU must be 1 to 64 characters"))]
+    #[schema(min_length = 1, max_length = 64)]
+    pub sku: Option<String>,
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
     pub name: Option<String>,
+    #[validate(range(min = 0, message = "Quantity cannot be negative"))]
+    #[schema(minimum = 0)]
     pub quantity: Option<i32>,
+    #[validate(range(min = 0.0, message = "Price cannot be negative"))]
+    #[schema(minimum = 0)]
     pub price: Option<f64>,
+    pub tracking: Option<Tracking>,
+    pub location: Option<String>,
+    pub category: Option<String>,
+    #[validate(range(min = 0, message = "Reorder point cannot be negative"))]
+    #[schema(minimum = 0)]
+    pub reorder_point: Option<i32>,
+}
+
+/// Filters shared by the list and export endpoints.
+#[derive(Deserialize)]
+pub struct InventoryFilter {
+    pub location: Option<String>,
+    pub category: Option<String>,
+    pub tracking: Option<Tracking>,
+}
+
+impl InventoryFilter {
+    pub fn select(&self, tenant: &Tenant) -> Select<inventory::Entity> {
+        let mut query = tenant
+            .find_active::<inventory::Entity>()
+            .order_by_asc(inventory::Column::Name);
+        if let Some(location) = &self.location {
+            query = query.filter(inventory::Column::Location.eq(location));
+        }
+        if let Some(category) = &self.category {
+            query = query.filter(inventory::Column::Category.eq(category));
+        }
+        if let Some(tracking) = &self.tracking {
+            query = query.filter(inventory::Column::Tracking.eq(tracking.clone()));
+        }
+        query
+    }
+}
+
+impl ExportRow for InventoryItem {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "id", "sku", "name", "quantity", "price", "tracking", "location", "category",
+        ]
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        vec![
+            self.id.clone().into(),
+            self.sku.clone().into(),
+            self.name.clone().into(),
+            self.quantity.into(),
+            self.price.into(),
+            self.tracking.to_value().into(),
+            self.location.clone().into(),
+            self.category.clone().into(),
+        ]
+    }
 }
@@ -1,20 +1,33 @@
+use crate::batch::MAX_BATCH_BYTES;
+use crate::error::json_config;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
 use crate::middlewares::jwt::JwtMiddleware;
 use actix_web::web;
 
 use super::handlers::{
-    create_item, delete_item, get_all_items, get_item_by_id, search_items, update_item,
+    batch_items, create_item, delete_item, export_items, get_all_items, get_item_by_id, purge_item,
+    restore_item, search_items, update_item,
 };
 
 pub fn init_routes(cfg: &mut web::ServiceConfig) {
     let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
     cfg.service(
         web::scope("/v1/inventory")
+            .wrap(IdempotencyMiddleware::new())
             .wrap(jwt_middleware)
             .route("", web::get().to(get_all_items))
             .route("/create", web::post().to(create_item))
             .route("/search", web::get().to(search_items))
+            .route("/export", web::get().to(export_items))
+            .service(
+                web::resource("/batch")
+                    .app_data(json_config().limit(MAX_BATCH_BYTES))
+                    .route(web::post().to(batch_items)),
+            )
             .route("/{id}", web::get().to(get_item_by_id))
             .route("/{id}", web::put().to(update_item))
-            .route("/{id}", web::delete().to(delete_item)),
+            .route("/{id}", web::delete().to(delete_item))
+            .route("/{id}/restore", web::post().to(restore_item))
+            .route("/{id}/purge", web::delete().to(purge_item)),
     );
 }
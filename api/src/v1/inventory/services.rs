@@ -0,0 +1,229 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseTransaction, IntoActiveModel,
+    QueryFilter, QuerySelect, Set,
+};
+
+use super::models::{CreateInventoryItem, InventoryItem, UpdateInventoryItem};
+use crate::batch::BatchResource;
+use crate::error::ApiError;
+use crate::shared::concurrency::Precondition;
+use crate::shared::db_utils::{conflict_on_duplicate, find_locked};
+use crate::tenant::Tenant;
+use crate::v1::stock::services::{post_movement, MovementDraft};
+use entity::inventory::{self, Tracking};
+use entity::stock_movement::{self, MovementType};
+
+pub async fn create_item<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    item: &CreateInventoryItem,
+) -> Result<InventoryItem, ApiError> {
+    if item.tracking != Tracking::None && item.quantity != 0 {
+        return Err(ApiError::ValidationError(
+            "Tracked items must be created empty and stocked through receipts".to_string(),
+        ));
+    }
+
+    let new_item = inventory::ActiveModel {
+        id: Set(uuid::Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        sku: Set(item.sku.clone()),
+        name: Set(item.name.clone()),
+        quantity: Set(item.quantity),
+        price: Set(item.price),
+        tracking: Set(item.tracking.clone()),
+        location: Set(item.location.clone()),
+        category: Set(item.category.clone()),
+        reorder_point: Set(item.reorder_point),
+        ..Default::default()
+    };
+
+    new_item
+        .insert(db)
+        .await
+        .map_err(|e| conflict_on_duplicate(e, "SKU already in use"))
+}
+
+/// Applies a partial update. Run it inside a transaction: quantity edits
+/// are booked as a separate adjustment movement.
+pub async fn update_item<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    item_id: &str,
+    precondition: &Precondition,
+    item: &UpdateInventoryItem,
+) -> Result<InventoryItem, ApiError> {
+    let found_item = tenant
+        .find_active_by_id::<inventory::Entity>(item_id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Item with id {} not found", item_id)))?;
+    precondition.check(found_item.version)?;
+
+    let found_tracking = found_item.tracking.clone();
+    let found_quantity = found_item.quantity;
+    let mut quantity_change = 0;
+    let mut active_item = found_item.into_active_model();
+
+    if let Some(sku) = &item.sku {
+        active_item.sku = Set(Some(sku.clone()));
+    }
+    if let Some(name) = &item.name {
+        active_item.name = Set(name.clone());
+    }
+    if let Some(quantity) = item.quantity {
+        if found_tracking != Tracking::None && quantity != found_quantity {
+            return Err(ApiError::ValidationError(
+                "Quantity of tracked items can only be changed through stock movements".to_string(),
+            ));
+        }
+        quantity_change = quantity - found_quantity;
+    }
+    if let Some(price) = item.price {
+        active_item.price = Set(price);
+    }
+    if let Some(tracking) = &item.tracking {
+        if *tracking != found_tracking && found_quantity != 0 {
+            return Err(ApiError::ValidationError(
+                "Tracking can only be changed while the item has no stock".to_string(),
+            ));
+        }
+        active_item.tracking = Set(tracking.clone());
+    }
+    if let Some(location) = &item.location {
+        active_item.location = Set(Some(location.clone()));
+    }
+    if let Some(category) = &item.category {
+        active_item.category = Set(Some(category.clone()));
+    }
+    if let Some(reorder_point) = item.reorder_point {
+        active_item.reorder_point = Set(Some(reorder_point));
+    }
+
+    let updated_item = active_item
+        .update(db)
+        .await
+        .map_err(|e| conflict_on_duplicate(e, "SKU already in use"))?;
+
+    // Quantity edits are booked as adjustments so the difference stays traceable
+    if quantity_change != 0 {
+        post_movement(
+            db,
+            tenant,
+            MovementDraft {
+                inventory_id: updated_item.id.clone(),
+                lot_id: None,
+                movement_type: MovementType::Adjustment,
+                quantity: quantity_change,
+                partner_id: None,
+                reference: None,
+                reason: Some("manual".to_string()),
+            },
+        )
+        .await?;
+
+        // The movement changed quantity and version, hand back the stored row
+        return tenant
+            .find_by_id::<inventory::Entity>(&updated_item.id)
+            .one(db)
+            .await?
+            .ok_or(ApiError::InternalServerError);
+    }
+
+    Ok(updated_item)
+}
+
+pub async fn delete_item<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    item_id: &str,
+    precondition: &Precondition,
+) -> Result<(), ApiError> {
+    let found_item = tenant
+        .find_active_by_id::<inventory::Entity>(item_id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Item with id {} not found", item_id)))?;
+    precondition.check(found_item.version)?;
+
+    let mut active_item: inventory::ActiveModel = found_item.into();
+    active_item.deleted_at = Set(Some(Utc::now().naive_utc()));
+    active_item.update(db).await?;
+
+    Ok(())
+}
+
+/// Items with stock movements are kept for good, so their lots and
+/// movements can still be traced.
+pub async fn check_purge<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    item_id: &str,
+) -> Result<(), ApiError> {
+    let moved = tenant
+        .find::<stock_movement::Entity>()
+        .filter(stock_movement::Column::InventoryId.eq(item_id))
+        .one(db)
+        .await?
+        .is_some();
+    if moved {
+        return Err(ApiError::Conflict(
+            "The item has stock movements and cannot be purged".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub struct InventoryBatch;
+
+#[async_trait]
+impl BatchResource for InventoryBatch {
+    const ENTITY: &'static str = "inventory";
+
+    type Create = CreateInventoryItem;
+    type Update = UpdateInventoryItem;
+    type Model = InventoryItem;
+
+    async fn find(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        id: &str,
+    ) -> Result<Option<InventoryItem>, ApiError> {
+        find_locked::<inventory::Entity, _>(txn, tenant, id).await
+    }
+
+    async fn create(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        data: &CreateInventoryItem,
+    ) -> Result<InventoryItem, ApiError> {
+        create_item(txn, tenant, data).await
+    }
+
+    async fn update(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        id: &str,
+        precondition: &Precondition,
+        data: &UpdateInventoryItem,
+    ) -> Result<InventoryItem, ApiError> {
+        update_item(txn, tenant, id, precondition, data).await
+    }
+
+    async fn delete(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        id: &str,
+        precondition: &Precondition,
+    ) -> Result<(), ApiError> {
+        delete_item(txn, tenant, id, precondition).await
+    }
+
+    fn id(model: &InventoryItem) -> String {
+        model.id.clone()
+    }
+}
@@ -0,0 +1,642 @@
+use actix_web::{web, HttpResponse};
+use chrono::{Datelike, Utc};
+use sea_orm::{ConnectionTrait, ModelTrait, QueryOrder, QuerySelect, TransactionTrait};
+use serde_json::json;
+
+use super::models::{
+    BalanceQuery, CreateHoliday, CreateLeaveType, DecideLeave, Holiday, HolidayFilter,
+    LeaveBalance, LeaveFilter, LeaveRequest, LeaveType, SubmitLeave, UpdateLeaveType,
+};
+use super::services;
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::extractors::ValidatedJson;
+use crate::middlewares::jwt::Claims;
+use crate::tenant::Tenant;
+use crate::v1::auth::services as auth_services;
+use crate::v1::employee::models::Employee;
+use crate::v1::employee::services as employee_services;
+use entity::audit_log::AuditAction;
+use entity::leave_request::{self, LeaveStatus};
+use entity::{employee, holiday, leave_type};
+
+/// List leave types
+#[utoipa::path(
+    get,
+    path = "/v1/leave/type",
+    tag = "leave",
+    responses(
+        (status = 200, description = "List of leave types", body = Vec<LeaveType>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_leave_types(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+) -> Result<HttpResponse, ApiError> {
+    let leave_types = tenant
+        .find::<leave_type::Entity>()
+        .order_by_asc(leave_type::Column::Name)
+        .all(&data.db)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(leave_types))
+}
+
+/// Create a leave type
+#[utoipa::path(
+    post,
+    path = "/v1/leave/type",
+    tag = "leave",
+    request_body = CreateLeaveType,
+    responses(
+        (status = 200, description = "Leave type created", body = LeaveType),
+        (status = 400, description = "Validation error"),
+        (status = 409, description = "Name already in use"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_leave_type(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    leave_type: ValidatedJson<CreateLeaveType>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let leave_type = services::create_leave_type(&txn, &tenant, &leave_type).await?;
+    audit
+        .record(
+            &txn,
+            "leave_type",
+            &leave_type.id,
+            AuditAction::Create,
+            None,
+            Some(&leave_type),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(leave_type))
+}
+
+/// Update a leave type
+#[utoipa::path(
+    put,
+    path = "/v1/leave/type/{id}",
+    tag = "leave",
+    params(
+        ("id" = String, Path, description = "Leave type ID")
+    ),
+    request_body = UpdateLeaveType,
+    responses(
+        (status = 200, description = "Leave type updated", body = LeaveType),
+        (status = 400, description = "Validation error"),
+        (status = 404, description = "Leave type not found"),
+        (status = 409, description = "Name already in use"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_leave_type(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+    update: ValidatedJson<UpdateLeaveType>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let leave_type = tenant
+        .find_by_id::<leave_type::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Leave type not found".to_string()))?;
+
+    let updated = services::update_leave_type(&txn, leave_type.clone(), &update).await?;
+    audit
+        .record(
+            &txn,
+            "leave_type",
+            &updated.id,
+            AuditAction::Update,
+            Some(&leave_type),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Delete a leave type nobody has requested
+#[utoipa::path(
+    delete,
+    path = "/v1/leave/type/{id}",
+    tag = "leave",
+    params(
+        ("id" = String, Path, description = "Leave type ID")
+    ),
+    responses(
+        (status = 200, description = "Leave type deleted"),
+        (status = 404, description = "Leave type not found"),
+        (status = 409, description = "Leave of this type has been requested"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_leave_type(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let leave_type = tenant
+        .find_by_id::<leave_type::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Leave type not found".to_string()))?;
+
+    services::delete_leave_type(&txn, &tenant, leave_type.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "leave_type",
+            &leave_type.id,
+            AuditAction::Delete,
+            Some(&leave_type),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Leave type deleted successfully"})))
+}
+
+/// List company holidays
+#[utoipa::path(
+    get,
+    path = "/v1/leave/holiday",
+    tag = "leave",
+    params(
+        HolidayFilter
+    ),
+    responses(
+        (status = 200, description = "List of holidays", body = Vec<Holiday>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_holidays(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<HolidayFilter>,
+) -> Result<HttpResponse, ApiError> {
+    let holidays = filter.select(&tenant).all(&data.db).await?;
+    Ok(HttpResponse::Ok().json(holidays))
+}
+
+/// Add a company holiday, not counted against leave requested from then on
+#[utoipa::path(
+    post,
+    path = "/v1/leave/holiday",
+    tag = "leave",
+    request_body = CreateHoliday,
+    responses(
+        (status = 200, description = "Holiday created", body = Holiday),
+        (status = 400, description = "Validation error"),
+        (status = 409, description = "There is already a holiday on this date"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_holiday(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    holiday: ValidatedJson<CreateHoliday>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let holiday = services::create_holiday(&txn, &tenant, &holiday).await?;
+    audit
+        .record(
+            &txn,
+            "holiday",
+            &holiday.id,
+            AuditAction::Create,
+            None,
+            Some(&holiday),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(holiday))
+}
+
+/// Delete a company holiday
+#[utoipa::path(
+    delete,
+    path = "/v1/leave/holiday/{id}",
+    tag = "leave",
+    params(
+        ("id" = String, Path, description = "Holiday ID")
+    ),
+    responses(
+        (status = 200, description = "Holiday deleted"),
+        (status = 404, description = "Holiday not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_holiday(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let holiday = tenant
+        .find_by_id::<holiday::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Holiday not found".to_string()))?;
+
+    holiday.clone().delete(&txn).await?;
+    audit
+        .record(
+            &txn,
+            "holiday",
+            &holiday.id,
+            AuditAction::Delete,
+            Some(&holiday),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Holiday deleted successfully"})))
+}
+
+/// Leave an employee has earned, taken and has left in a year
+#[utoipa::path(
+    get,
+    path = "/v1/leave/balance",
+    tag = "leave",
+    params(
+        BalanceQuery
+    ),
+    responses(
+        (status = 200, description = "Balance per leave type", body = Vec<LeaveBalance>),
+        (status = 400, description = "No employee given"),
+        (status = 404, description = "Employee not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_balances(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    claims: web::ReqData<Claims>,
+    query: web::Query<BalanceQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let caller = auth_services::caller(&data.db, &tenant, &claims).await?;
+    let employee = employee_services::employee_for(
+        &data.db,
+        &tenant,
+        &caller,
+        query.employee_id.as_deref(),
+        false,
+    )
+    .await?;
+    let year = query.year.unwrap_or_else(|| Utc::now().year());
+
+    let balances = services::balances(&data.db, &tenant, &employee, year).await?;
+    Ok(HttpResponse::Ok().json(balances))
+}
+
+/// List leave requests
+#[utoipa::path(
+    get,
+    path = "/v1/leave/request",
+    tag = "leave",
+    params(
+        LeaveFilter
+    ),
+    responses(
+        (status = 200, description = "List of leave requests", body = Vec<LeaveRequest>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_requests(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<LeaveFilter>,
+) -> Result<HttpResponse, ApiError> {
+    let requests = filter.select(&tenant).all(&data.db).await?;
+    Ok(HttpResponse::Ok().json(requests))
+}
+
+/// Request leave for yourself or one of your reports
+#[utoipa::path(
+    post,
+    path = "/v1/leave/request",
+    tag = "leave",
+    request_body = SubmitLeave,
+    responses(
+        (status = 201, description = "Leave requested", body = LeaveRequest),
+        (status = 400, description = "Validation error or not enough leave left"),
+        (status = 403, description = "Not the employee or their manager"),
+        (status = 404, description = "Employee not found"),
+        (status = 409, description = "Overlaps other leave of the employee"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn submit_request(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    request: ValidatedJson<SubmitLeave>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    // Locking the employee keeps requests made at once from overlapping
+    let employee = employee_services::employee_for(
+        &txn,
+        &tenant,
+        &caller,
+        request.employee_id.as_deref(),
+        true,
+    )
+    .await?;
+    if !caller.is(&employee.id) && !caller.manages(&employee) {
+        return Err(ApiError::Forbidden(
+            "Only the employee or their manager can request their leave".to_string(),
+        ));
+    }
+
+    let leave = services::submit_leave(&txn, &tenant, &employee, &request).await?;
+    audit
+        .record(
+            &txn,
+            "leave_request",
+            &leave.id,
+            AuditAction::Create,
+            None,
+            Some(&leave),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Created().json(leave))
+}
+
+/// Get leave request by ID
+#[utoipa::path(
+    get,
+    path = "/v1/leave/request/{id}",
+    tag = "leave",
+    params(
+        ("id" = String, Path, description = "Leave request ID")
+    ),
+    responses(
+        (status = 200, description = "Leave request found", body = LeaveRequest),
+        (status = 404, description = "Leave request not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_request_by_id(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let request = tenant
+        .find_by_id::<leave_request::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Leave request not found".to_string()))?;
+
+    Ok(HttpResponse::Ok().json(request))
+}
+
+/// A locked request with the employee it is for.
+async fn find_request<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    id: &str,
+) -> Result<(LeaveRequest, Employee), ApiError> {
+    let request = tenant
+        .find_by_id::<leave_request::Entity>(id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Leave request not found".to_string()))?;
+    let employee = tenant
+        .find_by_id::<employee::Entity>(&request.employee_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Employee not found".to_string()))?;
+    Ok((request, employee))
+}
+
+async fn decide(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+    decision: DecideLeave,
+    status: LeaveStatus,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    let (request, employee) = find_request(&txn, &tenant, &id).await?;
+    // Nobody approves their own leave, administrators included
+    if !caller.manages(&employee) || caller.is(&employee.id) {
+        return Err(ApiError::Forbidden(
+            "Only the employee's manager can decide on their leave".to_string(),
+        ));
+    }
+
+    let updated = services::decide_leave(
+        &txn,
+        request.clone(),
+        status,
+        &caller.user_id,
+        decision.note,
+    )
+    .await?;
+    audit
+        .record(
+            &txn,
+            "leave_request",
+            &updated.id,
+            AuditAction::Update,
+            Some(&request),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Approve pending leave of one of your reports
+#[utoipa::path(
+    post,
+    path = "/v1/leave/request/{id}/approve",
+    tag = "leave",
+    params(
+        ("id" = String, Path, description = "Leave request ID")
+    ),
+    request_body = DecideLeave,
+    responses(
+        (status = 200, description = "Leave approved", body = LeaveRequest),
+        (status = 403, description = "Not the manager of the employee"),
+        (status = 404, description = "Leave request not found"),
+        (status = 409, description = "The request is no longer pending"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn approve_request(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+    decision: ValidatedJson<DecideLeave>,
+) -> Result<HttpResponse, ApiError> {
+    decide(
+        data,
+        tenant,
+        audit,
+        claims,
+        id,
+        decision.into_inner(),
+        LeaveStatus::Approved,
+    )
+    .await
+}
+
+/// Reject pending leave of one of your reports
+#[utoipa::path(
+    post,
+    path = "/v1/leave/request/{id}/reject",
+    tag = "leave",
+    params(
+        ("id" = String, Path, description = "Leave request ID")
+    ),
+    request_body = DecideLeave,
+    responses(
+        (status = 200, description = "Leave rejected", body = LeaveRequest),
+        (status = 403, description = "Not the manager of the employee"),
+        (status = 404, description = "Leave request not found"),
+        (status = 409, description = "The request is no longer pending"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn reject_request(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+    decision: ValidatedJson<DecideLeave>,
+) -> Result<HttpResponse, ApiError> {
+    decide(
+        data,
+        tenant,
+        audit,
+        claims,
+        id,
+        decision.into_inner(),
+        LeaveStatus::Rejected,
+    )
+    .await
+}
+
+/// Cancel pending or approved leave, returning its days
+#[utoipa::path(
+    post,
+    path = "/v1/leave/request/{id}/cancel",
+    tag = "leave",
+    params(
+        ("id" = String, Path, description = "Leave request ID")
+    ),
+    responses(
+        (status = 200, description = "Leave cancelled", body = LeaveRequest),
+        (status = 403, description = "Not the employee or their manager"),
+        (status = 404, description = "Leave request not found"),
+        (status = 409, description = "The request was already rejected or cancelled"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn cancel_request(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    let (request, employee) = find_request(&txn, &tenant, &id).await?;
+    if !caller.is(&employee.id) && !caller.manages(&employee) {
+        return Err(ApiError::Forbidden(
+            "Only the employee or their manager can cancel their leave".to_string(),
+        ));
+    }
+
+    let updated = services::cancel_leave(&txn, request.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "leave_request",
+            &updated.id,
+            AuditAction::Update,
+            Some(&request),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
@@ -0,0 +1,143 @@
+use chrono::{Datelike, NaiveDate};
+use entity::holiday;
+use entity::leave_request::{self, LeaveStatus};
+use entity::leave_type::{self, Accrual};
+use sea_orm::{ColumnTrait, QueryFilter, QueryOrder, Select};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::tenant::Tenant;
+
+pub type LeaveType = leave_type::Model;
+pub type LeaveRequest = leave_request::Model;
+pub type Holiday = holiday::Model;
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateLeaveType {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: String,
+    pub accrual: Accrual,
+    /// Days earned over a full year, ignored without accrual.
+    #[validate(range(min = 0.0, max = 366.0, message = "Days per year must be 0 to 366"))]
+    #[serde(default)]
+    pub days_per_year: f64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateLeaveType {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: Option<String>,
+    pub accrual: Option<Accrual>,
+    #[validate(range(min = 0.0, max = 366.0, message = "Days per year must be 0 to 366"))]
+    pub days_per_year: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateHoliday {
+    pub date: NaiveDate,
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct HolidayFilter {
+    /// Only holidays in this year
+    pub year: Option<i32>,
+}
+
+impl HolidayFilter {
+    pub fn select(&self, tenant: &Tenant) -> Select<holiday::Entity> {
+        let mut query = tenant
+            .find::<holiday::Entity>()
+            .order_by_asc(holiday::Column::Date);
+        if let Some(start) = self
+            .year
+            .and_then(|year| NaiveDate::from_ymd_opt(year, 1, 1))
+        {
+            let end = NaiveDate::from_ymd_opt(start.year(), 12, 31).unwrap_or(start);
+            query = query.filter(holiday::Column::Date.between(start, end));
+        }
+        query
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct SubmitLeave {
+    /// Employee taking the leave, the caller's own employee record by default.
+    pub employee_id: Option<String>,
+    pub leave_type_id: String,
+    pub start_date: NaiveDate,
+    /// Last day of the leave, inclusive.
+    pub end_date: NaiveDate,
+    #[validate(length(max = 1000, message = "Reason must be at most 1000 characters"))]
+    #[schema(max_length = 1000)]
+    pub reason: Option<String>,
+}
+
+/// Sent to approve or reject, `{}` without a note.
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct DecideLeave {
+    #[validate(length(max = 1000, message = "Note must be at most 1000 characters"))]
+    #[schema(max_length = 1000)]
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct LeaveFilter {
+    pub employee_id: Option<String>,
+    pub status: Option<LeaveStatus>,
+    /// Only leave still running on or after this date
+    pub from: Option<NaiveDate>,
+    /// Only leave starting on or before this date
+    pub to: Option<NaiveDate>,
+}
+
+impl LeaveFilter {
+    pub fn select(&self, tenant: &Tenant) -> Select<leave_request::Entity> {
+        let mut query = tenant
+            .find::<leave_request::Entity>()
+            .order_by_desc(leave_request::Column::StartDate)
+            .order_by_asc(leave_request::Column::Id);
+        if let Some(employee_id) = &self.employee_id {
+            query = query.filter(leave_request::Column::EmployeeId.eq(employee_id));
+        }
+        if let Some(status) = self.status {
+            query = query.filter(leave_request::Column::Status.eq(status));
+        }
+        if let Some(from) = self.from {
+            query = query.filter(leave_request::Column::EndDate.gte(from));
+        }
+        if let Some(to) = self.to {
+            query = query.filter(leave_request::Column::StartDate.lte(to));
+        }
+        query
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct BalanceQuery {
+    /// The caller's own employee record by default
+    pub employee_id: Option<String>,
+    /// The current year by default
+    pub year: Option<i32>,
+}
+
+/// Where an employee stands with one type of leave in a year.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct LeaveBalance {
+    pub leave_type_id: String,
+    pub name: String,
+    pub accrual: Accrual,
+    /// Days earned so far, `null` when the leave is not limited.
+    pub entitled: Option<f64>,
+    /// Working days of approved leave.
+    pub taken: f64,
+    /// Working days of leave waiting for approval.
+    pub pending: f64,
+    /// Days that can still be requested, `null` when not limited.
+    pub available: Option<f64>,
+}
@@ -0,0 +1,36 @@
+use super::handlers;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/leave")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .route("/type", web::get().to(handlers::get_leave_types))
+            .route("/type", web::post().to(handlers::create_leave_type))
+            .route("/type/{id}", web::put().to(handlers::update_leave_type))
+            .route("/type/{id}", web::delete().to(handlers::delete_leave_type))
+            .route("/holiday", web::get().to(handlers::get_holidays))
+            .route("/holiday", web::post().to(handlers::create_holiday))
+            .route("/holiday/{id}", web::delete().to(handlers::delete_holiday))
+            .route("/balance", web::get().to(handlers::get_balances))
+            .route("/request", web::get().to(handlers::get_requests))
+            .route("/request", web::post().to(handlers::submit_request))
+            .route("/request/{id}", web::get().to(handlers::get_request_by_id))
+            .route(
+                "/request/{id}/approve",
+                web::post().to(handlers::approve_request),
+            )
+            .route(
+                "/request/{id}/reject",
+                web::post().to(handlers::reject_request),
+            )
+            .route(
+                "/request/{id}/cancel",
+                web::post().to(handlers::cancel_request),
+            ),
+    );
+}
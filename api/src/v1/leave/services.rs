@@ -0,0 +1,285 @@
+use chrono::{Datelike, NaiveDate, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, IntoActiveModel, PaginatorTrait, QueryFilter,
+    QueryOrder, Set,
+};
+use uuid::Uuid;
+
+use super::models::{
+    CreateHoliday, CreateLeaveType, Holiday, LeaveBalance, LeaveRequest, LeaveType, SubmitLeave,
+    UpdateLeaveType,
+};
+use crate::calendar;
+use crate::error::{ApiError, FieldErrors};
+use crate::shared::db_utils::conflict_on_duplicate;
+use crate::tenant::Tenant;
+use crate::v1::employee::models::{Employee, EmploymentState};
+use crate::v1::employee::services::timeline;
+use entity::leave_request::{self, LeaveStatus};
+use entity::{holiday, leave_type};
+
+const DUPLICATE_TYPE: &str = "A leave type with this name already exists";
+const DUPLICATE_HOLIDAY: &str = "There is already a holiday on this date";
+
+pub async fn create_leave_type<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    data: &CreateLeaveType,
+) -> Result<LeaveType, ApiError> {
+    leave_type::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        name: Set(data.name.clone()),
+        accrual: Set(data.accrual),
+        days_per_year: Set(data.days_per_year),
+        created_at: Set(Utc::now().naive_utc()),
+    }
+    .insert(db)
+    .await
+    .map_err(|e| conflict_on_duplicate(e, DUPLICATE_TYPE))
+}
+
+/// Balances follow the new rule from then on, requests already made keep
+/// their days.
+pub async fn update_leave_type<C: ConnectionTrait>(
+    db: &C,
+    leave_type: LeaveType,
+    data: &UpdateLeaveType,
+) -> Result<LeaveType, ApiError> {
+    let mut active = leave_type.into_active_model();
+    if let Some(name) = &data.name {
+        active.name = Set(name.clone());
+    }
+    if let Some(accrual) = data.accrual {
+        active.accrual = Set(accrual);
+    }
+    if let Some(days_per_year) = data.days_per_year {
+        active.days_per_year = Set(days_per_year);
+    }
+    active
+        .update(db)
+        .await
+        .map_err(|e| conflict_on_duplicate(e, DUPLICATE_TYPE))
+}
+
+pub async fn delete_leave_type<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    leave_type: LeaveType,
+) -> Result<(), ApiError> {
+    let requests = tenant
+        .find::<leave_request::Entity>()
+        .filter(leave_request::Column::LeaveTypeId.eq(&leave_type.id))
+        .count(db)
+        .await?;
+    if requests > 0 {
+        return Err(ApiError::Conflict(
+            "Leave of this type has been requested".to_string(),
+        ));
+    }
+
+    leave_type.into_active_model().delete(db).await?;
+    Ok(())
+}
+
+pub async fn create_holiday<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    data: &CreateHoliday,
+) -> Result<Holiday, ApiError> {
+    holiday::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        date: Set(data.date),
+        name: Set(data.name.clone()),
+    }
+    .insert(db)
+    .await
+    .map_err(|e| conflict_on_duplicate(e, DUPLICATE_HOLIDAY))
+}
+
+fn year_bounds(year: i32) -> Result<(NaiveDate, NaiveDate), ApiError> {
+    NaiveDate::from_ymd_opt(year, 1, 1)
+        .zip(NaiveDate::from_ymd_opt(year, 12, 31))
+        .ok_or_else(|| ApiError::ValidationError("Invalid year".to_string()))
+}
+
+/// Balances of an employee for every type of leave in a year. Leave counts
+/// against the year it starts in.
+pub async fn balances<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee: &Employee,
+    year: i32,
+) -> Result<Vec<LeaveBalance>, ApiError> {
+    let (start, end) = year_bounds(year)?;
+    let leave_types = tenant
+        .find::<leave_type::Entity>()
+        .order_by_asc(leave_type::Column::Name)
+        .all(db)
+        .await?;
+    let requests = tenant
+        .find::<leave_request::Entity>()
+        .filter(leave_request::Column::EmployeeId.eq(&employee.id))
+        .filter(leave_request::Column::Status.is_in([LeaveStatus::Pending, LeaveStatus::Approved]))
+        .filter(leave_request::Column::StartDate.between(start, end))
+        .all(db)
+        .await?;
+
+    // Accrual starts with the current employment
+    let today = Utc::now().date_naive();
+    let records = timeline(db, tenant, &employee.id).await?;
+    let hire_date = EmploymentState::as_of(&records, today).hire_date;
+
+    Ok(leave_types
+        .into_iter()
+        .map(|leave_type| {
+            let days = |status: LeaveStatus| -> f64 {
+                requests
+                    .iter()
+                    .filter(|r| r.leave_type_id == leave_type.id && r.status == status)
+                    .map(|r| r.days)
+                    .sum()
+            };
+            let taken = days(LeaveStatus::Approved);
+            let pending = days(LeaveStatus::Pending);
+            let entitled = leave_type.entitlement(year, today, hire_date);
+            LeaveBalance {
+                available: entitled.map(|days| ((days - taken - pending) * 100.0).round() / 100.0),
+                leave_type_id: leave_type.id,
+                name: leave_type.name,
+                accrual: leave_type.accrual,
+                entitled,
+                taken,
+                pending,
+            }
+        })
+        .collect())
+}
+
+/// Requests leave for a locked employee, holding the working days it covers
+/// until it is decided on. Leave cannot overlap other pending or approved
+/// leave of the employee, nor take more days than are left.
+pub async fn submit_leave<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee: &Employee,
+    data: &SubmitLeave,
+) -> Result<LeaveRequest, ApiError> {
+    let mut errors = FieldErrors::new();
+    if data.end_date < data.start_date {
+        errors.add("end_date", "The leave cannot end before it starts");
+    } else if data.end_date.year() != data.start_date.year() {
+        errors.add(
+            "end_date",
+            "The leave must end in the year it starts, request the rest separately",
+        );
+    }
+    let leave_type = tenant
+        .find_by_id::<leave_type::Entity>(&data.leave_type_id)
+        .one(db)
+        .await?;
+    let Some(leave_type) = leave_type else {
+        errors.add("leave_type_id", "Leave type not found");
+        return Err(ApiError::InvalidFields(errors));
+    };
+    errors.into_result()?;
+
+    let holidays = calendar::holidays(db, tenant, data.start_date, data.end_date).await?;
+    let days = calendar::working_days(data.start_date, data.end_date, &holidays) as f64;
+    if days == 0.0 {
+        let mut errors = FieldErrors::new();
+        errors.add("start_date", "The leave covers no working days");
+        return Err(ApiError::InvalidFields(errors));
+    }
+
+    let overlapping = tenant
+        .find::<leave_request::Entity>()
+        .filter(leave_request::Column::EmployeeId.eq(&employee.id))
+        .filter(leave_request::Column::Status.is_in([LeaveStatus::Pending, LeaveStatus::Approved]))
+        .filter(leave_request::Column::StartDate.lte(data.end_date))
+        .filter(leave_request::Column::EndDate.gte(data.start_date))
+        .count(db)
+        .await?;
+    if overlapping > 0 {
+        return Err(ApiError::Conflict(
+            "The employee already has leave requested on these dates".to_string(),
+        ));
+    }
+
+    let available = balances(db, tenant, employee, data.start_date.year())
+        .await?
+        .into_iter()
+        .find(|balance| balance.leave_type_id == leave_type.id)
+        .and_then(|balance| balance.available);
+    if let Some(available) = available {
+        if days > available {
+            return Err(ApiError::ValidationError(format!(
+                "Not enough {} left: {days} working days requested, {available} available",
+                leave_type.name
+            )));
+        }
+    }
+
+    let now = Utc::now().naive_utc();
+    Ok(leave_request::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        employee_id: Set(employee.id.clone()),
+        leave_type_id: Set(leave_type.id),
+        start_date: Set(data.start_date),
+        end_date: Set(data.end_date),
+        days: Set(days),
+        reason: Set(data.reason.clone()),
+        status: Set(LeaveStatus::Pending),
+        decided_by: Set(None),
+        decided_at: Set(None),
+        decision_note: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(db)
+    .await?)
+}
+
+/// Approves or rejects a locked pending request. Approved leave keeps its
+/// days, rejected leave gives them back.
+pub async fn decide_leave<C: ConnectionTrait>(
+    db: &C,
+    request: LeaveRequest,
+    status: LeaveStatus,
+    decided_by: &str,
+    note: Option<String>,
+) -> Result<LeaveRequest, ApiError> {
+    if request.status != LeaveStatus::Pending {
+        return Err(ApiError::Conflict(
+            "Only pending requests can be approved or rejected".to_string(),
+        ));
+    }
+
+    let now = Utc::now().naive_utc();
+    let mut active = request.into_active_model();
+    active.status = Set(status);
+    active.decided_by = Set(Some(decided_by.to_string()));
+    active.decided_at = Set(Some(now));
+    active.decision_note = Set(note);
+    active.updated_at = Set(now);
+    Ok(active.update(db).await?)
+}
+
+/// Withdraws a locked pending or approved request, returning its days.
+pub async fn cancel_leave<C: ConnectionTrait>(
+    db: &C,
+    request: LeaveRequest,
+) -> Result<LeaveRequest, ApiError> {
+    if !request.status.is_open() {
+        return Err(ApiError::Conflict(
+            "The request was already rejected or cancelled".to_string(),
+        ));
+    }
+
+    let mut active = request.into_active_model();
+    active.status = Set(LeaveStatus::Cancelled);
+    active.updated_at = Set(Utc::now().naive_utc());
+    Ok(active.update(db).await?)
+}
@@ -1,4 +1,24 @@
+pub mod attachment;
+pub mod attendance;
+pub mod audit;
 pub mod auth;
+pub mod company;
+pub mod customer;
+pub mod department;
 pub mod employee;
+pub mod events;
+pub mod expense;
+pub mod import;
 pub mod inventory;
+pub mod leave;
 pub mod order;
+pub mod payroll;
+pub mod position;
+pub mod pricing;
+pub mod project;
+pub mod quotation;
+pub mod stock;
+pub mod stock_count;
+pub mod timesheet;
+pub mod traceability;
+pub mod webhook;
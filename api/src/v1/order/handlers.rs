@@ -1,20 +1,41 @@
-use actix_web::{web, HttpResponse};
-use chrono::Utc;
-use sea_orm::{ActiveModelTrait, EntityTrait, QueryOrder, Set};
-use uuid::Uuid;
+use actix_web::{web, HttpRequest, HttpResponse};
+use sea_orm::{ConnectionTrait, QuerySelect, TransactionTrait};
 
-use super::models::{CreateOrder, Order, UpdateOrder};
+use super::models::{CreateOrder, Order, OrderFilter, OrderLine, UpdateOrder};
+use super::services::{self, OrderBatch};
+use crate::audit::AuditContext;
+use crate::batch::{batch_response, run_batch, BatchRequest, BatchResponse};
 use crate::error::ApiError;
+use crate::export::{stream_export, ExportFormat, ExportQuery};
+use crate::extractors::{Admin, ValidatedJson};
+use crate::middlewares::jwt::Claims;
+use crate::shared::concurrency::{self, Precondition};
+use crate::shared::db_utils::find_locked;
+use crate::soft_delete;
+use crate::tenant::Tenant;
+use crate::v1::auth::services as auth_services;
+use entity::audit_log::AuditAction;
 use entity::order;
 use serde_json::json;
 
+/// The caller, when their orders need no approval for large discounts.
+async fn approver<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    claims: &Claims,
+) -> Result<Option<String>, ApiError> {
+    let caller = auth_services::caller(db, tenant, claims).await?;
+    Ok(caller.is_admin.then_some(caller.user_id))
+}
+
 #[utoipa::path(
     post,
     path = "/v1/order",
+    tag = "order",
     request_body = CreateOrder,
     responses(
         (status = 201, description = "Order created successfully", body = Order),
-        (status = 400, description = "Validation error"),
+        (status = 400, description = "Validation error or an item without a price"),
         (status = 500, description = "Internal server error")
     ),
     security(
@@ -23,32 +44,37 @@ use serde_json::json;
 )]
 pub async fn create_order(
     data: web::Data<config::app::AppState>,
-    order: web::Json<CreateOrder>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    order: ValidatedJson<CreateOrder>,
 ) -> Result<HttpResponse, ApiError> {
-    if order.total_amount < 0.0 {
-        return Err(ApiError::ValidationError(
-            "Total amount cannot be negative".to_string(),
-        ));
-    }
-
-    let new_uuid = Uuid::new_v4();
-    let now = Utc::now().naive_utc();
-    let new_order = order::ActiveModel {
-        id: Set(new_uuid.to_string()),
-        customer_id: Set(order.customer_id.clone()),
-        total_amount: Set(order.total_amount),
-        created_at: Set(now),
-    };
-
-    let inserted_order = new_order.insert(&data.db).await?;
-
-    let order_response: Order = inserted_order;
-    Ok(HttpResponse::Ok().json(order_response))
+    let txn = data.db.begin().await?;
+    let approver = approver(&txn, &tenant, &claims).await?;
+    let inserted_order = services::create_order(&txn, &tenant, &order, approver.as_deref()).await?;
+    audit
+        .record(
+            &txn,
+            "order",
+            &inserted_order.id,
+            AuditAction::Create,
+            None,
+            Some(&inserted_order),
+        )
+        .await?;
+    txn.commit().await?;
+    Ok(HttpResponse::Ok().json(inserted_order))
 }
 
 #[utoipa::path(
     get,
     path = "/v1/order",
+    tag = "order",
+    params(
+        ("customer_id" = Option<String>, Query, description = "Filter by customer"),
+        ("from" = Option<String>, Query, description = "Created on or after this date (YYYY-MM-DD)"),
+        ("to" = Option<String>, Query, description = "Created on or before this date (YYYY-MM-DD)")
+    ),
     responses(
         (status = 200, description = "List of orders", body = Vec<Order>),
         (status = 500, description = "Internal server error")
@@ -59,22 +85,62 @@ pub async fn create_order(
 )]
 pub async fn get_all_orders(
     data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<OrderFilter>,
 ) -> Result<HttpResponse, ApiError> {
-    let orders = order::Entity::find()
-        .order_by_asc(entity::order::Column::CreatedAt)
-        .all(&data.db)
-        .await?;
+    let orders = filter.select(&tenant).all(&data.db).await?;
 
     let order_responses: Vec<Order> = orders.into_iter().map(|o| o.into()).collect();
 
     Ok(HttpResponse::Ok().json(order_responses))
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/order/export",
+    tag = "order",
+    params(
+        ("format" = Option<ExportFormat>, Query, description = "csv (default), xlsx or jsonl"),
+        ("customer_id" = Option<String>, Query, description = "Filter by customer"),
+        ("from" = Option<String>, Query, description = "Created on or after this date (YYYY-MM-DD)"),
+        ("to" = Option<String>, Query, description = "Created on or before this date (YYYY-MM-DD)")
+    ),
+    responses(
+        (status = 200, description = "Export file", content(
+            (String = "text/csv"),
+            (String = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+            (String = "application/x-ndjson")
+        )),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn export_orders(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<OrderFilter>,
+    query: web::Query<ExportQuery>,
+) -> HttpResponse {
+    stream_export(
+        data.db.clone(),
+        filter.select(&tenant),
+        query.format,
+        "orders",
+    )
+}
+
 #[utoipa::path(
     get,
     path = "/v1/order/{id}",
+    tag = "order",
+    params(
+        ("If-None-Match" = Option<String>, Header, description = "ETag from an earlier response")
+    ),
     responses(
         (status = 200, description = "Order found", body = Order),
+        (status = 304, description = "Order unchanged since the given ETag"),
         (status = 404, description = "Order not found"),
         (status = 500, description = "Internal server error")
     ),
@@ -83,26 +149,69 @@ pub async fn get_all_orders(
     )
 )]
 pub async fn get_order_by_id(
+    req: HttpRequest,
     data: web::Data<config::app::AppState>,
+    tenant: Tenant,
     id: web::Path<String>,
 ) -> Result<HttpResponse, ApiError> {
-    let order = order::Entity::find_by_id(id.into_inner())
+    let order = tenant
+        .find_active_by_id::<order::Entity>(&id)
         .one(&data.db)
         .await?
         .ok_or(ApiError::NotFound("Order not found".to_string()))?;
 
+    if concurrency::is_not_modified(&req, order.version) {
+        return Ok(concurrency::not_modified(order.version));
+    }
+
     let order_response: Order = order.into();
-    Ok(HttpResponse::Ok().json(order_response))
+    Ok(HttpResponse::Ok()
+        .insert_header(concurrency::etag(order_response.version))
+        .json(order_response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/order/{id}/lines",
+    tag = "order",
+    responses(
+        (status = 200, description = "Lines of the order", body = Vec<OrderLine>),
+        (status = 404, description = "Order not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_order_lines(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let order = tenant
+        .find_active_by_id::<order::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or(ApiError::NotFound("Order not found".to_string()))?;
+    let lines = services::lines(&data.db, &tenant, &order.id).await?;
+
+    Ok(HttpResponse::Ok().json(lines))
 }
 
 #[utoipa::path(
     put,
     path = "/v1/order/{id}",
+    tag = "order",
+    params(
+        ("If-Match" = String, Header, description = "ETag of the version being replaced")
+    ),
     request_body = UpdateOrder,
     responses(
         (status = 200, description = "Order updated successfully", body = Order),
         (status = 400, description = "Validation error"),
         (status = 404, description = "Order not found"),
+        (status = 412, description = "Order was changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
         (status = 500, description = "Internal server error")
     ),
     security(
@@ -110,42 +219,58 @@ pub async fn get_order_by_id(
     )
 )]
 pub async fn update_order(
+    req: HttpRequest,
     data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
     id: web::Path<String>,
-    order: web::Json<UpdateOrder>,
+    order: ValidatedJson<UpdateOrder>,
 ) -> Result<HttpResponse, ApiError> {
+    let precondition = Precondition::from_request(&req)?;
     let order_id = id.into_inner();
 
-    let existing_order = order::Entity::find_by_id(&order_id)
-        .one(&data.db)
-        .await?
-        .ok_or(ApiError::NotFound("Order not found".to_string()))?;
-
-    let mut order_model: order::ActiveModel = existing_order.into();
-
-    if let Some(customer_id) = &order.customer_id {
-        order_model.customer_id = Set(customer_id.clone());
-    }
-    if let Some(total_amount) = order.total_amount {
-        if total_amount < 0.0 {
-            return Err(ApiError::ValidationError(
-                "Total amount cannot be negative".to_string(),
-            ));
-        }
-        order_model.total_amount = Set(total_amount);
-    }
+    let txn = data.db.begin().await?;
+    let approver = approver(&txn, &tenant, &claims).await?;
+    let before = find_locked::<order::Entity, _>(&txn, &tenant, &order_id).await?;
+    let updated_order = services::update_order(
+        &txn,
+        &tenant,
+        &order_id,
+        &precondition,
+        &order,
+        approver.as_deref(),
+    )
+    .await?;
+    audit
+        .record(
+            &txn,
+            "order",
+            &order_id,
+            AuditAction::Update,
+            before.as_ref(),
+            Some(&updated_order),
+        )
+        .await?;
+    txn.commit().await?;
 
-    let updated_order = order_model.update(&data.db).await?;
-    let order_response: Order = updated_order.into();
-    Ok(HttpResponse::Ok().json(order_response))
+    Ok(HttpResponse::Ok()
+        .insert_header(concurrency::etag(updated_order.version))
+        .json(updated_order))
 }
 
 #[utoipa::path(
     delete,
     path = "/v1/order/{id}",
+    tag = "order",
+    params(
+        ("If-Match" = String, Header, description = "ETag of the version being deleted")
+    ),
     responses(
         (status = 200, description = "Order deleted successfully"),
         (status = 404, description = "Order not found"),
+        (status = 412, description = "Order was changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
         (status = 500, description = "Internal server error")
     ),
     security(
@@ -153,15 +278,173 @@ pub async fn update_order(
     )
 )]
 pub async fn delete_order(
+    req: HttpRequest,
     data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
     id: web::Path<String>,
 ) -> Result<HttpResponse, ApiError> {
-    let order = order::Entity::find_by_id(id.into_inner())
-        .one(&data.db)
+    let precondition = Precondition::from_request(&req)?;
+    let order_id = id.into_inner();
+
+    let txn = data.db.begin().await?;
+    let before = find_locked::<order::Entity, _>(&txn, &tenant, &order_id).await?;
+    services::delete_order(&txn, &tenant, &order_id, &precondition).await?;
+    audit
+        .record(
+            &txn,
+            "order",
+            &order_id,
+            AuditAction::Delete,
+            before.as_ref(),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Order deleted successfully"})))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/order/batch",
+    tag = "order",
+    request_body = BatchRequest<CreateOrder, UpdateOrder>,
+    responses(
+        (status = 200, description = "Batch processed", body = BatchResponse),
+        (status = 400, description = "Empty or oversized batch"),
+        (status = 422, description = "Atomic batch rolled back", body = BatchResponse),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn batch_orders(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    batch: web::Json<BatchRequest<CreateOrder, UpdateOrder>>,
+) -> Result<HttpResponse, ApiError> {
+    let outcome = run_batch::<OrderBatch>(&data.db, &tenant, &audit, batch.into_inner()).await?;
+    Ok(batch_response(outcome.response))
+}
+
+/// Restore a deleted order
+#[utoipa::path(
+    post,
+    path = "/v1/order/{id}/restore",
+    tag = "order",
+    params(
+        ("id" = String, Path, description = "Order ID")
+    ),
+    responses(
+        (status = 200, description = "Order restored", body = Order),
+        (status = 404, description = "Order not found"),
+        (status = 409, description = "Order is not deleted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn restore_order(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let order =
+        soft_delete::restore::<order::Entity, _>(&txn, &tenant, &audit, "order", &id).await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(concurrency::etag(order.version))
+        .json(order))
+}
+
+/// Approve discounts past the company's limits, administrators only
+#[utoipa::path(
+    post,
+    path = "/v1/order/{id}/approve-discount",
+    tag = "order",
+    params(
+        ("id" = String, Path, description = "Order ID")
+    ),
+    responses(
+        (status = 200, description = "Discount approved", body = Order),
+        (status = 403, description = "Caller is not an administrator"),
+        (status = 404, description = "Order not found"),
+        (status = 409, description = "No discount is waiting for approval"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn approve_discount(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    _admin: Admin,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let before = tenant
+        .find_active_by_id::<order::Entity>(id.as_str())
+        .lock_exclusive()
+        .one(&txn)
         .await?
         .ok_or(ApiError::NotFound("Order not found".to_string()))?;
+    let approved = services::approve_discount(&txn, before.clone(), &claims.sub).await?;
+    audit
+        .record(
+            &txn,
+            "order",
+            &approved.id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&approved),
+        )
+        .await?;
+    txn.commit().await?;
 
-    let order_active: order::ActiveModel = order.into();
-    order_active.delete(&data.db).await?;
-    Ok(HttpResponse::Ok().json(json!({"message": "Order deleted successfully"})))
+    Ok(HttpResponse::Ok()
+        .insert_header(concurrency::etag(approved.version))
+        .json(approved))
+}
+
+/// Permanently remove a deleted order, administrators only
+#[utoipa::path(
+    delete,
+    path = "/v1/order/{id}/purge",
+    tag = "order",
+    params(
+        ("id" = String, Path, description = "Order ID")
+    ),
+    responses(
+        (status = 200, description = "Order purged"),
+        (status = 403, description = "Caller is not an administrator"),
+        (status = 404, description = "Order not found"),
+        (status = 409, description = "Order must be deleted first"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn purge_order(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    _admin: Admin,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    soft_delete::purge::<order::Entity, _>(&txn, &tenant, &audit, "order", &id).await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Order purged successfully"})))
 }
@@ -1,3 +1,4 @@
 pub mod handlers;
 pub mod models;
 pub mod routes;
+pub mod services;
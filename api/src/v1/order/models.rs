@@ -1,17 +1,169 @@
-use entity::order;
+use chrono::NaiveDate;
+use entity::{order, order_line};
+use sea_orm::{ColumnTrait, QueryFilter, QueryOrder, Select};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::export::{Cell, ExportRow};
+use crate::shared::nullable;
+use crate::shared::round;
+use crate::tenant::Tenant;
+use crate::v1::pricing::models::{Discount, PricingLineInput, PricingRequest};
 
 pub type Order = order::Model;
+pub type OrderLine = order_line::Model;
 
-#[derive(Serialize, Deserialize, ToSchema)]
+/// An order is priced from its lines, see `POST /v1/pricing/quote`.
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
 pub struct CreateOrder {
+    #[validate(length(min = 1, max = 36, message = "Customer id must be 1 to 36 characters"))]
+    #[schema(min_length = 1, max_length = 36)]
     pub customer_id: String,
-    pub total_amount: f64,
+    /// The company's own currency by default.
+    #[validate(length(equal = 3, message = "Currency must be a 3-letter code"))]
+    #[schema(min_length = 3, max_length = 3)]
+    pub currency: Option<String>,
+    #[validate(length(min = 1, message = "Order at least one line"))]
+    pub lines: Vec<PricingLineInput>,
+    /// Discount on the whole order, on top of the lines' own.
+    pub discount: Option<Discount>,
+}
+
+impl CreateOrder {
+    pub fn pricing(&self) -> PricingRequest {
+        PricingRequest {
+            customer_id: self.customer_id.clone(),
+            currency: self.currency.clone(),
+            date: None,
+            lines: self.lines.clone(),
+            discount: self.discount,
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, ToSchema)]
+/// Changes an order. New lines are priced at today's prices; the stored
+/// lines keep theirs, e.g. those agreed on a quote, and only the totals are
+/// worked out again.
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
 pub struct UpdateOrder {
+    #[validate(length(min = 1, max = 36, message = "Customer id must be 1 to 36 characters"))]
+    #[schema(min_length = 1, max_length = 36)]
     pub customer_id: Option<String>,
-    pub total_amount: Option<f64>,
+    #[validate(length(equal = 3, message = "Currency must be a 3-letter code"))]
+    #[schema(min_length = 3, max_length = 3)]
+    pub currency: Option<String>,
+    /// Replace all of the order's lines.
+    #[validate(length(min = 1, message = "Order at least one line"))]
+    pub lines: Option<Vec<PricingLineInput>>,
+    /// Discount on the whole order; `null` removes it.
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<Discount>, nullable)]
+    pub discount: Option<Option<Discount>>,
+}
+
+impl UpdateOrder {
+    /// Whether the update changes anything the order's prices depend on.
+    pub fn reprices(&self) -> bool {
+        self.customer_id.is_some()
+            || self.currency.is_some()
+            || self.lines.is_some()
+            || self.discount.is_some()
+    }
+}
+
+/// An order with its prices worked out, ready to be placed.
+#[derive(Clone, Debug)]
+pub struct NewOrder {
+    pub customer_id: String,
+    pub currency: Option<String>,
+    pub quotation_id: Option<String>,
+    pub lines: Vec<PricedLine>,
+    pub discount: Option<Discount>,
+    pub discount_total: f64,
+    pub total: f64,
+    /// Whether the discounts need an administrator's approval.
+    pub requires_approval: bool,
+}
+
+/// A line with its price worked out, ready to go on an order or a quote.
+#[derive(Clone, Debug)]
+pub struct PricedLine {
+    pub inventory_id: Option<String>,
+    pub description: String,
+    pub quantity: i32,
+    pub unit_price: f64,
+    pub discount_percent: f64,
+    pub line_total: f64,
+}
+
+impl PricedLine {
+    /// `quantity` at `unit_price` before the discount.
+    pub fn gross(&self) -> f64 {
+        f64::from(self.quantity) * self.unit_price
+    }
+
+    /// Money off the line, whatever kind of discount took it.
+    pub fn discount_amount(&self) -> f64 {
+        round(self.gross() - self.line_total)
+    }
+}
+
+impl From<&OrderLine> for PricedLine {
+    fn from(line: &OrderLine) -> Self {
+        PricedLine {
+            inventory_id: line.inventory_id.clone(),
+            description: line.description.clone(),
+            quantity: line.quantity,
+            unit_price: line.unit_price,
+            discount_percent: line.discount_percent,
+            line_total: line.line_total,
+        }
+    }
+}
+
+/// Filters shared by the list and export endpoints.
+#[derive(Deserialize)]
+pub struct OrderFilter {
+    pub customer_id: Option<String>,
+    /// First day included, by creation date.
+    pub from: Option<NaiveDate>,
+    /// Last day included, by creation date.
+    pub to: Option<NaiveDate>,
+}
+
+impl OrderFilter {
+    pub fn select(&self, tenant: &Tenant) -> Select<order::Entity> {
+        let mut query = tenant
+            .find_active::<order::Entity>()
+            .order_by_asc(order::Column::CreatedAt);
+        if let Some(customer_id) = &self.customer_id {
+            query = query.filter(order::Column::CustomerId.eq(customer_id));
+        }
+        if let Some(from) = self.from {
+            query = query.filter(order::Column::CreatedAt.gte(from.and_hms_opt(0, 0, 0)));
+        }
+        if let Some(to) = self.to.and_then(|to| to.succ_opt()) {
+            query = query.filter(order::Column::CreatedAt.lt(to.and_hms_opt(0, 0, 0)));
+        }
+        query
+    }
+}
+
+impl ExportRow for Order {
+    fn headers() -> &'static [&'static str] {
+        &["id", "customer_id", "total_amount", "created_at"]
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        vec![
+            self.id.clone().into(),
+            self.customer_id.clone().into(),
+            self.total_amount.into(),
+            self.created_at
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+                .into(),
+        ]
+    }
 }
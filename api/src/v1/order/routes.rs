@@ -1,4 +1,7 @@
 use super::handlers;
+use crate::batch::MAX_BATCH_BYTES;
+use crate::error::json_config;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
 use crate::middlewares::jwt::JwtMiddleware;
 use actix_web::web;
 
@@ -6,11 +9,25 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
     let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
     cfg.service(
         web::scope("/v1/order")
+            .wrap(IdempotencyMiddleware::new())
             .wrap(jwt_middleware)
             .route("", web::get().to(handlers::get_all_orders))
             .route("", web::post().to(handlers::create_order))
+            .route("/export", web::get().to(handlers::export_orders))
+            .service(
+                web::resource("/batch")
+                    .app_data(json_config().limit(MAX_BATCH_BYTES))
+                    .route(web::post().to(handlers::batch_orders)),
+            )
             .route("/{id}", web::get().to(handlers::get_order_by_id))
             .route("/{id}", web::put().to(handlers::update_order))
-            .route("/{id}", web::delete().to(handlers::delete_order)),
+            .route("/{id}", web::delete().to(handlers::delete_order))
+            .route("/{id}/lines", web::get().to(handlers::get_order_lines))
+            .route(
+                "/{id}/approve-discount",
+                web::post().to(handlers::approve_discount),
+            )
+            .route("/{id}/restore", web::post().to(handlers::restore_order))
+            .route("/{id}/purge", web::delete().to(handlers::purge_order)),
     );
 }
@@ -0,0 +1,289 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseTransaction, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use uuid::Uuid;
+
+use super::models::{CreateOrder, NewOrder, Order, OrderLine, PricedLine, UpdateOrder};
+use crate::batch::BatchResource;
+use crate::error::{ApiError, FieldErrors};
+use crate::shared::concurrency::Precondition;
+use crate::shared::db_utils::find_locked;
+use crate::tenant::Tenant;
+use crate::v1::pricing::models::{Discount, PricingRequest};
+use crate::v1::pricing::services as pricing_services;
+use entity::order::DiscountStatus;
+use entity::{customer, order, order_line};
+
+pub async fn create_order<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    order: &CreateOrder,
+    approver: Option<&str>,
+) -> Result<Order, ApiError> {
+    let quote = pricing_services::quote(db, tenant, &order.pricing()).await?;
+    let (order, _) = place_order(db, tenant, quote.into(), approver).await?;
+    Ok(order)
+}
+
+/// Sets what the order owes from its prices. Discounts past the company's
+/// limits wait for an administrator unless `approver` is one.
+fn set_prices(active: &mut order::ActiveModel, order: &NewOrder, approver: Option<&str>) {
+    active.customer_id = Set(order.customer_id.clone());
+    active.currency = Set(order.currency.clone());
+    active.total_amount = Set(order.total);
+    active.discount_total = Set(order.discount_total);
+    active.discount_kind = Set(order.discount.map(|discount| discount.kind));
+    active.discount_value = Set(order.discount.map(|discount| discount.value));
+    let (status, approved_by) = match (order.requires_approval, approver) {
+        (false, _) => (None, None),
+        (true, None) => (Some(DiscountStatus::Pending), None),
+        (true, Some(approver)) => (Some(DiscountStatus::Approved), Some(approver.to_string())),
+    };
+    active.discount_approved_at = Set(approved_by.as_ref().map(|_| Utc::now().naive_utc()));
+    active.discount_approved_by = Set(approved_by);
+    active.discount_status = Set(status);
+}
+
+async fn insert_lines<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    order_id: &str,
+    lines: &[PricedLine],
+) -> Result<Vec<OrderLine>, ApiError> {
+    let mut order_lines = Vec::with_capacity(lines.len());
+    for (position, line) in (1..).zip(lines) {
+        let order_line = order_line::ActiveModel {
+            id: Set(Uuid::new_v4().to_string()),
+            tenant_id: Set(tenant.id().to_string()),
+            order_id: Set(order_id.to_string()),
+            position: Set(position),
+            inventory_id: Set(line.inventory_id.clone()),
+            description: Set(line.description.clone()),
+            quantity: Set(line.quantity),
+            unit_price: Set(line.unit_price),
+            discount_percent: Set(line.discount_percent),
+            discount_amount: Set(line.discount_amount()),
+            line_total: Set(line.line_total),
+        }
+        .insert(db)
+        .await?;
+        order_lines.push(order_line);
+    }
+    Ok(order_lines)
+}
+
+/// Places an order priced beforehand, by the pricing rules or from a quote.
+pub async fn place_order<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    order: NewOrder,
+    approver: Option<&str>,
+) -> Result<(Order, Vec<OrderLine>), ApiError> {
+    let mut active = order::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        quotation_id: Set(order.quotation_id.clone()),
+        created_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    };
+    set_prices(&mut active, &order, approver);
+    let placed = active.insert(db).await?;
+    let lines = insert_lines(db, tenant, &placed.id, &order.lines).await?;
+    Ok((placed, lines))
+}
+
+pub async fn lines<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    order_id: &str,
+) -> Result<Vec<OrderLine>, ApiError> {
+    Ok(tenant
+        .find::<order_line::Entity>()
+        .filter(order_line::Column::OrderId.eq(order_id))
+        .order_by_asc(order_line::Column::Position)
+        .all(db)
+        .await?)
+}
+
+/// Applies the changes. Given lines are priced at today's prices and
+/// replace the order's; otherwise the stored lines keep their prices and
+/// only the totals are worked out again.
+pub async fn update_order<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    order_id: &str,
+    precondition: &Precondition,
+    order: &UpdateOrder,
+    approver: Option<&str>,
+) -> Result<Order, ApiError> {
+    let existing_order = tenant
+        .find_active_by_id::<order::Entity>(order_id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or(ApiError::NotFound("Order not found".to_string()))?;
+    precondition.check(existing_order.version)?;
+    if !order.reprices() {
+        return Ok(existing_order);
+    }
+
+    let customer_id = order
+        .customer_id
+        .clone()
+        .unwrap_or_else(|| existing_order.customer_id.clone());
+    let currency = order
+        .currency
+        .as_ref()
+        .map(|currency| currency.to_uppercase())
+        .or_else(|| existing_order.currency.clone());
+    let existing_discount = existing_order
+        .discount_kind
+        .zip(existing_order.discount_value)
+        .map(|(kind, value)| Discount { kind, value });
+    let discount = order.discount.unwrap_or(existing_discount);
+
+    let repriced = match &order.lines {
+        Some(lines) => {
+            let request = PricingRequest {
+                customer_id,
+                currency,
+                date: None,
+                lines: lines.clone(),
+                discount,
+            };
+            let repriced: NewOrder = pricing_services::quote(db, tenant, &request).await?.into();
+            tenant
+                .delete_many::<order_line::Entity>()
+                .filter(order_line::Column::OrderId.eq(&existing_order.id))
+                .exec(db)
+                .await?;
+            insert_lines(db, tenant, &existing_order.id, &repriced.lines).await?;
+            repriced
+        }
+        None => {
+            if order.customer_id.is_some() {
+                let customer = tenant
+                    .find_active_by_id::<customer::Entity>(&customer_id)
+                    .one(db)
+                    .await?;
+                if customer.is_none() {
+                    let mut errors = FieldErrors::new();
+                    errors.add("customer_id", "Customer not found");
+                    return Err(ApiError::InvalidFields(errors));
+                }
+            }
+            let lines: Vec<PricedLine> = self::lines(db, tenant, &existing_order.id)
+                .await?
+                .iter()
+                .map(PricedLine::from)
+                .collect();
+            let totals = pricing_services::totals(db, tenant, &lines, discount.as_ref()).await?;
+            NewOrder {
+                customer_id,
+                currency,
+                quotation_id: existing_order.quotation_id.clone(),
+                lines,
+                discount,
+                discount_total: totals.discount_total,
+                total: totals.total,
+                requires_approval: totals.requires_approval,
+            }
+        }
+    };
+    let mut order_model: order::ActiveModel = existing_order.into();
+    set_prices(&mut order_model, &repriced, approver);
+
+    Ok(order_model.update(db).await?)
+}
+
+/// An administrator lets an order's discounts past the company's limits.
+pub async fn approve_discount<C: ConnectionTrait>(
+    db: &C,
+    order: Order,
+    user_id: &str,
+) -> Result<Order, ApiError> {
+    if order.discount_status != Some(DiscountStatus::Pending) {
+        return Err(ApiError::Conflict(
+            "The order has no discount waiting for approval".to_string(),
+        ));
+    }
+    let mut active: order::ActiveModel = order.into();
+    active.discount_status = Set(Some(DiscountStatus::Approved));
+    active.discount_approved_by = Set(Some(user_id.to_string()));
+    active.discount_approved_at = Set(Some(Utc::now().naive_utc()));
+    Ok(active.update(db).await?)
+}
+
+pub async fn delete_order<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    order_id: &str,
+    precondition: &Precondition,
+) -> Result<(), ApiError> {
+    let order = tenant
+        .find_active_by_id::<order::Entity>(order_id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or(ApiError::NotFound("Order not found".to_string()))?;
+    precondition.check(order.version)?;
+
+    let mut order_active: order::ActiveModel = order.into();
+    order_active.deleted_at = Set(Some(Utc::now().naive_utc()));
+    order_active.update(db).await?;
+
+    Ok(())
+}
+
+pub struct OrderBatch;
+
+#[async_trait]
+impl BatchResource for OrderBatch {
+    const ENTITY: &'static str = "order";
+
+    type Create = CreateOrder;
+    type Update = UpdateOrder;
+    type Model = Order;
+
+    async fn find(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        id: &str,
+    ) -> Result<Option<Order>, ApiError> {
+        find_locked::<order::Entity, _>(txn, tenant, id).await
+    }
+
+    async fn create(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        data: &CreateOrder,
+    ) -> Result<Order, ApiError> {
+        create_order(txn, tenant, data, None).await
+    }
+
+    async fn update(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        id: &str,
+        precondition: &Precondition,
+        data: &UpdateOrder,
+    ) -> Result<Order, ApiError> {
+        update_order(txn, tenant, id, precondition, data, None).await
+    }
+
+    async fn delete(
+        txn: &DatabaseTransaction,
+        tenant: &Tenant,
+        id: &str,
+        precondition: &Precondition,
+    ) -> Result<(), ApiError> {
+        delete_order(txn, tenant, id, precondition).await
+    }
+
+    fn id(model: &Order) -> String {
+        model.id.clone()
+    }
+}
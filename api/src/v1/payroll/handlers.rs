@@ -0,0 +1,673 @@
+use actix_web::{http::header, web, HttpResponse};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, ModelTrait, QueryFilter, QueryOrder, QuerySelect,
+    TransactionTrait,
+};
+use serde_json::json;
+
+use super::models::{
+    CreateComponent, CreateRun, JournalEntry, PayrollComponent, PayrollRun, PayrollRunWithPayslips,
+    Payslip, PayslipFilter, PayslipWithLines, PostRun, RunFilter, UpdateComponent,
+};
+use super::services;
+use crate::audit::AuditContext;
+use crate::error::{ApiError, FieldErrors};
+use crate::extractors::{Admin, ValidatedJson};
+use crate::middlewares::jwt::Claims;
+use crate::tenant::Tenant;
+use crate::v1::auth::services as auth_services;
+use crate::v1::company::services as company_services;
+use entity::audit_log::AuditAction;
+use entity::payroll_run::{self, PayrollStatus};
+use entity::{payroll_component, payslip};
+
+/// List payroll components in the order they are applied
+#[utoipa::path(
+    get,
+    path = "/v1/payroll/component",
+    tag = "payroll",
+    responses(
+        (status = 200, description = "List of payroll components", body = Vec<PayrollComponent>),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_components(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    _admin: Admin,
+) -> Result<HttpResponse, ApiError> {
+    let components = services::components(&data.db, &tenant).await?;
+    Ok(HttpResponse::Ok().json(components))
+}
+
+/// Create a payroll component
+#[utoipa::path(
+    post,
+    path = "/v1/payroll/component",
+    tag = "payroll",
+    request_body = CreateComponent,
+    responses(
+        (status = 200, description = "Payroll component created", body = PayrollComponent),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not an administrator"),
+        (status = 409, description = "Code already in use"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_component(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    component: ValidatedJson<CreateComponent>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let component = services::create_component(&txn, &tenant, &component).await?;
+    audit
+        .record(
+            &txn,
+            "payroll_component",
+            &component.id,
+            AuditAction::Create,
+            None,
+            Some(&component),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(component))
+}
+
+/// Update a payroll component
+#[utoipa::path(
+    put,
+    path = "/v1/payroll/component/{id}",
+    tag = "payroll",
+    params(
+        ("id" = String, Path, description = "Payroll component ID")
+    ),
+    request_body = UpdateComponent,
+    responses(
+        (status = 200, description = "Payroll component updated", body = PayrollComponent),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Payroll component not found"),
+        (status = 409, description = "Code already in use"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_component(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    id: web::Path<String>,
+    update: ValidatedJson<UpdateComponent>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let component = tenant
+        .find_by_id::<payroll_component::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Payroll component not found".to_string()))?;
+
+    let updated = services::update_component(&txn, component.clone(), &update).await?;
+    audit
+        .record(
+            &txn,
+            "payroll_component",
+            &updated.id,
+            AuditAction::Update,
+            Some(&component),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Delete a payroll component; payslips already calculated keep their lines
+#[utoipa::path(
+    delete,
+    path = "/v1/payroll/component/{id}",
+    tag = "payroll",
+    params(
+        ("id" = String, Path, description = "Payroll component ID")
+    ),
+    responses(
+        (status = 200, description = "Payroll component deleted"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Payroll component not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_component(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let component = tenant
+        .find_by_id::<payroll_component::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Payroll component not found".to_string()))?;
+
+    component.clone().delete(&txn).await?;
+    audit
+        .record(
+            &txn,
+            "payroll_component",
+            &component.id,
+            AuditAction::Delete,
+            Some(&component),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Payroll component deleted successfully"})))
+}
+
+/// List payroll runs, latest period first
+#[utoipa::path(
+    get,
+    path = "/v1/payroll/run",
+    tag = "payroll",
+    params(
+        RunFilter
+    ),
+    responses(
+        (status = 200, description = "List of payroll runs", body = Vec<PayrollRun>),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_runs(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    _admin: Admin,
+    filter: web::Query<RunFilter>,
+) -> Result<HttpResponse, ApiError> {
+    let mut query = tenant.find::<payroll_run::Entity>();
+    if let Some(status) = filter.status {
+        query = query.filter(payroll_run::Column::Status.eq(status));
+    }
+    let runs = query
+        .order_by_desc(payroll_run::Column::PeriodStart)
+        .all(&data.db)
+        .await?;
+    Ok(HttpResponse::Ok().json(runs))
+}
+
+/// Start a payroll run for a period and calculate its payslips
+#[utoipa::path(
+    post,
+    path = "/v1/payroll/run",
+    tag = "payroll",
+    request_body = CreateRun,
+    responses(
+        (status = 201, description = "Payroll run calculated", body = PayrollRun),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not an administrator"),
+        (status = 409, description = "Another run covers part of the period"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_run(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    run: ValidatedJson<CreateRun>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let run = services::create_run(&txn, &tenant, &run).await?;
+    audit
+        .record(
+            &txn,
+            "payroll_run",
+            &run.id,
+            AuditAction::Create,
+            None,
+            Some(&run),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Created().json(run))
+}
+
+/// Get a payroll run with its payslips
+#[utoipa::path(
+    get,
+    path = "/v1/payroll/run/{id}",
+    tag = "payroll",
+    params(
+        ("id" = String, Path, description = "Payroll run ID")
+    ),
+    responses(
+        (status = 200, description = "Payroll run found", body = PayrollRunWithPayslips),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Payroll run not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_run_by_id(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    _admin: Admin,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let run = tenant
+        .find_by_id::<payroll_run::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Payroll run not found".to_string()))?;
+    let payslips = services::payslips(&data.db, &tenant, &run.id).await?;
+
+    Ok(HttpResponse::Ok().json(PayrollRunWithPayslips { run, payslips }))
+}
+
+async fn find_run<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    id: &str,
+) -> Result<PayrollRun, ApiError> {
+    tenant
+        .find_by_id::<payroll_run::Entity>(id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Payroll run not found".to_string()))
+}
+
+/// Calculate the payslips of a draft run again
+#[utoipa::path(
+    post,
+    path = "/v1/payroll/run/{id}/recalculate",
+    tag = "payroll",
+    params(
+        ("id" = String, Path, description = "Payroll run ID")
+    ),
+    responses(
+        (status = 200, description = "Payroll run calculated", body = PayrollRun),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Payroll run not found"),
+        (status = 409, description = "The run is approved and locked"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn recalculate_run(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let run = find_run(&txn, &tenant, &id).await?;
+    let updated = services::calculate(&txn, &tenant, run.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "payroll_run",
+            &updated.id,
+            AuditAction::Update,
+            Some(&run),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Approve a draft run, locking its payslips
+#[utoipa::path(
+    post,
+    path = "/v1/payroll/run/{id}/approve",
+    tag = "payroll",
+    params(
+        ("id" = String, Path, description = "Payroll run ID")
+    ),
+    responses(
+        (status = 200, description = "Payroll run approved", body = PayrollRun),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Payroll run not found"),
+        (status = 409, description = "The run was already approved"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn approve_run(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    admin: Admin,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let run = find_run(&txn, &tenant, &id).await?;
+    let updated = services::approve_run(&txn, run.clone(), &admin.0.id).await?;
+    audit
+        .record(
+            &txn,
+            "payroll_run",
+            &updated.id,
+            AuditAction::Update,
+            Some(&run),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Post an approved run, booking its journal entries
+#[utoipa::path(
+    post,
+    path = "/v1/payroll/run/{id}/post",
+    tag = "payroll",
+    params(
+        ("id" = String, Path, description = "Payroll run ID")
+    ),
+    request_body = PostRun,
+    responses(
+        (status = 200, description = "Journal entries booked", body = Vec<JournalEntry>),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Payroll run not found"),
+        (status = 409, description = "The run is not approved or was already posted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn post_run(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    id: web::Path<String>,
+    post: ValidatedJson<PostRun>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let run = find_run(&txn, &tenant, &id).await?;
+    let (updated, entries) =
+        services::post_run(&txn, &tenant, run.clone(), &post.net_pay_account).await?;
+    audit
+        .record(
+            &txn,
+            "payroll_run",
+            &updated.id,
+            AuditAction::Update,
+            Some(&run),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+/// Delete a draft run
+#[utoipa::path(
+    delete,
+    path = "/v1/payroll/run/{id}",
+    tag = "payroll",
+    params(
+        ("id" = String, Path, description = "Payroll run ID")
+    ),
+    responses(
+        (status = 200, description = "Payroll run deleted"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Payroll run not found"),
+        (status = 409, description = "The run is approved and locked"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_run(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let run = find_run(&txn, &tenant, &id).await?;
+    services::delete_run(&txn, run.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "payroll_run",
+            &run.id,
+            AuditAction::Delete,
+            Some(&run),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Payroll run deleted successfully"})))
+}
+
+/// List the journal entries a posted run booked
+#[utoipa::path(
+    get,
+    path = "/v1/payroll/run/{id}/journal",
+    tag = "payroll",
+    params(
+        ("id" = String, Path, description = "Payroll run ID")
+    ),
+    responses(
+        (status = 200, description = "Journal entries of the run", body = Vec<JournalEntry>),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Payroll run not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_journal(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    _admin: Admin,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let run = tenant
+        .find_by_id::<payroll_run::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Payroll run not found".to_string()))?;
+    let entries = services::journal(&data.db, &tenant, &run.id).await?;
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+/// List an employee's payslips of approved runs, your own unless an
+/// administrator names someone
+#[utoipa::path(
+    get,
+    path = "/v1/payroll/payslip",
+    tag = "payroll",
+    params(
+        PayslipFilter
+    ),
+    responses(
+        (status = 200, description = "List of payslips", body = Vec<Payslip>),
+        (status = 400, description = "No employee given and none linked to the login"),
+        (status = 403, description = "Someone else's payslips"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_payslips(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    claims: web::ReqData<Claims>,
+    filter: web::Query<PayslipFilter>,
+) -> Result<HttpResponse, ApiError> {
+    let caller = auth_services::caller(&data.db, &tenant, &claims).await?;
+    let employee_id = match (&filter.employee_id, &caller.employee) {
+        (Some(id), _) if caller.is_admin || caller.is(id) => id.clone(),
+        (Some(_), _) => {
+            return Err(ApiError::Forbidden(
+                "Only administrators can see other employees' payslips".to_string(),
+            ))
+        }
+        (None, Some(employee)) => employee.id.clone(),
+        (None, None) => {
+            let mut errors = FieldErrors::new();
+            errors.add("employee_id", "This login is not linked to an employee");
+            return Err(ApiError::InvalidFields(errors));
+        }
+    };
+    let payslips = services::employee_payslips(&data.db, &tenant, &employee_id).await?;
+    Ok(HttpResponse::Ok().json(payslips))
+}
+
+/// A payslip with its run, readable by administrators and, once the run is
+/// approved, the employee.
+async fn readable_payslip<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    claims: &Claims,
+    id: &str,
+) -> Result<(PayrollRun, Payslip), ApiError> {
+    let payslip = tenant
+        .find_by_id::<payslip::Entity>(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Payslip not found".to_string()))?;
+    let run = tenant
+        .find_by_id::<payroll_run::Entity>(&payslip.payroll_run_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Payroll run not found".to_string()))?;
+    let caller = auth_services::caller(db, tenant, claims).await?;
+    if caller.is_admin {
+        return Ok((run, payslip));
+    }
+    if !caller.is(&payslip.employee_id) {
+        return Err(ApiError::Forbidden(
+            "Only administrators can see other employees' payslips".to_string(),
+        ));
+    }
+    if run.status == PayrollStatus::Draft {
+        return Err(ApiError::NotFound("Payslip not found".to_string()));
+    }
+    Ok((run, payslip))
+}
+
+/// Get a payslip with its lines
+#[utoipa::path(
+    get,
+    path = "/v1/payroll/payslip/{id}",
+    tag = "payroll",
+    params(
+        ("id" = String, Path, description = "Payslip ID")
+    ),
+    responses(
+        (status = 200, description = "Payslip found", body = PayslipWithLines),
+        (status = 403, description = "Someone else's payslip"),
+        (status = 404, description = "Payslip not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_payslip_by_id(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let (_, payslip) = readable_payslip(&data.db, &tenant, &claims, &id).await?;
+    let lines = services::lines(&data.db, &tenant, &payslip.id).await?;
+    Ok(HttpResponse::Ok().json(PayslipWithLines { payslip, lines }))
+}
+
+/// Download a payslip as a PDF
+#[utoipa::path(
+    get,
+    path = "/v1/payroll/payslip/{id}/pdf",
+    tag = "payroll",
+    params(
+        ("id" = String, Path, description = "Payslip ID")
+    ),
+    responses(
+        (status = 200, description = "Payslip PDF", content_type = "application/pdf", body = Vec<u8>),
+        (status = 403, description = "Someone else's payslip"),
+        (status = 404, description = "Payslip not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_payslip_pdf(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let (run, payslip) = readable_payslip(&data.db, &tenant, &claims, &id).await?;
+    let lines = services::lines(&data.db, &tenant, &payslip.id).await?;
+    let company = company_services::find_company(&data.db, tenant.id())
+        .await?
+        .map(|company| company.name)
+        .unwrap_or_default();
+
+    let pdf = services::payslip_pdf(&company, &run, &payslip, &lines);
+    Ok(HttpResponse::Ok()
+        .content_type("application/pdf")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"payslip-{}-{}.pdf\"",
+                run.period_start, payslip.employee_id
+            ),
+        ))
+        .body(pdf))
+}
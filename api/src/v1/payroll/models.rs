@@ -0,0 +1,222 @@
+use chrono::NaiveDate;
+use entity::payroll_component::{self, ComponentBase, ComponentKind, ComponentQuantity};
+use entity::payroll_run::{self, PayrollStatus};
+use entity::{journal_entry, payslip, payslip_line};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::shared::{nullable, round};
+
+pub type PayrollComponent = payroll_component::Model;
+pub type PayrollRun = payroll_run::Model;
+pub type Payslip = payslip::Model;
+pub type PayslipLine = payslip_line::Model;
+pub type JournalEntry = journal_entry::Model;
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct Bracket {
+    /// Highest base in the bracket, none for the last one.
+    pub up_to: Option<f64>,
+    /// Percentage, 0 to 100.
+    pub rate: f64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateComponent {
+    #[validate(length(min = 1, max = 50, message = "Code must be 1 to 50 characters"))]
+    #[schema(min_length = 1, max_length = 50)]
+    pub code: String,
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: String,
+    pub kind: ComponentKind,
+    pub base: ComponentBase,
+    #[validate(range(min = 0.0, message = "Rate cannot be negative"))]
+    #[serde(default)]
+    pub rate: f64,
+    #[validate(range(min = 0.0, message = "Amount cannot be negative"))]
+    #[serde(default)]
+    pub amount: f64,
+    /// One unless given.
+    pub quantity: Option<ComponentQuantity>,
+    #[validate(range(min = 0.0, message = "Base cap cannot be negative"))]
+    pub base_cap: Option<f64>,
+    pub brackets: Option<Vec<Bracket>>,
+    #[serde(default)]
+    pub progressive: bool,
+    #[validate(length(min = 1, max = 50, message = "Account must be 1 to 50 characters"))]
+    #[schema(min_length = 1, max_length = 50)]
+    pub account: String,
+    /// Required for employer contributions.
+    #[validate(length(min = 1, max = 50, message = "Account must be 1 to 50 characters"))]
+    #[schema(min_length = 1, max_length = 50)]
+    pub payable_account: Option<String>,
+    #[serde(default)]
+    pub sequence: i32,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateComponent {
+    #[validate(length(min = 1, max = 50, message = "Code must be 1 to 50 characters"))]
+    #[schema(min_length = 1, max_length = 50)]
+    pub code: Option<String>,
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: Option<String>,
+    pub kind: Option<ComponentKind>,
+    pub base: Option<ComponentBase>,
+    #[validate(range(min = 0.0, message = "Rate cannot be negative"))]
+    pub rate: Option<f64>,
+    #[validate(range(min = 0.0, message = "Amount cannot be negative"))]
+    pub amount: Option<f64>,
+    pub quantity: Option<ComponentQuantity>,
+    /// `null` removes the cap, and likewise for the brackets and the payable
+    /// account.
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<f64>, nullable)]
+    pub base_cap: Option<Option<f64>>,
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<Vec<Bracket>>, nullable)]
+    pub brackets: Option<Option<Vec<Bracket>>>,
+    pub progressive: Option<bool>,
+    #[validate(length(min = 1, max = 50, message = "Account must be 1 to 50 characters"))]
+    #[schema(min_length = 1, max_length = 50)]
+    pub account: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable)]
+    pub payable_account: Option<Option<String>>,
+    pub sequence: Option<i32>,
+    /// `false` leaves the component off runs calculated from then on.
+    pub active: Option<bool>,
+}
+
+/// What a component is applied to for one employee in one period.
+#[derive(Clone, Debug, Default)]
+pub struct PayInputs {
+    /// Monthly salary.
+    pub salary: f64,
+    /// Working days of the period.
+    pub working_days: u32,
+    pub days_worked: u32,
+    pub days_present: u32,
+    pub overtime_hours: f64,
+    pub unpaid_leave_days: f64,
+}
+
+impl PayInputs {
+    /// Salary for the days employed in the period.
+    pub fn basic(&self) -> f64 {
+        if self.working_days == 0 {
+            return 0.0;
+        }
+        round(self.salary * self.days_worked as f64 / self.working_days as f64)
+    }
+}
+
+fn bracket_rate(brackets: &[Bracket], base: f64, progressive: bool) -> f64 {
+    if !progressive {
+        let bracket = brackets
+            .iter()
+            .find(|b| b.up_to.is_none_or(|up_to| base <= up_to));
+        return bracket.map_or(0.0, |b| base * b.rate / 100.0);
+    }
+    let mut amount = 0.0;
+    let mut lower = 0.0;
+    for bracket in brackets {
+        let upper = bracket.up_to.unwrap_or(f64::INFINITY);
+        if base > lower {
+            amount += (base.min(upper) - lower) * bracket.rate / 100.0;
+        }
+        lower = upper;
+    }
+    amount
+}
+
+/// Applies active components in order, returning the amount of each.
+pub fn compute<'a>(
+    components: &'a [PayrollComponent],
+    inputs: &PayInputs,
+) -> Vec<(&'a PayrollComponent, f64)> {
+    let basic = inputs.basic();
+    let mut gross = 0.0;
+    let mut contributions = 0.0;
+    let mut lines = Vec::new();
+    for component in components.iter().filter(|c| c.active) {
+        let base = match component.base {
+            ComponentBase::None => 0.0,
+            ComponentBase::Basic => basic,
+            ComponentBase::DailyBasic if inputs.working_days > 0 => {
+                inputs.salary / inputs.working_days as f64
+            }
+            ComponentBase::DailyBasic => 0.0,
+            ComponentBase::Gross => gross,
+            ComponentBase::GrossWithContributions => gross + contributions,
+        };
+        let base = component.base_cap.map_or(base, |cap| base.min(cap));
+        let brackets: Option<Vec<Bracket>> = component
+            .brackets
+            .clone()
+            .and_then(|brackets| serde_json::from_value(brackets).ok());
+        let per_unit = component.amount
+            + match &brackets {
+                Some(brackets) => bracket_rate(brackets, base, component.progressive),
+                None => base * component.rate / 100.0,
+            };
+        let quantity = match component.quantity {
+            ComponentQuantity::One => 1.0,
+            ComponentQuantity::DaysWorked => inputs.days_worked as f64,
+            ComponentQuantity::DaysPresent => inputs.days_present as f64,
+            ComponentQuantity::OvertimeHours => inputs.overtime_hours,
+            ComponentQuantity::UnpaidLeaveDays => inputs.unpaid_leave_days,
+        };
+        let amount = round(per_unit * quantity);
+        match component.kind {
+            ComponentKind::Earning => gross += amount,
+            ComponentKind::EmployerContribution => contributions += amount,
+            ComponentKind::Deduction => {}
+        }
+        lines.push((component, amount));
+    }
+    lines
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateRun {
+    pub period_start: NaiveDate,
+    /// Last day of the period, inclusive.
+    pub period_end: NaiveDate,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct PostRun {
+    /// Liability account net pay is owed on until it is paid out.
+    #[validate(length(min = 1, max = 50, message = "Account must be 1 to 50 characters"))]
+    #[schema(min_length = 1, max_length = 50)]
+    pub net_pay_account: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct RunFilter {
+    pub status: Option<PayrollStatus>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct PayslipFilter {
+    /// Administrators can see anyone's payslips, others only their own
+    pub employee_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PayrollRunWithPayslips {
+    #[serde(flatten)]
+    pub run: PayrollRun,
+    pub payslips: Vec<Payslip>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PayslipWithLines {
+    #[serde(flatten)]
+    pub payslip: Payslip,
+    pub lines: Vec<PayslipLine>,
+}
@@ -0,0 +1,37 @@
+use super::handlers;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/payroll")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .route("/component", web::get().to(handlers::get_components))
+            .route("/component", web::post().to(handlers::create_component))
+            .route("/component/{id}", web::put().to(handlers::update_component))
+            .route(
+                "/component/{id}",
+                web::delete().to(handlers::delete_component),
+            )
+            .route("/run", web::get().to(handlers::get_runs))
+            .route("/run", web::post().to(handlers::create_run))
+            .route("/run/{id}", web::get().to(handlers::get_run_by_id))
+            .route("/run/{id}", web::delete().to(handlers::delete_run))
+            .route(
+                "/run/{id}/recalculate",
+                web::post().to(handlers::recalculate_run),
+            )
+            .route("/run/{id}/approve", web::post().to(handlers::approve_run))
+            .route("/run/{id}/post", web::post().to(handlers::post_run))
+            .route("/run/{id}/journal", web::get().to(handlers::get_journal))
+            .route("/payslip", web::get().to(handlers::get_payslips))
+            .route("/payslip/{id}", web::get().to(handlers::get_payslip_by_id))
+            .route(
+                "/payslip/{id}/pdf",
+                web::get().to(handlers::get_payslip_pdf),
+            ),
+    );
+}
@@ -0,0 +1,756 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{Duration, NaiveDate, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, IntoActiveModel, QueryFilter, QueryOrder, Set,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use super::models::{
+    compute, Bracket, CreateComponent, CreateRun, JournalEntry, PayInputs, PayrollComponent,
+    PayrollRun, Payslip, PayslipLine, UpdateComponent,
+};
+use crate::calendar;
+use crate::error::{ApiError, FieldErrors};
+use crate::pdf::{self, TextDocument};
+use crate::shared::db_utils::conflict_on_duplicate;
+use crate::shared::round;
+use crate::tenant::Tenant;
+use crate::v1::employee::models::EmploymentState;
+use crate::v1::employee::services::timeline;
+use crate::v1::expense::services as expense_services;
+use entity::leave_request::{self, LeaveStatus};
+use entity::leave_type::{self, Accrual};
+use entity::payroll_component::{self, ComponentKind};
+use entity::payroll_run::{self, PayrollStatus};
+use entity::timesheet::{self, TimesheetStatus};
+use entity::{attendance, employee, journal_entry, payslip, payslip_line};
+
+const DUPLICATE_CODE: &str = "A payroll component with this code already exists";
+const DUPLICATE_PERIOD: &str = "There is already a payroll run for this period";
+const LOCKED: &str = "The payroll run is approved and locked";
+/// Code of the payslip lines paying back expense claims.
+pub const REIMBURSEMENT_CODE: &str = "EXPENSES";
+
+/// Source of the journal entries of a run.
+pub const JOURNAL_SOURCE: &str = "payroll_run";
+
+fn check_component(
+    kind: ComponentKind,
+    payable_account: Option<&String>,
+    brackets: Option<&Vec<Bracket>>,
+) -> Result<(), ApiError> {
+    let mut errors = FieldErrors::new();
+    if kind == ComponentKind::EmployerContribution && payable_account.is_none() {
+        errors.add(
+            "payable_account",
+            "Employer contributions need the account they are owed on",
+        );
+    }
+    if let Some(brackets) = brackets {
+        let mut lower = 0.0;
+        let problem = brackets.iter().enumerate().find_map(|(i, bracket)| {
+            if !(0.0..=100.0).contains(&bracket.rate) {
+                return Some("Bracket rates must be 0 to 100");
+            }
+            match bracket.up_to {
+                None if i + 1 < brackets.len() => Some("Only the last bracket can be open-ended"),
+                Some(up_to) if up_to <= lower => Some("Brackets must go up from one to the next"),
+                Some(up_to) => {
+                    lower = up_to;
+                    None
+                }
+                None => None,
+            }
+        });
+        if brackets.is_empty() {
+            errors.add(
+                "brackets",
+                "Give at least one bracket, or none for a flat rate",
+            );
+        } else if let Some(problem) = problem {
+            errors.add("brackets", problem);
+        }
+    }
+    errors.into_result()
+}
+
+pub async fn components<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+) -> Result<Vec<PayrollComponent>, ApiError> {
+    Ok(tenant
+        .find::<payroll_component::Entity>()
+        .order_by_asc(payroll_component::Column::Sequence)
+        .order_by_asc(payroll_component::Column::Code)
+        .all(db)
+        .await?)
+}
+
+pub async fn create_component<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    data: &CreateComponent,
+) -> Result<PayrollComponent, ApiError> {
+    check_component(
+        data.kind,
+        data.payable_account.as_ref(),
+        data.brackets.as_ref(),
+    )?;
+    let now = Utc::now().naive_utc();
+    payroll_component::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        code: Set(data.code.clone()),
+        name: Set(data.name.clone()),
+        kind: Set(data.kind),
+        base: Set(data.base),
+        rate: Set(data.rate),
+        amount: Set(data.amount),
+        quantity: Set(data
+            .quantity
+            .unwrap_or(payroll_component::ComponentQuantity::One)),
+        base_cap: Set(data.base_cap),
+        brackets: Set(data.brackets.as_ref().map(|brackets| json!(brackets))),
+        progressive: Set(data.progressive),
+        account: Set(data.account.clone()),
+        payable_account: Set(data.payable_account.clone()),
+        sequence: Set(data.sequence),
+        active: Set(true),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(db)
+    .await
+    .map_err(|e| conflict_on_duplicate(e, DUPLICATE_CODE))
+}
+
+/// Runs keep the lines they were calculated with, so a change only shows
+/// on runs calculated after it.
+pub async fn update_component<C: ConnectionTrait>(
+    db: &C,
+    component: PayrollComponent,
+    data: &UpdateComponent,
+) -> Result<PayrollComponent, ApiError> {
+    let brackets = match &data.brackets {
+        Some(brackets) => brackets.clone(),
+        None => component
+            .brackets
+            .clone()
+            .and_then(|brackets| serde_json::from_value(brackets).ok()),
+    };
+    let payable_account = match &data.payable_account {
+        Some(account) => account.clone(),
+        None => component.payable_account.clone(),
+    };
+    check_component(
+        data.kind.unwrap_or(component.kind),
+        payable_account.as_ref(),
+        brackets.as_ref(),
+    )?;
+
+    let mut active = component.into_active_model();
+    if let Some(code) = &data.code {
+        active.code = Set(code.clone());
+    }
+    if let Some(name) = &data.name {
+        active.name = Set(name.clone());
+    }
+    if let Some(kind) = data.kind {
+        active.kind = Set(kind);
+    }
+    if let Some(base) = data.base {
+        active.base = Set(base);
+    }
+    if let Some(rate) = data.rate {
+        active.rate = Set(rate);
+    }
+    if let Some(amount) = data.amount {
+        active.amount = Set(amount);
+    }
+    if let Some(quantity) = data.quantity {
+        active.quantity = Set(quantity);
+    }
+    if let Some(base_cap) = data.base_cap {
+        active.base_cap = Set(base_cap);
+    }
+    if data.brackets.is_some() {
+        active.brackets = Set(brackets.map(|brackets| json!(brackets)));
+    }
+    if let Some(progressive) = data.progressive {
+        active.progressive = Set(progressive);
+    }
+    if let Some(account) = &data.account {
+        active.account = Set(account.clone());
+    }
+    active.payable_account = Set(payable_account);
+    if let Some(sequence) = data.sequence {
+        active.sequence = Set(sequence);
+    }
+    if let Some(is_active) = data.active {
+        active.active = Set(is_active);
+    }
+    active.updated_at = Set(Utc::now().naive_utc());
+    active
+        .update(db)
+        .await
+        .map_err(|e| conflict_on_duplicate(e, DUPLICATE_CODE))
+}
+
+fn check_draft(run: &PayrollRun) -> Result<(), ApiError> {
+    match run.status {
+        PayrollStatus::Draft => Ok(()),
+        PayrollStatus::Approved | PayrollStatus::Posted => {
+            Err(ApiError::Conflict(LOCKED.to_string()))
+        }
+    }
+}
+
+/// Starts a run for a period no other run covers and calculates it.
+pub async fn create_run<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    data: &CreateRun,
+) -> Result<PayrollRun, ApiError> {
+    if data.period_end < data.period_start {
+        let mut errors = FieldErrors::new();
+        errors.add("period_end", "The period cannot end before it starts");
+        return Err(ApiError::InvalidFields(errors));
+    }
+    let overlapping = tenant
+        .find::<payroll_run::Entity>()
+        .filter(payroll_run::Column::PeriodStart.lte(data.period_end))
+        .filter(payroll_run::Column::PeriodEnd.gte(data.period_start))
+        .one(db)
+        .await?;
+    if let Some(run) = overlapping {
+        return Err(ApiError::Conflict(format!(
+            "The payroll run for {} to {} covers part of this period",
+            run.period_start, run.period_end
+        )));
+    }
+
+    let now = Utc::now().naive_utc();
+    let run = payroll_run::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        period_start: Set(data.period_start),
+        period_end: Set(data.period_end),
+        status: Set(PayrollStatus::Draft),
+        working_days: Set(0),
+        employees: Set(0),
+        gross: Set(0.0),
+        deductions: Set(0.0),
+        net: Set(0.0),
+        employer_contributions: Set(0.0),
+        approved_by: Set(None),
+        approved_at: Set(None),
+        posted_at: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(db)
+    .await
+    .map_err(|e| conflict_on_duplicate(e, DUPLICATE_PERIOD))?;
+    calculate(db, tenant, run).await
+}
+
+/// Distinct days each employee clocked in on.
+async fn days_present<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<HashMap<String, u32>, ApiError> {
+    let shifts = tenant
+        .find::<attendance::Entity>()
+        .filter(attendance::Column::ClockIn.gte(start.and_time(Default::default())))
+        .filter(
+            attendance::Column::ClockIn.lt((end + Duration::days(1)).and_time(Default::default())),
+        )
+        .all(db)
+        .await?;
+    let mut days: HashMap<String, HashSet<NaiveDate>> = HashMap::new();
+    for shift in shifts {
+        days.entry(shift.employee_id)
+            .or_default()
+            .insert(shift.clock_in.date());
+    }
+    Ok(days
+        .into_iter()
+        .map(|(employee_id, days)| (employee_id, days.len() as u32))
+        .collect())
+}
+
+/// Overtime on the approved timesheets of weeks starting in the period.
+async fn overtime_hours<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<HashMap<String, f64>, ApiError> {
+    let timesheets = tenant
+        .find::<timesheet::Entity>()
+        .filter(timesheet::Column::Status.eq(TimesheetStatus::Approved))
+        .filter(timesheet::Column::WeekStart.between(start, end))
+        .all(db)
+        .await?;
+    let mut hours = HashMap::new();
+    for timesheet in timesheets {
+        *hours.entry(timesheet.employee_id).or_default() += timesheet.overtime_hours;
+    }
+    Ok(hours)
+}
+
+/// Working days in the period of approved leave of types without an
+/// allowance, which are the unpaid ones.
+async fn unpaid_leave_days<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    start: NaiveDate,
+    end: NaiveDate,
+    holidays: &HashSet<NaiveDate>,
+) -> Result<HashMap<String, f64>, ApiError> {
+    let unpaid: Vec<String> = tenant
+        .find::<leave_type::Entity>()
+        .filter(leave_type::Column::Accrual.eq(Accrual::None))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|leave_type| leave_type.id)
+        .collect();
+    let mut days = HashMap::new();
+    if unpaid.is_empty() {
+        return Ok(days);
+    }
+    let requests = tenant
+        .find::<leave_request::Entity>()
+        .filter(leave_request::Column::Status.eq(LeaveStatus::Approved))
+        .filter(leave_request::Column::LeaveTypeId.is_in(unpaid))
+        .filter(leave_request::Column::StartDate.lte(end))
+        .filter(leave_request::Column::EndDate.gte(start))
+        .all(db)
+        .await?;
+    for request in requests {
+        let overlap = calendar::working_days(
+            request.start_date.max(start),
+            request.end_date.min(end),
+            holidays,
+        );
+        *days.entry(request.employee_id).or_default() += overlap as f64;
+    }
+    Ok(days)
+}
+
+/// A payslip line before it is stored.
+struct Line {
+    component_id: Option<String>,
+    code: String,
+    name: String,
+    kind: ComponentKind,
+    amount: f64,
+    account: String,
+    payable_account: Option<String>,
+    sequence: i32,
+}
+
+/// Works the payslips of a draft run out again from the components,
+/// employment history, attendance, timesheets and leave as they are now.
+pub async fn calculate<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    run: PayrollRun,
+) -> Result<PayrollRun, ApiError> {
+    check_draft(&run)?;
+    tenant
+        .delete_many::<payslip::Entity>()
+        .filter(payslip::Column::PayrollRunId.eq(&run.id))
+        .exec(db)
+        .await?;
+
+    let (start, end) = (run.period_start, run.period_end);
+    let holidays = calendar::holidays(db, tenant, start, end).await?;
+    let days: Vec<NaiveDate> = start
+        .iter_days()
+        .take_while(|day| *day <= end)
+        .filter(|day| calendar::is_working_day(*day, &holidays))
+        .collect();
+    let components: Vec<PayrollComponent> = components(db, tenant)
+        .await?
+        .into_iter()
+        .filter(|component| component.active)
+        .collect();
+    let present = days_present(db, tenant, start, end).await?;
+    let overtime = overtime_hours(db, tenant, start, end).await?;
+    let unpaid = unpaid_leave_days(db, tenant, start, end, &holidays).await?;
+    let mut reimbursements = expense_services::payroll_reimbursements(db, tenant, &run).await?;
+    let employees = tenant
+        .find_active::<employee::Entity>()
+        .order_by_asc(employee::Column::Name)
+        .all(db)
+        .await?;
+
+    let now = Utc::now().naive_utc();
+    let mut totals = payroll_run::ActiveModel::from(run.clone());
+    let (mut count, mut gross, mut deductions, mut net, mut contributions) =
+        (0, 0.0, 0.0, 0.0, 0.0);
+    for employee in employees {
+        let records = timeline(db, tenant, &employee.id).await?;
+        let employed: Vec<NaiveDate> = days
+            .iter()
+            .copied()
+            .filter(|day| EmploymentState::as_of(&records, *day).employed)
+            .collect();
+        // Nobody is paid for a period they were not employed in
+        let Some(last) = employed.last() else {
+            continue;
+        };
+        let inputs = PayInputs {
+            salary: EmploymentState::as_of(&records, *last)
+                .salary
+                .unwrap_or(0.0),
+            working_days: days.len() as u32,
+            days_worked: employed.len() as u32,
+            days_present: present.get(&employee.id).copied().unwrap_or(0),
+            overtime_hours: overtime.get(&employee.id).copied().unwrap_or(0.0),
+            unpaid_leave_days: unpaid.get(&employee.id).copied().unwrap_or(0.0),
+        };
+        let mut lines: Vec<Line> = compute(&components, &inputs)
+            .into_iter()
+            .map(|(component, amount)| Line {
+                component_id: Some(component.id.clone()),
+                code: component.code.clone(),
+                name: component.name.clone(),
+                kind: component.kind,
+                amount,
+                account: component.account.clone(),
+                payable_account: component.payable_account.clone(),
+                sequence: component.sequence,
+            })
+            .collect();
+        // Paid back after every component, so nothing is worked out on it
+        if let Some(reimbursement) = reimbursements.remove(&employee.id) {
+            expense_services::link_to_run(db, tenant, &run.id, &reimbursement.claim_ids).await?;
+            lines.extend(
+                reimbursement
+                    .categories
+                    .into_iter()
+                    .map(|(category, amount)| Line {
+                        component_id: None,
+                        code: REIMBURSEMENT_CODE.to_string(),
+                        name: format!("Expenses: {}", category.name),
+                        kind: ComponentKind::Earning,
+                        amount,
+                        account: category.account,
+                        payable_account: None,
+                        sequence: i32::MAX,
+                    }),
+            );
+        }
+        let total = |kind: ComponentKind| {
+            round(
+                lines
+                    .iter()
+                    .filter(|line| line.kind == kind)
+                    .map(|line| line.amount)
+                    .sum(),
+            )
+        };
+        let slip_gross = total(ComponentKind::Earning);
+        let slip_deductions = total(ComponentKind::Deduction);
+        let slip_contributions = total(ComponentKind::EmployerContribution);
+        let slip_net = round(slip_gross - slip_deductions);
+
+        let slip = payslip::ActiveModel {
+            id: Set(Uuid::new_v4().to_string()),
+            tenant_id: Set(tenant.id().to_string()),
+            payroll_run_id: Set(run.id.clone()),
+            employee_id: Set(employee.id.clone()),
+            employee_name: Set(employee.name.clone()),
+            salary: Set(inputs.salary),
+            basic_salary: Set(inputs.basic()),
+            days_worked: Set(inputs.days_worked as i32),
+            days_present: Set(inputs.days_present as i32),
+            overtime_hours: Set(inputs.overtime_hours),
+            unpaid_leave_days: Set(inputs.unpaid_leave_days),
+            gross: Set(slip_gross),
+            deductions: Set(slip_deductions),
+            net: Set(slip_net),
+            employer_contributions: Set(slip_contributions),
+            created_at: Set(now),
+        }
+        .insert(db)
+        .await?;
+        for line in lines {
+            payslip_line::ActiveModel {
+                id: Set(Uuid::new_v4().to_string()),
+                tenant_id: Set(tenant.id().to_string()),
+                payslip_id: Set(slip.id.clone()),
+                component_id: Set(line.component_id),
+                code: Set(line.code),
+                name: Set(line.name),
+                kind: Set(line.kind),
+                amount: Set(line.amount),
+                account: Set(line.account),
+                payable_account: Set(line.payable_account),
+                sequence: Set(line.sequence),
+            }
+            .insert(db)
+            .await?;
+        }
+
+        count += 1;
+        gross += slip_gross;
+        deductions += slip_deductions;
+        net += slip_net;
+        contributions += slip_contributions;
+    }
+
+    totals.working_days = Set(days.len() as i32);
+    totals.employees = Set(count);
+    totals.gross = Set(round(gross));
+    totals.deductions = Set(round(deductions));
+    totals.net = Set(round(net));
+    totals.employer_contributions = Set(round(contributions));
+    totals.updated_at = Set(now);
+    Ok(totals.update(db).await?)
+}
+
+/// Locks a draft run.
+pub async fn approve_run<C: ConnectionTrait>(
+    db: &C,
+    run: PayrollRun,
+    user_id: &str,
+) -> Result<PayrollRun, ApiError> {
+    check_draft(&run)?;
+    let now = Utc::now().naive_utc();
+    let mut active = run.into_active_model();
+    active.status = Set(PayrollStatus::Approved);
+    active.approved_by = Set(Some(user_id.to_string()));
+    active.approved_at = Set(Some(now));
+    active.updated_at = Set(now);
+    Ok(active.update(db).await?)
+}
+
+pub async fn delete_run<C: ConnectionTrait>(db: &C, run: PayrollRun) -> Result<(), ApiError> {
+    check_draft(&run)?;
+    run.into_active_model().delete(db).await?;
+    Ok(())
+}
+
+/// Books an approved run on the last day of its period: earnings and
+/// employer contributions as expenses, what is withheld, owed on
+/// contributions and owed as net pay as liabilities. Lines are summed per
+/// account and component.
+pub async fn post_run<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    run: PayrollRun,
+    net_pay_account: &str,
+) -> Result<(PayrollRun, Vec<JournalEntry>), ApiError> {
+    match run.status {
+        PayrollStatus::Approved => {}
+        PayrollStatus::Draft => {
+            return Err(ApiError::Conflict(
+                "The payroll run has to be approved before it is posted".to_string(),
+            ))
+        }
+        PayrollStatus::Posted => {
+            return Err(ApiError::Conflict(
+                "The payroll run was already posted".to_string(),
+            ))
+        }
+    }
+
+    let lines = tenant
+        .find::<payslip_line::Entity>()
+        .inner_join(payslip::Entity)
+        .filter(payslip::Column::PayrollRunId.eq(&run.id))
+        .order_by_asc(payslip_line::Column::Sequence)
+        .order_by_asc(payslip_line::Column::Code)
+        .all(db)
+        .await?;
+    // Keyed by account, description and whether it is a debit
+    let mut postings: BTreeMap<(String, String, bool), f64> = BTreeMap::new();
+    for line in lines {
+        let mut post = |account: &str, debit: bool| {
+            *postings
+                .entry((account.to_string(), line.name.clone(), debit))
+                .or_default() += line.amount;
+        };
+        match line.kind {
+            ComponentKind::Earning => post(&line.account, true),
+            ComponentKind::Deduction => post(&line.account, false),
+            ComponentKind::EmployerContribution => {
+                post(&line.account, true);
+                post(
+                    line.payable_account.as_deref().unwrap_or(&line.account),
+                    false,
+                );
+            }
+        }
+    }
+    postings.insert(
+        (net_pay_account.to_string(), "Net pay".to_string(), false),
+        run.net,
+    );
+
+    let now = Utc::now().naive_utc();
+    let mut entries = Vec::new();
+    for ((account, description, debit), amount) in postings {
+        let amount = round(amount);
+        if amount == 0.0 {
+            continue;
+        }
+        let entry = journal_entry::ActiveModel {
+            id: Set(Uuid::new_v4().to_string()),
+            tenant_id: Set(tenant.id().to_string()),
+            source: Set(JOURNAL_SOURCE.to_string()),
+            source_id: Set(run.id.clone()),
+            entry_date: Set(run.period_end),
+            account: Set(account),
+            description: Set(description),
+            debit: Set(if debit { amount } else { 0.0 }),
+            credit: Set(if debit { 0.0 } else { amount }),
+            created_at: Set(now),
+        }
+        .insert(db)
+        .await?;
+        entries.push(entry);
+    }
+
+    expense_services::reimbursed_by_run(db, tenant, &run).await?;
+
+    let mut active = run.into_active_model();
+    active.status = Set(PayrollStatus::Posted);
+    active.posted_at = Set(Some(now));
+    active.updated_at = Set(now);
+    Ok((active.update(db).await?, entries))
+}
+
+pub async fn journal<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    run_id: &str,
+) -> Result<Vec<JournalEntry>, ApiError> {
+    Ok(tenant
+        .find::<journal_entry::Entity>()
+        .filter(journal_entry::Column::Source.eq(JOURNAL_SOURCE))
+        .filter(journal_entry::Column::SourceId.eq(run_id))
+        .order_by_desc(journal_entry::Column::Debit)
+        .order_by_asc(journal_entry::Column::Account)
+        .order_by_asc(journal_entry::Column::Description)
+        .all(db)
+        .await?)
+}
+
+pub async fn payslips<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    run_id: &str,
+) -> Result<Vec<Payslip>, ApiError> {
+    Ok(tenant
+        .find::<payslip::Entity>()
+        .filter(payslip::Column::PayrollRunId.eq(run_id))
+        .order_by_asc(payslip::Column::EmployeeName)
+        .all(db)
+        .await?)
+}
+
+/// An employee's payslips of approved and posted runs, latest first.
+pub async fn employee_payslips<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee_id: &str,
+) -> Result<Vec<Payslip>, ApiError> {
+    Ok(tenant
+        .find::<payslip::Entity>()
+        .inner_join(payroll_run::Entity)
+        .filter(payslip::Column::EmployeeId.eq(employee_id))
+        .filter(payroll_run::Column::Status.ne(PayrollStatus::Draft))
+        .order_by_desc(payroll_run::Column::PeriodStart)
+        .all(db)
+        .await?)
+}
+
+pub async fn lines<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    payslip_id: &str,
+) -> Result<Vec<PayslipLine>, ApiError> {
+    Ok(tenant
+        .find::<payslip_line::Entity>()
+        .filter(payslip_line::Column::PayslipId.eq(payslip_id))
+        .order_by_asc(payslip_line::Column::Sequence)
+        .order_by_asc(payslip_line::Column::Code)
+        .all(db)
+        .await?)
+}
+
+pub fn payslip_pdf(
+    company: &str,
+    run: &PayrollRun,
+    payslip: &Payslip,
+    lines: &[PayslipLine],
+) -> Vec<u8> {
+    let mut document = TextDocument::new();
+    document
+        .bold(company)
+        .line(format!(
+            "Payslip {} to {}",
+            run.period_start, run.period_end
+        ))
+        .blank()
+        .row("Employee", &payslip.employee_name)
+        .row("Monthly salary", &pdf::amount(payslip.salary))
+        .row(
+            "Days worked",
+            &format!("{} of {}", payslip.days_worked, run.working_days),
+        );
+    if payslip.overtime_hours > 0.0 {
+        document.row("Overtime hours", &payslip.overtime_hours.to_string());
+    }
+    if payslip.unpaid_leave_days > 0.0 {
+        document.row("Unpaid leave days", &payslip.unpaid_leave_days.to_string());
+    }
+
+    let sections = [
+        (
+            ComponentKind::Earning,
+            "Earnings",
+            "Gross pay",
+            payslip.gross,
+        ),
+        (
+            ComponentKind::Deduction,
+            "Deductions",
+            "Total deductions",
+            payslip.deductions,
+        ),
+    ];
+    for (kind, title, total_label, total) in sections {
+        document.blank().bold(title);
+        for line in lines.iter().filter(|line| line.kind == kind) {
+            document.row(&line.name, &pdf::amount(line.amount));
+        }
+        document.rule().row(total_label, &pdf::amount(total));
+    }
+    document
+        .blank()
+        .rule()
+        .row("Net pay", &pdf::amount(payslip.net))
+        .rule();
+
+    let contributions: Vec<&PayslipLine> = lines
+        .iter()
+        .filter(|line| line.kind == ComponentKind::EmployerContribution)
+        .collect();
+    if !contributions.is_empty() {
+        document.blank().bold("Paid by the employer");
+        for line in contributions {
+            document.row(&line.name, &pdf::amount(line.amount));
+        }
+    }
+    document.render()
+}
@@ -0,0 +1,207 @@
+use actix_web::{web, HttpResponse};
+use sea_orm::{ColumnTrait, QuerySelect, TransactionTrait};
+use serde_json::json;
+
+use super::models::{CreatePosition, Position, PositionFilter, UpdatePosition};
+use super::services;
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::extractors::ValidatedJson;
+use crate::tenant::Tenant;
+use crate::v1::employee::services::reindex_employees;
+use entity::audit_log::AuditAction;
+use entity::{employee, position};
+
+/// List positions
+#[utoipa::path(
+    get,
+    path = "/v1/position",
+    tag = "position",
+    params(PositionFilter),
+    responses(
+        (status = 200, description = "List of positions", body = Vec<Position>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_positions(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<PositionFilter>,
+) -> Result<HttpResponse, ApiError> {
+    let positions = filter.select(&tenant).all(&data.db).await?;
+    Ok(HttpResponse::Ok().json(positions))
+}
+
+/// Create a position
+#[utoipa::path(
+    post,
+    path = "/v1/position",
+    tag = "position",
+    request_body = CreatePosition,
+    responses(
+        (status = 200, description = "Position created", body = Position),
+        (status = 400, description = "Validation error or unknown department"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_position(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    position: ValidatedJson<CreatePosition>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let position = services::create_position(&txn, &tenant, &position).await?;
+    audit
+        .record(
+            &txn,
+            "position",
+            &position.id,
+            AuditAction::Create,
+            None,
+            Some(&position),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(position))
+}
+
+/// Get position by ID
+#[utoipa::path(
+    get,
+    path = "/v1/position/{id}",
+    tag = "position",
+    params(
+        ("id" = String, Path, description = "Position ID")
+    ),
+    responses(
+        (status = 200, description = "Position found", body = Position),
+        (status = 404, description = "Position not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_position_by_id(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let position = tenant
+        .find_by_id::<position::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Position not found".to_string()))?;
+
+    Ok(HttpResponse::Ok().json(position))
+}
+
+/// Update a position
+#[utoipa::path(
+    put,
+    path = "/v1/position/{id}",
+    tag = "position",
+    params(
+        ("id" = String, Path, description = "Position ID")
+    ),
+    request_body = UpdatePosition,
+    responses(
+        (status = 200, description = "Position updated", body = Position),
+        (status = 400, description = "Validation error or unknown department"),
+        (status = 404, description = "Position not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_position(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+    update: ValidatedJson<UpdatePosition>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let position = tenant
+        .find_by_id::<position::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Position not found".to_string()))?;
+
+    let updated = services::update_position(&txn, &tenant, position.clone(), &update).await?;
+    audit
+        .record(
+            &txn,
+            "position",
+            &updated.id,
+            AuditAction::Update,
+            Some(&position),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    if updated.title != position.title {
+        reindex_employees(&data, &tenant, employee::Column::PositionId.eq(&updated.id)).await?;
+    }
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Delete a position nobody holds
+#[utoipa::path(
+    delete,
+    path = "/v1/position/{id}",
+    tag = "position",
+    params(
+        ("id" = String, Path, description = "Position ID")
+    ),
+    responses(
+        (status = 200, description = "Position deleted"),
+        (status = 404, description = "Position not found"),
+        (status = 409, description = "Position is still held by employees"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_position(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let position = tenant
+        .find_by_id::<position::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Position not found".to_string()))?;
+
+    services::delete_position(&txn, &tenant, position.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "position",
+            &position.id,
+            AuditAction::Delete,
+            Some(&position),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Position deleted successfully"})))
+}
@@ -0,0 +1,47 @@
+use entity::position;
+use sea_orm::{ColumnTrait, QueryFilter, QueryOrder, Select};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::shared::nullable;
+use crate::tenant::Tenant;
+
+pub type Position = position::Model;
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreatePosition {
+    #[validate(length(min = 1, max = 255, message = "Title must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub title: String,
+    pub department_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdatePosition {
+    #[validate(length(min = 1, max = 255, message = "Title must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub title: Option<String>,
+    /// `null` detaches the position from its department.
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>, nullable)]
+    pub department_id: Option<Option<String>>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct PositionFilter {
+    /// Only positions of this department
+    pub department_id: Option<String>,
+}
+
+impl PositionFilter {
+    pub fn select(&self, tenant: &Tenant) -> Select<position::Entity> {
+        let mut query = tenant
+            .find::<position::Entity>()
+            .order_by_asc(position::Column::Title);
+        if let Some(department_id) = &self.department_id {
+            query = query.filter(position::Column::DepartmentId.eq(department_id));
+        }
+        query
+    }
+}
@@ -0,0 +1,18 @@
+use super::handlers;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/position")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .route("", web::get().to(handlers::get_positions))
+            .route("", web::post().to(handlers::create_position))
+            .route("/{id}", web::get().to(handlers::get_position_by_id))
+            .route("/{id}", web::put().to(handlers::update_position))
+            .route("/{id}", web::delete().to(handlers::delete_position)),
+    );
+}
@@ -0,0 +1,82 @@
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, IntoActiveModel, PaginatorTrait, QueryFilter,
+    Set,
+};
+use uuid::Uuid;
+
+use super::models::{CreatePosition, Position, UpdatePosition};
+use crate::error::{ApiError, FieldErrors};
+use crate::tenant::Tenant;
+use crate::v1::department::services as departments;
+use entity::{employee, position};
+
+async fn check_department<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    department_id: Option<&str>,
+) -> Result<(), ApiError> {
+    let mut errors = FieldErrors::new();
+    if let Some(id) = department_id {
+        if !departments::exists(db, tenant, id).await? {
+            errors.add("department_id", "Department not found");
+        }
+    }
+    errors.into_result()
+}
+
+pub async fn create_position<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    data: &CreatePosition,
+) -> Result<Position, ApiError> {
+    check_department(db, tenant, data.department_id.as_deref()).await?;
+
+    Ok(position::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        title: Set(data.title.clone()),
+        department_id: Set(data.department_id.clone()),
+        created_at: Set(Utc::now().naive_utc()),
+    }
+    .insert(db)
+    .await?)
+}
+
+pub async fn update_position<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    position: Position,
+    data: &UpdatePosition,
+) -> Result<Position, ApiError> {
+    let mut active = position.into_active_model();
+    if let Some(title) = &data.title {
+        active.title = Set(title.clone());
+    }
+    if let Some(department_id) = &data.department_id {
+        check_department(db, tenant, department_id.as_deref()).await?;
+        active.department_id = Set(department_id.clone());
+    }
+    Ok(active.update(db).await?)
+}
+
+/// Positions held by any employee, deleted ones included, are kept.
+pub async fn delete_position<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    position: Position,
+) -> Result<(), ApiError> {
+    let holders = tenant
+        .find::<employee::Entity>()
+        .filter(employee::Column::PositionId.eq(&position.id))
+        .count(db)
+        .await?;
+    if holders > 0 {
+        return Err(ApiError::Conflict(
+            "The position is still held by employees".to_string(),
+        ));
+    }
+
+    position.into_active_model().delete(db).await?;
+    Ok(())
+}
@@ -0,0 +1,308 @@
+use actix_web::{web, HttpResponse};
+use sea_orm::{ConnectionTrait, ModelTrait, QueryOrder, QuerySelect, TransactionTrait};
+use serde_json::json;
+
+use super::models::{
+    CreatePriceList, DiscountPolicy, PriceList, PriceListWithItems, PricingQuote, PricingRequest,
+    UpdateDiscountPolicy, UpdatePriceList,
+};
+use super::services;
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::extractors::{Admin, ValidatedJson};
+use crate::tenant::Tenant;
+use entity::audit_log::AuditAction;
+use entity::price_list;
+
+/// List price lists
+#[utoipa::path(
+    get,
+    path = "/v1/pricing/price-list",
+    tag = "pricing",
+    responses(
+        (status = 200, description = "List of price lists", body = Vec<PriceList>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_price_lists(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+) -> Result<HttpResponse, ApiError> {
+    let price_lists = tenant
+        .find::<price_list::Entity>()
+        .order_by_asc(price_list::Column::Name)
+        .all(&data.db)
+        .await?;
+    Ok(HttpResponse::Ok().json(price_lists))
+}
+
+/// Create a price list with its prices
+#[utoipa::path(
+    post,
+    path = "/v1/pricing/price-list",
+    tag = "pricing",
+    request_body = CreatePriceList,
+    responses(
+        (status = 201, description = "Price list created", body = PriceListWithItems),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not an administrator"),
+        (status = 409, description = "Name already in use"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_price_list(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    price_list: ValidatedJson<CreatePriceList>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let (price_list, items) = services::create_price_list(&txn, &tenant, &price_list).await?;
+    let created = PriceListWithItems { price_list, items };
+    audit
+        .record(
+            &txn,
+            "price_list",
+            &created.price_list.id,
+            AuditAction::Create,
+            None,
+            Some(&created),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Created().json(created))
+}
+
+async fn find_price_list<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    id: &str,
+) -> Result<PriceList, ApiError> {
+    tenant
+        .find_by_id::<price_list::Entity>(id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Price list not found".to_string()))
+}
+
+/// Get a price list with its prices
+#[utoipa::path(
+    get,
+    path = "/v1/pricing/price-list/{id}",
+    tag = "pricing",
+    params(
+        ("id" = String, Path, description = "Price list ID")
+    ),
+    responses(
+        (status = 200, description = "Price list", body = PriceListWithItems),
+        (status = 404, description = "Price list not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_price_list_by_id(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let price_list = tenant
+        .find_by_id::<price_list::Entity>(id.as_str())
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Price list not found".to_string()))?;
+    let items = services::items(&data.db, &tenant, &price_list.id).await?;
+    Ok(HttpResponse::Ok().json(PriceListWithItems { price_list, items }))
+}
+
+/// Update a price list
+#[utoipa::path(
+    put,
+    path = "/v1/pricing/price-list/{id}",
+    tag = "pricing",
+    params(
+        ("id" = String, Path, description = "Price list ID")
+    ),
+    request_body = UpdatePriceList,
+    responses(
+        (status = 200, description = "Price list updated", body = PriceListWithItems),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Price list not found"),
+        (status = 409, description = "Name already in use"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_price_list(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    id: web::Path<String>,
+    update: ValidatedJson<UpdatePriceList>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let price_list = find_price_list(&txn, &tenant, &id).await?;
+    let before = PriceListWithItems {
+        items: services::items(&txn, &tenant, &price_list.id).await?,
+        price_list: price_list.clone(),
+    };
+    let (price_list, items) =
+        services::update_price_list(&txn, &tenant, price_list, &update).await?;
+    let updated = PriceListWithItems { price_list, items };
+    audit
+        .record(
+            &txn,
+            "price_list",
+            &updated.price_list.id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Delete a price list and its prices
+#[utoipa::path(
+    delete,
+    path = "/v1/pricing/price-list/{id}",
+    tag = "pricing",
+    params(
+        ("id" = String, Path, description = "Price list ID")
+    ),
+    responses(
+        (status = 200, description = "Price list deleted"),
+        (status = 403, description = "Not an administrator"),
+        (status = 404, description = "Price list not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_price_list(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let price_list = find_price_list(&txn, &tenant, &id).await?;
+    audit
+        .record(
+            &txn,
+            "price_list",
+            &price_list.id,
+            AuditAction::Delete,
+            Some(&price_list),
+            None,
+        )
+        .await?;
+    price_list.delete(&txn).await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Price list deleted successfully"})))
+}
+
+/// Get the limits past which discounts need an administrator's approval
+#[utoipa::path(
+    get,
+    path = "/v1/pricing/discount-policy",
+    tag = "pricing",
+    responses(
+        (status = 200, description = "Discount policy", body = DiscountPolicy),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_discount_policy(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+) -> Result<HttpResponse, ApiError> {
+    let policy = services::discount_policy(&data.db, &tenant).await?;
+    Ok(HttpResponse::Ok().json(policy))
+}
+
+/// Set the limits past which discounts need an administrator's approval
+#[utoipa::path(
+    put,
+    path = "/v1/pricing/discount-policy",
+    tag = "pricing",
+    request_body = UpdateDiscountPolicy,
+    responses(
+        (status = 200, description = "Discount policy updated", body = DiscountPolicy),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_discount_policy(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    update: ValidatedJson<UpdateDiscountPolicy>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let before = services::discount_policy(&txn, &tenant).await?;
+    let policy = services::update_discount_policy(&txn, &tenant, &update).await?;
+    audit
+        .record(
+            &txn,
+            "discount_policy",
+            &policy.tenant_id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&policy),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(policy))
+}
+
+/// Price items for a customer as an order would be priced
+#[utoipa::path(
+    post,
+    path = "/v1/pricing/quote",
+    tag = "pricing",
+    request_body = PricingRequest,
+    responses(
+        (status = 200, description = "Prices, discounts and totals", body = PricingQuote),
+        (status = 400, description = "Validation error or an item without a price"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn quote(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    request: ValidatedJson<PricingRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let quote = services::quote(&data.db, &tenant, &request).await?;
+    Ok(HttpResponse::Ok().json(quote))
+}
@@ -0,0 +1,159 @@
+use chrono::NaiveDate;
+use entity::order::DiscountKind;
+use entity::{discount_policy, price_list, price_list_item};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+pub type PriceList = price_list::Model;
+pub type PriceListItem = price_list_item::Model;
+pub type DiscountPolicy = discount_policy::Model;
+
+/// An item's price on a list from `min_quantity` up.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PriceBreak {
+    pub inventory_id: String,
+    /// 1 by default.
+    #[schema(minimum = 1)]
+    pub min_quantity: Option<i32>,
+    #[schema(minimum = 0)]
+    pub unit_price: f64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreatePriceList {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: String,
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Customer group must be 1 to 50 characters"
+    ))]
+    #[schema(min_length = 1, max_length = 50)]
+    pub customer_group: Option<String>,
+    #[validate(length(equal = 3, message = "Currency must be a 3-letter code"))]
+    #[schema(min_length = 3, max_length = 3, example = "USD")]
+    pub currency: Option<String>,
+    pub valid_from: Option<NaiveDate>,
+    pub valid_to: Option<NaiveDate>,
+    pub items: Vec<PriceBreak>,
+}
+
+/// Changes a price list. Items, when given, replace all of the list's.
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdatePriceList {
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: Option<String>,
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Customer group must be 1 to 50 characters"
+    ))]
+    #[schema(min_length = 1, max_length = 50)]
+    pub customer_group: Option<String>,
+    #[validate(length(equal = 3, message = "Currency must be a 3-letter code"))]
+    #[schema(min_length = 3, max_length = 3)]
+    pub currency: Option<String>,
+    pub valid_from: Option<NaiveDate>,
+    pub valid_to: Option<NaiveDate>,
+    pub items: Option<Vec<PriceBreak>>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PriceListWithItems {
+    #[serde(flatten)]
+    pub price_list: PriceList,
+    pub items: Vec<PriceListItem>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateDiscountPolicy {
+    /// Empty for no limit.
+    #[validate(range(min = 0.0, max = 100.0, message = "Limit must be 0 to 100 percent"))]
+    #[schema(minimum = 0, maximum = 100)]
+    pub line_limit_percent: Option<f64>,
+    /// Empty for no limit.
+    #[validate(range(min = 0.0, max = 100.0, message = "Limit must be 0 to 100 percent"))]
+    #[schema(minimum = 0, maximum = 100)]
+    pub order_limit_percent: Option<f64>,
+}
+
+/// A percentage or an amount off a line or an order.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, ToSchema)]
+pub struct Discount {
+    pub kind: DiscountKind,
+    #[schema(minimum = 0)]
+    pub value: f64,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct PricingLineInput {
+    pub inventory_id: String,
+    #[schema(minimum = 1)]
+    pub quantity: i32,
+    pub discount: Option<Discount>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct PricingRequest {
+    #[validate(length(min = 1, max = 36, message = "Customer id must be 1 to 36 characters"))]
+    #[schema(min_length = 1, max_length = 36)]
+    pub customer_id: String,
+    /// The company's own currency by default.
+    #[validate(length(equal = 3, message = "Currency must be a 3-letter code"))]
+    #[schema(min_length = 3, max_length = 3)]
+    pub currency: Option<String>,
+    /// Day the prices are for, today by default.
+    pub date: Option<NaiveDate>,
+    #[validate(length(min = 1, message = "Price at least one line"))]
+    pub lines: Vec<PricingLineInput>,
+    /// Discount on the whole order, on top of the lines' own.
+    pub discount: Option<Discount>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct PricedItem {
+    pub inventory_id: String,
+    pub description: String,
+    pub quantity: i32,
+    /// The item's own price.
+    pub list_price: f64,
+    /// Price the customer pays before discounts.
+    pub unit_price: f64,
+    /// Price list `unit_price` comes from, if any.
+    pub price_list_id: Option<String>,
+    pub discount: Option<Discount>,
+    pub discount_amount: f64,
+    pub line_total: f64,
+}
+
+/// Prices for a customer, as an order for them would be placed.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct PricingQuote {
+    pub customer_id: String,
+    pub currency: Option<String>,
+    pub date: NaiveDate,
+    pub lines: Vec<PricedItem>,
+    /// Sum of the lines before discounts.
+    pub subtotal: f64,
+    pub discount: Option<Discount>,
+    /// Money off the whole order, on top of the lines' own.
+    pub order_discount_amount: f64,
+    pub discount_total: f64,
+    pub total: f64,
+    /// Whether the discounts go past the company's limits, so that an
+    /// order needs an administrator's approval.
+    pub requires_approval: bool,
+}
+
+/// What priced lines come to once the discount on the whole order is taken.
+#[derive(Clone, Copy, Debug)]
+pub struct OrderTotals {
+    pub subtotal: f64,
+    pub order_discount_amount: f64,
+    pub discount_total: f64,
+    pub total: f64,
+    pub requires_approval: bool,
+}
@@ -0,0 +1,36 @@
+use super::handlers;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/pricing")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .route("/price-list", web::get().to(handlers::get_price_lists))
+            .route("/price-list", web::post().to(handlers::create_price_list))
+            .route(
+                "/price-list/{id}",
+                web::get().to(handlers::get_price_list_by_id),
+            )
+            .route(
+                "/price-list/{id}",
+                web::put().to(handlers::update_price_list),
+            )
+            .route(
+                "/price-list/{id}",
+                web::delete().to(handlers::delete_price_list),
+            )
+            .route(
+                "/discount-policy",
+                web::get().to(handlers::get_discount_policy),
+            )
+            .route(
+                "/discount-policy",
+                web::put().to(handlers::update_discount_policy),
+            )
+            .route("/quote", web::post().to(handlers::quote)),
+    );
+}
@@ -0,0 +1,505 @@
+use std::collections::HashSet;
+
+use chrono::{NaiveDate, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, Set,
+};
+use uuid::Uuid;
+
+use super::models::{
+    CreatePriceList, Discount, DiscountPolicy, OrderTotals, PriceBreak, PriceList, PriceListItem,
+    PricedItem, PricingQuote, PricingRequest, UpdateDiscountPolicy, UpdatePriceList,
+};
+use crate::error::{ApiError, FieldErrors};
+use crate::shared::db_utils::conflict_on_duplicate;
+use crate::shared::round;
+use crate::tenant::Tenant;
+use crate::v1::customer::models::Customer;
+use crate::v1::inventory::models::InventoryItem;
+use crate::v1::order::models::{NewOrder, PricedLine};
+use entity::order::DiscountKind;
+use entity::{customer, discount_policy, inventory, price_list, price_list_item};
+
+const DUPLICATE_PRICE_LIST: &str = "A price list with this name already exists";
+
+fn check_dates(from: Option<NaiveDate>, to: Option<NaiveDate>, errors: &mut FieldErrors) {
+    if let (Some(from), Some(to)) = (from, to) {
+        if to < from {
+            errors.add("valid_to", "The list cannot end before it starts");
+        }
+    }
+}
+
+async fn check_items<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    items: &[PriceBreak],
+    errors: &mut FieldErrors,
+) -> Result<(), ApiError> {
+    let mut seen = HashSet::new();
+    for (i, item) in items.iter().enumerate() {
+        let field = |name: &str| format!("items[{i}].{name}");
+        let exists = tenant
+            .find_active_by_id::<inventory::Entity>(&item.inventory_id)
+            .one(db)
+            .await?
+            .is_some();
+        if !exists {
+            errors.add(&field("inventory_id"), "Inventory item not found");
+        }
+        let min_quantity = item.min_quantity.unwrap_or(1);
+        if min_quantity < 1 {
+            errors.add(
+                &field("min_quantity"),
+                "Minimum quantity must be at least 1",
+            );
+        }
+        if item.unit_price < 0.0 {
+            errors.add(&field("unit_price"), "Unit price cannot be negative");
+        }
+        if !seen.insert((item.inventory_id.as_str(), min_quantity)) {
+            errors.add(
+                &field("min_quantity"),
+                "The item already has a price from this quantity",
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the list's items.
+async fn set_items<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    price_list_id: &str,
+    items: &[PriceBreak],
+) -> Result<Vec<PriceListItem>, ApiError> {
+    tenant
+        .delete_many::<price_list_item::Entity>()
+        .filter(price_list_item::Column::PriceListId.eq(price_list_id))
+        .exec(db)
+        .await?;
+    for item in items {
+        price_list_item::ActiveModel {
+            id: Set(Uuid::new_v4().to_string()),
+            tenant_id: Set(tenant.id().to_string()),
+            price_list_id: Set(price_list_id.to_string()),
+            inventory_id: Set(item.inventory_id.clone()),
+            min_quantity: Set(item.min_quantity.unwrap_or(1)),
+            unit_price: Set(item.unit_price),
+        }
+        .insert(db)
+        .await?;
+    }
+    self::items(db, tenant, price_list_id).await
+}
+
+pub async fn items<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    price_list_id: &str,
+) -> Result<Vec<PriceListItem>, ApiError> {
+    Ok(tenant
+        .find::<price_list_item::Entity>()
+        .filter(price_list_item::Column::PriceListId.eq(price_list_id))
+        .order_by_asc(price_list_item::Column::InventoryId)
+        .order_by_asc(price_list_item::Column::MinQuantity)
+        .all(db)
+        .await?)
+}
+
+pub async fn create_price_list<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    data: &CreatePriceList,
+) -> Result<(PriceList, Vec<PriceListItem>), ApiError> {
+    let mut errors = FieldErrors::new();
+    check_dates(data.valid_from, data.valid_to, &mut errors);
+    check_items(db, tenant, &data.items, &mut errors).await?;
+    errors.into_result()?;
+
+    let now = Utc::now().naive_utc();
+    let price_list = price_list::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        name: Set(data.name.clone()),
+        customer_group: Set(data.customer_group.clone()),
+        currency: Set(data.currency.as_ref().map(|c| c.to_uppercase())),
+        valid_from: Set(data.valid_from),
+        valid_to: Set(data.valid_to),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(db)
+    .await
+    .map_err(|e| conflict_on_duplicate(e, DUPLICATE_PRICE_LIST))?;
+    let items = set_items(db, tenant, &price_list.id, &data.items).await?;
+    Ok((price_list, items))
+}
+
+pub async fn update_price_list<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    price_list: PriceList,
+    data: &UpdatePriceList,
+) -> Result<(PriceList, Vec<PriceListItem>), ApiError> {
+    let mut errors = FieldErrors::new();
+    check_dates(
+        data.valid_from.or(price_list.valid_from),
+        data.valid_to.or(price_list.valid_to),
+        &mut errors,
+    );
+    if let Some(items) = &data.items {
+        check_items(db, tenant, items, &mut errors).await?;
+    }
+    errors.into_result()?;
+
+    let mut active = price_list.into_active_model();
+    if let Some(name) = &data.name {
+        active.name = Set(name.clone());
+    }
+    if let Some(customer_group) = &data.customer_group {
+        active.customer_group = Set(Some(customer_group.clone()));
+    }
+    if let Some(currency) = &data.currency {
+        active.currency = Set(Some(currency.to_uppercase()));
+    }
+    if let Some(valid_from) = data.valid_from {
+        active.valid_from = Set(Some(valid_from));
+    }
+    if let Some(valid_to) = data.valid_to {
+        active.valid_to = Set(Some(valid_to));
+    }
+    active.updated_at = Set(Utc::now().naive_utc());
+    let price_list = active
+        .update(db)
+        .await
+        .map_err(|e| conflict_on_duplicate(e, DUPLICATE_PRICE_LIST))?;
+
+    let items = match &data.items {
+        Some(items) => set_items(db, tenant, &price_list.id, items).await?,
+        None => items(db, tenant, &price_list.id).await?,
+    };
+    Ok((price_list, items))
+}
+
+/// The company's limits, none until they are set.
+pub async fn discount_policy<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+) -> Result<DiscountPolicy, ApiError> {
+    let policy = tenant.find::<discount_policy::Entity>().one(db).await?;
+    Ok(policy.unwrap_or_else(|| DiscountPolicy {
+        tenant_id: tenant.id().to_string(),
+        line_limit_percent: None,
+        order_limit_percent: None,
+        updated_at: Utc::now().naive_utc(),
+    }))
+}
+
+pub async fn update_discount_policy<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    data: &UpdateDiscountPolicy,
+) -> Result<DiscountPolicy, ApiError> {
+    let exists = tenant
+        .find::<discount_policy::Entity>()
+        .one(db)
+        .await?
+        .is_some();
+    let policy = discount_policy::ActiveModel {
+        tenant_id: Set(tenant.id().to_string()),
+        line_limit_percent: Set(data.line_limit_percent),
+        order_limit_percent: Set(data.order_limit_percent),
+        updated_at: Set(Utc::now().naive_utc()),
+    };
+    Ok(if exists {
+        policy.update(db).await?
+    } else {
+        policy.insert(db).await?
+    })
+}
+
+/// A price from a price list.
+pub struct ListPrice {
+    pub price_list_id: String,
+    pub unit_price: f64,
+}
+
+/// The customer's price for `quantity` of `item` on `date`, from the price
+/// lists that apply. Lists for the customer's group win over lists for
+/// everyone, then the list that started last, then the largest quantity
+/// break reached. `None` when no list has a price for the item.
+pub async fn list_price<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    customer: &Customer,
+    currency: Option<&str>,
+    date: NaiveDate,
+    item: &InventoryItem,
+    quantity: i32,
+) -> Result<Option<ListPrice>, ApiError> {
+    let mut group = Condition::any().add(price_list::Column::CustomerGroup.is_null());
+    if let Some(customer_group) = &customer.customer_group {
+        group = group.add(price_list::Column::CustomerGroup.eq(customer_group));
+    }
+    let currency = match currency {
+        Some(currency) => price_list::Column::Currency.eq(currency),
+        None => price_list::Column::Currency.is_null(),
+    };
+    let lists = tenant
+        .find::<price_list::Entity>()
+        .filter(group)
+        .filter(currency)
+        .filter(
+            Condition::any()
+                .add(price_list::Column::ValidFrom.is_null())
+                .add(price_list::Column::ValidFrom.lte(date)),
+        )
+        .filter(
+            Condition::any()
+                .add(price_list::Column::ValidTo.is_null())
+                .add(price_list::Column::ValidTo.gte(date)),
+        )
+        .all(db)
+        .await?;
+    if lists.is_empty() {
+        return Ok(None);
+    }
+
+    let breaks = tenant
+        .find::<price_list_item::Entity>()
+        .filter(price_list_item::Column::PriceListId.is_in(lists.iter().map(|l| l.id.clone())))
+        .filter(price_list_item::Column::InventoryId.eq(&item.id))
+        .filter(price_list_item::Column::MinQuantity.lte(quantity))
+        .all(db)
+        .await?;
+    let best = breaks
+        .into_iter()
+        .filter_map(|price| {
+            let list = lists.iter().find(|l| l.id == price.price_list_id)?;
+            let rank = (
+                list.customer_group.is_some(),
+                list.valid_from,
+                price.min_quantity,
+                list.created_at,
+            );
+            Some((rank, price))
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, price)| ListPrice {
+            price_list_id: price.price_list_id,
+            unit_price: price.unit_price,
+        });
+    Ok(best)
+}
+
+fn check_discount(field: &str, discount: &Discount, errors: &mut FieldErrors) {
+    if discount.value < 0.0 {
+        errors.add(field, "Discount cannot be negative");
+    } else if discount.kind == DiscountKind::Percent && discount.value > 100.0 {
+        errors.add(field, "Discount cannot be more than 100 percent");
+    }
+}
+
+/// Money `discount` takes off `amount`, or `None` when it is more than the
+/// amount.
+fn discount_amount(discount: Option<&Discount>, amount: f64) -> Option<f64> {
+    let off = match discount {
+        None => 0.0,
+        Some(Discount {
+            kind: DiscountKind::Percent,
+            value,
+        }) => round(amount * value / 100.0),
+        Some(Discount {
+            kind: DiscountKind::Fixed,
+            value,
+        }) => round(*value),
+    };
+    (off <= amount).then_some(off)
+}
+
+/// Whether the discounts go past the company's limits, a line's against
+/// its own price and the order's overall against `subtotal`.
+pub fn exceeds_limits(
+    policy: &DiscountPolicy,
+    lines: &[PricedLine],
+    subtotal: f64,
+    discount_total: f64,
+) -> bool {
+    let over = |limit: Option<f64>, off: f64, of: f64| match limit {
+        Some(limit) if of > 0.0 => off / of * 100.0 > limit + 1e-9,
+        _ => false,
+    };
+    lines.iter().any(|line| {
+        over(
+            policy.line_limit_percent,
+            line.discount_amount(),
+            line.gross(),
+        )
+    }) || over(policy.order_limit_percent, discount_total, subtotal)
+}
+
+/// Prices lines for a customer: at the price lists that apply, or the
+/// items' own prices in the company's currency, less any discounts.
+pub async fn quote<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    data: &PricingRequest,
+) -> Result<PricingQuote, ApiError> {
+    let mut errors = FieldErrors::new();
+    let customer = tenant
+        .find_active_by_id::<customer::Entity>(&data.customer_id)
+        .one(db)
+        .await?;
+    let Some(customer) = customer else {
+        errors.add("customer_id", "Customer not found");
+        return Err(ApiError::InvalidFields(errors));
+    };
+    let currency = data.currency.as_ref().map(|c| c.to_uppercase());
+    let date = data.date.unwrap_or_else(|| Utc::now().date_naive());
+
+    let mut lines = Vec::with_capacity(data.lines.len());
+    for (i, line) in data.lines.iter().enumerate() {
+        let field = |name: &str| format!("lines[{i}].{name}");
+        if line.quantity < 1 {
+            errors.add(&field("quantity"), "Quantity must be at least 1");
+        }
+        if let Some(discount) = &line.discount {
+            check_discount(&field("discount"), discount, &mut errors);
+        }
+        let item = tenant
+            .find_active_by_id::<inventory::Entity>(&line.inventory_id)
+            .one(db)
+            .await?;
+        let Some(item) = item else {
+            errors.add(&field("inventory_id"), "Inventory item not found");
+            continue;
+        };
+
+        let listed = list_price(
+            db,
+            tenant,
+            &customer,
+            currency.as_deref(),
+            date,
+            &item,
+            line.quantity,
+        )
+        .await?;
+        let (unit_price, price_list_id) = match (listed, &currency) {
+            (Some(listed), _) => (listed.unit_price, Some(listed.price_list_id)),
+            (None, None) => (item.price, None),
+            (None, Some(currency)) => {
+                errors.add(
+                    &field("inventory_id"),
+                    format!("No price list has a {currency} price for {}", item.name),
+                );
+                continue;
+            }
+        };
+        let gross = round(f64::from(line.quantity) * unit_price);
+        let Some(off) = discount_amount(line.discount.as_ref(), gross) else {
+            errors.add(&field("discount"), "Discount is more than the line");
+            continue;
+        };
+        lines.push(PricedItem {
+            inventory_id: item.id,
+            description: item.name,
+            quantity: line.quantity,
+            list_price: item.price,
+            unit_price,
+            price_list_id,
+            discount: line.discount,
+            discount_amount: off,
+            line_total: round(gross - off),
+        });
+    }
+    errors.into_result()?;
+
+    let priced: Vec<PricedLine> = lines.iter().map(PricedLine::from).collect();
+    let totals = totals(db, tenant, &priced, data.discount.as_ref()).await?;
+
+    Ok(PricingQuote {
+        customer_id: customer.id,
+        currency,
+        date,
+        lines,
+        subtotal: totals.subtotal,
+        discount: data.discount,
+        order_discount_amount: totals.order_discount_amount,
+        discount_total: totals.discount_total,
+        total: totals.total,
+        requires_approval: totals.requires_approval,
+    })
+}
+
+/// Adds up lines priced beforehand, less `discount` on the whole order, and
+/// checks the discounts against the company's limits.
+pub async fn totals<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    lines: &[PricedLine],
+    discount: Option<&Discount>,
+) -> Result<OrderTotals, ApiError> {
+    let mut errors = FieldErrors::new();
+    if let Some(discount) = discount {
+        check_discount("discount", discount, &mut errors);
+    }
+    errors.into_result()?;
+
+    let subtotal = round(lines.iter().map(PricedLine::gross).sum());
+    let after_lines = round(lines.iter().map(|line| line.line_total).sum());
+    let Some(order_discount_amount) = discount_amount(discount, after_lines) else {
+        let mut errors = FieldErrors::new();
+        errors.add("discount", "Discount is more than the order");
+        return Err(ApiError::InvalidFields(errors));
+    };
+    let total = round(after_lines - order_discount_amount);
+    let discount_total = round(subtotal - total);
+
+    let policy = discount_policy(db, tenant).await?;
+    Ok(OrderTotals {
+        subtotal,
+        order_discount_amount,
+        discount_total,
+        total,
+        requires_approval: exceeds_limits(&policy, lines, subtotal, discount_total),
+    })
+}
+
+impl From<&PricedItem> for PricedLine {
+    fn from(item: &PricedItem) -> Self {
+        let discount_percent = match item.discount {
+            Some(Discount {
+                kind: DiscountKind::Percent,
+                value,
+            }) => value,
+            _ => 0.0,
+        };
+        PricedLine {
+            inventory_id: Some(item.inventory_id.clone()),
+            description: item.description.clone(),
+            quantity: item.quantity,
+            unit_price: item.unit_price,
+            discount_percent,
+            line_total: item.line_total,
+        }
+    }
+}
+
+impl From<PricingQuote> for NewOrder {
+    fn from(quote: PricingQuote) -> Self {
+        NewOrder {
+            customer_id: quote.customer_id,
+            currency: quote.currency,
+            quotation_id: None,
+            lines: quote.lines.iter().map(PricedLine::from).collect(),
+            discount: quote.discount,
+            discount_total: quote.discount_total,
+            total: quote.total,
+            requires_approval: quote.requires_approval,
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use actix_web::{web, HttpResponse};
+use sea_orm::{QuerySelect, TransactionTrait};
+use serde_json::json;
+
+use super::models::{CreateProject, Project, ProjectFilter, UpdateProject};
+use super::services;
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::extractors::ValidatedJson;
+use crate::tenant::Tenant;
+use entity::audit_log::AuditAction;
+use entity::project;
+
+/// List projects
+#[utoipa::path(
+    get,
+    path = "/v1/project",
+    tag = "project",
+    params(
+        ProjectFilter
+    ),
+    responses(
+        (status = 200, description = "List of projects", body = Vec<Project>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_projects(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<ProjectFilter>,
+) -> Result<HttpResponse, ApiError> {
+    let projects = filter.select(&tenant).all(&data.db).await?;
+
+    Ok(HttpResponse::Ok().json(projects))
+}
+
+/// Create a project
+#[utoipa::path(
+    post,
+    path = "/v1/project",
+    tag = "project",
+    request_body = CreateProject,
+    responses(
+        (status = 200, description = "Project created", body = Project),
+        (status = 400, description = "Validation error"),
+        (status = 409, description = "Code already in use"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_project(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    project: ValidatedJson<CreateProject>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let project = services::create_project(&txn, &tenant, &project).await?;
+    audit
+        .record(
+            &txn,
+            "project",
+            &project.id,
+            AuditAction::Create,
+            None,
+            Some(&project),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(project))
+}
+
+/// Get project by ID
+#[utoipa::path(
+    get,
+    path = "/v1/project/{id}",
+    tag = "project",
+    params(
+        ("id" = String, Path, description = "Project ID")
+    ),
+    responses(
+        (status = 200, description = "Project found", body = Project),
+        (status = 404, description = "Project not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_project_by_id(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let project = tenant
+        .find_by_id::<project::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Project not found".to_string()))?;
+
+    Ok(HttpResponse::Ok().json(project))
+}
+
+/// Update a project, or close it to new time
+#[utoipa::path(
+    put,
+    path = "/v1/project/{id}",
+    tag = "project",
+    params(
+        ("id" = String, Path, description = "Project ID")
+    ),
+    request_body = UpdateProject,
+    responses(
+        (status = 200, description = "Project updated", body = Project),
+        (status = 400, description = "Validation error"),
+        (status = 404, description = "Project not found"),
+        (status = 409, description = "Code already in use"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_project(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+    update: ValidatedJson<UpdateProject>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let project = tenant
+        .find_by_id::<project::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Project not found".to_string()))?;
+
+    let updated = services::update_project(&txn, project.clone(), &update).await?;
+    audit
+        .record(
+            &txn,
+            "project",
+            &updated.id,
+            AuditAction::Update,
+            Some(&project),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Delete a project nobody has booked time on
+#[utoipa::path(
+    delete,
+    path = "/v1/project/{id}",
+    tag = "project",
+    params(
+        ("id" = String, Path, description = "Project ID")
+    ),
+    responses(
+        (status = 200, description = "Project deleted"),
+        (status = 404, description = "Project not found"),
+        (status = 409, description = "Time has been booked on the project"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_project(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let project = tenant
+        .find_by_id::<project::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Project not found".to_string()))?;
+
+    services::delete_project(&txn, &tenant, project.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "project",
+            &project.id,
+            AuditAction::Delete,
+            Some(&project),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Project deleted successfully"})))
+}
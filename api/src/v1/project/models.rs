@@ -0,0 +1,54 @@
+use entity::project::{self, ProjectKind};
+use sea_orm::{ColumnTrait, QueryFilter, QueryOrder, Select};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::tenant::Tenant;
+
+pub type Project = project::Model;
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateProject {
+    #[validate(length(min = 1, max = 50, message = "Code must be 1 to 50 characters"))]
+    #[schema(min_length = 1, max_length = 50)]
+    pub code: String,
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: String,
+    /// A project unless given.
+    pub kind: Option<ProjectKind>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateProject {
+    #[validate(length(min = 1, max = 50, message = "Code must be 1 to 50 characters"))]
+    #[schema(min_length = 1, max_length = 50)]
+    pub code: Option<String>,
+    #[validate(length(min = 1, max = 255, message = "Name must be 1 to 255 characters"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: Option<String>,
+    /// `false` closes the project to new time.
+    pub active: Option<bool>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ProjectFilter {
+    pub kind: Option<ProjectKind>,
+    pub active: Option<bool>,
+}
+
+impl ProjectFilter {
+    pub fn select(&self, tenant: &Tenant) -> Select<project::Entity> {
+        let mut query = tenant
+            .find::<project::Entity>()
+            .order_by_asc(project::Column::Code);
+        if let Some(kind) = self.kind {
+            query = query.filter(project::Column::Kind.eq(kind));
+        }
+        if let Some(active) = self.active {
+            query = query.filter(project::Column::Active.eq(active));
+        }
+        query
+    }
+}
@@ -0,0 +1,76 @@
+use chrono::Utc;
+use entity::project::ProjectKind;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, IntoActiveModel, PaginatorTrait, QueryFilter,
+    Set,
+};
+use uuid::Uuid;
+
+use super::models::{CreateProject, Project, UpdateProject};
+use crate::error::ApiError;
+use crate::shared::db_utils::conflict_on_duplicate;
+use crate::tenant::Tenant;
+use entity::{project, timesheet_entry};
+
+const DUPLICATE_CODE: &str = "A project with this code already exists";
+
+pub async fn create_project<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    data: &CreateProject,
+) -> Result<Project, ApiError> {
+    project::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        code: Set(data.code.clone()),
+        name: Set(data.name.clone()),
+        kind: Set(data.kind.unwrap_or(ProjectKind::Project)),
+        active: Set(true),
+        created_at: Set(Utc::now().naive_utc()),
+    }
+    .insert(db)
+    .await
+    .map_err(|e| conflict_on_duplicate(e, DUPLICATE_CODE))
+}
+
+pub async fn update_project<C: ConnectionTrait>(
+    db: &C,
+    project: Project,
+    data: &UpdateProject,
+) -> Result<Project, ApiError> {
+    let mut active = project.into_active_model();
+    if let Some(code) = &data.code {
+        active.code = Set(code.clone());
+    }
+    if let Some(name) = &data.name {
+        active.name = Set(name.clone());
+    }
+    if let Some(is_active) = data.active {
+        active.active = Set(is_active);
+    }
+    active
+        .update(db)
+        .await
+        .map_err(|e| conflict_on_duplicate(e, DUPLICATE_CODE))
+}
+
+/// Projects with time booked on them can only be deactivated.
+pub async fn delete_project<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    project: Project,
+) -> Result<(), ApiError> {
+    let entries = tenant
+        .find::<timesheet_entry::Entity>()
+        .filter(timesheet_entry::Column::ProjectId.eq(&project.id))
+        .count(db)
+        .await?;
+    if entries > 0 {
+        return Err(ApiError::Conflict(
+            "Time has been booked on the project, deactivate it instead".to_string(),
+        ));
+    }
+
+    project.into_active_model().delete(db).await?;
+    Ok(())
+}
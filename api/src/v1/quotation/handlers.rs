@@ -0,0 +1,560 @@
+use actix_web::{http::header, web, HttpResponse};
+use sea_orm::{ConnectionTrait, QuerySelect, TransactionTrait};
+use serde_json::json;
+
+use super::models::{
+    ConvertedQuotation, CreateQuotation, DecideQuotation, Quotation, QuotationFilter,
+    QuotationRevision, QuotationWithLines, UpdateQuotation,
+};
+use super::services;
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::extractors::ValidatedJson;
+use crate::middlewares::jwt::Claims;
+use crate::tenant::Tenant;
+use crate::v1::auth::services as auth_services;
+use crate::v1::company::services as company_services;
+use entity::audit_log::AuditAction;
+use entity::{customer, quotation};
+
+/// List quotations, latest first
+#[utoipa::path(
+    get,
+    path = "/v1/quotation",
+    tag = "quotation",
+    params(
+        QuotationFilter
+    ),
+    responses(
+        (status = 200, description = "List of quotations", body = Vec<Quotation>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_quotations(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<QuotationFilter>,
+) -> Result<HttpResponse, ApiError> {
+    let quotations = filter.select(&tenant).all(&data.db).await?;
+    Ok(HttpResponse::Ok().json(quotations))
+}
+
+/// Draft a quotation for a customer
+#[utoipa::path(
+    post,
+    path = "/v1/quotation",
+    tag = "quotation",
+    request_body = CreateQuotation,
+    responses(
+        (status = 201, description = "Quotation drafted", body = QuotationWithLines),
+        (status = 400, description = "Validation error"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_quotation(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    quotation: ValidatedJson<CreateQuotation>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let (quotation, lines) =
+        services::create_quotation(&txn, &tenant, &claims.sub, &quotation).await?;
+    audit
+        .record(
+            &txn,
+            "quotation",
+            &quotation.id,
+            AuditAction::Create,
+            None,
+            Some(&quotation),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Created().json(QuotationWithLines { quotation, lines }))
+}
+
+/// Get a quotation with its lines
+#[utoipa::path(
+    get,
+    path = "/v1/quotation/{id}",
+    tag = "quotation",
+    params(
+        ("id" = String, Path, description = "Quotation ID")
+    ),
+    responses(
+        (status = 200, description = "Quotation found", body = QuotationWithLines),
+        (status = 404, description = "Quotation not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_quotation_by_id(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let quotation = tenant
+        .find_by_id::<quotation::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Quotation not found".to_string()))?;
+    let lines = services::lines(&data.db, &tenant, &quotation.id).await?;
+
+    Ok(HttpResponse::Ok().json(QuotationWithLines { quotation, lines }))
+}
+
+async fn find_quotation<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    id: &str,
+) -> Result<Quotation, ApiError> {
+    tenant
+        .find_by_id::<quotation::Entity>(id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Quotation not found".to_string()))
+}
+
+/// Change a draft quotation
+#[utoipa::path(
+    put,
+    path = "/v1/quotation/{id}",
+    tag = "quotation",
+    params(
+        ("id" = String, Path, description = "Quotation ID")
+    ),
+    request_body = UpdateQuotation,
+    responses(
+        (status = 200, description = "Quotation updated", body = QuotationWithLines),
+        (status = 400, description = "Validation error"),
+        (status = 404, description = "Quotation not found"),
+        (status = 409, description = "The quotation was sent"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_quotation(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+    changes: ValidatedJson<UpdateQuotation>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let quotation = find_quotation(&txn, &tenant, &id).await?;
+
+    let (updated, lines) =
+        services::update_quotation(&txn, &tenant, quotation.clone(), &changes).await?;
+    audit
+        .record(
+            &txn,
+            "quotation",
+            &updated.id,
+            AuditAction::Update,
+            Some(&quotation),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(QuotationWithLines {
+        quotation: updated,
+        lines,
+    }))
+}
+
+/// Delete a quotation that was never sent
+#[utoipa::path(
+    delete,
+    path = "/v1/quotation/{id}",
+    tag = "quotation",
+    params(
+        ("id" = String, Path, description = "Quotation ID")
+    ),
+    responses(
+        (status = 200, description = "Quotation deleted"),
+        (status = 404, description = "Quotation not found"),
+        (status = 409, description = "The quotation was sent"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_quotation(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let quotation = find_quotation(&txn, &tenant, &id).await?;
+
+    services::delete_quotation(&txn, quotation.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "quotation",
+            &quotation.id,
+            AuditAction::Delete,
+            Some(&quotation),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Quotation deleted successfully"})))
+}
+
+/// Mark a draft quotation as sent to the customer
+#[utoipa::path(
+    post,
+    path = "/v1/quotation/{id}/send",
+    tag = "quotation",
+    params(
+        ("id" = String, Path, description = "Quotation ID")
+    ),
+    responses(
+        (status = 200, description = "Quotation sent", body = Quotation),
+        (status = 404, description = "Quotation not found"),
+        (status = 409, description = "Already sent, or past its validity"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn send_quotation(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let quotation = find_quotation(&txn, &tenant, &id).await?;
+
+    let updated = services::send_quotation(&txn, quotation.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "quotation",
+            &updated.id,
+            AuditAction::Update,
+            Some(&quotation),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+async fn decide(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+    decision: ValidatedJson<DecideQuotation>,
+    accept: bool,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let quotation = find_quotation(&txn, &tenant, &id).await?;
+
+    let updated = services::decide_quotation(
+        &txn,
+        quotation.clone(),
+        &claims.sub,
+        accept,
+        decision.into_inner().note,
+    )
+    .await?;
+    audit
+        .record(
+            &txn,
+            "quotation",
+            &updated.id,
+            AuditAction::Update,
+            Some(&quotation),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Record that the customer accepted a sent quotation
+#[utoipa::path(
+    post,
+    path = "/v1/quotation/{id}/accept",
+    tag = "quotation",
+    params(
+        ("id" = String, Path, description = "Quotation ID")
+    ),
+    request_body = DecideQuotation,
+    responses(
+        (status = 200, description = "Quotation accepted", body = Quotation),
+        (status = 404, description = "Quotation not found"),
+        (status = 409, description = "Not sent, or past its validity"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn accept_quotation(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+    decision: ValidatedJson<DecideQuotation>,
+) -> Result<HttpResponse, ApiError> {
+    decide(data, tenant, audit, claims, id, decision, true).await
+}
+
+/// Record that the customer rejected a sent quotation
+#[utoipa::path(
+    post,
+    path = "/v1/quotation/{id}/reject",
+    tag = "quotation",
+    params(
+        ("id" = String, Path, description = "Quotation ID")
+    ),
+    request_body = DecideQuotation,
+    responses(
+        (status = 200, description = "Quotation rejected", body = Quotation),
+        (status = 404, description = "Quotation not found"),
+        (status = 409, description = "The quotation was not sent"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn reject_quotation(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+    decision: ValidatedJson<DecideQuotation>,
+) -> Result<HttpResponse, ApiError> {
+    decide(data, tenant, audit, claims, id, decision, false).await
+}
+
+/// Open the next revision of a sent or rejected quotation as a draft
+#[utoipa::path(
+    post,
+    path = "/v1/quotation/{id}/revise",
+    tag = "quotation",
+    params(
+        ("id" = String, Path, description = "Quotation ID")
+    ),
+    responses(
+        (status = 200, description = "Quotation revised", body = QuotationWithLines),
+        (status = 404, description = "Quotation not found"),
+        (status = 409, description = "Still a draft, or already accepted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn revise_quotation(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let quotation = find_quotation(&txn, &tenant, &id).await?;
+    let lines = services::lines(&txn, &tenant, &quotation.id).await?;
+
+    let updated =
+        services::revise_quotation(&txn, &tenant, quotation.clone(), &lines, &claims.sub).await?;
+    audit
+        .record(
+            &txn,
+            "quotation",
+            &updated.id,
+            AuditAction::Update,
+            Some(&quotation),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(QuotationWithLines {
+        quotation: updated,
+        lines,
+    }))
+}
+
+/// List the earlier revisions of a quotation as they were sent
+#[utoipa::path(
+    get,
+    path = "/v1/quotation/{id}/revisions",
+    tag = "quotation",
+    params(
+        ("id" = String, Path, description = "Quotation ID")
+    ),
+    responses(
+        (status = 200, description = "Earlier revisions, oldest first", body = Vec<QuotationRevision>),
+        (status = 404, description = "Quotation not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_revisions(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let quotation = tenant
+        .find_by_id::<quotation::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Quotation not found".to_string()))?;
+    let revisions = services::revisions(&data.db, &tenant, &quotation.id).await?;
+
+    Ok(HttpResponse::Ok().json(revisions))
+}
+
+/// Convert an accepted quotation into an order
+#[utoipa::path(
+    post,
+    path = "/v1/quotation/{id}/convert",
+    tag = "quotation",
+    params(
+        ("id" = String, Path, description = "Quotation ID")
+    ),
+    responses(
+        (status = 201, description = "Order placed from the quotation", body = ConvertedQuotation),
+        (status = 404, description = "Quotation not found"),
+        (status = 409, description = "Not accepted, or already converted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn convert_quotation(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let quotation = find_quotation(&txn, &tenant, &id).await?;
+    let lines = services::lines(&txn, &tenant, &quotation.id).await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    let approver = caller.is_admin.then_some(caller.user_id);
+
+    let (updated, order, order_lines) = services::convert_quotation(
+        &txn,
+        &tenant,
+        quotation.clone(),
+        &lines,
+        approver.as_deref(),
+    )
+    .await?;
+    audit
+        .record(
+            &txn,
+            "order",
+            &order.id,
+            AuditAction::Create,
+            None,
+            Some(&order),
+        )
+        .await?;
+    audit
+        .record(
+            &txn,
+            "quotation",
+            &updated.id,
+            AuditAction::Update,
+            Some(&quotation),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Created().json(ConvertedQuotation {
+        order,
+        lines: order_lines,
+    }))
+}
+
+/// Download a quotation as a PDF
+#[utoipa::path(
+    get,
+    path = "/v1/quotation/{id}/pdf",
+    tag = "quotation",
+    params(
+        ("id" = String, Path, description = "Quotation ID")
+    ),
+    responses(
+        (status = 200, description = "Quotation PDF", content_type = "application/pdf", body = Vec<u8>),
+        (status = 404, description = "Quotation not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_quotation_pdf(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let quotation = tenant
+        .find_by_id::<quotation::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Quotation not found".to_string()))?;
+    let lines = services::lines(&data.db, &tenant, &quotation.id).await?;
+    // Quotes outlive the customer record being deleted
+    let customer = tenant
+        .find_by_id::<customer::Entity>(&quotation.customer_id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Customer not found".to_string()))?;
+    let company = company_services::find_company(&data.db, tenant.id())
+        .await?
+        .map(|company| company.name)
+        .unwrap_or_default();
+
+    let pdf = services::quotation_pdf(&company, &customer, &quotation, &lines);
+    Ok(HttpResponse::Ok()
+        .content_type("application/pdf")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}-rev{}.pdf\"",
+                services::number(&quotation),
+                quotation.revision
+            ),
+        ))
+        .body(pdf))
+}
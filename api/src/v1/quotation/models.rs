@@ -0,0 +1,98 @@
+use chrono::NaiveDate;
+use entity::quotation::{self, QuoteStatus};
+use entity::{quotation_line, quotation_revision};
+use sea_orm::{ColumnTrait, QueryFilter, QueryOrder, Select};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::tenant::Tenant;
+use crate::v1::order::models::{Order, OrderLine};
+
+pub type Quotation = quotation::Model;
+pub type QuotationLine = quotation_line::Model;
+pub type QuotationRevision = quotation_revision::Model;
+
+/// A line as sent by the client. Lines for an inventory item take its name
+/// and price unless they are given.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct QuoteLineInput {
+    pub inventory_id: Option<String>,
+    #[schema(min_length = 1, max_length = 255)]
+    pub description: Option<String>,
+    #[schema(minimum = 1)]
+    pub quantity: i32,
+    #[schema(minimum = 0)]
+    pub unit_price: Option<f64>,
+    /// Percentage off the line, 0 by default.
+    #[schema(minimum = 0, maximum = 100)]
+    pub discount_percent: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateQuotation {
+    #[validate(length(min = 1, max = 36, message = "Customer id must be 1 to 36 characters"))]
+    #[schema(min_length = 1, max_length = 36)]
+    pub customer_id: String,
+    pub valid_until: NaiveDate,
+    #[validate(length(max = 2000, message = "Notes must be at most 2000 characters"))]
+    #[schema(max_length = 2000)]
+    pub notes: Option<String>,
+    #[validate(length(min = 1, message = "Quote at least one line"))]
+    pub lines: Vec<QuoteLineInput>,
+}
+
+/// Changes a draft. Lines, when given, replace all of the quote's lines.
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateQuotation {
+    pub valid_until: Option<NaiveDate>,
+    #[validate(length(max = 2000, message = "Notes must be at most 2000 characters"))]
+    #[schema(max_length = 2000)]
+    pub notes: Option<String>,
+    #[validate(length(min = 1, message = "Quote at least one line"))]
+    pub lines: Option<Vec<QuoteLineInput>>,
+}
+
+/// Sent to accept or reject, `{}` without a note.
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct DecideQuotation {
+    #[validate(length(max = 1000, message = "Note must be at most 1000 characters"))]
+    #[schema(max_length = 1000)]
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct QuotationWithLines {
+    #[serde(flatten)]
+    pub quotation: Quotation,
+    pub lines: Vec<QuotationLine>,
+}
+
+/// The order a quote was converted into.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ConvertedQuotation {
+    pub order: Order,
+    pub lines: Vec<OrderLine>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct QuotationFilter {
+    pub customer_id: Option<String>,
+    pub status: Option<QuoteStatus>,
+}
+
+impl QuotationFilter {
+    pub fn select(&self, tenant: &Tenant) -> Select<quotation::Entity> {
+        let mut query = tenant
+            .find::<quotation::Entity>()
+            .order_by_desc(quotation::Column::CreatedAt)
+            .order_by_asc(quotation::Column::Id);
+        if let Some(customer_id) = &self.customer_id {
+            query = query.filter(quotation::Column::CustomerId.eq(customer_id));
+        }
+        if let Some(status) = self.status {
+            query = query.filter(quotation::Column::Status.eq(status));
+        }
+        query
+    }
+}
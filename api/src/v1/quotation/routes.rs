@@ -0,0 +1,25 @@
+use super::handlers;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/quotation")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .route("", web::get().to(handlers::get_quotations))
+            .route("", web::post().to(handlers::create_quotation))
+            .route("/{id}", web::get().to(handlers::get_quotation_by_id))
+            .route("/{id}", web::put().to(handlers::update_quotation))
+            .route("/{id}", web::delete().to(handlers::delete_quotation))
+            .route("/{id}/send", web::post().to(handlers::send_quotation))
+            .route("/{id}/accept", web::post().to(handlers::accept_quotation))
+            .route("/{id}/reject", web::post().to(handlers::reject_quotation))
+            .route("/{id}/revise", web::post().to(handlers::revise_quotation))
+            .route("/{id}/revisions", web::get().to(handlers::get_revisions))
+            .route("/{id}/convert", web::post().to(handlers::convert_quotation))
+            .route("/{id}/pdf", web::get().to(handlers::get_quotation_pdf)),
+    );
+}
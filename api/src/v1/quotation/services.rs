@@ -0,0 +1,516 @@
+use chrono::{NaiveDate, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, IntoActiveModel, ModelTrait, QueryFilter,
+    QueryOrder, Set,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use super::models::{
+    CreateQuotation, Quotation, QuotationLine, QuotationRevision, QuoteLineInput, UpdateQuotation,
+};
+use crate::error::{ApiError, FieldErrors};
+use crate::pdf::{self, TextDocument};
+use crate::shared::round;
+use crate::tenant::Tenant;
+use crate::v1::customer::models::Customer;
+use crate::v1::order::models::{NewOrder, Order, OrderLine, PricedLine};
+use crate::v1::order::services as order_services;
+use crate::v1::pricing::services as pricing_services;
+use entity::quotation::{self, QuoteStatus};
+use entity::{customer, inventory, quotation_line, quotation_revision};
+
+/// Short number a quote goes by on paper.
+pub fn number(quotation: &Quotation) -> String {
+    let short: String = quotation.id.chars().take(8).collect();
+    format!("Q-{}", short.to_uppercase())
+}
+
+async fn check_customer<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    customer_id: &str,
+    errors: &mut FieldErrors,
+) -> Result<Option<Customer>, ApiError> {
+    let customer = tenant
+        .find_active_by_id::<customer::Entity>(customer_id)
+        .one(db)
+        .await?;
+    if customer.is_none() {
+        errors.add("customer_id", "Customer not found");
+    }
+    Ok(customer)
+}
+
+/// Works out the lines' prices, reporting problems against `lines[i]`.
+/// Items without a price given are at the customer's price lists.
+async fn price_lines<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    customer: Option<&Customer>,
+    lines: &[QuoteLineInput],
+    errors: &mut FieldErrors,
+) -> Result<Vec<PricedLine>, ApiError> {
+    let mut priced = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        let field = |name: &str| format!("lines[{i}].{name}");
+        let item = match &line.inventory_id {
+            Some(id) => {
+                let item = tenant
+                    .find_active_by_id::<inventory::Entity>(id)
+                    .one(db)
+                    .await?;
+                if item.is_none() {
+                    errors.add(&field("inventory_id"), "Inventory item not found");
+                }
+                item
+            }
+            None => None,
+        };
+
+        let description = match (&line.description, &item) {
+            (Some(description), _) => description.trim().to_string(),
+            (None, Some(item)) => item.name.clone(),
+            (None, None) => String::new(),
+        };
+        if description.is_empty() || description.chars().count() > 255 {
+            errors.add(
+                &field("description"),
+                "Description must be 1 to 255 characters",
+            );
+        }
+        if line.quantity < 1 {
+            errors.add(&field("quantity"), "Quantity must be at least 1");
+        }
+        let unit_price = match (line.unit_price, &item) {
+            (Some(price), _) => price,
+            (None, Some(item)) => match customer {
+                Some(customer) => pricing_services::list_price(
+                    db,
+                    tenant,
+                    customer,
+                    None,
+                    Utc::now().date_naive(),
+                    item,
+                    line.quantity,
+                )
+                .await?
+                .map_or(item.price, |listed| listed.unit_price),
+                None => item.price,
+            },
+            (None, None) => {
+                if line.inventory_id.is_none() {
+                    errors.add(
+                        &field("unit_price"),
+                        "Give a price for lines without an inventory item",
+                    );
+                }
+                0.0
+            }
+        };
+        if unit_price < 0.0 {
+            errors.add(&field("unit_price"), "Unit price cannot be negative");
+        }
+        let discount_percent = line.discount_percent.unwrap_or(0.0);
+        if !(0.0..=100.0).contains(&discount_percent) {
+            errors.add(
+                &field("discount_percent"),
+                "Discount must be between 0 and 100 percent",
+            );
+        }
+
+        let gross = f64::from(line.quantity) * unit_price;
+        priced.push(PricedLine {
+            inventory_id: line.inventory_id.clone(),
+            description,
+            quantity: line.quantity,
+            unit_price,
+            discount_percent,
+            line_total: round(gross * (1.0 - discount_percent / 100.0)),
+        });
+    }
+    Ok(priced)
+}
+
+/// Replaces the quote's lines and updates its totals to match.
+async fn set_lines<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    quotation: quotation::ActiveModel,
+    id: &str,
+    priced: &[PricedLine],
+) -> Result<(Quotation, Vec<QuotationLine>), ApiError> {
+    tenant
+        .delete_many::<quotation_line::Entity>()
+        .filter(quotation_line::Column::QuotationId.eq(id))
+        .exec(db)
+        .await?;
+    let mut lines = Vec::with_capacity(priced.len());
+    for (position, line) in (1..).zip(priced) {
+        let line = quotation_line::ActiveModel {
+            id: Set(Uuid::new_v4().to_string()),
+            tenant_id: Set(tenant.id().to_string()),
+            quotation_id: Set(id.to_string()),
+            position: Set(position),
+            inventory_id: Set(line.inventory_id.clone()),
+            description: Set(line.description.clone()),
+            quantity: Set(line.quantity),
+            unit_price: Set(line.unit_price),
+            discount_percent: Set(line.discount_percent),
+            line_total: Set(line.line_total),
+        }
+        .insert(db)
+        .await?;
+        lines.push(line);
+    }
+
+    let subtotal = round(priced.iter().map(PricedLine::gross).sum());
+    let total = round(priced.iter().map(|line| line.line_total).sum());
+    let mut quotation = quotation;
+    quotation.subtotal = Set(subtotal);
+    quotation.discount_total = Set(round(subtotal - total));
+    quotation.total = Set(total);
+    quotation.updated_at = Set(Utc::now().naive_utc());
+    Ok((quotation.update(db).await?, lines))
+}
+
+pub async fn create_quotation<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    user_id: &str,
+    data: &CreateQuotation,
+) -> Result<(Quotation, Vec<QuotationLine>), ApiError> {
+    let mut errors = FieldErrors::new();
+    let customer = check_customer(db, tenant, &data.customer_id, &mut errors).await?;
+    let priced = price_lines(db, tenant, customer.as_ref(), &data.lines, &mut errors).await?;
+    errors.into_result()?;
+
+    let now = Utc::now().naive_utc();
+    let quotation = quotation::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        customer_id: Set(data.customer_id.clone()),
+        revision: Set(1),
+        status: Set(QuoteStatus::Draft),
+        valid_until: Set(data.valid_until),
+        notes: Set(data.notes.clone()),
+        subtotal: Set(0.0),
+        discount_total: Set(0.0),
+        total: Set(0.0),
+        sent_at: Set(None),
+        decided_by: Set(None),
+        decided_at: Set(None),
+        decision_note: Set(None),
+        order_id: Set(None),
+        created_by: Set(user_id.to_string()),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(db)
+    .await?;
+    let id = quotation.id.clone();
+    set_lines(db, tenant, quotation.into_active_model(), &id, &priced).await
+}
+
+pub async fn lines<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    quotation_id: &str,
+) -> Result<Vec<QuotationLine>, ApiError> {
+    Ok(tenant
+        .find::<quotation_line::Entity>()
+        .filter(quotation_line::Column::QuotationId.eq(quotation_id))
+        .order_by_asc(quotation_line::Column::Position)
+        .all(db)
+        .await?)
+}
+
+fn check_draft(quotation: &Quotation) -> Result<(), ApiError> {
+    if quotation.status != QuoteStatus::Draft {
+        return Err(ApiError::Conflict(
+            "The quotation was sent; revise it to make changes".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn update_quotation<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    quotation: Quotation,
+    data: &UpdateQuotation,
+) -> Result<(Quotation, Vec<QuotationLine>), ApiError> {
+    check_draft(&quotation)?;
+    let priced = match &data.lines {
+        Some(lines) => {
+            let customer = tenant
+                .find_active_by_id::<customer::Entity>(&quotation.customer_id)
+                .one(db)
+                .await?;
+            let mut errors = FieldErrors::new();
+            let priced = price_lines(db, tenant, customer.as_ref(), lines, &mut errors).await?;
+            errors.into_result()?;
+            Some(priced)
+        }
+        None => None,
+    };
+
+    let id = quotation.id.clone();
+    let mut active = quotation.into_active_model();
+    if let Some(valid_until) = data.valid_until {
+        active.valid_until = Set(valid_until);
+    }
+    if let Some(notes) = &data.notes {
+        active.notes = Set(Some(notes.clone()));
+    }
+    match priced {
+        Some(priced) => set_lines(db, tenant, active, &id, &priced).await,
+        None => {
+            active.updated_at = Set(Utc::now().naive_utc());
+            let quotation = active.update(db).await?;
+            let lines = lines(db, tenant, &id).await?;
+            Ok((quotation, lines))
+        }
+    }
+}
+
+/// Only quotes the customer never saw can be deleted; the rest are kept
+/// with their revisions.
+pub async fn delete_quotation<C: ConnectionTrait>(
+    db: &C,
+    quotation: Quotation,
+) -> Result<(), ApiError> {
+    if quotation.status != QuoteStatus::Draft || quotation.revision > 1 {
+        return Err(ApiError::Conflict(
+            "The quotation was sent and is kept; reject it instead".to_string(),
+        ));
+    }
+    quotation.delete(db).await?;
+    Ok(())
+}
+
+fn check_valid(quotation: &Quotation, today: NaiveDate) -> Result<(), ApiError> {
+    if quotation.valid_until < today {
+        return Err(ApiError::Conflict(format!(
+            "The quotation expired on {}",
+            quotation.valid_until
+        )));
+    }
+    Ok(())
+}
+
+/// Marks a draft as sent to the customer, after which it only changes
+/// through a revision.
+pub async fn send_quotation<C: ConnectionTrait>(
+    db: &C,
+    quotation: Quotation,
+) -> Result<Quotation, ApiError> {
+    check_draft(&quotation)?;
+    let now = Utc::now().naive_utc();
+    check_valid(&quotation, now.date())?;
+
+    let mut active = quotation.into_active_model();
+    active.status = Set(QuoteStatus::Sent);
+    active.sent_at = Set(Some(now));
+    active.updated_at = Set(now);
+    Ok(active.update(db).await?)
+}
+
+/// Records the customer's answer to a sent quote. Quotes past their
+/// validity can be rejected but not accepted.
+pub async fn decide_quotation<C: ConnectionTrait>(
+    db: &C,
+    quotation: Quotation,
+    user_id: &str,
+    accept: bool,
+    note: Option<String>,
+) -> Result<Quotation, ApiError> {
+    if quotation.status != QuoteStatus::Sent {
+        return Err(ApiError::Conflict(
+            "Only sent quotations can be accepted or rejected".to_string(),
+        ));
+    }
+    let now = Utc::now().naive_utc();
+    if accept {
+        check_valid(&quotation, now.date())?;
+    }
+
+    let mut active = quotation.into_active_model();
+    active.status = Set(if accept {
+        QuoteStatus::Accepted
+    } else {
+        QuoteStatus::Rejected
+    });
+    active.decided_by = Set(Some(user_id.to_string()));
+    active.decided_at = Set(Some(now));
+    active.decision_note = Set(note);
+    active.updated_at = Set(now);
+    Ok(active.update(db).await?)
+}
+
+/// Keeps the quote as it was sent and opens the next revision as a draft
+/// with the same lines.
+pub async fn revise_quotation<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    quotation: Quotation,
+    lines: &[QuotationLine],
+    user_id: &str,
+) -> Result<Quotation, ApiError> {
+    if !quotation.status.is_revisable() {
+        return Err(ApiError::Conflict(match quotation.status {
+            QuoteStatus::Draft => "The quotation is still a draft".to_string(),
+            _ => "Accepted quotations cannot be revised".to_string(),
+        }));
+    }
+
+    let now = Utc::now().naive_utc();
+    quotation_revision::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        quotation_id: Set(quotation.id.clone()),
+        revision: Set(quotation.revision),
+        snapshot: Set(json!({ "quotation": quotation, "lines": lines })),
+        created_by: Set(user_id.to_string()),
+        created_at: Set(now),
+    }
+    .insert(db)
+    .await?;
+
+    let revision = quotation.revision + 1;
+    let mut active = quotation.into_active_model();
+    active.revision = Set(revision);
+    active.status = Set(QuoteStatus::Draft);
+    active.sent_at = Set(None);
+    active.decided_by = Set(None);
+    active.decided_at = Set(None);
+    active.decision_note = Set(None);
+    active.updated_at = Set(now);
+    Ok(active.update(db).await?)
+}
+
+pub async fn revisions<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    quotation_id: &str,
+) -> Result<Vec<QuotationRevision>, ApiError> {
+    Ok(tenant
+        .find::<quotation_revision::Entity>()
+        .filter(quotation_revision::Column::QuotationId.eq(quotation_id))
+        .order_by_asc(quotation_revision::Column::Revision)
+        .all(db)
+        .await?)
+}
+
+/// Places an order for an accepted quote, at the quoted prices and
+/// discounts, and links the two. Discounts past the company's limits still
+/// need an administrator's approval unless `approver` is one.
+pub async fn convert_quotation<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    quotation: Quotation,
+    lines: &[QuotationLine],
+    approver: Option<&str>,
+) -> Result<(Quotation, Order, Vec<OrderLine>), ApiError> {
+    match quotation.status {
+        QuoteStatus::Accepted => {}
+        QuoteStatus::Converted => {
+            return Err(ApiError::Conflict(format!(
+                "The quotation was already converted into order {}",
+                quotation.order_id.as_deref().unwrap_or_default()
+            )))
+        }
+        _ => {
+            return Err(ApiError::Conflict(
+                "Only accepted quotations can be converted into orders".to_string(),
+            ))
+        }
+    }
+
+    let priced: Vec<PricedLine> = lines
+        .iter()
+        .map(|line| PricedLine {
+            inventory_id: line.inventory_id.clone(),
+            description: line.description.clone(),
+            quantity: line.quantity,
+            unit_price: line.unit_price,
+            discount_percent: line.discount_percent,
+            line_total: line.line_total,
+        })
+        .collect();
+    let policy = pricing_services::discount_policy(db, tenant).await?;
+    let order = NewOrder {
+        customer_id: quotation.customer_id.clone(),
+        currency: None,
+        quotation_id: Some(quotation.id.clone()),
+        requires_approval: pricing_services::exceeds_limits(
+            &policy,
+            &priced,
+            quotation.subtotal,
+            quotation.discount_total,
+        ),
+        lines: priced,
+        discount: None,
+        discount_total: quotation.discount_total,
+        total: quotation.total,
+    };
+    let (order, order_lines) = order_services::place_order(db, tenant, order, approver).await?;
+
+    let mut active = quotation.into_active_model();
+    active.status = Set(QuoteStatus::Converted);
+    active.order_id = Set(Some(order.id.clone()));
+    active.updated_at = Set(Utc::now().naive_utc());
+    Ok((active.update(db).await?, order, order_lines))
+}
+
+pub fn quotation_pdf(
+    company: &str,
+    customer: &Customer,
+    quotation: &Quotation,
+    lines: &[QuotationLine],
+) -> Vec<u8> {
+    let date = quotation.sent_at.unwrap_or(quotation.updated_at).date();
+    let mut document = TextDocument::new();
+    document
+        .bold(company)
+        .line(format!(
+            "Quotation {} revision {}",
+            number(quotation),
+            quotation.revision
+        ))
+        .blank()
+        .row("Customer", &customer.name)
+        .row("Email", &customer.email);
+    if let Some(address) = &customer.address {
+        document.row("Address", address);
+    }
+    document
+        .row("Date", &date.to_string())
+        .row("Valid until", &quotation.valid_until.to_string());
+
+    document.blank().bold("Items");
+    for line in lines {
+        document.line(&line.description);
+        let mut terms = format!("  {} x {}", line.quantity, pdf::amount(line.unit_price));
+        if line.discount_percent > 0.0 {
+            terms.push_str(&format!(" less {}%", line.discount_percent));
+        }
+        document.row(&terms, &pdf::amount(line.line_total));
+    }
+    document
+        .rule()
+        .row("Subtotal", &pdf::amount(quotation.subtotal));
+    if quotation.discount_total > 0.0 {
+        document.row("Discounts", &pdf::amount(-quotation.discount_total));
+    }
+    document
+        .blank()
+        .rule()
+        .row("Total", &pdf::amount(quotation.total))
+        .rule();
+
+    if let Some(notes) = &quotation.notes {
+        document.blank().bold("Notes").paragraph(notes);
+    }
+    document.render()
+}
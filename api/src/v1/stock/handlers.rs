@@ -0,0 +1,450 @@
+use std::collections::HashSet;
+
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+
+use super::models::{
+    CreateReceipt, CreateShipment, PickLine, PickList, PickQuery, StockLot, StockMovement,
+};
+use super::services::{
+    allocate, fefo_lots, post_movement, record_stock_change, reindex_item, MovementDraft,
+};
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::tenant::Tenant;
+use entity::inventory::{self, Tracking};
+use entity::stock_lot;
+use entity::stock_movement::MovementType;
+
+/// Receive stock of an inventory item
+#[utoipa::path(
+    post,
+    path = "/v1/stock/receipt",
+    tag = "stock",
+    request_body = CreateReceipt,
+    responses(
+        (status = 200, description = "Stock received", body = Vec<StockMovement>),
+        (status = 400, description = "Validation error"),
+        (status = 404, description = "Item not found"),
+        (status = 409, description = "Serial number already in stock"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn receive_stock(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    receipt: web::Json<CreateReceipt>,
+) -> Result<HttpResponse, ApiError> {
+    let receipt = receipt.into_inner();
+    if receipt.quantity <= 0 {
+        return Err(ApiError::ValidationError(
+            "Quantity must be positive".to_string(),
+        ));
+    }
+
+    let txn = data.db.begin().await?;
+
+    let item = tenant
+        .find_active_by_id::<inventory::Entity>(&receipt.inventory_id)
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Item not found".to_string()))?;
+
+    let mut movements = Vec::new();
+
+    if item.tracking == Tracking::None {
+        if !receipt.lots.is_empty() {
+            return Err(ApiError::ValidationError(
+                "Lots can only be recorded for lot or serial tracked items".to_string(),
+            ));
+        }
+        movements.push(
+            post_movement(
+                &txn,
+                &tenant,
+                MovementDraft {
+                    inventory_id: item.id.clone(),
+                    lot_id: None,
+                    movement_type: MovementType::Receipt,
+                    quantity: receipt.quantity,
+                    partner_id: receipt.supplier_id.clone(),
+                    reference: receipt.reference.clone(),
+                    reason: None,
+                },
+            )
+            .await?,
+        );
+    } else {
+        validate_lot_quantities(
+            &item.tracking,
+            receipt
+                .lots
+                .iter()
+                .map(|lot| (lot.lot_number.as_str(), lot.quantity)),
+            receipt.quantity,
+        )?;
+
+        for lot in &receipt.lots {
+            let existing = tenant
+                .find::<stock_lot::Entity>()
+                .filter(stock_lot::Column::InventoryId.eq(&item.id))
+                .filter(stock_lot::Column::LotNumber.eq(&lot.lot_number))
+                .one(&txn)
+                .await?;
+
+            let stock_lot = match existing {
+                Some(existing) => {
+                    if item.tracking == Tracking::Serial && existing.quantity > 0 {
+                        return Err(ApiError::Conflict(format!(
+                            "Serial number {} is already in stock",
+                            lot.lot_number
+                        )));
+                    }
+                    if lot.expiry_date.is_some() && lot.expiry_date != existing.expiry_date {
+                        return Err(ApiError::ValidationError(format!(
+                            "Expiry date does not match existing lot {}",
+                            lot.lot_number
+                        )));
+                    }
+                    existing
+                }
+                None => {
+                    stock_lot::ActiveModel {
+                        id: Set(uuid::Uuid::new_v4().to_string()),
+                        tenant_id: Set(tenant.id().to_string()),
+                        inventory_id: Set(item.id.clone()),
+                        lot_number: Set(lot.lot_number.clone()),
+                        expiry_date: Set(lot.expiry_date),
+                        quantity: Set(0),
+                        created_at: Set(Utc::now().naive_utc()),
+                    }
+                    .insert(&txn)
+                    .await?
+                }
+            };
+
+            movements.push(
+                post_movement(
+                    &txn,
+                    &tenant,
+                    MovementDraft {
+                        inventory_id: item.id.clone(),
+                        lot_id: Some(stock_lot.id),
+                        movement_type: MovementType::Receipt,
+                        quantity: lot.quantity,
+                        partner_id: receipt.supplier_id.clone(),
+                        reference: receipt.reference.clone(),
+                        reason: None,
+                    },
+                )
+                .await?,
+            );
+        }
+    }
+
+    record_stock_change(&txn, &tenant, &audit, &item).await?;
+    txn.commit().await?;
+    reindex_item(&data, &tenant, &item.id).await?;
+
+    Ok(HttpResponse::Ok().json(movements))
+}
+
+/// Ship stock of an inventory item to a customer
+#[utoipa::path(
+    post,
+    path = "/v1/stock/shipment",
+    tag = "stock",
+    request_body = CreateShipment,
+    responses(
+        (status = 200, description = "Stock shipped", body = Vec<StockMovement>),
+        (status = 400, description = "Validation error or insufficient stock"),
+        (status = 404, description = "Item or lot not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn ship_stock(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    shipment: web::Json<CreateShipment>,
+) -> Result<HttpResponse, ApiError> {
+    let shipment = shipment.into_inner();
+    if shipment.quantity <= 0 {
+        return Err(ApiError::ValidationError(
+            "Quantity must be positive".to_string(),
+        ));
+    }
+
+    let txn = data.db.begin().await?;
+
+    // Locked, so concurrent shipments cannot both take the last units
+    let item = tenant
+        .find_active_by_id::<inventory::Entity>(&shipment.inventory_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Item not found".to_string()))?;
+
+    let mut movements = Vec::new();
+
+    if item.tracking == Tracking::None {
+        if !shipment.lots.is_empty() {
+            return Err(ApiError::ValidationError(
+                "Lots can only be recorded for lot or serial tracked items".to_string(),
+            ));
+        }
+        if item.quantity < shipment.quantity {
+            return Err(ApiError::ValidationError("Insufficient stock".to_string()));
+        }
+        movements.push(
+            post_movement(
+                &txn,
+                &tenant,
+                MovementDraft {
+                    inventory_id: item.id.clone(),
+                    lot_id: None,
+                    movement_type: MovementType::Shipment,
+                    quantity: -shipment.quantity,
+                    partner_id: Some(shipment.customer_id.clone()),
+                    reference: shipment.reference.clone(),
+                    reason: None,
+                },
+            )
+            .await?,
+        );
+    } else {
+        let today = Utc::now().date_naive();
+
+        let allocation = if shipment.lots.is_empty() {
+            let (allocation, allocated) = allocate(
+                fefo_lots(&txn, &tenant, &item.id, today, true).await?,
+                shipment.quantity,
+            );
+            if allocated < shipment.quantity {
+                return Err(ApiError::ValidationError(
+                    "Insufficient stock in non-expired lots".to_string(),
+                ));
+            }
+            allocation
+        } else {
+            validate_lot_quantities(
+                &item.tracking,
+                shipment
+                    .lots
+                    .iter()
+                    .map(|lot| (lot.lot_number.as_str(), lot.quantity)),
+                shipment.quantity,
+            )?;
+
+            let mut allocation = Vec::new();
+            for lot in &shipment.lots {
+                let stock_lot = tenant
+                    .find::<stock_lot::Entity>()
+                    .filter(stock_lot::Column::InventoryId.eq(&item.id))
+                    .filter(stock_lot::Column::LotNumber.eq(&lot.lot_number))
+                    .lock_exclusive()
+                    .one(&txn)
+                    .await?
+                    .ok_or_else(|| {
+                        ApiError::NotFound(format!("Lot {} not found", lot.lot_number))
+                    })?;
+
+                if stock_lot.expiry_date.is_some_and(|expiry| expiry < today) {
+                    return Err(ApiError::ValidationError(format!(
+                        "Lot {} has expired",
+                        lot.lot_number
+                    )));
+                }
+                if stock_lot.quantity < lot.quantity {
+                    return Err(ApiError::ValidationError(format!(
+                        "Insufficient stock in lot {}",
+                        lot.lot_number
+                    )));
+                }
+                allocation.push((stock_lot, lot.quantity));
+            }
+            allocation
+        };
+
+        for (stock_lot, quantity) in allocation {
+            movements.push(
+                post_movement(
+                    &txn,
+                    &tenant,
+                    MovementDraft {
+                        inventory_id: item.id.clone(),
+                        lot_id: Some(stock_lot.id),
+                        movement_type: MovementType::Shipment,
+                        quantity: -quantity,
+                        partner_id: Some(shipment.customer_id.clone()),
+                        reference: shipment.reference.clone(),
+                        reason: None,
+                    },
+                )
+                .await?,
+            );
+        }
+    }
+
+    record_stock_change(&txn, &tenant, &audit, &item).await?;
+    txn.commit().await?;
+    reindex_item(&data, &tenant, &item.id).await?;
+
+    Ok(HttpResponse::Ok().json(movements))
+}
+
+/// Suggest lots to pick for a quantity, first-expired-first-out
+#[utoipa::path(
+    get,
+    path = "/v1/stock/pick",
+    tag = "stock",
+    params(
+        ("inventory_id" = String, Query, description = "Item ID"),
+        ("quantity" = i32, Query, description = "Quantity to pick")
+    ),
+    responses(
+        (status = 200, description = "Pick suggestion", body = PickList),
+        (status = 400, description = "Validation error"),
+        (status = 404, description = "Item not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn suggest_pick(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    query: web::Query<PickQuery>,
+) -> Result<HttpResponse, ApiError> {
+    if query.quantity <= 0 {
+        return Err(ApiError::ValidationError(
+            "Quantity must be positive".to_string(),
+        ));
+    }
+
+    let item = tenant
+        .find_active_by_id::<inventory::Entity>(&query.inventory_id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Item not found".to_string()))?;
+
+    if item.tracking == Tracking::None {
+        return Err(ApiError::ValidationError(
+            "Item is not lot or serial tracked".to_string(),
+        ));
+    }
+
+    let today = Utc::now().date_naive();
+    let (allocation, allocated) = allocate(
+        fefo_lots(&data.db, &tenant, &item.id, today, false).await?,
+        query.quantity,
+    );
+
+    let lines = allocation
+        .into_iter()
+        .map(|(lot, quantity)| PickLine {
+            lot_id: lot.id,
+            lot_number: lot.lot_number,
+            expiry_date: lot.expiry_date,
+            available: lot.quantity,
+            quantity,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(PickList {
+        inventory_id: item.id,
+        requested: query.quantity,
+        allocated,
+        lines,
+    }))
+}
+
+/// Get the lots of an inventory item
+#[utoipa::path(
+    get,
+    path = "/v1/stock/{inventory_id}/lots",
+    tag = "stock",
+    params(
+        ("inventory_id" = String, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 200, description = "Lots of the item", body = Vec<StockLot>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_item_lots(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    inventory_id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let lots = tenant
+        .find::<stock_lot::Entity>()
+        .filter(stock_lot::Column::InventoryId.eq(inventory_id.into_inner()))
+        .order_by_asc(stock_lot::Column::ExpiryDate)
+        .order_by_asc(stock_lot::Column::LotNumber)
+        .all(&data.db)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(lots))
+}
+
+fn validate_lot_quantities<'a>(
+    tracking: &Tracking,
+    lots: impl Iterator<Item = (&'a str, i32)>,
+    quantity: i32,
+) -> Result<(), ApiError> {
+    let mut total = 0;
+    let mut seen = HashSet::new();
+
+    for (lot_number, lot_quantity) in lots {
+        if lot_number.trim().is_empty() {
+            return Err(ApiError::ValidationError(
+                "Lot number cannot be empty".to_string(),
+            ));
+        }
+        if !seen.insert(lot_number) {
+            return Err(ApiError::ValidationError(format!(
+                "Lot {} is listed more than once",
+                lot_number
+            )));
+        }
+        if lot_quantity <= 0 {
+            return Err(ApiError::ValidationError(
+                "Lot quantity must be positive".to_string(),
+            ));
+        }
+        if *tracking == Tracking::Serial && lot_quantity != 1 {
+            return Err(ApiError::ValidationError(
+                "Each serial number must have a quantity of 1".to_string(),
+            ));
+        }
+        total += lot_quantity;
+    }
+
+    if seen.is_empty() {
+        return Err(ApiError::ValidationError(
+            "Lot or serial numbers are required for tracked items".to_string(),
+        ));
+    }
+    if total != quantity {
+        return Err(ApiError::ValidationError(
+            "Lot quantities must add up to the total quantity".to_string(),
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use chrono::NaiveDate;
+use entity::{stock_lot, stock_movement};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+pub type StockLot = stock_lot::Model;
+pub type StockMovement = stock_movement::Model;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ReceiptLot {
+    pub lot_number: String,
+    pub expiry_date: Option<NaiveDate>,
+    pub quantity: i32,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateReceipt {
+    pub inventory_id: String,
+    pub quantity: i32,
+    pub supplier_id: Option<String>,
+    pub reference: Option<String>,
+    /// Required for lot and serial tracked items, must add up to `quantity`.
+    #[serde(default)]
+    pub lots: Vec<ReceiptLot>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ShipmentLot {
+    pub lot_number: String,
+    pub quantity: i32,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateShipment {
+    pub inventory_id: String,
+    pub quantity: i32,
+    pub customer_id: String,
+    pub reference: Option<String>,
+    /// Lots to ship from. When omitted for a tracked item the lots are
+    /// picked first-expired-first-out.
+    #[serde(default)]
+    pub lots: Vec<ShipmentLot>,
+}
+
+#[derive(Deserialize)]
+pub struct PickQuery {
+    pub inventory_id: String,
+    pub quantity: i32,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PickLine {
+    pub lot_id: String,
+    pub lot_number: String,
+    pub expiry_date: Option<NaiveDate>,
+    pub available: i32,
+    pub quantity: i32,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PickList {
+    pub inventory_id: String,
+    pub requested: i32,
+    pub allocated: i32,
+    pub lines: Vec<PickLine>,
+}
@@ -0,0 +1,20 @@
+use super::handlers;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/stock")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .route("/receipt", web::post().to(handlers::receive_stock))
+            .route("/shipment", web::post().to(handlers::ship_stock))
+            .route("/pick", web::get().to(handlers::suggest_pick))
+            .route(
+                "/{inventory_id}/lots",
+                web::get().to(handlers::get_item_lots),
+            ),
+    );
+}
@@ -0,0 +1,195 @@
+use chrono::{NaiveDate, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, Order, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::tenant::Tenant;
+use crate::webhooks;
+use entity::audit_log::AuditAction;
+use entity::{inventory, stock_lot, stock_movement};
+
+pub struct MovementDraft {
+    pub inventory_id: String,
+    pub lot_id: Option<String>,
+    pub movement_type: stock_movement::MovementType,
+    pub quantity: i32,
+    pub partner_id: Option<String>,
+    pub reference: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// Records a stock movement and applies its quantity to the item and lot.
+/// Callers are responsible for checking that stock does not go negative,
+/// against the item and lots they locked.
+pub async fn post_movement<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    draft: MovementDraft,
+) -> Result<stock_movement::Model, ApiError> {
+    tenant
+        .update_many::<inventory::Entity>()
+        .col_expr(
+            inventory::Column::Quantity,
+            Expr::col(inventory::Column::Quantity).add(draft.quantity),
+        )
+        .col_expr(
+            inventory::Column::Version,
+            Expr::col(inventory::Column::Version).add(1),
+        )
+        .col_expr(
+            inventory::Column::UpdatedAt,
+            Expr::value(Utc::now().naive_utc()),
+        )
+        .filter(inventory::Column::Id.eq(&draft.inventory_id))
+        .exec(db)
+        .await?;
+    if draft.quantity < 0 {
+        notify_low_stock(db, tenant, &draft.inventory_id, draft.quantity).await?;
+    }
+
+    if let Some(lot_id) = &draft.lot_id {
+        tenant
+            .update_many::<stock_lot::Entity>()
+            .col_expr(
+                stock_lot::Column::Quantity,
+                Expr::col(stock_lot::Column::Quantity).add(draft.quantity),
+            )
+            .filter(stock_lot::Column::Id.eq(lot_id))
+            .exec(db)
+            .await?;
+    }
+
+    let movement = stock_movement::ActiveModel {
+        id: Set(uuid::Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        inventory_id: Set(draft.inventory_id),
+        lot_id: Set(draft.lot_id),
+        movement_type: Set(draft.movement_type),
+        quantity: Set(draft.quantity),
+        partner_id: Set(draft.partner_id),
+        reference: Set(draft.reference),
+        reason: Set(draft.reason),
+        created_at: Set(Utc::now().naive_utc()),
+    };
+
+    Ok(movement.insert(db).await?)
+}
+
+/// Raises `inventory.low_stock` when a movement of `change` units took the
+/// item from above its reorder point to at or below it.
+async fn notify_low_stock<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    inventory_id: &str,
+    change: i32,
+) -> Result<(), ApiError> {
+    let Some(item) = tenant
+        .find_by_id::<inventory::Entity>(inventory_id)
+        .one(db)
+        .await?
+    else {
+        return Ok(());
+    };
+    let Some(reorder_point) = item.reorder_point else {
+        return Ok(());
+    };
+
+    if item.quantity <= reorder_point && item.quantity - change > reorder_point {
+        webhooks::enqueue(db, tenant, webhooks::LOW_STOCK, &item).await?;
+    }
+    Ok(())
+}
+
+/// Lots of an item that can still be picked, first-expired-first-out. Lots
+/// without an expiry date come last and expired lots are left out.
+pub async fn fefo_lots<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    inventory_id: &str,
+    today: NaiveDate,
+    lock: bool,
+) -> Result<Vec<stock_lot::Model>, DbErr> {
+    let mut query = tenant
+        .find::<stock_lot::Entity>()
+        .filter(stock_lot::Column::InventoryId.eq(inventory_id))
+        .filter(stock_lot::Column::Quantity.gt(0))
+        .filter(
+            stock_lot::Column::ExpiryDate
+                .is_null()
+                .or(stock_lot::Column::ExpiryDate.gte(today)),
+        )
+        .order_by(
+            Expr::col(stock_lot::Column::ExpiryDate).is_null(),
+            Order::Asc,
+        )
+        .order_by_asc(stock_lot::Column::ExpiryDate)
+        .order_by_asc(stock_lot::Column::CreatedAt);
+    if lock {
+        query = query.lock_exclusive();
+    }
+    query.all(db).await
+}
+
+/// Takes `quantity` units from `lots` in order. Returns the allocation and
+/// the number of units that could be allocated.
+pub fn allocate(lots: Vec<stock_lot::Model>, quantity: i32) -> (Vec<(stock_lot::Model, i32)>, i32) {
+    let mut remaining = quantity;
+    let mut allocation = Vec::new();
+
+    for lot in lots {
+        if remaining <= 0 {
+            break;
+        }
+        let take = lot.quantity.min(remaining);
+        remaining -= take;
+        allocation.push((lot, take));
+    }
+
+    (allocation, quantity - remaining)
+}
+
+/// Audits the movements posted for an item as one update of the item, from
+/// `before` as it was loaded ahead of them to how it is now.
+pub async fn record_stock_change<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    audit: &AuditContext,
+    before: &inventory::Model,
+) -> Result<(), ApiError> {
+    let after = tenant
+        .find_by_id::<inventory::Entity>(&before.id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Item not found".to_string()))?;
+    audit
+        .record(
+            db,
+            "inventory",
+            &before.id,
+            AuditAction::Update,
+            Some(before),
+            Some(&after),
+        )
+        .await
+}
+
+/// Pushes the current state of an item to the search index. Deleted items
+/// stay out of it.
+pub async fn reindex_item(
+    data: &config::app::AppState,
+    tenant: &Tenant,
+    inventory_id: &str,
+) -> Result<(), ApiError> {
+    if let Some(item) = tenant
+        .find_active_by_id::<inventory::Entity>(inventory_id)
+        .one(&data.db)
+        .await?
+    {
+        let index = data.meilisearch.index("inventory");
+        index.add_documents(&[&item], Some("id")).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,460 @@
+use std::collections::HashMap;
+
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
+};
+
+use super::models::{
+    CreateStockCount, StockCount, StockCountDetail, StockCountLineView, SubmitCounts,
+};
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::tenant::Tenant;
+use crate::v1::stock::services::{post_movement, record_stock_change, reindex_item, MovementDraft};
+use entity::inventory::{self, Tracking};
+use entity::stock_count::{self, CountStatus};
+use entity::stock_movement::MovementType;
+use entity::{stock_count_line, stock_lot};
+
+/// Open a stock count and freeze the expected quantities
+#[utoipa::path(
+    post,
+    path = "/v1/stock-count",
+    tag = "stock",
+    request_body = CreateStockCount,
+    responses(
+        (status = 200, description = "Stock count opened", body = StockCountDetail),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_count(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    count: web::Json<CreateStockCount>,
+) -> Result<HttpResponse, ApiError> {
+    let count = count.into_inner();
+    let txn = data.db.begin().await?;
+
+    let mut items = tenant.find_active::<inventory::Entity>();
+    if let Some(location) = &count.location {
+        items = items.filter(inventory::Column::Location.eq(location));
+    }
+    if let Some(category) = &count.category {
+        items = items.filter(inventory::Column::Category.eq(category));
+    }
+    let items = items
+        .order_by_asc(inventory::Column::Name)
+        .all(&txn)
+        .await?;
+
+    let session = stock_count::ActiveModel {
+        id: Set(uuid::Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        location: Set(count.location),
+        category: Set(count.category),
+        note: Set(count.note),
+        status: Set(CountStatus::Open),
+        created_at: Set(Utc::now().naive_utc()),
+        closed_at: Set(None),
+    }
+    .insert(&txn)
+    .await?;
+
+    let mut lines = Vec::new();
+    for item in items {
+        if item.tracking == Tracking::None {
+            lines.push(snapshot_line(
+                &tenant,
+                &session.id,
+                &item.id,
+                None,
+                item.quantity,
+            ));
+            continue;
+        }
+
+        let lots = tenant
+            .find::<stock_lot::Entity>()
+            .filter(stock_lot::Column::InventoryId.eq(&item.id))
+            .filter(stock_lot::Column::Quantity.gt(0))
+            .order_by_asc(stock_lot::Column::LotNumber)
+            .all(&txn)
+            .await?;
+        for lot in lots {
+            lines.push(snapshot_line(
+                &tenant,
+                &session.id,
+                &item.id,
+                Some(lot.id),
+                lot.quantity,
+            ));
+        }
+    }
+
+    if !lines.is_empty() {
+        // Bulk insert for large counts; snapshot_line sets the tenant
+        #[allow(clippy::disallowed_methods)]
+        stock_count_line::Entity::insert_many(lines)
+            .exec(&txn)
+            .await?;
+    }
+
+    let detail = load_detail(&txn, &tenant, session).await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(detail))
+}
+
+/// Get all stock counts
+#[utoipa::path(
+    get,
+    path = "/v1/stock-count",
+    tag = "stock",
+    responses(
+        (status = 200, description = "List of stock counts", body = Vec<StockCount>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_all_counts(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+) -> Result<HttpResponse, ApiError> {
+    let counts = tenant
+        .find::<stock_count::Entity>()
+        .order_by_desc(stock_count::Column::CreatedAt)
+        .all(&data.db)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(counts))
+}
+
+/// Get a stock count with its lines
+#[utoipa::path(
+    get,
+    path = "/v1/stock-count/{id}",
+    tag = "stock",
+    params(
+        ("id" = String, Path, description = "Stock count ID")
+    ),
+    responses(
+        (status = 200, description = "Stock count found", body = StockCountDetail),
+        (status = 404, description = "Stock count not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_count_by_id(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let session = find_count(&data.db, &tenant, &id.into_inner()).await?;
+    let detail = load_detail(&data.db, &tenant, session).await?;
+
+    Ok(HttpResponse::Ok().json(detail))
+}
+
+/// Submit counted quantities, possibly over several passes
+#[utoipa::path(
+    post,
+    path = "/v1/stock-count/{id}/counts",
+    tag = "stock",
+    params(
+        ("id" = String, Path, description = "Stock count ID")
+    ),
+    request_body = SubmitCounts,
+    responses(
+        (status = 200, description = "Counts recorded", body = StockCountDetail),
+        (status = 400, description = "Validation error"),
+        (status = 404, description = "Stock count or line not found"),
+        (status = 409, description = "Stock count is closed"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn submit_counts(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+    counts: web::Json<SubmitCounts>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let session = find_open_count(&txn, &tenant, &id.into_inner()).await?;
+    let now = Utc::now().naive_utc();
+
+    for counted in &counts.lines {
+        if counted.counted_quantity < 0 {
+            return Err(ApiError::ValidationError(
+                "Counted quantity cannot be negative".to_string(),
+            ));
+        }
+
+        let line = tenant
+            .find_by_id::<stock_count_line::Entity>(&counted.line_id)
+            .filter(stock_count_line::Column::CountId.eq(&session.id))
+            .one(&txn)
+            .await?
+            .ok_or_else(|| {
+                ApiError::NotFound(format!("Count line {} not found", counted.line_id))
+            })?;
+
+        let passes = line.passes;
+        let mut line = line.into_active_model();
+        line.counted_quantity = Set(Some(counted.counted_quantity));
+        line.passes = Set(passes + 1);
+        line.counted_at = Set(Some(now));
+        line.update(&txn).await?;
+    }
+
+    let detail = load_detail(&txn, &tenant, session).await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(detail))
+}
+
+/// Get the counted lines that differ from the frozen quantity
+#[utoipa::path(
+    get,
+    path = "/v1/stock-count/{id}/variances",
+    tag = "stock",
+    params(
+        ("id" = String, Path, description = "Stock count ID")
+    ),
+    responses(
+        (status = 200, description = "Lines with a variance", body = Vec<StockCountLineView>),
+        (status = 404, description = "Stock count not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_variances(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let session = find_count(&data.db, &tenant, &id.into_inner()).await?;
+    let variances: Vec<StockCountLineView> = load_detail(&data.db, &tenant, session)
+        .await?
+        .lines
+        .into_iter()
+        .filter(|line| line.variance.is_some_and(|variance| variance != 0))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(variances))
+}
+
+/// Approve a stock count and post its variances as adjustments.
+/// Lines that were never counted are left untouched.
+#[utoipa::path(
+    post,
+    path = "/v1/stock-count/{id}/approve",
+    tag = "stock",
+    params(
+        ("id" = String, Path, description = "Stock count ID")
+    ),
+    responses(
+        (status = 200, description = "Stock count approved", body = StockCountDetail),
+        (status = 400, description = "Adjustment would make stock negative"),
+        (status = 404, description = "Stock count not found"),
+        (status = 409, description = "Stock count is closed"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn approve_count(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let session = find_open_count(&txn, &tenant, &id.into_inner()).await?;
+
+    let lines = tenant
+        .find::<stock_count_line::Entity>()
+        .filter(stock_count_line::Column::CountId.eq(&session.id))
+        .all(&txn)
+        .await?;
+
+    // Items and lots may have moved since the snapshot, so only the
+    // difference between counted and frozen quantity is booked.
+    let mut adjusted: HashMap<String, inventory::Model> = HashMap::new();
+    for line in lines {
+        let Some(counted) = line.counted_quantity else {
+            continue;
+        };
+        let variance = counted - line.expected_quantity;
+        if variance == 0 {
+            continue;
+        }
+
+        let item = tenant
+            .find_by_id::<inventory::Entity>(&line.inventory_id)
+            .one(&txn)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Counted item no longer exists".to_string()))?;
+        let current = match &line.lot_id {
+            Some(lot_id) => tenant
+                .find_by_id::<stock_lot::Entity>(lot_id)
+                .one(&txn)
+                .await?
+                .map(|lot| lot.quantity)
+                .ok_or_else(|| ApiError::NotFound("Counted lot no longer exists".to_string()))?,
+            None => item.quantity,
+        };
+
+        if current + variance < 0 {
+            return Err(ApiError::ValidationError(format!(
+                "Adjusting line {} would make stock negative",
+                line.id
+            )));
+        }
+
+        post_movement(
+            &txn,
+            &tenant,
+            MovementDraft {
+                inventory_id: line.inventory_id.clone(),
+                lot_id: line.lot_id.clone(),
+                movement_type: MovementType::Adjustment,
+                quantity: variance,
+                partner_id: None,
+                reference: Some(session.id.clone()),
+                reason: Some("count".to_string()),
+            },
+        )
+        .await?;
+        // Audited as it was before the item's first adjustment
+        adjusted.entry(line.inventory_id).or_insert(item);
+    }
+    for before in adjusted.values() {
+        record_stock_change(&txn, &tenant, &audit, before).await?;
+    }
+
+    let mut session = session.into_active_model();
+    session.status = Set(CountStatus::Approved);
+    session.closed_at = Set(Some(Utc::now().naive_utc()));
+    let session = session.update(&txn).await?;
+
+    let detail = load_detail(&txn, &tenant, session).await?;
+    txn.commit().await?;
+
+    for inventory_id in adjusted.keys() {
+        reindex_item(&data, &tenant, inventory_id).await?;
+    }
+
+    Ok(HttpResponse::Ok().json(detail))
+}
+
+/// Cancel a stock count without posting adjustments
+#[utoipa::path(
+    post,
+    path = "/v1/stock-count/{id}/cancel",
+    tag = "stock",
+    params(
+        ("id" = String, Path, description = "Stock count ID")
+    ),
+    responses(
+        (status = 200, description = "Stock count cancelled", body = StockCount),
+        (status = 404, description = "Stock count not found"),
+        (status = 409, description = "Stock count is closed"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn cancel_count(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let session = find_open_count(&data.db, &tenant, &id.into_inner()).await?;
+
+    let mut session = session.into_active_model();
+    session.status = Set(CountStatus::Cancelled);
+    session.closed_at = Set(Some(Utc::now().naive_utc()));
+    let session = session.update(&data.db).await?;
+
+    Ok(HttpResponse::Ok().json(session))
+}
+
+fn snapshot_line(
+    tenant: &Tenant,
+    count_id: &str,
+    inventory_id: &str,
+    lot_id: Option<String>,
+    expected_quantity: i32,
+) -> stock_count_line::ActiveModel {
+    stock_count_line::ActiveModel {
+        id: Set(uuid::Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        count_id: Set(count_id.to_string()),
+        inventory_id: Set(inventory_id.to_string()),
+        lot_id: Set(lot_id),
+        expected_quantity: Set(expected_quantity),
+        counted_quantity: Set(None),
+        passes: Set(0),
+        counted_at: Set(None),
+    }
+}
+
+async fn find_count<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    id: &str,
+) -> Result<StockCount, ApiError> {
+    tenant
+        .find_by_id::<stock_count::Entity>(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Stock count not found".to_string()))
+}
+
+async fn find_open_count<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    id: &str,
+) -> Result<StockCount, ApiError> {
+    let session = find_count(db, tenant, id).await?;
+    if session.status != CountStatus::Open {
+        return Err(ApiError::Conflict("Stock count is closed".to_string()));
+    }
+    Ok(session)
+}
+
+async fn load_detail<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    count: StockCount,
+) -> Result<StockCountDetail, ApiError> {
+    let lines = tenant
+        .find::<stock_count_line::Entity>()
+        .filter(stock_count_line::Column::CountId.eq(&count.id))
+        .order_by_asc(stock_count_line::Column::InventoryId)
+        .order_by_asc(stock_count_line::Column::LotId)
+        .all(db)
+        .await?;
+
+    Ok(StockCountDetail {
+        count,
+        lines: lines.into_iter().map(Into::into).collect(),
+    })
+}
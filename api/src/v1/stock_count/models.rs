@@ -0,0 +1,50 @@
+use entity::{stock_count, stock_count_line};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+pub type StockCount = stock_count::Model;
+pub type StockCountLine = stock_count_line::Model;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateStockCount {
+    /// Only count items at this location.
+    pub location: Option<String>,
+    /// Only count items in this category.
+    pub category: Option<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CountedLine {
+    pub line_id: String,
+    pub counted_quantity: i32,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SubmitCounts {
+    pub lines: Vec<CountedLine>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct StockCountLineView {
+    #[serde(flatten)]
+    pub line: StockCountLine,
+    /// Counted minus expected quantity, absent until the line is counted.
+    pub variance: Option<i32>,
+}
+
+impl From<StockCountLine> for StockCountLineView {
+    fn from(line: StockCountLine) -> Self {
+        let variance = line
+            .counted_quantity
+            .map(|counted| counted - line.expected_quantity);
+        Self { line, variance }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct StockCountDetail {
+    #[serde(flatten)]
+    pub count: StockCount,
+    pub lines: Vec<StockCountLineView>,
+}
@@ -0,0 +1,20 @@
+use super::handlers;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/stock-count")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .route("", web::get().to(handlers::get_all_counts))
+            .route("", web::post().to(handlers::create_count))
+            .route("/{id}", web::get().to(handlers::get_count_by_id))
+            .route("/{id}/counts", web::post().to(handlers::submit_counts))
+            .route("/{id}/variances", web::get().to(handlers::get_variances))
+            .route("/{id}/approve", web::post().to(handlers::approve_count))
+            .route("/{id}/cancel", web::post().to(handlers::cancel_count)),
+    );
+}
@@ -0,0 +1,417 @@
+use actix_web::{web, HttpResponse};
+use sea_orm::{ConnectionTrait, QuerySelect, TransactionTrait};
+use serde_json::json;
+
+use super::models::{
+    CreateEntry, DecideTimesheet, Timesheet, TimesheetEntry, TimesheetFilter, TimesheetWithEntries,
+};
+use super::services;
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::extractors::ValidatedJson;
+use crate::middlewares::jwt::Claims;
+use crate::tenant::Tenant;
+use crate::v1::auth::models::Caller;
+use crate::v1::auth::services as auth_services;
+use crate::v1::employee::models::Employee;
+use crate::v1::employee::services as employee_services;
+use entity::audit_log::AuditAction;
+use entity::timesheet::{self, TimesheetStatus};
+use entity::{employee, timesheet_entry};
+
+/// List timesheets, latest week first
+#[utoipa::path(
+    get,
+    path = "/v1/timesheet",
+    tag = "timesheet",
+    params(
+        TimesheetFilter
+    ),
+    responses(
+        (status = 200, description = "List of timesheets", body = Vec<Timesheet>),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_timesheets(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    filter: web::Query<TimesheetFilter>,
+) -> Result<HttpResponse, ApiError> {
+    let timesheets = filter.select(&tenant).all(&data.db).await?;
+    Ok(HttpResponse::Ok().json(timesheets))
+}
+
+/// Get a timesheet with its entries
+#[utoipa::path(
+    get,
+    path = "/v1/timesheet/{id}",
+    tag = "timesheet",
+    params(
+        ("id" = String, Path, description = "Timesheet ID")
+    ),
+    responses(
+        (status = 200, description = "Timesheet found", body = TimesheetWithEntries),
+        (status = 404, description = "Timesheet not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_timesheet_by_id(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let timesheet = tenant
+        .find_by_id::<timesheet::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Timesheet not found".to_string()))?;
+    let entries = services::entries(&data.db, &tenant, &timesheet.id).await?;
+
+    Ok(HttpResponse::Ok().json(TimesheetWithEntries { timesheet, entries }))
+}
+
+/// Time is booked by the employee or their manager.
+fn check_booker(caller: &Caller, employee: &Employee) -> Result<(), ApiError> {
+    if caller.is(&employee.id) || caller.manages(employee) {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(
+            "Only the employee or their manager can change their timesheet".to_string(),
+        ))
+    }
+}
+
+/// Book time on a project for a day, on the timesheet of its week
+#[utoipa::path(
+    post,
+    path = "/v1/timesheet/entry",
+    tag = "timesheet",
+    request_body = CreateEntry,
+    responses(
+        (status = 201, description = "Time booked", body = TimesheetEntry),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not the employee or their manager"),
+        (status = 404, description = "Employee not found"),
+        (status = 409, description = "The timesheet of the week was already submitted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn add_entry(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    entry: ValidatedJson<CreateEntry>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    // Locking the employee keeps two first entries of a week on one timesheet
+    let employee =
+        employee_services::employee_for(&txn, &tenant, &caller, entry.employee_id.as_deref(), true)
+            .await?;
+    check_booker(&caller, &employee)?;
+
+    let (before, timesheet, entry) = services::add_entry(&txn, &tenant, &employee, &entry).await?;
+    match &before {
+        None => {
+            audit
+                .record(
+                    &txn,
+                    "timesheet",
+                    &timesheet.id,
+                    AuditAction::Create,
+                    None,
+                    Some(&timesheet),
+                )
+                .await?
+        }
+        Some(before) if before.status != timesheet.status => {
+            audit
+                .record(
+                    &txn,
+                    "timesheet",
+                    &timesheet.id,
+                    AuditAction::Update,
+                    Some(before),
+                    Some(&timesheet),
+                )
+                .await?
+        }
+        Some(_) => {}
+    }
+    audit
+        .record(
+            &txn,
+            "timesheet_entry",
+            &entry.id,
+            AuditAction::Create,
+            None,
+            Some(&entry),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Created().json(entry))
+}
+
+/// A locked timesheet with the employee it is for.
+async fn find_timesheet<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    id: &str,
+) -> Result<(Timesheet, Employee), ApiError> {
+    let timesheet = tenant
+        .find_by_id::<timesheet::Entity>(id)
+        .lock_exclusive()
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Timesheet not found".to_string()))?;
+    let employee = tenant
+        .find_by_id::<employee::Entity>(&timesheet.employee_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Employee not found".to_string()))?;
+    Ok((timesheet, employee))
+}
+
+/// Remove booked time
+#[utoipa::path(
+    delete,
+    path = "/v1/timesheet/entry/{id}",
+    tag = "timesheet",
+    params(
+        ("id" = String, Path, description = "Timesheet entry ID")
+    ),
+    responses(
+        (status = 200, description = "Entry deleted"),
+        (status = 403, description = "Not the employee or their manager"),
+        (status = 404, description = "Entry not found"),
+        (status = 409, description = "The timesheet was already submitted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_entry(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let entry = tenant
+        .find_by_id::<timesheet_entry::Entity>(&id)
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Entry not found".to_string()))?;
+    let (timesheet, employee) = find_timesheet(&txn, &tenant, &entry.timesheet_id).await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    check_booker(&caller, &employee)?;
+
+    let updated = services::delete_entry(&txn, timesheet.clone(), entry.clone()).await?;
+    if updated.status != timesheet.status {
+        audit
+            .record(
+                &txn,
+                "timesheet",
+                &updated.id,
+                AuditAction::Update,
+                Some(&timesheet),
+                Some(&updated),
+            )
+            .await?;
+    }
+    audit
+        .record(
+            &txn,
+            "timesheet_entry",
+            &entry.id,
+            AuditAction::Delete,
+            Some(&entry),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Entry deleted successfully"})))
+}
+
+/// Submit a week for approval, working out its overtime
+#[utoipa::path(
+    post,
+    path = "/v1/timesheet/{id}/submit",
+    tag = "timesheet",
+    params(
+        ("id" = String, Path, description = "Timesheet ID")
+    ),
+    responses(
+        (status = 200, description = "Timesheet submitted", body = Timesheet),
+        (status = 400, description = "No time booked"),
+        (status = 403, description = "Not the employee or their manager"),
+        (status = 404, description = "Timesheet not found"),
+        (status = 409, description = "Already submitted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn submit_timesheet(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let (timesheet, employee) = find_timesheet(&txn, &tenant, &id).await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    check_booker(&caller, &employee)?;
+
+    let updated = services::submit_timesheet(&txn, &tenant, timesheet.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "timesheet",
+            &updated.id,
+            AuditAction::Update,
+            Some(&timesheet),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+async fn decide(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+    decision: DecideTimesheet,
+    status: TimesheetStatus,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let (timesheet, employee) = find_timesheet(&txn, &tenant, &id).await?;
+    let caller = auth_services::caller(&txn, &tenant, &claims).await?;
+    if !caller.manages(&employee) || caller.is(&employee.id) {
+        return Err(ApiError::Forbidden(
+            "Only the employee's manager can decide on their timesheet".to_string(),
+        ));
+    }
+
+    let updated = services::decide_timesheet(
+        &txn,
+        timesheet.clone(),
+        status,
+        &caller.user_id,
+        decision.note,
+    )
+    .await?;
+    audit
+        .record(
+            &txn,
+            "timesheet",
+            &updated.id,
+            AuditAction::Update,
+            Some(&timesheet),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Approve the submitted week of one of your reports
+#[utoipa::path(
+    post,
+    path = "/v1/timesheet/{id}/approve",
+    tag = "timesheet",
+    params(
+        ("id" = String, Path, description = "Timesheet ID")
+    ),
+    request_body = DecideTimesheet,
+    responses(
+        (status = 200, description = "Timesheet approved", body = Timesheet),
+        (status = 403, description = "Not the manager of the employee"),
+        (status = 404, description = "Timesheet not found"),
+        (status = 409, description = "The timesheet is not submitted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn approve_timesheet(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+    decision: ValidatedJson<DecideTimesheet>,
+) -> Result<HttpResponse, ApiError> {
+    decide(
+        data,
+        tenant,
+        audit,
+        claims,
+        id,
+        decision.into_inner(),
+        TimesheetStatus::Approved,
+    )
+    .await
+}
+
+/// Send a submitted week back to be corrected
+#[utoipa::path(
+    post,
+    path = "/v1/timesheet/{id}/reject",
+    tag = "timesheet",
+    params(
+        ("id" = String, Path, description = "Timesheet ID")
+    ),
+    request_body = DecideTimesheet,
+    responses(
+        (status = 200, description = "Timesheet rejected", body = Timesheet),
+        (status = 403, description = "Not the manager of the employee"),
+        (status = 404, description = "Timesheet not found"),
+        (status = 409, description = "The timesheet is not submitted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn reject_timesheet(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    claims: web::ReqData<Claims>,
+    id: web::Path<String>,
+    decision: ValidatedJson<DecideTimesheet>,
+) -> Result<HttpResponse, ApiError> {
+    decide(
+        data,
+        tenant,
+        audit,
+        claims,
+        id,
+        decision.into_inner(),
+        TimesheetStatus::Rejected,
+    )
+    .await
+}
@@ -0,0 +1,76 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use entity::timesheet::{self, TimesheetStatus};
+use entity::timesheet_entry;
+use sea_orm::{ColumnTrait, QueryFilter, QueryOrder, Select};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::tenant::Tenant;
+
+pub type Timesheet = timesheet::Model;
+pub type TimesheetEntry = timesheet_entry::Model;
+
+/// Monday of the week `date` falls in.
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct TimesheetWithEntries {
+    #[serde(flatten)]
+    pub timesheet: Timesheet,
+    pub entries: Vec<TimesheetEntry>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateEntry {
+    /// Employee the time is for, the caller's own employee record by default.
+    pub employee_id: Option<String>,
+    pub project_id: String,
+    pub work_date: NaiveDate,
+    #[validate(range(
+        exclusive_min = 0.0,
+        max = 24.0,
+        message = "Hours must be more than 0 and at most 24"
+    ))]
+    pub hours: f64,
+    #[validate(length(max = 1000, message = "Note must be at most 1000 characters"))]
+    #[schema(max_length = 1000)]
+    pub note: Option<String>,
+}
+
+/// Sent to approve or reject, `{}` without a note.
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+pub struct DecideTimesheet {
+    #[validate(length(max = 1000, message = "Note must be at most 1000 characters"))]
+    #[schema(max_length = 1000)]
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct TimesheetFilter {
+    pub employee_id: Option<String>,
+    pub status: Option<TimesheetStatus>,
+    /// Only the timesheets of the week this date falls in
+    pub week: Option<NaiveDate>,
+}
+
+impl TimesheetFilter {
+    pub fn select(&self, tenant: &Tenant) -> Select<timesheet::Entity> {
+        let mut query = tenant
+            .find::<timesheet::Entity>()
+            .order_by_desc(timesheet::Column::WeekStart)
+            .order_by_asc(timesheet::Column::Id);
+        if let Some(employee_id) = &self.employee_id {
+            query = query.filter(timesheet::Column::EmployeeId.eq(employee_id));
+        }
+        if let Some(status) = self.status {
+            query = query.filter(timesheet::Column::Status.eq(status));
+        }
+        if let Some(week) = self.week {
+            query = query.filter(timesheet::Column::WeekStart.eq(week_start(week)));
+        }
+        query
+    }
+}
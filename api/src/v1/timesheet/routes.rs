@@ -0,0 +1,20 @@
+use super::handlers;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/timesheet")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .route("", web::get().to(handlers::get_timesheets))
+            .route("/entry", web::post().to(handlers::add_entry))
+            .route("/entry/{id}", web::delete().to(handlers::delete_entry))
+            .route("/{id}", web::get().to(handlers::get_timesheet_by_id))
+            .route("/{id}/submit", web::post().to(handlers::submit_timesheet))
+            .route("/{id}/approve", web::post().to(handlers::approve_timesheet))
+            .route("/{id}/reject", web::post().to(handlers::reject_timesheet)),
+    );
+}
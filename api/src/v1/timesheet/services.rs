@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+
+use chrono::{Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, IntoActiveModel, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use uuid::Uuid;
+
+use super::models::{week_start, CreateEntry, Timesheet, TimesheetEntry};
+use crate::calendar;
+use crate::error::{ApiError, FieldErrors};
+use crate::tenant::Tenant;
+use crate::v1::attendance::services::overtime_policy;
+use crate::v1::employee::models::Employee;
+use entity::timesheet::{self, TimesheetStatus};
+use entity::{project, timesheet_entry};
+
+pub async fn entries<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    timesheet_id: &str,
+) -> Result<Vec<TimesheetEntry>, ApiError> {
+    Ok(tenant
+        .find::<timesheet_entry::Entity>()
+        .filter(timesheet_entry::Column::TimesheetId.eq(timesheet_id))
+        .order_by_asc(timesheet_entry::Column::WorkDate)
+        .order_by_asc(timesheet_entry::Column::CreatedAt)
+        .all(db)
+        .await?)
+}
+
+/// Lets a locked timesheet be changed, putting a rejected one back into
+/// draft.
+async fn reopen<C: ConnectionTrait>(db: &C, timesheet: Timesheet) -> Result<Timesheet, ApiError> {
+    match timesheet.status {
+        TimesheetStatus::Draft => Ok(timesheet),
+        TimesheetStatus::Rejected => {
+            let mut active = timesheet.into_active_model();
+            active.status = Set(TimesheetStatus::Draft);
+            active.updated_at = Set(Utc::now().naive_utc());
+            Ok(active.update(db).await?)
+        }
+        TimesheetStatus::Submitted | TimesheetStatus::Approved => Err(ApiError::Conflict(
+            "The timesheet was already submitted".to_string(),
+        )),
+    }
+}
+
+/// Books time for a locked employee on the timesheet of the week, which is
+/// started on the first entry. Returns the timesheet as it was, if there
+/// was one, and as it is now, with the new entry.
+pub async fn add_entry<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    employee: &Employee,
+    data: &CreateEntry,
+) -> Result<(Option<Timesheet>, Timesheet, TimesheetEntry), ApiError> {
+    let mut errors = FieldErrors::new();
+    match tenant
+        .find_by_id::<project::Entity>(&data.project_id)
+        .one(db)
+        .await?
+    {
+        None => errors.add("project_id", "Project not found"),
+        Some(project) if !project.active => {
+            errors.add("project_id", "The project is closed to new time")
+        }
+        Some(_) => {}
+    }
+    errors.into_result()?;
+
+    let week = week_start(data.work_date);
+    let existing = tenant
+        .find::<timesheet::Entity>()
+        .filter(timesheet::Column::EmployeeId.eq(&employee.id))
+        .filter(timesheet::Column::WeekStart.eq(week))
+        .lock_exclusive()
+        .one(db)
+        .await?;
+    let now = Utc::now().naive_utc();
+    let timesheet = match existing.clone() {
+        Some(timesheet) => reopen(db, timesheet).await?,
+        None => {
+            timesheet::ActiveModel {
+                id: Set(Uuid::new_v4().to_string()),
+                tenant_id: Set(tenant.id().to_string()),
+                employee_id: Set(employee.id.clone()),
+                week_start: Set(week),
+                status: Set(TimesheetStatus::Draft),
+                regular_hours: Set(0.0),
+                overtime_hours: Set(0.0),
+                submitted_at: Set(None),
+                decided_by: Set(None),
+                decided_at: Set(None),
+                decision_note: Set(None),
+                created_at: Set(now),
+                updated_at: Set(now),
+            }
+            .insert(db)
+            .await?
+        }
+    };
+
+    let booked: f64 = entries(db, tenant, &timesheet.id)
+        .await?
+        .iter()
+        .filter(|entry| entry.work_date == data.work_date)
+        .map(|entry| entry.hours)
+        .sum();
+    if booked + data.hours > 24.0 {
+        let mut errors = FieldErrors::new();
+        errors.add(
+            "hours",
+            format!(
+                "Only {} more hours can be booked on {}",
+                24.0 - booked,
+                data.work_date
+            ),
+        );
+        return Err(ApiError::InvalidFields(errors));
+    }
+
+    let entry = timesheet_entry::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        timesheet_id: Set(timesheet.id.clone()),
+        project_id: Set(data.project_id.clone()),
+        work_date: Set(data.work_date),
+        hours: Set(data.hours),
+        note: Set(data.note.clone()),
+        created_at: Set(now),
+    }
+    .insert(db)
+    .await?;
+
+    Ok((existing, timesheet, entry))
+}
+
+/// Removes an entry from its locked timesheet, returning the timesheet.
+pub async fn delete_entry<C: ConnectionTrait>(
+    db: &C,
+    timesheet: Timesheet,
+    entry: TimesheetEntry,
+) -> Result<Timesheet, ApiError> {
+    let timesheet = reopen(db, timesheet).await?;
+    entry.into_active_model().delete(db).await?;
+    Ok(timesheet)
+}
+
+/// Hands a locked timesheet in for approval, splitting its hours into
+/// regular hours and overtime under the company's policy.
+pub async fn submit_timesheet<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    timesheet: Timesheet,
+) -> Result<Timesheet, ApiError> {
+    if !timesheet.status.is_editable() {
+        return Err(ApiError::Conflict(
+            "The timesheet was already submitted".to_string(),
+        ));
+    }
+    let mut hours_by_day = BTreeMap::new();
+    for entry in entries(db, tenant, &timesheet.id).await? {
+        *hours_by_day.entry(entry.work_date).or_insert(0.0) += entry.hours;
+    }
+    if hours_by_day.is_empty() {
+        return Err(ApiError::ValidationError(
+            "The timesheet has no time booked".to_string(),
+        ));
+    }
+
+    let week_end = timesheet.week_start + Duration::days(6);
+    let holidays = calendar::holidays(db, tenant, timesheet.week_start, week_end).await?;
+    let policy = overtime_policy(db, tenant).await?;
+    let (regular, overtime) = policy.split(
+        hours_by_day
+            .into_iter()
+            .map(|(date, hours)| (hours, calendar::is_working_day(date, &holidays))),
+    );
+
+    let now = Utc::now().naive_utc();
+    let mut active = timesheet.into_active_model();
+    active.status = Set(TimesheetStatus::Submitted);
+    active.regular_hours = Set(regular);
+    active.overtime_hours = Set(overtime);
+    active.submitted_at = Set(Some(now));
+    active.decided_by = Set(None);
+    active.decided_at = Set(None);
+    active.decision_note = Set(None);
+    active.updated_at = Set(now);
+    Ok(active.update(db).await?)
+}
+
+/// Approves or rejects a locked submitted timesheet. A rejected timesheet
+/// can be corrected and submitted again.
+pub async fn decide_timesheet<C: ConnectionTrait>(
+    db: &C,
+    timesheet: Timesheet,
+    status: TimesheetStatus,
+    decided_by: &str,
+    note: Option<String>,
+) -> Result<Timesheet, ApiError> {
+    if timesheet.status != TimesheetStatus::Submitted {
+        return Err(ApiError::Conflict(
+            "Only submitted timesheets can be approved or rejected".to_string(),
+        ));
+    }
+
+    let now = Utc::now().naive_utc();
+    let mut active = timesheet.into_active_model();
+    active.status = Set(status);
+    active.decided_by = Set(Some(decided_by.to_string()));
+    active.decided_at = Set(Some(now));
+    active.decision_note = Set(note);
+    active.updated_at = Set(now);
+    Ok(active.update(db).await?)
+}
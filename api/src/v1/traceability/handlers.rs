@@ -0,0 +1,71 @@
+use actix_web::{web, HttpResponse};
+use sea_orm::{ColumnTrait, QueryFilter, QueryOrder};
+
+use super::models::LotTrace;
+use crate::error::ApiError;
+use crate::tenant::Tenant;
+use entity::stock_movement::MovementType;
+use entity::{inventory, stock_lot, stock_movement};
+
+/// Trace a lot or serial number forward to customers and back to suppliers
+#[utoipa::path(
+    get,
+    path = "/v1/traceability/{lot}",
+    tag = "stock",
+    params(
+        ("lot" = String, Path, description = "Lot or serial number")
+    ),
+    responses(
+        (status = 200, description = "Trace of every lot with this number", body = Vec<LotTrace>),
+        (status = 404, description = "Lot not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn trace_lot(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    lot: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let lots = tenant
+        .find::<stock_lot::Entity>()
+        .filter(stock_lot::Column::LotNumber.eq(lot.into_inner()))
+        .find_also_related(inventory::Entity)
+        .all(&data.db)
+        .await?;
+
+    if lots.is_empty() {
+        return Err(ApiError::NotFound("Lot not found".to_string()));
+    }
+
+    let mut traces = Vec::with_capacity(lots.len());
+    for (lot, item) in lots {
+        let item = item.ok_or(ApiError::InternalServerError)?;
+        let movements = tenant
+            .find::<stock_movement::Entity>()
+            .filter(stock_movement::Column::LotId.eq(&lot.id))
+            .order_by_asc(stock_movement::Column::CreatedAt)
+            .all(&data.db)
+            .await?;
+
+        let mut trace = LotTrace {
+            lot,
+            item,
+            backward: Vec::new(),
+            forward: Vec::new(),
+            adjustments: Vec::new(),
+        };
+        for movement in movements {
+            match movement.movement_type {
+                MovementType::Receipt => trace.backward.push(movement),
+                MovementType::Shipment => trace.forward.push(movement),
+                MovementType::Adjustment => trace.adjustments.push(movement),
+            }
+        }
+        traces.push(trace);
+    }
+
+    Ok(HttpResponse::Ok().json(traces))
+}
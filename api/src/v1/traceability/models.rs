@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::v1::inventory::models::InventoryItem;
+use crate::v1::stock::models::{StockLot, StockMovement};
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct LotTrace {
+    pub lot: StockLot,
+    pub item: InventoryItem,
+    /// Receipts of the lot, pointing back to the suppliers it came from.
+    pub backward: Vec<StockMovement>,
+    /// Shipments of the lot, pointing forward to the customers it went to.
+    pub forward: Vec<StockMovement>,
+    pub adjustments: Vec<StockMovement>,
+}
@@ -0,0 +1,12 @@
+use super::handlers;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/traceability")
+            .wrap(jwt_middleware)
+            .route("/{lot}", web::get().to(handlers::trace_lot)),
+    );
+}
@@ -0,0 +1,313 @@
+use actix_web::{web, HttpResponse};
+use sea_orm::{ActiveModelTrait, IntoActiveModel, QueryOrder, QuerySelect, TransactionTrait};
+use serde_json::json;
+use validator::ValidateArgs;
+
+use super::models::{
+    CreateWebhook, DeliveryFilter, UpdateWebhook, UrlPolicy, Webhook, WebhookDelivery,
+};
+use super::services;
+use crate::audit::AuditContext;
+use crate::error::ApiError;
+use crate::extractors::Admin;
+use crate::pagination::{paginate, Page, PageQuery};
+use crate::tenant::Tenant;
+use entity::audit_log::AuditAction;
+use entity::{webhook, webhook_delivery};
+
+fn url_policy(data: &config::app::AppState) -> UrlPolicy {
+    UrlPolicy {
+        allow_private_hosts: data.webhook_allow_private_hosts,
+    }
+}
+
+/// List webhooks
+#[utoipa::path(
+    get,
+    path = "/v1/webhook",
+    tag = "webhook",
+    responses(
+        (status = 200, description = "List of webhooks", body = Vec<Webhook>),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_webhooks(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    _admin: Admin,
+) -> Result<HttpResponse, ApiError> {
+    let webhooks = tenant
+        .find::<webhook::Entity>()
+        .order_by_asc(webhook::Column::CreatedAt)
+        .all(&data.db)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(webhooks))
+}
+
+/// Subscribe an endpoint to events
+#[utoipa::path(
+    post,
+    path = "/v1/webhook",
+    tag = "webhook",
+    request_body = CreateWebhook,
+    responses(
+        (status = 200, description = "Webhook created", body = Webhook),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_webhook(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    webhook: web::Json<CreateWebhook>,
+) -> Result<HttpResponse, ApiError> {
+    webhook.validate_with_args(&url_policy(&data))?;
+    let txn = data.db.begin().await?;
+    let webhook = services::create_webhook(&txn, &tenant, &webhook).await?;
+    audit
+        .record(
+            &txn,
+            "webhook",
+            &webhook.id,
+            AuditAction::Create,
+            None,
+            Some(&webhook),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(webhook))
+}
+
+/// Get webhook by ID
+#[utoipa::path(
+    get,
+    path = "/v1/webhook/{id}",
+    tag = "webhook",
+    params(
+        ("id" = String, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 200, description = "Webhook found", body = Webhook),
+        (status = 404, description = "Webhook not found"),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_webhook_by_id(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    _admin: Admin,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let webhook = tenant
+        .find_by_id::<webhook::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Webhook not found".to_string()))?;
+
+    Ok(HttpResponse::Ok().json(webhook))
+}
+
+/// Update a webhook
+#[utoipa::path(
+    put,
+    path = "/v1/webhook/{id}",
+    tag = "webhook",
+    params(
+        ("id" = String, Path, description = "Webhook ID")
+    ),
+    request_body = UpdateWebhook,
+    responses(
+        (status = 200, description = "Webhook updated", body = Webhook),
+        (status = 400, description = "Validation error"),
+        (status = 404, description = "Webhook not found"),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_webhook(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    id: web::Path<String>,
+    update: web::Json<UpdateWebhook>,
+) -> Result<HttpResponse, ApiError> {
+    update.validate_with_args(&url_policy(&data))?;
+    let txn = data.db.begin().await?;
+    let webhook = tenant
+        .find_by_id::<webhook::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Webhook not found".to_string()))?;
+
+    let updated = services::update_webhook(&txn, webhook.clone(), &update).await?;
+    audit
+        .record(
+            &txn,
+            "webhook",
+            &updated.id,
+            AuditAction::Update,
+            Some(&webhook),
+            Some(&updated),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Delete a webhook along with its deliveries
+#[utoipa::path(
+    delete,
+    path = "/v1/webhook/{id}",
+    tag = "webhook",
+    params(
+        ("id" = String, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 200, description = "Webhook deleted"),
+        (status = 404, description = "Webhook not found"),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_webhook(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let webhook = tenant
+        .find_by_id::<webhook::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Webhook not found".to_string()))?;
+
+    webhook.clone().into_active_model().delete(&txn).await?;
+    audit
+        .record(
+            &txn,
+            "webhook",
+            &webhook.id,
+            AuditAction::Delete,
+            Some(&webhook),
+            None,
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Ok().json(json!({"message": "Webhook deleted successfully"})))
+}
+
+/// List the deliveries of a webhook, newest first
+#[utoipa::path(
+    get,
+    path = "/v1/webhook/{id}/deliveries",
+    tag = "webhook",
+    params(
+        ("id" = String, Path, description = "Webhook ID"),
+        DeliveryFilter,
+        PageQuery
+    ),
+    responses(
+        (status = 200, description = "One page of deliveries", body = Page<WebhookDelivery>),
+        (status = 404, description = "Webhook not found"),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_deliveries(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    _admin: Admin,
+    id: web::Path<String>,
+    filter: web::Query<DeliveryFilter>,
+    page: web::Query<PageQuery>,
+) -> Result<HttpResponse, ApiError> {
+    tenant
+        .find_by_id::<webhook::Entity>(&id)
+        .one(&data.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Webhook not found".to_string()))?;
+
+    let deliveries = paginate(&data.db, filter.select(&tenant, &id), &page).await?;
+    Ok(HttpResponse::Ok().json(deliveries))
+}
+
+/// Send a delivery again, e.g. after the receiver was fixed
+#[utoipa::path(
+    post,
+    path = "/v1/webhook/deliveries/{id}/redeliver",
+    tag = "webhook",
+    params(
+        ("id" = String, Path, description = "Delivery ID")
+    ),
+    responses(
+        (status = 202, description = "Delivery queued again", body = WebhookDelivery),
+        (status = 404, description = "Delivery not found"),
+        (status = 403, description = "Not an administrator"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn redeliver(
+    data: web::Data<config::app::AppState>,
+    tenant: Tenant,
+    audit: AuditContext,
+    _admin: Admin,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let txn = data.db.begin().await?;
+    let delivery = tenant
+        .find_by_id::<webhook_delivery::Entity>(&id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Delivery not found".to_string()))?;
+
+    let queued = services::redeliver(&txn, delivery.clone()).await?;
+    audit
+        .record(
+            &txn,
+            "webhook_delivery",
+            &queued.id,
+            AuditAction::Update,
+            Some(&delivery),
+            Some(&queued),
+        )
+        .await?;
+    txn.commit().await?;
+
+    Ok(HttpResponse::Accepted().json(queued))
+}
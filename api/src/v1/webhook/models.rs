@@ -0,0 +1,136 @@
+use std::net::Ipv4Addr;
+
+use entity::webhook;
+use entity::webhook_delivery::{self, DeliveryStatus};
+use sea_orm::{ColumnTrait, QueryFilter, QueryOrder, Select};
+use serde::{Deserialize, Serialize};
+use url::{Host, Url};
+use utoipa::{IntoParams, ToSchema};
+use validator::{Validate, ValidationError};
+
+use crate::tenant::Tenant;
+use crate::webhooks::is_known_event;
+
+pub type Webhook = webhook::Model;
+pub type WebhookDelivery = webhook_delivery::Model;
+
+/// Where webhooks may be sent. They are sent from inside the deployment, so
+/// private addresses are refused unless it allows them, e.g. for receivers
+/// on the same network.
+pub struct UrlPolicy {
+    pub allow_private_hosts: bool,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+#[validate(context = UrlPolicy)]
+pub struct CreateWebhook {
+    #[validate(custom(function = "webhook_url", use_context))]
+    #[validate(length(max = 2048, message = "URL must be at most 2048 characters"))]
+    #[schema(max_length = 2048)]
+    pub url: String,
+    /// Key the payloads are signed with, shared with the receiver.
+    #[validate(length(min = 16, max = 255, message = "Secret must be 16 to 255 characters"))]
+    #[schema(min_length = 16, max_length = 255)]
+    pub secret: String,
+    /// Events to send, e.g. `order.created` or `inventory.low_stock`.
+    #[validate(length(min = 1, message = "Subscribe to at least one event"))]
+    #[validate(custom(function = "known_events"))]
+    pub events: Vec<String>,
+    #[serde(default = "enabled")]
+    pub active: bool,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+#[validate(context = UrlPolicy)]
+pub struct UpdateWebhook {
+    #[validate(custom(function = "webhook_url", use_context))]
+    #[validate(length(max = 2048, message = "URL must be at most 2048 characters"))]
+    #[schema(max_length = 2048)]
+    pub url: Option<String>,
+    #[validate(length(min = 16, max = 255, message = "Secret must be 16 to 255 characters"))]
+    #[schema(min_length = 16, max_length = 255)]
+    pub secret: Option<String>,
+    #[validate(length(min = 1, message = "Subscribe to at least one event"))]
+    #[validate(custom(function = "known_events"))]
+    pub events: Option<Vec<String>>,
+    pub active: Option<bool>,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn webhook_url(url: &str, policy: &UrlPolicy) -> Result<(), ValidationError> {
+    let invalid =
+        |message: &'static str| Err(ValidationError::new("url").with_message(message.into()));
+    let Ok(url) = Url::parse(url) else {
+        return invalid("URL must be an absolute http(s) URL");
+    };
+    if !matches!(url.scheme(), "http" | "https") {
+        return invalid("URL must be an absolute http(s) URL");
+    }
+    let private = match url.host() {
+        None => return invalid("URL must be an absolute http(s) URL"),
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain == "localhost" || domain.ends_with(".localhost")
+        }
+        Some(Host::Ipv4(ip)) => is_private_v4(ip),
+        Some(Host::Ipv6(ip)) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_private_v4(ip),
+            None => {
+                let first = ip.segments()[0];
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    // Unique local fc00::/7 and link-local fe80::/10
+                    || first & 0xfe00 == 0xfc00
+                    || first & 0xffc0 == 0xfe80
+            }
+        },
+    };
+    if private && !policy.allow_private_hosts {
+        return invalid("URL must not point to a loopback, link-local or private address");
+    }
+    Ok(())
+}
+
+fn is_private_v4(ip: Ipv4Addr) -> bool {
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+}
+
+fn known_events(events: &[String]) -> Result<(), ValidationError> {
+    match events.iter().find(|event| !is_known_event(event)) {
+        None => Ok(()),
+        Some(event) => Err(ValidationError::new("unknown_event")
+            .with_message(format!("Unknown event '{event}'").into())),
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct DeliveryFilter {
+    pub status: Option<DeliveryStatus>,
+    /// Event name, e.g. `order.created`.
+    pub event: Option<String>,
+}
+
+impl DeliveryFilter {
+    /// Deliveries of one webhook, newest first.
+    pub fn select(&self, tenant: &Tenant, webhook_id: &str) -> Select<webhook_delivery::Entity> {
+        let mut query = tenant
+            .find::<webhook_delivery::Entity>()
+            .filter(webhook_delivery::Column::WebhookId.eq(webhook_id))
+            .order_by_desc(webhook_delivery::Column::CreatedAt)
+            .order_by_desc(webhook_delivery::Column::Id);
+        if let Some(status) = &self.status {
+            query = query.filter(webhook_delivery::Column::Status.eq(status.clone()));
+        }
+        if let Some(event) = &self.event {
+            query = query.filter(webhook_delivery::Column::Event.eq(event));
+        }
+        query
+    }
+}
@@ -0,0 +1,23 @@
+use super::handlers;
+use crate::middlewares::idempotency::IdempotencyMiddleware;
+use crate::middlewares::jwt::JwtMiddleware;
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    let jwt_middleware = JwtMiddleware::new("Bearer ".to_string());
+    cfg.service(
+        web::scope("/v1/webhook")
+            .wrap(IdempotencyMiddleware::new())
+            .wrap(jwt_middleware)
+            .route("", web::get().to(handlers::get_webhooks))
+            .route("", web::post().to(handlers::create_webhook))
+            .route(
+                "/deliveries/{id}/redeliver",
+                web::post().to(handlers::redeliver),
+            )
+            .route("/{id}", web::get().to(handlers::get_webhook_by_id))
+            .route("/{id}", web::put().to(handlers::update_webhook))
+            .route("/{id}", web::delete().to(handlers::delete_webhook))
+            .route("/{id}/deliveries", web::get().to(handlers::get_deliveries)),
+    );
+}
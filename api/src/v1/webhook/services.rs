@@ -0,0 +1,63 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ConnectionTrait, IntoActiveModel, Set};
+
+use super::models::{CreateWebhook, UpdateWebhook, Webhook, WebhookDelivery};
+use crate::error::ApiError;
+use crate::tenant::Tenant;
+use entity::webhook;
+use entity::webhook_delivery::DeliveryStatus;
+
+pub async fn create_webhook<C: ConnectionTrait>(
+    db: &C,
+    tenant: &Tenant,
+    data: &CreateWebhook,
+) -> Result<Webhook, ApiError> {
+    let now = Utc::now().naive_utc();
+    Ok(webhook::ActiveModel {
+        id: Set(uuid::Uuid::new_v4().to_string()),
+        tenant_id: Set(tenant.id().to_string()),
+        url: Set(data.url.clone()),
+        secret: Set(data.secret.clone()),
+        events: Set(serde_json::json!(data.events)),
+        active: Set(data.active),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(db)
+    .await?)
+}
+
+pub async fn update_webhook<C: ConnectionTrait>(
+    db: &C,
+    webhook: Webhook,
+    data: &UpdateWebhook,
+) -> Result<Webhook, ApiError> {
+    let mut active = webhook.into_active_model();
+    if let Some(url) = &data.url {
+        active.url = Set(url.clone());
+    }
+    if let Some(secret) = &data.secret {
+        active.secret = Set(secret.clone());
+    }
+    if let Some(events) = &data.events {
+        active.events = Set(serde_json::json!(events));
+    }
+    if let Some(enabled) = data.active {
+        active.active = Set(enabled);
+    }
+    Ok(active.update(db).await?)
+}
+
+/// Queues a delivery to be sent again right away with a fresh set of
+/// attempts, whatever became of it before.
+pub async fn redeliver<C: ConnectionTrait>(
+    db: &C,
+    delivery: WebhookDelivery,
+) -> Result<WebhookDelivery, ApiError> {
+    let mut active = delivery.into_active_model();
+    active.status = Set(DeliveryStatus::Pending);
+    active.attempts = Set(0);
+    active.next_attempt_at = Set(Utc::now().naive_utc());
+    active.delivered_at = Set(None);
+    Ok(active.update(db).await?)
+}
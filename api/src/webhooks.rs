@@ -0,0 +1,356 @@
+//! Outgoing webhooks. Events are queued in the transaction of the change
+//! that raised them, so a rolled back change never notifies anyone, and a
+//! background dispatcher posts them with retries.
+
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, Utc};
+use entity::audit_log::AuditAction;
+use entity::webhook::{self, Model as Webhook};
+use entity::webhook_delivery::{self, DeliveryStatus, Model as Delivery};
+use futures_util::future::join_all;
+use hmac::{Hmac, Mac};
+use log::warn;
+use sea_orm::sea_query::{Expr, LockBehavior, LockType};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    IntoActiveModel, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use serde::Serialize;
+use serde_json::json;
+use sha2::Sha256;
+
+use crate::error::ApiError;
+use crate::tenant::Tenant;
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+
+/// Raised when a stock movement takes an item down to its reorder point.
+pub const LOW_STOCK: &str = "inventory.low_stock";
+
+/// Entities whose changes can be subscribed to, as named in the audit log.
+const ENTITIES: &[&str] = &["customer", "employee", "inventory", "order"];
+const ACTIONS: &[&str] = &["created", "updated", "deleted", "restored", "purged"];
+
+/// Attempts made before a delivery is given up on.
+pub const MAX_ATTEMPTS: i32 = 8;
+const FIRST_RETRY: Duration = Duration::from_secs(30);
+const LONGEST_RETRY: Duration = Duration::from_secs(6 * 60 * 60);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long a claimed delivery is hidden from other dispatchers. Longer
+/// than a request can take, so a delivery is not sent twice at once.
+const CLAIM_LEASE: Duration = Duration::from_secs(60);
+const BATCH_SIZE: u64 = 20;
+
+/// Event raised by a change recorded in the audit log, e.g. `order.created`.
+pub fn event_name(entity: &str, action: &AuditAction) -> String {
+    let action = match action {
+        AuditAction::Create => "created",
+        AuditAction::Update => "updated",
+        AuditAction::Delete => "deleted",
+        AuditAction::Restore => "restored",
+        AuditAction::Purge => "purged",
+    };
+    format!("{entity}.{action}")
+}
+
+/// Whether webhooks can subscribe to `event`.
+pub fn is_known_event(event: &str) -> bool {
+    if event == LOW_STOCK {
+        return true;
+    }
+    event
+        .split_once('.')
+        .is_some_and(|(entity, action)| ENTITIES.contains(&entity) && ACTIONS.contains(&action))
+}
+
+/// Queues `event` for every active webhook of the tenant subscribed to it.
+/// Pass the connection the change was made on so the deliveries are
+/// committed or rolled back with it.
+pub async fn enqueue<C, T>(db: &C, tenant: &Tenant, event: &str, data: &T) -> Result<(), ApiError>
+where
+    C: ConnectionTrait,
+    T: Serialize,
+{
+    if !is_known_event(event) {
+        return Ok(());
+    }
+
+    let webhooks: Vec<Webhook> = tenant
+        .find::<webhook::Entity>()
+        .filter(webhook::Column::Active.eq(true))
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|webhook| webhook.subscribes_to(event))
+        .collect();
+    if webhooks.is_empty() {
+        return Ok(());
+    }
+
+    let data = serde_json::to_value(data).map_err(|_| ApiError::InternalServerError)?;
+    let now = Utc::now().naive_utc();
+    for webhook in webhooks {
+        let id = uuid::Uuid::new_v4().to_string();
+        // The delivery id doubles as the event id receivers deduplicate on
+        let payload = json!({
+            "id": id,
+            "event": event,
+            "created_at": now,
+            "data": data,
+        });
+        webhook_delivery::ActiveModel {
+            id: Set(id),
+            tenant_id: Set(tenant.id().to_string()),
+            webhook_id: Set(webhook.id),
+            event: Set(event.to_string()),
+            payload: Set(payload),
+            status: Set(DeliveryStatus::Pending),
+            attempts: Set(0),
+            next_attempt_at: Set(now),
+            response_status: Set(None),
+            last_error: Set(None),
+            delivered_at: Set(None),
+            created_at: Set(now),
+        }
+        .insert(db)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Hex HMAC-SHA256 of `{timestamp}.{body}` keyed with the webhook secret,
+/// sent as `sha256=<hex>`. Covering the timestamp lets receivers reject
+/// replayed requests.
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Wait before the next attempt once `attempts` have failed: doubling from
+/// 30 seconds, capped at 6 hours.
+pub fn backoff(attempts: i32) -> Duration {
+    let doublings = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    FIRST_RETRY
+        .saturating_mul(2u32.saturating_pow(doublings))
+        .min(LONGEST_RETRY)
+}
+
+/// Starts the dispatcher that sends queued deliveries as they become due.
+/// Several instances may run against one database.
+pub fn spawn_dispatcher(db: DatabaseConnection) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("Failed to build the webhook HTTP client");
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = dispatch_due(&db, &client).await {
+                warn!("Webhook dispatch failed: {}", e);
+            }
+        }
+    })
+}
+
+// The dispatcher works through the queue of every company
+#[allow(clippy::disallowed_methods)]
+async fn dispatch_due(db: &DatabaseConnection, client: &reqwest::Client) -> Result<(), DbErr> {
+    let deliveries = claim_due(db).await?;
+    if deliveries.is_empty() {
+        return Ok(());
+    }
+
+    let webhook_ids: Vec<String> = deliveries.iter().map(|d| d.webhook_id.clone()).collect();
+    let webhooks = webhook::Entity::find()
+        .filter(webhook::Column::Id.is_in(webhook_ids))
+        .all(db)
+        .await?;
+
+    let attempts = deliveries.into_iter().map(|delivery| {
+        let webhook = webhooks.iter().find(|w| w.id == delivery.webhook_id);
+        async move {
+            let outcome = match webhook {
+                Some(webhook) if webhook.active => send(client, webhook, &delivery).await,
+                _ => Outcome::failed(None, "Webhook is disabled"),
+            };
+            record(db, delivery, outcome).await
+        }
+    });
+    for result in join_all(attempts).await {
+        result?;
+    }
+    Ok(())
+}
+
+/// Picks the deliveries that are due and pushes them back by the lease, so
+/// concurrent dispatchers skip them while they are being sent.
+#[allow(clippy::disallowed_methods)]
+async fn claim_due(db: &DatabaseConnection) -> Result<Vec<Delivery>, DbErr> {
+    let now = Utc::now().naive_utc();
+    let txn = db.begin().await?;
+    let deliveries = webhook_delivery::Entity::find()
+        .filter(webhook_delivery::Column::Status.eq(DeliveryStatus::Pending))
+        .filter(webhook_delivery::Column::NextAttemptAt.lte(now))
+        .order_by_asc(webhook_delivery::Column::NextAttemptAt)
+        .limit(BATCH_SIZE)
+        .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+        .all(&txn)
+        .await?;
+
+    if !deliveries.is_empty() {
+        let ids: Vec<String> = deliveries.iter().map(|d| d.id.clone()).collect();
+        webhook_delivery::Entity::update_many()
+            .col_expr(
+                webhook_delivery::Column::NextAttemptAt,
+                Expr::value(later(now, CLAIM_LEASE)),
+            )
+            .filter(webhook_delivery::Column::Id.is_in(ids))
+            .exec(&txn)
+            .await?;
+    }
+    txn.commit().await?;
+    Ok(deliveries)
+}
+
+struct Outcome {
+    delivered: bool,
+    response_status: Option<i16>,
+    error: Option<String>,
+}
+
+impl Outcome {
+    fn failed(response_status: Option<i16>, error: impl Into<String>) -> Self {
+        Outcome {
+            delivered: false,
+            response_status,
+            error: Some(error.into()),
+        }
+    }
+}
+
+async fn send(client: &reqwest::Client, webhook: &Webhook, delivery: &Delivery) -> Outcome {
+    let body = match serde_json::to_vec(&delivery.payload) {
+        Ok(body) => body,
+        Err(e) => return Outcome::failed(None, e.to_string()),
+    };
+    let timestamp = Utc::now().timestamp();
+
+    let response = client
+        .post(&webhook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, &delivery.event)
+        .header(DELIVERY_HEADER, &delivery.id)
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, sign(&webhook.secret, timestamp, &body))
+        .body(body)
+        .send()
+        .await;
+
+    match response {
+        Ok(response) if response.status().is_success() => Outcome {
+            delivered: true,
+            response_status: Some(response.status().as_u16() as i16),
+            error: None,
+        },
+        Ok(response) => Outcome::failed(
+            Some(response.status().as_u16() as i16),
+            format!("Endpoint answered {}", response.status()),
+        ),
+        Err(e) => Outcome::failed(None, e.to_string()),
+    }
+}
+
+async fn record(
+    db: &DatabaseConnection,
+    delivery: Delivery,
+    outcome: Outcome,
+) -> Result<(), DbErr> {
+    let now = Utc::now().naive_utc();
+    let attempts = delivery.attempts + 1;
+    let (id, event, webhook_id) = (
+        delivery.id.clone(),
+        delivery.event.clone(),
+        delivery.webhook_id.clone(),
+    );
+
+    let mut active = delivery.into_active_model();
+    active.attempts = Set(attempts);
+    active.response_status = Set(outcome.response_status);
+    active.last_error = Set(outcome.error.clone());
+
+    if outcome.delivered {
+        active.status = Set(DeliveryStatus::Delivered);
+        active.delivered_at = Set(Some(now));
+    } else if attempts >= MAX_ATTEMPTS {
+        warn!(
+            "Webhook delivery {} of {} to webhook {} failed for good after {} attempts: {}",
+            id,
+            event,
+            webhook_id,
+            attempts,
+            outcome.error.unwrap_or_default()
+        );
+        active.status = Set(DeliveryStatus::Failed);
+    } else {
+        let wait = backoff(attempts);
+        warn!(
+            "Webhook delivery {} of {} to webhook {} failed (attempt {}), retrying in {}s: {}",
+            id,
+            event,
+            webhook_id,
+            attempts,
+            wait.as_secs(),
+            outcome.error.unwrap_or_default()
+        );
+        active.next_attempt_at = Set(later(now, wait));
+    }
+
+    active.update(db).await?;
+    Ok(())
+}
+
+fn later(now: NaiveDateTime, wait: Duration) -> NaiveDateTime {
+    now + chrono::Duration::from_std(wait).unwrap_or(chrono::Duration::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_a_cap() {
+        assert_eq!(backoff(1), Duration::from_secs(30));
+        assert_eq!(backoff(2), Duration::from_secs(60));
+        assert_eq!(backoff(4), Duration::from_secs(240));
+        assert_eq!(backoff(30), LONGEST_RETRY);
+    }
+
+    #[test]
+    fn signature_covers_timestamp_and_body() {
+        let signature = sign("secret", 1700000000, b"{}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_eq!(signature, sign("secret", 1700000000, b"{}"));
+        assert_ne!(signature, sign("secret", 1700000001, b"{}"));
+        assert_ne!(signature, sign("other", 1700000000, b"{}"));
+    }
+
+    #[test]
+    fn events_follow_the_audit_log() {
+        assert_eq!(event_name("order", &AuditAction::Create), "order.created");
+        assert!(is_known_event("employee.updated"));
+        assert!(is_known_event(LOW_STOCK));
+        assert!(!is_known_event("company.created"));
+        assert!(!is_known_event("order"));
+    }
+}
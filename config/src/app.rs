@@ -6,6 +6,9 @@ use std::collections::HashMap;
 pub struct AppConfig {
     pub tables: Vec<String>,
     pub meilisearch_indexes: HashMap<String, Vec<String>>,
+    /// Extra attributes an index can be filtered on, besides the tenant.
+    #[serde(default)]
+    pub meilisearch_filters: HashMap<String, Vec<String>>,
 }
 
 #[derive(Debug, Clone)]
@@ -16,6 +19,10 @@ pub struct AppState {
     pub jwt_expires_in_seconds: u64,
     pub bcrypt_cost: u32,
     pub jwt_algorithm: Algorithm,
+    pub idempotency_window_seconds: u64,
+    /// Whether webhooks may be sent to loopback, link-local and private
+    /// addresses.
+    pub webhook_allow_private_hosts: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -32,4 +39,11 @@ impl AppConfig {
             toml::from_str(&config_str).expect("Failed to parse TOML config");
         toml_config.app
     }
+
+    pub fn filterable_attributes(&self, index_name: &str) -> Vec<&str> {
+        self.meilisearch_filters
+            .get(index_name)
+            .map(|attributes| attributes.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
 }
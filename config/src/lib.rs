@@ -1,6 +1,7 @@
 pub mod app;
 pub mod db;
 pub mod file_session;
+pub mod file_storage;
 pub mod inertia;
 pub mod meilisearch;
 pub mod vite;
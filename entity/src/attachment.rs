@@ -0,0 +1,63 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A file attached to an order, inventory item or employee. Files are kept
+/// once per company under their checksum, however many records share them.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "attachment")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub entity_type: AttachedTo,
+    pub entity_id: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i32,
+    /// Hex SHA-256 of the content.
+    pub checksum: String,
+    #[serde(default, skip_serializing)]
+    pub storage_key: String,
+    /// Where the thumbnail of an image is kept.
+    #[serde(default, skip_serializing)]
+    pub thumbnail_key: Option<String>,
+    pub uploaded_by: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// Kinds of record files can be attached to, named as in their routes.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum AttachedTo {
+    #[sea_orm(string_value = "order")]
+    Order,
+    #[sea_orm(string_value = "inventory")]
+    Inventory,
+    #[sea_orm(string_value = "employee")]
+    Employee,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
@@ -0,0 +1,60 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Time an employee was at work, from clocking in to clocking out.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "attendance")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub employee_id: String,
+    pub clock_in: NaiveDateTime,
+    /// `null` while the employee is still clocked in.
+    pub clock_out: Option<NaiveDateTime>,
+    pub note: Option<String>,
+}
+
+impl Model {
+    /// Hours between clocking in and out, none while still clocked in.
+    pub fn hours(&self) -> f64 {
+        self.clock_out
+            .map(|out| (out - self.clock_in).num_seconds() as f64 / 3600.0)
+            .unwrap_or(0.0)
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::employee::Entity",
+        from = "Column::EmployeeId",
+        to = "super::employee::Column::Id"
+    )]
+    Employee,
+}
+
+impl Related<super::employee::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Employee.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
@@ -0,0 +1,79 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// One change made through the API. `before` and `after` only hold the
+/// fields that changed; creates have no `before` and deletes no `after`.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "audit_log")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    /// User that made the change, from the token subject.
+    pub actor: Option<String>,
+    pub entity: String,
+    pub entity_id: String,
+    pub action: AuditAction,
+    #[schema(value_type = Option<Object>)]
+    pub before: Option<Json>,
+    #[schema(value_type = Option<Object>)]
+    pub after: Option<Json>,
+    pub request_id: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    #[sea_orm(string_value = "create")]
+    Create,
+    #[sea_orm(string_value = "update")]
+    Update,
+    #[sea_orm(string_value = "delete")]
+    Delete,
+    #[sea_orm(string_value = "restore")]
+    Restore,
+    /// A deleted record was removed for good.
+    #[sea_orm(string_value = "purge")]
+    Purge,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+/// The log is append-only: existing entries can be neither changed nor removed.
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert {
+            return Err(DbErr::Custom(
+                "Audit log entries cannot be changed".to_string(),
+            ));
+        }
+        Ok(self)
+    }
+
+    async fn before_delete<C>(self, _db: &C) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Err(DbErr::Custom(
+            "Audit log entries cannot be deleted".to_string(),
+        ))
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
@@ -0,0 +1,29 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A legal entity run on this deployment. Business data belongs to exactly
+/// one company, its tenant.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "company")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::company_user::Entity")]
+    CompanyUser,
+}
+
+impl Related<super::company_user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CompanyUser.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,49 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "customer")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub name: String,
+    pub email: String,
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    /// Group whose price lists the customer buys at.
+    pub customer_group: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub version: i32,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        crate::versioning::touch(&mut self.version, &mut self.updated_at, insert);
+        Ok(self)
+    }
+}
+
+impl crate::soft_delete::SoftDelete for Entity {
+    fn deleted_at() -> Column {
+        Column::DeletedAt
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::NaiveDateTime;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -7,12 +8,69 @@ use utoipa::ToSchema;
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: String,
+    pub tenant_id: String,
     pub name: String,
     pub role: String,
     pub email: String,
+    pub department_id: Option<String>,
+    pub position_id: Option<String>,
+    /// Employee this one reports to. The chain never loops back.
+    pub manager_id: Option<String>,
+    pub version: i32,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::department::Entity",
+        from = "Column::DepartmentId",
+        to = "super::department::Column::Id"
+    )]
+    Department,
+    #[sea_orm(
+        belongs_to = "super::position::Entity",
+        from = "Column::PositionId",
+        to = "super::position::Column::Id"
+    )]
+    Position,
+    #[sea_orm(belongs_to = "Entity", from = "Column::ManagerId", to = "Column::Id")]
+    Manager,
+}
+
+impl Related<super::department::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Department.def()
+    }
+}
+
+impl Related<super::position::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Position.def()
+    }
+}
 
-impl ActiveModelBehavior for ActiveModel {}
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        crate::versioning::touch(&mut self.version, &mut self.updated_at, insert);
+        Ok(self)
+    }
+}
+
+impl crate::soft_delete::SoftDelete for Entity {
+    fn deleted_at() -> Column {
+        Column::DeletedAt
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
@@ -0,0 +1,76 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// One change in an employee's employment, effective from a date. Only the
+/// fields the change sets are filled in; the state on a date follows from
+/// applying the records up to it in order.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "employment_record")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub employee_id: String,
+    pub kind: EmploymentEvent,
+    pub effective_date: NaiveDate,
+    pub department_id: Option<String>,
+    pub position_id: Option<String>,
+    pub manager_id: Option<String>,
+    pub role: Option<String>,
+    pub salary: Option<f64>,
+    /// Why the employment ended, for terminations.
+    pub reason: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum EmploymentEvent {
+    #[sea_orm(string_value = "hire")]
+    Hire,
+    /// A new department, position, manager or role.
+    #[sea_orm(string_value = "transfer")]
+    Transfer,
+    #[sea_orm(string_value = "salary_change")]
+    SalaryChange,
+    #[sea_orm(string_value = "termination")]
+    Termination,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::employee::Entity",
+        from = "Column::EmployeeId",
+        to = "super::employee::Column::Id"
+    )]
+    Employee,
+}
+
+impl Related<super::employee::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Employee.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
@@ -0,0 +1,111 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Out-of-pocket spending an employee asks to be paid back, approved by
+/// their manager and then by finance.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "expense_claim")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub employee_id: String,
+    pub title: String,
+    /// ISO 4217 code the claim is paid back in.
+    pub currency: String,
+    /// Sum of the lines in the claim's currency.
+    pub total: f64,
+    pub status: ClaimStatus,
+    pub submitted_at: Option<NaiveDateTime>,
+    pub manager_approved_by: Option<String>,
+    pub manager_approved_at: Option<NaiveDateTime>,
+    pub finance_approved_by: Option<String>,
+    pub finance_approved_at: Option<NaiveDateTime>,
+    pub rejected_by: Option<String>,
+    pub rejected_at: Option<NaiveDateTime>,
+    pub decision_note: Option<String>,
+    pub reimbursement_method: Option<ReimbursementMethod>,
+    /// Payment reference, or the payroll run that paid the claim back.
+    pub reimbursement_reference: Option<String>,
+    /// Run the claim is paid back on, when reimbursed through payroll.
+    pub payroll_run_id: Option<String>,
+    pub reimbursed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimStatus {
+    #[sea_orm(string_value = "draft")]
+    Draft,
+    /// Waiting for the employee's manager.
+    #[sea_orm(string_value = "submitted")]
+    Submitted,
+    /// Waiting for finance.
+    #[sea_orm(string_value = "manager_approved")]
+    ManagerApproved,
+    /// Waiting to be paid back.
+    #[sea_orm(string_value = "approved")]
+    Approved,
+    #[sea_orm(string_value = "rejected")]
+    Rejected,
+    #[sea_orm(string_value = "reimbursed")]
+    Reimbursed,
+}
+
+impl ClaimStatus {
+    /// Lines can change until the claim is submitted, and again once it is
+    /// rejected.
+    pub fn is_editable(self) -> bool {
+        matches!(self, ClaimStatus::Draft | ClaimStatus::Rejected)
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum ReimbursementMethod {
+    /// Paid out separately, e.g. by bank transfer.
+    #[sea_orm(string_value = "payment")]
+    Payment,
+    /// Added to the employee's next payslip.
+    #[sea_orm(string_value = "payroll")]
+    Payroll,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::expense_line::Entity")]
+    ExpenseLine,
+}
+
+impl Related<super::expense_line::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExpenseLine.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
@@ -0,0 +1,62 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "expense_line")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub claim_id: String,
+    pub category_id: String,
+    pub expense_date: NaiveDate,
+    pub description: String,
+    /// Amount as spent, in `currency`.
+    pub amount: f64,
+    pub currency: String,
+    /// Claim currency per unit of `currency`.
+    pub exchange_rate: f64,
+    /// Amount in the claim's currency.
+    pub claim_amount: f64,
+    /// Where the receipt is kept in file storage.
+    #[serde(default, skip_serializing)]
+    pub receipt_key: Option<String>,
+    pub receipt_type: Option<String>,
+    pub receipt_size: Option<i32>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::expense_claim::Entity",
+        from = "Column::ClaimId",
+        to = "super::expense_claim::Column::Id"
+    )]
+    ExpenseClaim,
+}
+
+impl Related<super::expense_claim::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExpenseClaim.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
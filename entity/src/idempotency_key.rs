@@ -0,0 +1,27 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+
+/// A POST request made with an `Idempotency-Key`. The response columns stay
+/// empty while the first attempt is still running, and `expires_at` is then
+/// the end of its short lease rather than of the replay window.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "idempotency_key")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub user_id: String,
+    pub key: String,
+    pub fingerprint: String,
+    pub response_status: Option<i16>,
+    pub response_content_type: Option<String>,
+    /// `ETag` and `Location` of the response, by name.
+    pub response_headers: Option<Json>,
+    pub response_body: Option<Vec<u8>>,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
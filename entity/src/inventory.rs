@@ -1,3 +1,4 @@
+use chrono::NaiveDateTime;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -7,12 +8,94 @@ use utoipa::ToSchema;
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: String,
+    pub tenant_id: String,
+    pub sku: Option<String>,
     pub name: String,
     pub quantity: i32,
     pub price: f64,
+    pub tracking: Tracking,
+    pub location: Option<String>,
+    pub category: Option<String>,
+    /// Stock level at or below which the item needs reordering.
+    pub reorder_point: Option<i32>,
+    pub version: i32,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+/// How individual units of an inventory item are identified in stock.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(10))")]
+#[serde(rename_all = "snake_case")]
+pub enum Tracking {
+    #[default]
+    #[sea_orm(string_value = "none")]
+    None,
+    #[sea_orm(string_value = "lot")]
+    Lot,
+    #[sea_orm(string_value = "serial")]
+    Serial,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_many = "super::stock_lot::Entity")]
+    StockLot,
+    #[sea_orm(has_many = "super::stock_movement::Entity")]
+    StockMovement,
+    #[sea_orm(has_many = "super::stock_count_line::Entity")]
+    StockCountLine,
+}
+
+impl Related<super::stock_lot::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StockLot.def()
+    }
+}
+
+impl Related<super::stock_movement::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StockMovement.def()
+    }
+}
 
-impl ActiveModelBehavior for ActiveModel {}
+impl Related<super::stock_count_line::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StockCountLine.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        crate::versioning::touch(&mut self.version, &mut self.updated_at, insert);
+        Ok(self)
+    }
+}
+
+impl crate::soft_delete::SoftDelete for Entity {
+    fn deleted_at() -> Column {
+        Column::DeletedAt
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
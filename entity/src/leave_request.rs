@@ -0,0 +1,95 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "leave_request")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub employee_id: String,
+    pub leave_type_id: String,
+    pub start_date: NaiveDate,
+    /// Last day of the leave, inclusive.
+    pub end_date: NaiveDate,
+    /// Working days taken from the balance.
+    pub days: f64,
+    pub reason: Option<String>,
+    pub status: LeaveStatus,
+    /// User who approved or rejected the request.
+    pub decided_by: Option<String>,
+    pub decided_at: Option<NaiveDateTime>,
+    pub decision_note: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum LeaveStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "approved")]
+    Approved,
+    #[sea_orm(string_value = "rejected")]
+    Rejected,
+    #[sea_orm(string_value = "cancelled")]
+    Cancelled,
+}
+
+impl LeaveStatus {
+    /// Pending and approved requests hold their days.
+    pub fn is_open(self) -> bool {
+        matches!(self, LeaveStatus::Pending | LeaveStatus::Approved)
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::employee::Entity",
+        from = "Column::EmployeeId",
+        to = "super::employee::Column::Id"
+    )]
+    Employee,
+    #[sea_orm(
+        belongs_to = "super::leave_type::Entity",
+        from = "Column::LeaveTypeId",
+        to = "super::leave_type::Column::Id"
+    )]
+    LeaveType,
+}
+
+impl Related<super::employee::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Employee.def()
+    }
+}
+
+impl Related<super::leave_type::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LeaveType.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
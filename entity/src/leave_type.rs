@@ -0,0 +1,95 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A kind of leave, such as annual or sick leave, with how days are earned.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "leave_type")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub name: String,
+    pub accrual: Accrual,
+    /// Days earned over a full calendar year.
+    pub days_per_year: f64,
+    pub created_at: NaiveDateTime,
+}
+
+impl Model {
+    /// Days of this leave an employee has earned in `year` by `as_of`.
+    /// Someone hired during the year earns from the month they joined.
+    /// `None` when the leave is not limited.
+    pub fn entitlement(
+        &self,
+        year: i32,
+        as_of: NaiveDate,
+        hire_date: Option<NaiveDate>,
+    ) -> Option<f64> {
+        let first_month = match hire_date {
+            Some(hired) if hired.year() > year => return Some(0.0),
+            Some(hired) if hired.year() == year => hired.month0(),
+            _ => 0,
+        };
+        let months = match self.accrual {
+            Accrual::None => return None,
+            Accrual::Upfront => 12 - first_month,
+            Accrual::Monthly => {
+                let earned_to = match as_of.year() {
+                    y if y > year => 12,
+                    y if y < year => 0,
+                    _ => as_of.month0() + 1,
+                };
+                earned_to.saturating_sub(first_month)
+            }
+        };
+        Some((self.days_per_year * months as f64 / 12.0 * 100.0).round() / 100.0)
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum Accrual {
+    /// No balance is kept, e.g. for unpaid leave.
+    #[sea_orm(string_value = "none")]
+    None,
+    /// The days of the year are available from its start.
+    #[sea_orm(string_value = "upfront")]
+    Upfront,
+    /// A twelfth of the days is earned at the start of every month.
+    #[sea_orm(string_value = "monthly")]
+    Monthly,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::leave_request::Entity")]
+    LeaveRequest,
+}
+
+impl Related<super::leave_request::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LeaveRequest.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
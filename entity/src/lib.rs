@@ -1,5 +1,47 @@
+pub mod attachment;
+pub mod attendance;
+pub mod audit_log;
+pub mod company;
+pub mod company_user;
+pub mod customer;
+pub mod department;
+pub mod discount_policy;
 pub mod employee;
+pub mod employment_record;
+pub mod expense_category;
+pub mod expense_claim;
+pub mod expense_line;
+pub mod holiday;
+pub mod idempotency_key;
 pub mod inventory;
+pub mod journal_entry;
+pub mod leave_request;
+pub mod leave_type;
 pub mod order;
+pub mod order_line;
+pub mod overtime_policy;
+pub mod payroll_component;
+pub mod payroll_run;
+pub mod payslip;
+pub mod payslip_line;
+pub mod position;
 pub mod prelude;
+pub mod price_list;
+pub mod price_list_item;
+pub mod project;
+pub mod quotation;
+pub mod quotation_line;
+pub mod quotation_revision;
+pub mod soft_delete;
+pub mod stock_count;
+pub mod stock_count_line;
+pub mod stock_lot;
+pub mod stock_movement;
+pub mod tenant;
+pub mod timesheet;
+pub mod timesheet_entry;
 pub mod user;
+pub mod user_invite;
+pub mod versioning;
+pub mod webhook;
+pub mod webhook_delivery;
@@ -8,12 +8,87 @@ use utoipa::ToSchema;
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: String,
+    pub tenant_id: String,
     pub customer_id: String,
     pub total_amount: f64,
+    /// ISO 4217 code of the amounts; empty for the company's own currency.
+    pub currency: Option<String>,
+    /// Money off the order's prices, on the lines and on the whole order.
+    pub discount_total: f64,
+    /// Discount on the whole order, on top of the lines' own.
+    pub discount_kind: Option<DiscountKind>,
+    pub discount_value: Option<f64>,
+    /// Set when the discounts go past the company's limits.
+    pub discount_status: Option<DiscountStatus>,
+    pub discount_approved_by: Option<String>,
+    pub discount_approved_at: Option<NaiveDateTime>,
+    /// Quote the order was converted from.
+    pub quotation_id: Option<String>,
     pub created_at: NaiveDateTime,
+    pub version: i32,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(10))")]
+#[serde(rename_all = "snake_case")]
+pub enum DiscountKind {
+    /// A percentage of the price.
+    #[sea_orm(string_value = "percent")]
+    Percent,
+    /// An amount off the price.
+    #[sea_orm(string_value = "fixed")]
+    Fixed,
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum DiscountStatus {
+    /// Waiting for an administrator.
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "approved")]
+    Approved,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_many = "super::order_line::Entity")]
+    OrderLine,
+}
+
+impl Related<super::order_line::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OrderLine.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        crate::versioning::touch(&mut self.version, &mut self.updated_at, insert);
+        Ok(self)
+    }
+}
 
-impl ActiveModelBehavior for ActiveModel {}
+impl crate::soft_delete::SoftDelete for Entity {
+    fn deleted_at() -> Column {
+        Column::DeletedAt
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
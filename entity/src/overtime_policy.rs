@@ -0,0 +1,75 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// How a company counts overtime on timesheets. Companies without one use
+/// the default.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "overtime_policy")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub tenant_id: String,
+    /// Hours in a working day before the rest is overtime.
+    pub daily_hours: f64,
+    /// Regular hours in a week before the rest is overtime.
+    pub weekly_hours: f64,
+    /// Whether every hour on weekends and holidays is overtime.
+    pub non_working_days: bool,
+    /// Pay rate of overtime relative to regular hours.
+    pub multiplier: f64,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Model {
+    /// Eight hours a day, forty a week, paid at time and a half.
+    pub fn default_for(tenant_id: &str) -> Self {
+        Model {
+            tenant_id: tenant_id.to_string(),
+            daily_hours: 8.0,
+            weekly_hours: 40.0,
+            non_working_days: true,
+            multiplier: 1.5,
+            updated_at: NaiveDateTime::default(),
+        }
+    }
+
+    /// Splits the hours of a week, given per day in order with whether the
+    /// day was a working day, into regular hours and overtime.
+    pub fn split(&self, days: impl IntoIterator<Item = (f64, bool)>) -> (f64, f64) {
+        let mut regular = 0.0;
+        let mut overtime = 0.0;
+        for (hours, working_day) in days {
+            if !working_day && self.non_working_days {
+                overtime += hours;
+                continue;
+            }
+            let within_day = hours.min(self.daily_hours);
+            let within_week = within_day.min((self.weekly_hours - regular).max(0.0));
+            regular += within_week;
+            overtime += hours - within_week;
+        }
+        let round = |hours: f64| (hours * 100.0).round() / 100.0;
+        (round(regular), round(overtime))
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
@@ -0,0 +1,125 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A line of every payslip, worked out as `(amount + rate% of base) x
+/// quantity`, or from a bracket table on the base. Components are applied
+/// in sequence, so a base of gross pay sees the earnings before it.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "payroll_component")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub code: String,
+    pub name: String,
+    pub kind: ComponentKind,
+    pub base: ComponentBase,
+    /// Percentage of the base.
+    pub rate: f64,
+    /// Fixed amount, added to the share of the base.
+    pub amount: f64,
+    pub quantity: ComponentQuantity,
+    /// Base above which nothing more is counted, like the BPJS wage ceilings.
+    pub base_cap: Option<f64>,
+    /// Rates by how high the base is, used instead of `rate`, such as the
+    /// PPh21 tariffs. Each bracket reads `{"up_to": 5400000, "rate": 0}`,
+    /// the last one without `up_to`.
+    #[schema(value_type = Option<Vec<Object>>)]
+    pub brackets: Option<Json>,
+    /// Whether each bracket's rate only applies to the part of the base in
+    /// it, rather than to the whole base.
+    pub progressive: bool,
+    /// Expense account of earnings and employer contributions, liability
+    /// account of deductions.
+    pub account: String,
+    /// Liability account employer contributions are owed on.
+    pub payable_account: Option<String>,
+    pub sequence: i32,
+    pub active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(30))")]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentKind {
+    /// Paid to the employee.
+    #[sea_orm(string_value = "earning")]
+    Earning,
+    /// Withheld from the employee's pay.
+    #[sea_orm(string_value = "deduction")]
+    Deduction,
+    /// Paid by the company on top of the employee's pay.
+    #[sea_orm(string_value = "employer_contribution")]
+    EmployerContribution,
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(30))")]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentBase {
+    /// Only the fixed amount counts.
+    #[sea_orm(string_value = "none")]
+    None,
+    /// Salary for the days employed in the period.
+    #[sea_orm(string_value = "basic")]
+    Basic,
+    /// Salary for one working day of the period.
+    #[sea_orm(string_value = "daily_basic")]
+    DailyBasic,
+    /// Earnings of the components before this one.
+    #[sea_orm(string_value = "gross")]
+    Gross,
+    /// Earnings and employer contributions before this one, as taxed.
+    #[sea_orm(string_value = "gross_with_contributions")]
+    GrossWithContributions,
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(30))")]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentQuantity {
+    #[sea_orm(string_value = "one")]
+    One,
+    /// Working days of the period the employee was employed.
+    #[sea_orm(string_value = "days_worked")]
+    DaysWorked,
+    /// Days the employee clocked in.
+    #[sea_orm(string_value = "days_present")]
+    DaysPresent,
+    /// Overtime on approved timesheets.
+    #[sea_orm(string_value = "overtime_hours")]
+    OvertimeHours,
+    /// Working days of approved leave that is not paid.
+    #[sea_orm(string_value = "unpaid_leave_days")]
+    UnpaidLeaveDays,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
@@ -0,0 +1,71 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// What an employee is paid for a payroll run, with the figures it was
+/// worked out from.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "payslip")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub payroll_run_id: String,
+    pub employee_id: String,
+    pub employee_name: String,
+    /// Monthly salary from the employment history.
+    pub salary: f64,
+    /// Salary for the days employed in the period.
+    pub basic_salary: f64,
+    pub days_worked: i32,
+    pub days_present: i32,
+    pub overtime_hours: f64,
+    pub unpaid_leave_days: f64,
+    pub gross: f64,
+    pub deductions: f64,
+    pub net: f64,
+    pub employer_contributions: f64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::payroll_run::Entity",
+        from = "Column::PayrollRunId",
+        to = "super::payroll_run::Column::Id"
+    )]
+    PayrollRun,
+    #[sea_orm(has_many = "super::payslip_line::Entity")]
+    PayslipLine,
+}
+
+impl Related<super::payroll_run::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PayrollRun.def()
+    }
+}
+
+impl Related<super::payslip_line::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PayslipLine.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
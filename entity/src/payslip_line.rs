@@ -0,0 +1,56 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::payroll_component::ComponentKind;
+
+/// A component as applied on a payslip, with its accounts at the time.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "payslip_line")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub payslip_id: String,
+    pub component_id: Option<String>,
+    pub code: String,
+    pub name: String,
+    pub kind: ComponentKind,
+    pub amount: f64,
+    pub account: String,
+    pub payable_account: Option<String>,
+    pub sequence: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::payslip::Entity",
+        from = "Column::PayslipId",
+        to = "super::payslip::Column::Id"
+    )]
+    Payslip,
+}
+
+impl Related<super::payslip::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Payslip.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
@@ -1,4 +1,43 @@
+pub use super::attachment::Entity as Attachment;
+pub use super::attendance::Entity as Attendance;
+pub use super::audit_log::Entity as AuditLog;
+pub use super::company::Entity as Company;
+pub use super::company_user::Entity as CompanyUser;
+pub use super::customer::Entity as Customer;
+pub use super::department::Entity as Department;
+pub use super::discount_policy::Entity as DiscountPolicy;
 pub use super::employee::Entity as Employee;
+pub use super::employment_record::Entity as EmploymentRecord;
+pub use super::expense_category::Entity as ExpenseCategory;
+pub use super::expense_claim::Entity as ExpenseClaim;
+pub use super::expense_line::Entity as ExpenseLine;
+pub use super::holiday::Entity as Holiday;
+pub use super::idempotency_key::Entity as IdempotencyKey;
 pub use super::inventory::Entity as Inventory;
+pub use super::journal_entry::Entity as JournalEntry;
+pub use super::leave_request::Entity as LeaveRequest;
+pub use super::leave_type::Entity as LeaveType;
 pub use super::order::Entity as Order;
+pub use super::order_line::Entity as OrderLine;
+pub use super::overtime_policy::Entity as OvertimePolicy;
+pub use super::payroll_component::Entity as PayrollComponent;
+pub use super::payroll_run::Entity as PayrollRun;
+pub use super::payslip::Entity as Payslip;
+pub use super::payslip_line::Entity as PayslipLine;
+pub use super::position::Entity as Position;
+pub use super::price_list::Entity as PriceList;
+pub use super::price_list_item::Entity as PriceListItem;
+pub use super::project::Entity as Project;
+pub use super::quotation::Entity as Quotation;
+pub use super::quotation_line::Entity as QuotationLine;
+pub use super::quotation_revision::Entity as QuotationRevision;
+pub use super::stock_count::Entity as StockCount;
+pub use super::stock_count_line::Entity as StockCountLine;
+pub use super::stock_lot::Entity as StockLot;
+pub use super::stock_movement::Entity as StockMovement;
+pub use super::timesheet::Entity as Timesheet;
+pub use super::timesheet_entry::Entity as TimesheetEntry;
 pub use super::user::Entity as User;
+pub use super::user_invite::Entity as UserInvite;
+pub use super::webhook::Entity as Webhook;
+pub use super::webhook_delivery::Entity as WebhookDelivery;
@@ -0,0 +1,56 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Prices that replace an item's own for some customers, in a currency
+/// and for a while.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "price_list")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub name: String,
+    /// Customers in this group get the list's prices; everyone does when
+    /// it is empty.
+    pub customer_group: Option<String>,
+    /// ISO 4217 code of the prices; empty for the company's own currency,
+    /// the one inventory prices are in.
+    pub currency: Option<String>,
+    /// First day the list applies, open-ended when empty.
+    pub valid_from: Option<NaiveDate>,
+    /// Last day the list applies, open-ended when empty.
+    pub valid_to: Option<NaiveDate>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::price_list_item::Entity")]
+    PriceListItem,
+}
+
+impl Related<super::price_list_item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PriceListItem.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
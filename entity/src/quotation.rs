@@ -0,0 +1,99 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Prices offered to a customer before there is an order. A quote that is
+/// sent can be revised, which keeps what was sent as a revision and opens
+/// the next one as a draft.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "quotation")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub customer_id: String,
+    /// Starts at 1 and goes up each time the quote is revised.
+    pub revision: i32,
+    pub status: QuoteStatus,
+    /// Last day the customer can accept the quote.
+    pub valid_until: NaiveDate,
+    pub notes: Option<String>,
+    /// Sum of the lines before discounts.
+    pub subtotal: f64,
+    pub discount_total: f64,
+    pub total: f64,
+    pub sent_at: Option<NaiveDateTime>,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<NaiveDateTime>,
+    pub decision_note: Option<String>,
+    /// Order the quote was converted into.
+    pub order_id: Option<String>,
+    pub created_by: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStatus {
+    #[sea_orm(string_value = "draft")]
+    Draft,
+    /// Waiting for the customer.
+    #[sea_orm(string_value = "sent")]
+    Sent,
+    #[sea_orm(string_value = "accepted")]
+    Accepted,
+    #[sea_orm(string_value = "rejected")]
+    Rejected,
+    /// Turned into an order.
+    #[sea_orm(string_value = "converted")]
+    Converted,
+}
+
+impl QuoteStatus {
+    /// A quote the customer has seen changes only through a new revision.
+    pub fn is_revisable(self) -> bool {
+        matches!(self, QuoteStatus::Sent | QuoteStatus::Rejected)
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::quotation_line::Entity")]
+    QuotationLine,
+    #[sea_orm(has_many = "super::quotation_revision::Entity")]
+    QuotationRevision,
+}
+
+impl Related<super::quotation_line::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::QuotationLine.def()
+    }
+}
+
+impl Related<super::quotation_revision::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::QuotationRevision.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
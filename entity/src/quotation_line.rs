@@ -0,0 +1,55 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "quotation_line")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub quotation_id: String,
+    /// Order of the line on the quotation, from 1.
+    pub position: i32,
+    /// Inventory item sold on the line, if any.
+    pub inventory_id: Option<String>,
+    pub description: String,
+    pub quantity: i32,
+    pub unit_price: f64,
+    pub discount_percent: f64,
+    /// `quantity` at `unit_price`, less the discount.
+    pub line_total: f64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::quotation::Entity",
+        from = "Column::QuotationId",
+        to = "super::quotation::Column::Id"
+    )]
+    Quotation,
+}
+
+impl Related<super::quotation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Quotation.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
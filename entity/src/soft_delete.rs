@@ -0,0 +1,8 @@
+use sea_orm::EntityTrait;
+
+/// Entities whose rows are hidden by setting `deleted_at` instead of being
+/// removed, so documents referring to them keep working. Queries go through
+/// the tenant scope, which leaves deleted rows out by default.
+pub trait SoftDelete: EntityTrait {
+    fn deleted_at() -> Self::Column;
+}
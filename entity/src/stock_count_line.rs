@@ -0,0 +1,65 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "stock_count_line")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub count_id: String,
+    pub inventory_id: String,
+    pub lot_id: Option<String>,
+    pub expected_quantity: i32,
+    pub counted_quantity: Option<i32>,
+    /// Number of times a quantity was submitted for this line.
+    pub passes: i32,
+    pub counted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::stock_count::Entity",
+        from = "Column::CountId",
+        to = "super::stock_count::Column::Id"
+    )]
+    StockCount,
+    #[sea_orm(
+        belongs_to = "super::inventory::Entity",
+        from = "Column::InventoryId",
+        to = "super::inventory::Column::Id"
+    )]
+    Inventory,
+}
+
+impl Related<super::stock_count::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StockCount.def()
+    }
+}
+
+impl Related<super::inventory::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Inventory.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
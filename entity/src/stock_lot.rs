@@ -0,0 +1,60 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A lot or serial number of a tracked inventory item. Serial numbers are
+/// stored as lots whose quantity never exceeds one.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "stock_lot")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub inventory_id: String,
+    pub lot_number: String,
+    pub expiry_date: Option<NaiveDate>,
+    pub quantity: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::inventory::Entity",
+        from = "Column::InventoryId",
+        to = "super::inventory::Column::Id"
+    )]
+    Inventory,
+    #[sea_orm(has_many = "super::stock_movement::Entity")]
+    StockMovement,
+}
+
+impl Related<super::inventory::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Inventory.def()
+    }
+}
+
+impl Related<super::stock_movement::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StockMovement.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
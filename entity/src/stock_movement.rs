@@ -0,0 +1,82 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A single change of on-hand quantity. `quantity` is signed: receipts are
+/// positive, shipments negative and adjustments either way.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "stock_movement")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub inventory_id: String,
+    pub lot_id: Option<String>,
+    pub movement_type: MovementType,
+    pub quantity: i32,
+    /// Supplier of a receipt or customer of a shipment.
+    pub partner_id: Option<String>,
+    pub reference: Option<String>,
+    pub reason: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum MovementType {
+    #[sea_orm(string_value = "receipt")]
+    Receipt,
+    #[sea_orm(string_value = "shipment")]
+    Shipment,
+    #[sea_orm(string_value = "adjustment")]
+    Adjustment,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::inventory::Entity",
+        from = "Column::InventoryId",
+        to = "super::inventory::Column::Id"
+    )]
+    Inventory,
+    #[sea_orm(
+        belongs_to = "super::stock_lot::Entity",
+        from = "Column::LotId",
+        to = "super::stock_lot::Column::Id"
+    )]
+    StockLot,
+}
+
+impl Related<super::inventory::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Inventory.def()
+    }
+}
+
+impl Related<super::stock_lot::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StockLot.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
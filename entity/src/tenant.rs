@@ -0,0 +1,22 @@
+use sea_orm::{ActiveValue, DbErr, EntityTrait};
+
+/// Entities holding company data. Every row belongs to one tenant, and the
+/// API only reaches these tables through a tenant scope.
+pub trait TenantOwned: EntityTrait {
+    fn tenant_id() -> Self::Column;
+}
+
+/// Guards the tenant of a row being saved: new rows must name one and
+/// existing rows cannot move to another.
+pub fn check(tenant_id: &ActiveValue<String>, insert: bool) -> Result<(), DbErr> {
+    match (tenant_id, insert) {
+        (ActiveValue::Set(id), true) if !id.is_empty() => Ok(()),
+        (ActiveValue::Unchanged(_) | ActiveValue::NotSet, false) => Ok(()),
+        (_, true) => Err(DbErr::Custom(
+            "A tenant is required to create this record".to_string(),
+        )),
+        (_, false) => Err(DbErr::Custom(
+            "Records cannot be moved to another tenant".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,17 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::ActiveValue::{self, Set};
+
+/// Stamps a row before it is saved: new rows start at version 1 and every
+/// update bumps the version, which the API exposes as the `ETag`.
+pub fn touch(
+    version: &mut ActiveValue<i32>,
+    updated_at: &mut ActiveValue<NaiveDateTime>,
+    insert: bool,
+) {
+    let next = match version.try_as_ref() {
+        Some(current) if !insert => current + 1,
+        _ => 1,
+    };
+    *version = Set(next);
+    *updated_at = Set(Utc::now().naive_utc());
+}
@@ -0,0 +1,64 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// An endpoint that is notified when the events it subscribes to happen.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "webhook")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub tenant_id: String,
+    pub url: String,
+    /// Key the payloads are signed with. Never returned by the API.
+    #[serde(default, skip_serializing)]
+    #[schema(write_only)]
+    pub secret: String,
+    /// Names of the events sent to this endpoint, e.g. `order.created`.
+    #[schema(value_type = Vec<String>)]
+    pub events: Json,
+    pub active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Model {
+    pub fn subscribes_to(&self, event: &str) -> bool {
+        self.events
+            .as_array()
+            .is_some_and(|events| events.iter().any(|e| e.as_str() == Some(event)))
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::webhook_delivery::Entity")]
+    WebhookDelivery,
+}
+
+impl Related<super::webhook_delivery::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WebhookDelivery.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        crate::tenant::check(&self.tenant_id, insert)?;
+        if !insert {
+            self.updated_at = sea_orm::Set(chrono::Utc::now().naive_utc());
+        }
+        Ok(self)
+    }
+}
+
+impl crate::tenant::TenantOwned for Entity {
+    fn tenant_id() -> Column {
+        Column::TenantId
+    }
+}
@@ -4,6 +4,30 @@ mod m20250604_000000_create_inventory;
 mod m20250604_000001_create_employee;
 mod m20250604_000002_create_order;
 mod m20250604_000003_create_user;
+mod m20250612_000000_add_inventory_tracking;
+mod m20250612_000001_create_stock_lot;
+mod m20250612_000002_create_stock_movement;
+mod m20250613_000000_add_inventory_location_category;
+mod m20250613_000001_create_stock_count;
+mod m20250613_000002_create_stock_count_line;
+mod m20250614_000000_add_inventory_sku;
+mod m20250614_000001_create_customer;
+mod m20250615_000000_add_row_versions;
+mod m20250616_000000_create_idempotency_key;
+mod m20250617_000000_create_audit_log;
+mod m20250618_000000_add_soft_delete;
+mod m20250619_000000_create_company;
+mod m20250620_000000_create_webhook;
+mod m20250621_000000_create_department;
+mod m20250622_000000_link_user_employee;
+mod m20250623_000000_create_employment_record;
+mod m20250624_000000_create_leave;
+mod m20250625_000000_create_attendance;
+mod m20250626_000000_create_payroll;
+mod m20250627_000000_create_expense;
+mod m20250628_000000_create_attachment;
+mod m20250629_000000_create_quotation;
+mod m20250630_000000_create_price_list;
 
 pub struct Migrator;
 
@@ -15,6 +39,30 @@ impl MigratorTrait for Migrator {
             Box::new(m20250604_000001_create_employee::Migration),
             Box::new(m20250604_000002_create_order::Migration),
             Box::new(m20250604_000003_create_user::Migration),
+            Box::new(m20250612_000000_add_inventory_tracking::Migration),
+            Box::new(m20250612_000001_create_stock_lot::Migration),
+            Box::new(m20250612_000002_create_stock_movement::Migration),
+            Box::new(m20250613_000000_add_inventory_location_category::Migration),
+            Box::new(m20250613_000001_create_stock_count::Migration),
+            Box::new(m20250613_000002_create_stock_count_line::Migration),
+            Box::new(m20250614_000000_add_inventory_sku::Migration),
+            Box::new(m20250614_000001_create_customer::Migration),
+            Box::new(m20250615_000000_add_row_versions::Migration),
+            Box::new(m20250616_000000_create_idempotency_key::Migration),
+            Box::new(m20250617_000000_create_audit_log::Migration),
+            Box::new(m20250618_000000_add_soft_delete::Migration),
+            Box::new(m20250619_000000_create_company::Migration),
+            Box::new(m20250620_000000_create_webhook::Migration),
+            Box::new(m20250621_000000_create_department::Migration),
+            Box::new(m20250622_000000_link_user_employee::Migration),
+            Box::new(m20250623_000000_create_employment_record::Migration),
+            Box::new(m20250624_000000_create_leave::Migration),
+            Box::new(m20250625_000000_create_attendance::Migration),
+            Box::new(m20250626_000000_create_payroll::Migration),
+            Box::new(m20250627_000000_create_expense::Migration),
+            Box::new(m20250628_000000_create_attachment::Migration),
+            Box::new(m20250629_000000_create_quotation::Migration),
+            Box::new(m20250630_000000_create_price_list::Migration),
         ]
     }
 }
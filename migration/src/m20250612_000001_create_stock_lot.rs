@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StockLot::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StockLot::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(StockLot::InventoryId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StockLot::LotNumber)
+                            .string_len(100)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(StockLot::ExpiryDate).date().null())
+                    .col(ColumnDef::new(StockLot::Quantity).integer().not_null())
+                    .col(
+                        ColumnDef::new(StockLot::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stock_lot_inventory")
+                            .from(StockLot::Table, StockLot::InventoryId)
+                            .to(Inventory::Table, Inventory::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_stock_lot_inventory_lot_number")
+                            .col(StockLot::InventoryId)
+                            .col(StockLot::LotNumber)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_stock_lot_lot_number")
+                    .table(StockLot::Table)
+                    .col(StockLot::LotNumber)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StockLot::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StockLot {
+    Table,
+    Id,
+    InventoryId,
+    LotNumber,
+    ExpiryDate,
+    Quantity,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Inventory {
+    Table,
+    Id,
+}
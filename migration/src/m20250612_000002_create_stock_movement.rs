@@ -0,0 +1,91 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StockMovement::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StockMovement::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(StockMovement::InventoryId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(StockMovement::LotId).char_len(36).null())
+                    .col(
+                        ColumnDef::new(StockMovement::MovementType)
+                            .string_len(20)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(StockMovement::Quantity).integer().not_null())
+                    .col(ColumnDef::new(StockMovement::PartnerId).char_len(36).null())
+                    .col(ColumnDef::new(StockMovement::Reference).string().null())
+                    .col(ColumnDef::new(StockMovement::Reason).string().null())
+                    .col(
+                        ColumnDef::new(StockMovement::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stock_movement_inventory")
+                            .from(StockMovement::Table, StockMovement::InventoryId)
+                            .to(Inventory::Table, Inventory::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stock_movement_lot")
+                            .from(StockMovement::Table, StockMovement::LotId)
+                            .to(StockLot::Table, StockLot::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StockMovement::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StockMovement {
+    Table,
+    Id,
+    InventoryId,
+    LotId,
+    MovementType,
+    Quantity,
+    PartnerId,
+    Reference,
+    Reason,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Inventory {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum StockLot {
+    Table,
+    Id,
+}
@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Inventory::Table)
+                    .add_column(ColumnDef::new(Inventory::Location).string_len(100).null())
+                    .add_column(ColumnDef::new(Inventory::Category).string_len(100).null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Inventory::Table)
+                    .drop_column(Inventory::Location)
+                    .drop_column(Inventory::Category)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Inventory {
+    Table,
+    Location,
+    Category,
+}
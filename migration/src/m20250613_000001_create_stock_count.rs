@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StockCount::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StockCount::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(StockCount::Location).string_len(100).null())
+                    .col(ColumnDef::new(StockCount::Category).string_len(100).null())
+                    .col(ColumnDef::new(StockCount::Note).string().null())
+                    .col(ColumnDef::new(StockCount::Status).string_len(20).not_null())
+                    .col(
+                        ColumnDef::new(StockCount::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(StockCount::ClosedAt).date_time().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StockCount::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StockCount {
+    Table,
+    Id,
+    Location,
+    Category,
+    Note,
+    Status,
+    CreatedAt,
+    ClosedAt,
+}
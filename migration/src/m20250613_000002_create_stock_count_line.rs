@@ -0,0 +1,97 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StockCountLine::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StockCountLine::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(StockCountLine::CountId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StockCountLine::InventoryId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(StockCountLine::LotId).char_len(36).null())
+                    .col(
+                        ColumnDef::new(StockCountLine::ExpectedQuantity)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StockCountLine::CountedQuantity)
+                            .integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(StockCountLine::Passes)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(StockCountLine::CountedAt).date_time().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stock_count_line_count")
+                            .from(StockCountLine::Table, StockCountLine::CountId)
+                            .to(StockCount::Table, StockCount::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stock_count_line_inventory")
+                            .from(StockCountLine::Table, StockCountLine::InventoryId)
+                            .to(Inventory::Table, Inventory::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StockCountLine::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StockCountLine {
+    Table,
+    Id,
+    CountId,
+    InventoryId,
+    LotId,
+    ExpectedQuantity,
+    CountedQuantity,
+    Passes,
+    CountedAt,
+}
+
+#[derive(DeriveIden)]
+enum StockCount {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Inventory {
+    Table,
+    Id,
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Customer::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Customer::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Customer::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(Customer::Email)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Customer::Phone).string_len(50).null())
+                    .col(ColumnDef::new(Customer::Address).string().null())
+                    .col(
+                        ColumnDef::new(Customer::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Customer::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Customer {
+    Table,
+    Id,
+    Name,
+    Email,
+    Phone,
+    Address,
+    CreatedAt,
+}
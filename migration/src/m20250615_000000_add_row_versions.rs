@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Tables whose rows carry a version for optimistic concurrency.
+fn versioned_tables() -> Vec<DynIden> {
+    vec![
+        Inventory::Table.into_iden(),
+        Employee::Table.into_iden(),
+        Order::Table.into_iden(),
+        Customer::Table.into_iden(),
+    ]
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in versioned_tables() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .add_column(
+                            ColumnDef::new(Versioned::Version)
+                                .integer()
+                                .not_null()
+                                .default(1),
+                        )
+                        .add_column(
+                            ColumnDef::new(Versioned::UpdatedAt)
+                                .date_time()
+                                .not_null()
+                                .default(Expr::current_timestamp()),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in versioned_tables() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_column(Versioned::Version)
+                        .drop_column(Versioned::UpdatedAt)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Versioned {
+    Version,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Inventory {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum Employee {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum Order {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum Customer {
+    Table,
+}
@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(IdempotencyKey::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(IdempotencyKey::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(IdempotencyKey::UserId).string().not_null())
+                    .col(ColumnDef::new(IdempotencyKey::Key).string().not_null())
+                    .col(
+                        ColumnDef::new(IdempotencyKey::Fingerprint)
+                            .char_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdempotencyKey::ResponseStatus)
+                            .small_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdempotencyKey::ResponseContentType)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdempotencyKey::ResponseHeaders)
+                            .json()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdempotencyKey::ResponseBody)
+                            .custom(Alias::new("LONGBLOB"))
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(IdempotencyKey::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(IdempotencyKey::ExpiresAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .index(
+                        Index::create()
+                            .name("uq_idempotency_key_user_key")
+                            .col(IdempotencyKey::UserId)
+                            .col(IdempotencyKey::Key)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_idempotency_key_expires_at")
+                    .table(IdempotencyKey::Table)
+                    .col(IdempotencyKey::ExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IdempotencyKey::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum IdempotencyKey {
+    Table,
+    Id,
+    UserId,
+    Key,
+    Fingerprint,
+    ResponseStatus,
+    ResponseContentType,
+    ResponseHeaders,
+    ResponseBody,
+    CreatedAt,
+    ExpiresAt,
+}
@@ -0,0 +1,81 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditLog::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditLog::Actor).string().null())
+                    .col(ColumnDef::new(AuditLog::Entity).string_len(50).not_null())
+                    .col(ColumnDef::new(AuditLog::EntityId).string().not_null())
+                    .col(ColumnDef::new(AuditLog::Action).string_len(20).not_null())
+                    .col(ColumnDef::new(AuditLog::Before).json().null())
+                    .col(ColumnDef::new(AuditLog::After).json().null())
+                    .col(ColumnDef::new(AuditLog::RequestId).string_len(128).null())
+                    .col(ColumnDef::new(AuditLog::Ip).string_len(45).null())
+                    .col(
+                        ColumnDef::new(AuditLog::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_log_entity")
+                    .table(AuditLog::Table)
+                    .col(AuditLog::Entity)
+                    .col(AuditLog::EntityId)
+                    .col(AuditLog::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_log_created_at")
+                    .table(AuditLog::Table)
+                    .col(AuditLog::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditLog::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuditLog {
+    Table,
+    Id,
+    Actor,
+    Entity,
+    EntityId,
+    Action,
+    Before,
+    After,
+    RequestId,
+    Ip,
+    CreatedAt,
+}
@@ -0,0 +1,98 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Tables whose rows are soft-deleted through `deleted_at`.
+fn soft_deleted_tables() -> Vec<DynIden> {
+    vec![
+        Inventory::Table.into_iden(),
+        Employee::Table.into_iden(),
+        Order::Table.into_iden(),
+        Customer::Table.into_iden(),
+    ]
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in soft_deleted_tables() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .add_column(ColumnDef::new(SoftDeleted::DeletedAt).date_time().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(
+                        ColumnDef::new(User::IsAdmin)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::IsAdmin)
+                    .to_owned(),
+            )
+            .await?;
+
+        for table in soft_deleted_tables() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_column(SoftDeleted::DeletedAt)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SoftDeleted {
+    DeletedAt,
+}
+
+#[derive(DeriveIden)]
+enum Inventory {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum Employee {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum Order {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum Customer {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    IsAdmin,
+}
@@ -0,0 +1,384 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Company that owns the rows written before tenants existed.
+const DEFAULT_COMPANY_ID: &str = "00000000-0000-0000-0000-000000000001";
+
+/// Tables holding company data, each gaining a `tenant_id`.
+fn tenant_tables() -> Vec<(DynIden, &'static str)> {
+    vec![
+        (Inventory::Table.into_iden(), "fk_inventory_company"),
+        (Employee::Table.into_iden(), "fk_employee_company"),
+        (Order::Table.into_iden(), "fk_order_company"),
+        (Customer::Table.into_iden(), "fk_customer_company"),
+        (StockLot::Table.into_iden(), "fk_stock_lot_company"),
+        (
+            StockMovement::Table.into_iden(),
+            "fk_stock_movement_company",
+        ),
+        (StockCount::Table.into_iden(), "fk_stock_count_company"),
+        (
+            StockCountLine::Table.into_iden(),
+            "fk_stock_count_line_company",
+        ),
+        (AuditLog::Table.into_iden(), "fk_audit_log_company"),
+    ]
+}
+
+/// Keys that used to be unique across the deployment and are now unique per
+/// company: (table, column, old index, new index).
+fn tenant_unique_keys() -> Vec<(DynIden, DynIden, &'static str, &'static str)> {
+    vec![
+        (
+            Inventory::Table.into_iden(),
+            Inventory::Sku.into_iden(),
+            "sku",
+            "idx_inventory_tenant_sku",
+        ),
+        (
+            Employee::Table.into_iden(),
+            Employee::Email.into_iden(),
+            "email",
+            "idx_employee_tenant_email",
+        ),
+        (
+            Customer::Table.into_iden(),
+            Customer::Email.into_iden(),
+            "email",
+            "idx_customer_tenant_email",
+        ),
+    ]
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Company::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Company::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Company::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(Company::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CompanyUser::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CompanyUser::CompanyId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(CompanyUser::UserId).char_len(36).not_null())
+                    .col(
+                        ColumnDef::new(CompanyUser::Role)
+                            .string_len(20)
+                            .not_null()
+                            .default("member"),
+                    )
+                    .col(
+                        ColumnDef::new(CompanyUser::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(CompanyUser::CompanyId)
+                            .col(CompanyUser::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_company_user_company")
+                            .from(CompanyUser::Table, CompanyUser::CompanyId)
+                            .to(Company::Table, Company::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_company_user_user")
+                            .from(CompanyUser::Table, CompanyUser::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing data and users move to a default company
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(Company::Table)
+                    .columns([Company::Id, Company::Name])
+                    .values_panic([DEFAULT_COMPANY_ID.into(), "Default company".into()])
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(CompanyUser::Table)
+                    .columns([CompanyUser::CompanyId, CompanyUser::UserId])
+                    .select_from(
+                        Query::select()
+                            .expr(Expr::val(DEFAULT_COMPANY_ID))
+                            .column(User::Id)
+                            .from(User::Table)
+                            .to_owned(),
+                    )
+                    .map_err(|e| DbErr::Migration(e.to_string()))?
+                    .to_owned(),
+            )
+            .await?;
+        // Administrators are so per company from now on
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(CompanyUser::Table)
+                    .value(CompanyUser::Role, "admin")
+                    .and_where(
+                        Expr::col(CompanyUser::UserId).in_subquery(
+                            Query::select()
+                                .column(User::Id)
+                                .from(User::Table)
+                                .and_where(Expr::col(User::IsAdmin).eq(true))
+                                .to_owned(),
+                        ),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::IsAdmin)
+                    .to_owned(),
+            )
+            .await?;
+
+        for (table, foreign_key) in tenant_tables() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table.clone())
+                        .add_column(
+                            ColumnDef::new(Tenant::TenantId)
+                                .char_len(36)
+                                .not_null()
+                                .default(DEFAULT_COMPANY_ID),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+            // The default only backfills existing rows; new ones must say
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table.clone())
+                        .modify_column(ColumnDef::new(Tenant::TenantId).char_len(36).not_null())
+                        .add_foreign_key(
+                            TableForeignKey::new()
+                                .name(foreign_key)
+                                .from_tbl(table)
+                                .from_col(Tenant::TenantId)
+                                .to_tbl(Company::Table)
+                                .to_col(Company::Id),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        for (table, column, old_index, new_index) in tenant_unique_keys() {
+            manager
+                .drop_index(
+                    Index::drop()
+                        .name(old_index)
+                        .table(table.clone())
+                        .to_owned(),
+                )
+                .await?;
+            manager
+                .create_index(
+                    Index::create()
+                        .name(new_index)
+                        .table(table)
+                        .col(Tenant::TenantId)
+                        .col(column)
+                        .unique()
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (table, column, old_index, new_index) in tenant_unique_keys() {
+            manager
+                .drop_index(
+                    Index::drop()
+                        .name(new_index)
+                        .table(table.clone())
+                        .to_owned(),
+                )
+                .await?;
+            manager
+                .create_index(
+                    Index::create()
+                        .name(old_index)
+                        .table(table)
+                        .col(column)
+                        .unique()
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        for (table, foreign_key) in tenant_tables() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_foreign_key(Alias::new(foreign_key))
+                        .drop_column(Tenant::TenantId)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(
+                        ColumnDef::new(User::IsAdmin)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(User::Table)
+                    .value(User::IsAdmin, true)
+                    .and_where(
+                        Expr::col(User::Id).in_subquery(
+                            Query::select()
+                                .column(CompanyUser::UserId)
+                                .from(CompanyUser::Table)
+                                .and_where(Expr::col(CompanyUser::Role).eq("admin"))
+                                .to_owned(),
+                        ),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(CompanyUser::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Company::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Company {
+    Table,
+    Id,
+    Name,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum CompanyUser {
+    Table,
+    CompanyId,
+    UserId,
+    Role,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Tenant {
+    TenantId,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+    IsAdmin,
+}
+
+#[derive(DeriveIden)]
+enum Inventory {
+    Table,
+    Sku,
+}
+
+#[derive(DeriveIden)]
+enum Employee {
+    Table,
+    Email,
+}
+
+#[derive(DeriveIden)]
+enum Order {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum Customer {
+    Table,
+    Email,
+}
+
+#[derive(DeriveIden)]
+enum StockLot {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum StockMovement {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum StockCount {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum StockCountLine {
+    Table,
+}
+
+#[derive(DeriveIden)]
+enum AuditLog {
+    Table,
+}
@@ -0,0 +1,174 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Department::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Department::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Department::TenantId).char_len(36).not_null())
+                    .col(ColumnDef::new(Department::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(Department::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_department_company")
+                            .from(Department::Table, Department::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_department_tenant_name")
+                            .col(Department::TenantId)
+                            .col(Department::Name)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Position::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Position::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Position::TenantId).char_len(36).not_null())
+                    .col(ColumnDef::new(Position::Title).string().not_null())
+                    .col(ColumnDef::new(Position::DepartmentId).char_len(36).null())
+                    .col(
+                        ColumnDef::new(Position::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_position_company")
+                            .from(Position::Table, Position::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_position_department")
+                            .from(Position::Table, Position::DepartmentId)
+                            .to(Department::Table, Department::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Departments and positions in use cannot be deleted
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Employee::Table)
+                    .add_column(ColumnDef::new(Employee::DepartmentId).char_len(36).null())
+                    .add_column(ColumnDef::new(Employee::PositionId).char_len(36).null())
+                    .add_column(ColumnDef::new(Employee::ManagerId).char_len(36).null())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_employee_department")
+                            .from_tbl(Employee::Table)
+                            .from_col(Employee::DepartmentId)
+                            .to_tbl(Department::Table)
+                            .to_col(Department::Id),
+                    )
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_employee_position")
+                            .from_tbl(Employee::Table)
+                            .from_col(Employee::PositionId)
+                            .to_tbl(Position::Table)
+                            .to_col(Position::Id),
+                    )
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_employee_manager")
+                            .from_tbl(Employee::Table)
+                            .from_col(Employee::ManagerId)
+                            .to_tbl(Employee::Table)
+                            .to_col(Employee::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Employee::Table)
+                    .drop_foreign_key(Alias::new("fk_employee_department"))
+                    .drop_foreign_key(Alias::new("fk_employee_position"))
+                    .drop_foreign_key(Alias::new("fk_employee_manager"))
+                    .drop_column(Employee::DepartmentId)
+                    .drop_column(Employee::PositionId)
+                    .drop_column(Employee::ManagerId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Position::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Department::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Department {
+    Table,
+    Id,
+    TenantId,
+    Name,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Position {
+    Table,
+    Id,
+    TenantId,
+    Title,
+    DepartmentId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Employee {
+    Table,
+    Id,
+    DepartmentId,
+    PositionId,
+    ManagerId,
+}
+
+#[derive(DeriveIden)]
+enum Company {
+    Table,
+    Id,
+}
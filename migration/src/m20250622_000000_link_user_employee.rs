@@ -0,0 +1,182 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(ColumnDef::new(User::EmployeeId).char_len(36).null())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_user_employee")
+                            .from_tbl(User::Table)
+                            .from_col(User::EmployeeId)
+                            .to_tbl(Employee::Table)
+                            .to_col(Employee::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Logins are disabled per company, where the employee was let go
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CompanyUser::Table)
+                    .add_column(
+                        ColumnDef::new(CompanyUser::Active)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // An employee has at most one login
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_user_employee")
+                    .table(User::Table)
+                    .col(User::EmployeeId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserInvite::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserInvite::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(UserInvite::TenantId).char_len(36).not_null())
+                    .col(
+                        ColumnDef::new(UserInvite::EmployeeId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserInvite::TokenHash)
+                            .char_len(64)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(UserInvite::InvitedBy)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(UserInvite::ExpiresAt).date_time().not_null())
+                    .col(ColumnDef::new(UserInvite::AcceptedAt).date_time().null())
+                    .col(
+                        ColumnDef::new(UserInvite::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_invite_company")
+                            .from(UserInvite::Table, UserInvite::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_invite_employee")
+                            .from(UserInvite::Table, UserInvite::EmployeeId)
+                            .to(Employee::Table, Employee::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserInvite::Table).to_owned())
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_foreign_key(Alias::new("fk_user_employee"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_user_employee")
+                    .table(User::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::EmployeeId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CompanyUser::Table)
+                    .drop_column(CompanyUser::Active)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    EmployeeId,
+}
+
+#[derive(DeriveIden)]
+enum CompanyUser {
+    Table,
+    Active,
+}
+
+#[derive(DeriveIden)]
+enum UserInvite {
+    Table,
+    Id,
+    TenantId,
+    EmployeeId,
+    TokenHash,
+    InvitedBy,
+    ExpiresAt,
+    AcceptedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Employee {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Company {
+    Table,
+    Id,
+}
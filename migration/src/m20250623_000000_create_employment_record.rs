@@ -0,0 +1,128 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(EmploymentRecord::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(EmploymentRecord::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(EmploymentRecord::TenantId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EmploymentRecord::EmployeeId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EmploymentRecord::Kind)
+                            .string_len(20)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EmploymentRecord::EffectiveDate)
+                            .date()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EmploymentRecord::DepartmentId)
+                            .char_len(36)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(EmploymentRecord::PositionId)
+                            .char_len(36)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(EmploymentRecord::ManagerId)
+                            .char_len(36)
+                            .null(),
+                    )
+                    .col(ColumnDef::new(EmploymentRecord::Role).string().null())
+                    .col(ColumnDef::new(EmploymentRecord::Salary).double().null())
+                    .col(ColumnDef::new(EmploymentRecord::Reason).text().null())
+                    .col(
+                        ColumnDef::new(EmploymentRecord::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_employment_record_company")
+                            .from(EmploymentRecord::Table, EmploymentRecord::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_employment_record_employee")
+                            .from(EmploymentRecord::Table, EmploymentRecord::EmployeeId)
+                            .to(Employee::Table, Employee::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_employment_record_employee")
+                            .col(EmploymentRecord::EmployeeId)
+                            .col(EmploymentRecord::EffectiveDate),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_employment_record_tenant_date")
+                            .col(EmploymentRecord::TenantId)
+                            .col(EmploymentRecord::EffectiveDate),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EmploymentRecord::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum EmploymentRecord {
+    Table,
+    Id,
+    TenantId,
+    EmployeeId,
+    Kind,
+    EffectiveDate,
+    DepartmentId,
+    PositionId,
+    ManagerId,
+    Role,
+    Salary,
+    Reason,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Employee {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Company {
+    Table,
+    Id,
+}
@@ -0,0 +1,226 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LeaveType::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LeaveType::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(LeaveType::TenantId).char_len(36).not_null())
+                    .col(ColumnDef::new(LeaveType::Name).string().not_null())
+                    .col(ColumnDef::new(LeaveType::Accrual).string_len(20).not_null())
+                    .col(
+                        ColumnDef::new(LeaveType::DaysPerYear)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(
+                        ColumnDef::new(LeaveType::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_leave_type_company")
+                            .from(LeaveType::Table, LeaveType::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_leave_type_tenant_name")
+                            .col(LeaveType::TenantId)
+                            .col(LeaveType::Name)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Holiday::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Holiday::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Holiday::TenantId).char_len(36).not_null())
+                    .col(ColumnDef::new(Holiday::Date).date().not_null())
+                    .col(ColumnDef::new(Holiday::Name).string().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_holiday_company")
+                            .from(Holiday::Table, Holiday::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_holiday_tenant_date")
+                            .col(Holiday::TenantId)
+                            .col(Holiday::Date)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(LeaveRequest::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LeaveRequest::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(LeaveRequest::TenantId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LeaveRequest::EmployeeId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LeaveRequest::LeaveTypeId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(LeaveRequest::StartDate).date().not_null())
+                    .col(ColumnDef::new(LeaveRequest::EndDate).date().not_null())
+                    .col(ColumnDef::new(LeaveRequest::Days).double().not_null())
+                    .col(ColumnDef::new(LeaveRequest::Reason).text().null())
+                    .col(
+                        ColumnDef::new(LeaveRequest::Status)
+                            .string_len(20)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(LeaveRequest::DecidedBy).char_len(36).null())
+                    .col(ColumnDef::new(LeaveRequest::DecidedAt).date_time().null())
+                    .col(ColumnDef::new(LeaveRequest::DecisionNote).text().null())
+                    .col(
+                        ColumnDef::new(LeaveRequest::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(LeaveRequest::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_leave_request_company")
+                            .from(LeaveRequest::Table, LeaveRequest::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_leave_request_employee")
+                            .from(LeaveRequest::Table, LeaveRequest::EmployeeId)
+                            .to(Employee::Table, Employee::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_leave_request_type")
+                            .from(LeaveRequest::Table, LeaveRequest::LeaveTypeId)
+                            .to(LeaveType::Table, LeaveType::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_leave_request_employee_dates")
+                            .col(LeaveRequest::EmployeeId)
+                            .col(LeaveRequest::StartDate)
+                            .col(LeaveRequest::EndDate),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LeaveRequest::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Holiday::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(LeaveType::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum LeaveType {
+    Table,
+    Id,
+    TenantId,
+    Name,
+    Accrual,
+    DaysPerYear,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Holiday {
+    Table,
+    Id,
+    TenantId,
+    Date,
+    Name,
+}
+
+#[derive(DeriveIden)]
+enum LeaveRequest {
+    Table,
+    Id,
+    TenantId,
+    EmployeeId,
+    LeaveTypeId,
+    StartDate,
+    EndDate,
+    Days,
+    Reason,
+    Status,
+    DecidedBy,
+    DecidedAt,
+    DecisionNote,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Employee {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Company {
+    Table,
+    Id,
+}
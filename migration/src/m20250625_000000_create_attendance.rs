@@ -0,0 +1,367 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Project::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Project::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Project::TenantId).char_len(36).not_null())
+                    .col(ColumnDef::new(Project::Code).string_len(50).not_null())
+                    .col(ColumnDef::new(Project::Name).string().not_null())
+                    .col(ColumnDef::new(Project::Kind).string_len(20).not_null())
+                    .col(
+                        ColumnDef::new(Project::Active)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(Project::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_company")
+                            .from(Project::Table, Project::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_project_tenant_code")
+                            .col(Project::TenantId)
+                            .col(Project::Code)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Attendance::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Attendance::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Attendance::TenantId).char_len(36).not_null())
+                    .col(
+                        ColumnDef::new(Attendance::EmployeeId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Attendance::ClockIn).date_time().not_null())
+                    .col(ColumnDef::new(Attendance::ClockOut).date_time().null())
+                    .col(ColumnDef::new(Attendance::Note).text().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_attendance_company")
+                            .from(Attendance::Table, Attendance::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_attendance_employee")
+                            .from(Attendance::Table, Attendance::EmployeeId)
+                            .to(Employee::Table, Employee::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_attendance_employee_clock_in")
+                            .col(Attendance::EmployeeId)
+                            .col(Attendance::ClockIn),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Timesheet::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Timesheet::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Timesheet::TenantId).char_len(36).not_null())
+                    .col(
+                        ColumnDef::new(Timesheet::EmployeeId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Timesheet::WeekStart).date().not_null())
+                    .col(ColumnDef::new(Timesheet::Status).string_len(20).not_null())
+                    .col(
+                        ColumnDef::new(Timesheet::RegularHours)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(
+                        ColumnDef::new(Timesheet::OvertimeHours)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(ColumnDef::new(Timesheet::SubmittedAt).date_time().null())
+                    .col(ColumnDef::new(Timesheet::DecidedBy).char_len(36).null())
+                    .col(ColumnDef::new(Timesheet::DecidedAt).date_time().null())
+                    .col(ColumnDef::new(Timesheet::DecisionNote).text().null())
+                    .col(
+                        ColumnDef::new(Timesheet::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Timesheet::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_timesheet_company")
+                            .from(Timesheet::Table, Timesheet::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_timesheet_employee")
+                            .from(Timesheet::Table, Timesheet::EmployeeId)
+                            .to(Employee::Table, Employee::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_timesheet_employee_week")
+                            .col(Timesheet::EmployeeId)
+                            .col(Timesheet::WeekStart)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TimesheetEntry::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TimesheetEntry::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(TimesheetEntry::TenantId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TimesheetEntry::TimesheetId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TimesheetEntry::ProjectId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TimesheetEntry::WorkDate).date().not_null())
+                    .col(ColumnDef::new(TimesheetEntry::Hours).double().not_null())
+                    .col(ColumnDef::new(TimesheetEntry::Note).text().null())
+                    .col(
+                        ColumnDef::new(TimesheetEntry::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_timesheet_entry_company")
+                            .from(TimesheetEntry::Table, TimesheetEntry::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_timesheet_entry_timesheet")
+                            .from(TimesheetEntry::Table, TimesheetEntry::TimesheetId)
+                            .to(Timesheet::Table, Timesheet::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_timesheet_entry_project")
+                            .from(TimesheetEntry::Table, TimesheetEntry::ProjectId)
+                            .to(Project::Table, Project::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(OvertimePolicy::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OvertimePolicy::TenantId)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OvertimePolicy::DailyHours)
+                            .double()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OvertimePolicy::WeeklyHours)
+                            .double()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OvertimePolicy::NonWorkingDays)
+                            .boolean()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OvertimePolicy::Multiplier)
+                            .double()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OvertimePolicy::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_overtime_policy_company")
+                            .from(OvertimePolicy::Table, OvertimePolicy::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OvertimePolicy::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(TimesheetEntry::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Timesheet::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Attendance::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Project::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Project {
+    Table,
+    Id,
+    TenantId,
+    Code,
+    Name,
+    Kind,
+    Active,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Attendance {
+    Table,
+    Id,
+    TenantId,
+    EmployeeId,
+    ClockIn,
+    ClockOut,
+    Note,
+}
+
+#[derive(DeriveIden)]
+enum Timesheet {
+    Table,
+    Id,
+    TenantId,
+    EmployeeId,
+    WeekStart,
+    Status,
+    RegularHours,
+    OvertimeHours,
+    SubmittedAt,
+    DecidedBy,
+    DecidedAt,
+    DecisionNote,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum TimesheetEntry {
+    Table,
+    Id,
+    TenantId,
+    TimesheetId,
+    ProjectId,
+    WorkDate,
+    Hours,
+    Note,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum OvertimePolicy {
+    Table,
+    TenantId,
+    DailyHours,
+    WeeklyHours,
+    NonWorkingDays,
+    Multiplier,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Employee {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Company {
+    Table,
+    Id,
+}
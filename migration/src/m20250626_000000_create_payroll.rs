@@ -0,0 +1,525 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PayrollComponent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PayrollComponent::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollComponent::TenantId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollComponent::Code)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PayrollComponent::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(PayrollComponent::Kind)
+                            .string_len(30)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollComponent::Base)
+                            .string_len(30)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollComponent::Rate)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollComponent::Amount)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollComponent::Quantity)
+                            .string_len(30)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PayrollComponent::BaseCap).double().null())
+                    .col(ColumnDef::new(PayrollComponent::Brackets).json().null())
+                    .col(
+                        ColumnDef::new(PayrollComponent::Progressive)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollComponent::Account)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollComponent::PayableAccount)
+                            .string_len(50)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollComponent::Sequence)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollComponent::Active)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollComponent::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollComponent::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_payroll_component_company")
+                            .from(PayrollComponent::Table, PayrollComponent::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_payroll_component_tenant_code")
+                            .col(PayrollComponent::TenantId)
+                            .col(PayrollComponent::Code)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(PayrollRun::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PayrollRun::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PayrollRun::TenantId).char_len(36).not_null())
+                    .col(ColumnDef::new(PayrollRun::PeriodStart).date().not_null())
+                    .col(ColumnDef::new(PayrollRun::PeriodEnd).date().not_null())
+                    .col(ColumnDef::new(PayrollRun::Status).string_len(20).not_null())
+                    .col(ColumnDef::new(PayrollRun::WorkingDays).integer().not_null())
+                    .col(
+                        ColumnDef::new(PayrollRun::Employees)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollRun::Gross)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollRun::Deductions)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollRun::Net)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollRun::EmployerContributions)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(ColumnDef::new(PayrollRun::ApprovedBy).char_len(36).null())
+                    .col(ColumnDef::new(PayrollRun::ApprovedAt).date_time().null())
+                    .col(ColumnDef::new(PayrollRun::PostedAt).date_time().null())
+                    .col(
+                        ColumnDef::new(PayrollRun::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(PayrollRun::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_payroll_run_company")
+                            .from(PayrollRun::Table, PayrollRun::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_payroll_run_tenant_period")
+                            .col(PayrollRun::TenantId)
+                            .col(PayrollRun::PeriodStart)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(Payslip::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Payslip::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Payslip::TenantId).char_len(36).not_null())
+                    .col(
+                        ColumnDef::new(Payslip::PayrollRunId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Payslip::EmployeeId).char_len(36).not_null())
+                    .col(ColumnDef::new(Payslip::EmployeeName).string().not_null())
+                    .col(ColumnDef::new(Payslip::Salary).double().not_null())
+                    .col(ColumnDef::new(Payslip::BasicSalary).double().not_null())
+                    .col(ColumnDef::new(Payslip::DaysWorked).integer().not_null())
+                    .col(ColumnDef::new(Payslip::DaysPresent).integer().not_null())
+                    .col(ColumnDef::new(Payslip::OvertimeHours).double().not_null())
+                    .col(ColumnDef::new(Payslip::UnpaidLeaveDays).double().not_null())
+                    .col(ColumnDef::new(Payslip::Gross).double().not_null())
+                    .col(ColumnDef::new(Payslip::Deductions).double().not_null())
+                    .col(ColumnDef::new(Payslip::Net).double().not_null())
+                    .col(
+                        ColumnDef::new(Payslip::EmployerContributions)
+                            .double()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Payslip::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_payslip_company")
+                            .from(Payslip::Table, Payslip::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_payslip_payroll_run")
+                            .from(Payslip::Table, Payslip::PayrollRunId)
+                            .to(PayrollRun::Table, PayrollRun::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_payslip_employee")
+                            .from(Payslip::Table, Payslip::EmployeeId)
+                            .to(Employee::Table, Employee::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_payslip_run_employee")
+                            .col(Payslip::PayrollRunId)
+                            .col(Payslip::EmployeeId)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(PayslipLine::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PayslipLine::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PayslipLine::TenantId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PayslipLine::PayslipId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PayslipLine::ComponentId).char_len(36).null())
+                    .col(ColumnDef::new(PayslipLine::Code).string_len(50).not_null())
+                    .col(ColumnDef::new(PayslipLine::Name).string().not_null())
+                    .col(ColumnDef::new(PayslipLine::Kind).string_len(30).not_null())
+                    .col(ColumnDef::new(PayslipLine::Amount).double().not_null())
+                    .col(
+                        ColumnDef::new(PayslipLine::Account)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PayslipLine::PayableAccount)
+                            .string_len(50)
+                            .null(),
+                    )
+                    .col(ColumnDef::new(PayslipLine::Sequence).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_payslip_line_company")
+                            .from(PayslipLine::Table, PayslipLine::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_payslip_line_payslip")
+                            .from(PayslipLine::Table, PayslipLine::PayslipId)
+                            .to(Payslip::Table, Payslip::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_payslip_line_component")
+                            .from(PayslipLine::Table, PayslipLine::ComponentId)
+                            .to(PayrollComponent::Table, PayrollComponent::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(JournalEntry::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(JournalEntry::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(JournalEntry::TenantId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(JournalEntry::Source)
+                            .string_len(30)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(JournalEntry::SourceId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(JournalEntry::EntryDate).date().not_null())
+                    .col(
+                        ColumnDef::new(JournalEntry::Account)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(JournalEntry::Description)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(JournalEntry::Debit)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(
+                        ColumnDef::new(JournalEntry::Credit)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(
+                        ColumnDef::new(JournalEntry::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_journal_entry_company")
+                            .from(JournalEntry::Table, JournalEntry::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_journal_entry_source")
+                            .col(JournalEntry::Source)
+                            .col(JournalEntry::SourceId),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_journal_entry_tenant_date")
+                            .col(JournalEntry::TenantId)
+                            .col(JournalEntry::EntryDate),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JournalEntry::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(PayslipLine::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Payslip::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(PayrollRun::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(PayrollComponent::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PayrollComponent {
+    Table,
+    Id,
+    TenantId,
+    Code,
+    Name,
+    Kind,
+    Base,
+    Rate,
+    Amount,
+    Quantity,
+    BaseCap,
+    Brackets,
+    Progressive,
+    Account,
+    PayableAccount,
+    Sequence,
+    Active,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum PayrollRun {
+    Table,
+    Id,
+    TenantId,
+    PeriodStart,
+    PeriodEnd,
+    Status,
+    WorkingDays,
+    Employees,
+    Gross,
+    Deductions,
+    Net,
+    EmployerContributions,
+    ApprovedBy,
+    ApprovedAt,
+    PostedAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Payslip {
+    Table,
+    Id,
+    TenantId,
+    PayrollRunId,
+    EmployeeId,
+    EmployeeName,
+    Salary,
+    BasicSalary,
+    DaysWorked,
+    DaysPresent,
+    OvertimeHours,
+    UnpaidLeaveDays,
+    Gross,
+    Deductions,
+    Net,
+    EmployerContributions,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum PayslipLine {
+    Table,
+    Id,
+    TenantId,
+    PayslipId,
+    ComponentId,
+    Code,
+    Name,
+    Kind,
+    Amount,
+    Account,
+    PayableAccount,
+    Sequence,
+}
+
+#[derive(DeriveIden)]
+enum JournalEntry {
+    Table,
+    Id,
+    TenantId,
+    Source,
+    SourceId,
+    EntryDate,
+    Account,
+    Description,
+    Debit,
+    Credit,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Company {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Employee {
+    Table,
+    Id,
+}
@@ -0,0 +1,335 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ExpenseCategory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ExpenseCategory::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseCategory::TenantId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ExpenseCategory::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(ExpenseCategory::Account)
+                            .string_len(50)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseCategory::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_expense_category_company")
+                            .from(ExpenseCategory::Table, ExpenseCategory::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_expense_category_tenant_name")
+                            .col(ExpenseCategory::TenantId)
+                            .col(ExpenseCategory::Name)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(ExpenseClaim::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ExpenseClaim::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseClaim::TenantId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseClaim::EmployeeId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ExpenseClaim::Title).string().not_null())
+                    .col(
+                        ColumnDef::new(ExpenseClaim::Currency)
+                            .string_len(3)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseClaim::Total)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseClaim::Status)
+                            .string_len(20)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ExpenseClaim::SubmittedAt).date_time().null())
+                    .col(
+                        ColumnDef::new(ExpenseClaim::ManagerApprovedBy)
+                            .char_len(36)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseClaim::ManagerApprovedAt)
+                            .date_time()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseClaim::FinanceApprovedBy)
+                            .char_len(36)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseClaim::FinanceApprovedAt)
+                            .date_time()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(ExpenseClaim::RejectedBy).char_len(36).null())
+                    .col(ColumnDef::new(ExpenseClaim::RejectedAt).date_time().null())
+                    .col(ColumnDef::new(ExpenseClaim::DecisionNote).text().null())
+                    .col(
+                        ColumnDef::new(ExpenseClaim::ReimbursementMethod)
+                            .string_len(20)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseClaim::ReimbursementReference)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseClaim::PayrollRunId)
+                            .char_len(36)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseClaim::ReimbursedAt)
+                            .date_time()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseClaim::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseClaim::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_expense_claim_company")
+                            .from(ExpenseClaim::Table, ExpenseClaim::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_expense_claim_employee")
+                            .from(ExpenseClaim::Table, ExpenseClaim::EmployeeId)
+                            .to(Employee::Table, Employee::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_expense_claim_payroll_run")
+                            .from(ExpenseClaim::Table, ExpenseClaim::PayrollRunId)
+                            .to(PayrollRun::Table, PayrollRun::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_expense_claim_tenant_status")
+                            .col(ExpenseClaim::TenantId)
+                            .col(ExpenseClaim::Status),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(ExpenseLine::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ExpenseLine::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseLine::TenantId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ExpenseLine::ClaimId).char_len(36).not_null())
+                    .col(
+                        ColumnDef::new(ExpenseLine::CategoryId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ExpenseLine::ExpenseDate).date().not_null())
+                    .col(ColumnDef::new(ExpenseLine::Description).string().not_null())
+                    .col(ColumnDef::new(ExpenseLine::Amount).double().not_null())
+                    .col(
+                        ColumnDef::new(ExpenseLine::Currency)
+                            .string_len(3)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ExpenseLine::ExchangeRate)
+                            .double()
+                            .not_null()
+                            .default(1.0),
+                    )
+                    .col(ColumnDef::new(ExpenseLine::ClaimAmount).double().not_null())
+                    .col(ColumnDef::new(ExpenseLine::ReceiptKey).string().null())
+                    .col(
+                        ColumnDef::new(ExpenseLine::ReceiptType)
+                            .string_len(100)
+                            .null(),
+                    )
+                    .col(ColumnDef::new(ExpenseLine::ReceiptSize).integer().null())
+                    .col(
+                        ColumnDef::new(ExpenseLine::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_expense_line_company")
+                            .from(ExpenseLine::Table, ExpenseLine::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_expense_line_claim")
+                            .from(ExpenseLine::Table, ExpenseLine::ClaimId)
+                            .to(ExpenseClaim::Table, ExpenseClaim::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_expense_line_category")
+                            .from(ExpenseLine::Table, ExpenseLine::CategoryId)
+                            .to(ExpenseCategory::Table, ExpenseCategory::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ExpenseLine::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ExpenseClaim::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ExpenseCategory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ExpenseCategory {
+    Table,
+    Id,
+    TenantId,
+    Name,
+    Account,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ExpenseClaim {
+    Table,
+    Id,
+    TenantId,
+    EmployeeId,
+    Title,
+    Currency,
+    Total,
+    Status,
+    SubmittedAt,
+    ManagerApprovedBy,
+    ManagerApprovedAt,
+    FinanceApprovedBy,
+    FinanceApprovedAt,
+    RejectedBy,
+    RejectedAt,
+    DecisionNote,
+    ReimbursementMethod,
+    ReimbursementReference,
+    PayrollRunId,
+    ReimbursedAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ExpenseLine {
+    Table,
+    Id,
+    TenantId,
+    ClaimId,
+    CategoryId,
+    ExpenseDate,
+    Description,
+    Amount,
+    Currency,
+    ExchangeRate,
+    ClaimAmount,
+    ReceiptKey,
+    ReceiptType,
+    ReceiptSize,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Company {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Employee {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum PayrollRun {
+    Table,
+    Id,
+}
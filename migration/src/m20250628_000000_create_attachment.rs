@@ -0,0 +1,102 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Attachment::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Attachment::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Attachment::TenantId).char_len(36).not_null())
+                    .col(
+                        ColumnDef::new(Attachment::EntityType)
+                            .string_len(20)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Attachment::EntityId).char_len(36).not_null())
+                    .col(ColumnDef::new(Attachment::FileName).string().not_null())
+                    .col(
+                        ColumnDef::new(Attachment::ContentType)
+                            .string_len(100)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Attachment::Size).integer().not_null())
+                    .col(ColumnDef::new(Attachment::Checksum).char_len(64).not_null())
+                    .col(ColumnDef::new(Attachment::StorageKey).string().not_null())
+                    .col(ColumnDef::new(Attachment::ThumbnailKey).string().null())
+                    .col(
+                        ColumnDef::new(Attachment::UploadedBy)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Attachment::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_attachment_company")
+                            .from(Attachment::Table, Attachment::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_attachment_entity_checksum")
+                            .col(Attachment::TenantId)
+                            .col(Attachment::EntityType)
+                            .col(Attachment::EntityId)
+                            .col(Attachment::Checksum)
+                            .unique(),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_attachment_tenant_checksum")
+                            .col(Attachment::TenantId)
+                            .col(Attachment::Checksum),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Attachment::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Attachment {
+    Table,
+    Id,
+    TenantId,
+    EntityType,
+    EntityId,
+    FileName,
+    ContentType,
+    Size,
+    Checksum,
+    StorageKey,
+    ThumbnailKey,
+    UploadedBy,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Company {
+    Table,
+    Id,
+}
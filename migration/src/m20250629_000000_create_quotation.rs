@@ -0,0 +1,346 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Quotation::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Quotation::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Quotation::TenantId).char_len(36).not_null())
+                    .col(
+                        ColumnDef::new(Quotation::CustomerId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Quotation::Revision)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(ColumnDef::new(Quotation::Status).string_len(20).not_null())
+                    .col(ColumnDef::new(Quotation::ValidUntil).date().not_null())
+                    .col(ColumnDef::new(Quotation::Notes).text().null())
+                    .col(ColumnDef::new(Quotation::Subtotal).double().not_null())
+                    .col(ColumnDef::new(Quotation::DiscountTotal).double().not_null())
+                    .col(ColumnDef::new(Quotation::Total).double().not_null())
+                    .col(ColumnDef::new(Quotation::SentAt).date_time().null())
+                    .col(ColumnDef::new(Quotation::DecidedBy).char_len(36).null())
+                    .col(ColumnDef::new(Quotation::DecidedAt).date_time().null())
+                    .col(ColumnDef::new(Quotation::DecisionNote).text().null())
+                    .col(ColumnDef::new(Quotation::OrderId).char_len(36).null())
+                    .col(ColumnDef::new(Quotation::CreatedBy).char_len(36).not_null())
+                    .col(
+                        ColumnDef::new(Quotation::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Quotation::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_quotation_company")
+                            .from(Quotation::Table, Quotation::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_quotation_tenant_customer")
+                            .col(Quotation::TenantId)
+                            .col(Quotation::CustomerId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(QuotationLine::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(QuotationLine::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(QuotationLine::TenantId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(QuotationLine::QuotationId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(QuotationLine::Position).integer().not_null())
+                    .col(
+                        ColumnDef::new(QuotationLine::InventoryId)
+                            .char_len(36)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(QuotationLine::Description)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(QuotationLine::Quantity).integer().not_null())
+                    .col(ColumnDef::new(QuotationLine::UnitPrice).double().not_null())
+                    .col(
+                        ColumnDef::new(QuotationLine::DiscountPercent)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(ColumnDef::new(QuotationLine::LineTotal).double().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_quotation_line_quotation")
+                            .from(QuotationLine::Table, QuotationLine::QuotationId)
+                            .to(Quotation::Table, Quotation::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_quotation_line_inventory")
+                            .from(QuotationLine::Table, QuotationLine::InventoryId)
+                            .to(Inventory::Table, Inventory::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(QuotationRevision::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(QuotationRevision::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(QuotationRevision::TenantId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(QuotationRevision::QuotationId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(QuotationRevision::Revision)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(QuotationRevision::Snapshot)
+                            .json()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(QuotationRevision::CreatedBy)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(QuotationRevision::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_quotation_revision_quotation")
+                            .from(QuotationRevision::Table, QuotationRevision::QuotationId)
+                            .to(Quotation::Table, Quotation::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_quotation_revision_quotation_revision")
+                            .col(QuotationRevision::QuotationId)
+                            .col(QuotationRevision::Revision)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(OrderLine::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OrderLine::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(OrderLine::TenantId).char_len(36).not_null())
+                    .col(ColumnDef::new(OrderLine::OrderId).char_len(36).not_null())
+                    .col(ColumnDef::new(OrderLine::Position).integer().not_null())
+                    .col(ColumnDef::new(OrderLine::InventoryId).char_len(36).null())
+                    .col(ColumnDef::new(OrderLine::Description).string().not_null())
+                    .col(ColumnDef::new(OrderLine::Quantity).integer().not_null())
+                    .col(ColumnDef::new(OrderLine::UnitPrice).double().not_null())
+                    .col(
+                        ColumnDef::new(OrderLine::DiscountPercent)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(ColumnDef::new(OrderLine::LineTotal).double().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_order_line_order")
+                            .from(OrderLine::Table, OrderLine::OrderId)
+                            .to(Order::Table, Order::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_order_line_inventory")
+                            .from(OrderLine::Table, OrderLine::InventoryId)
+                            .to(Inventory::Table, Inventory::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Order::Table)
+                    .add_column(ColumnDef::new(Order::QuotationId).char_len(36).null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Order::Table)
+                    .drop_column(Order::QuotationId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(OrderLine::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(QuotationRevision::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(QuotationLine::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Quotation::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Quotation {
+    Table,
+    Id,
+    TenantId,
+    CustomerId,
+    Revision,
+    Status,
+    ValidUntil,
+    Notes,
+    Subtotal,
+    DiscountTotal,
+    Total,
+    SentAt,
+    DecidedBy,
+    DecidedAt,
+    DecisionNote,
+    OrderId,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum QuotationLine {
+    Table,
+    Id,
+    TenantId,
+    QuotationId,
+    Position,
+    InventoryId,
+    Description,
+    Quantity,
+    UnitPrice,
+    DiscountPercent,
+    LineTotal,
+}
+
+#[derive(DeriveIden)]
+enum QuotationRevision {
+    Table,
+    Id,
+    TenantId,
+    QuotationId,
+    Revision,
+    Snapshot,
+    CreatedBy,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum OrderLine {
+    Table,
+    Id,
+    TenantId,
+    OrderId,
+    Position,
+    InventoryId,
+    Description,
+    Quantity,
+    UnitPrice,
+    DiscountPercent,
+    LineTotal,
+}
+
+#[derive(DeriveIden)]
+enum Company {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Inventory {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Order {
+    Table,
+    Id,
+    QuotationId,
+}
@@ -0,0 +1,330 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PriceList::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PriceList::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PriceList::TenantId).char_len(36).not_null())
+                    .col(ColumnDef::new(PriceList::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(PriceList::CustomerGroup)
+                            .string_len(50)
+                            .null(),
+                    )
+                    .col(ColumnDef::new(PriceList::Currency).string_len(3).null())
+                    .col(ColumnDef::new(PriceList::ValidFrom).date().null())
+                    .col(ColumnDef::new(PriceList::ValidTo).date().null())
+                    .col(
+                        ColumnDef::new(PriceList::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(PriceList::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_price_list_company")
+                            .from(PriceList::Table, PriceList::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_price_list_tenant_name")
+                            .col(PriceList::TenantId)
+                            .col(PriceList::Name)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(PriceListItem::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PriceListItem::Id)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PriceListItem::TenantId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PriceListItem::PriceListId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PriceListItem::InventoryId)
+                            .char_len(36)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PriceListItem::MinQuantity)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(ColumnDef::new(PriceListItem::UnitPrice).double().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_price_list_item_price_list")
+                            .from(PriceListItem::Table, PriceListItem::PriceListId)
+                            .to(PriceList::Table, PriceList::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_price_list_item_inventory")
+                            .from(PriceListItem::Table, PriceListItem::InventoryId)
+                            .to(Inventory::Table, Inventory::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_price_list_item_list_inventory_quantity")
+                            .col(PriceListItem::PriceListId)
+                            .col(PriceListItem::InventoryId)
+                            .col(PriceListItem::MinQuantity)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(DiscountPolicy::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DiscountPolicy::TenantId)
+                            .char_len(36)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DiscountPolicy::LineLimitPercent)
+                            .double()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(DiscountPolicy::OrderLimitPercent)
+                            .double()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(DiscountPolicy::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_discount_policy_company")
+                            .from(DiscountPolicy::Table, DiscountPolicy::TenantId)
+                            .to(Company::Table, Company::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Customer::Table)
+                    .add_column(
+                        ColumnDef::new(Customer::CustomerGroup)
+                            .string_len(50)
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Order::Table)
+                    .add_column(ColumnDef::new(Order::Currency).string_len(3).null())
+                    .add_column(
+                        ColumnDef::new(Order::DiscountTotal)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .add_column(ColumnDef::new(Order::DiscountKind).string_len(10).null())
+                    .add_column(ColumnDef::new(Order::DiscountValue).double().null())
+                    .add_column(ColumnDef::new(Order::DiscountStatus).string_len(20).null())
+                    .add_column(
+                        ColumnDef::new(Order::DiscountApprovedBy)
+                            .char_len(36)
+                            .null(),
+                    )
+                    .add_column(ColumnDef::new(Order::DiscountApprovedAt).date_time().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OrderLine::Table)
+                    .add_column(
+                        ColumnDef::new(OrderLine::DiscountAmount)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // Lines of orders converted from quotations so far
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(OrderLine::Table)
+                    .value(
+                        OrderLine::DiscountAmount,
+                        Expr::col(OrderLine::Quantity)
+                            .mul(Expr::col(OrderLine::UnitPrice))
+                            .sub(Expr::col(OrderLine::LineTotal)),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OrderLine::Table)
+                    .drop_column(OrderLine::DiscountAmount)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Order::Table)
+                    .drop_column(Order::Currency)
+                    .drop_column(Order::DiscountTotal)
+                    .drop_column(Order::DiscountKind)
+                    .drop_column(Order::DiscountValue)
+                    .drop_column(Order::DiscountStatus)
+                    .drop_column(Order::DiscountApprovedBy)
+                    .drop_column(Order::DiscountApprovedAt)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Customer::Table)
+                    .drop_column(Customer::CustomerGroup)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(DiscountPolicy::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(PriceListItem::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(PriceList::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PriceList {
+    Table,
+    Id,
+    TenantId,
+    Name,
+    CustomerGroup,
+    Currency,
+    ValidFrom,
+    ValidTo,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum PriceListItem {
+    Table,
+    Id,
+    TenantId,
+    PriceListId,
+    InventoryId,
+    MinQuantity,
+    UnitPrice,
+}
+
+#[derive(DeriveIden)]
+enum DiscountPolicy {
+    Table,
+    TenantId,
+    LineLimitPercent,
+    OrderLimitPercent,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Company {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Inventory {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Customer {
+    Table,
+    CustomerGroup,
+}
+
+#[derive(DeriveIden)]
+enum Order {
+    Table,
+    Currency,
+    DiscountTotal,
+    DiscountKind,
+    DiscountValue,
+    DiscountStatus,
+    DiscountApprovedBy,
+    DiscountApprovedAt,
+}
+
+#[derive(DeriveIden)]
+enum OrderLine {
+    Table,
+    Quantity,
+    UnitPrice,
+    DiscountAmount,
+    LineTotal,
+}
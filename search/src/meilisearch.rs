@@ -1,4 +1,11 @@
+use std::time::Duration;
+
 use meilisearch_sdk::client::Client;
+use meilisearch_sdk::documents::{DocumentDeletionQuery, DocumentsQuery};
+
+/// Documents indexed before data belonged to a company carry no tenant, so
+/// no company's search finds them.
+const WITHOUT_TENANT: &str = "tenant_id NOT EXISTS";
 
 pub async fn init_meilisearch(
     host: &str,
@@ -12,10 +19,49 @@ pub async fn configure_index(
     client: &Client,
     index_name: &str,
     searchable_attributes: &[&str],
+    filterable_attributes: &[&str],
 ) -> Result<(), meilisearch_sdk::errors::Error> {
     let index = client.index(index_name);
     index
         .set_searchable_attributes(searchable_attributes)
         .await?;
+    // Searches are always limited to the caller's company
+    let mut filterable = vec!["tenant_id"];
+    filterable.extend_from_slice(filterable_attributes);
+    // Waited for, so the index can be filtered on the tenant right away
+    index
+        .set_filterable_attributes(&filterable)
+        .await?
+        .wait_for_completion(client, None, Some(Duration::from_secs(300)))
+        .await?;
+    Ok(())
+}
+
+/// Whether the index holds documents without a `tenant_id`, which it must be
+/// rebuilt to drop.
+pub async fn has_documents_without_tenant(
+    client: &Client,
+    index_name: &str,
+) -> Result<bool, meilisearch_sdk::errors::Error> {
+    let index = client.index(index_name);
+    let documents = DocumentsQuery::new(&index)
+        .with_filter(WITHOUT_TENANT)
+        .with_limit(1)
+        .execute::<serde_json::Value>()
+        .await?;
+    Ok(!documents.results.is_empty())
+}
+
+/// Removes the documents without a `tenant_id` still left once every row has
+/// been indexed again, i.e. those of rows deleted since.
+pub async fn delete_documents_without_tenant(
+    client: &Client,
+    index_name: &str,
+) -> Result<(), meilisearch_sdk::errors::Error> {
+    let index = client.index(index_name);
+    DocumentDeletionQuery::new(&index)
+        .with_filter(WITHOUT_TENANT)
+        .execute::<serde_json::Value>()
+        .await?;
     Ok(())
 }
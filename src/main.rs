@@ -5,13 +5,22 @@ use actix_web::{
     web,
 };
 use api::{
-    openapi::ApiDoc,
-    v1::{auth, employee, inventory, order},
+    error::{json_config, query_config},
+    events::EventBus,
+    middlewares::{events::EventsMiddleware, request_id::RequestIdMiddleware},
+    openapi::{self, ApiDoc},
+    v1::{
+        attachment, attendance, audit, auth, company, customer, department, employee, events,
+        expense, import, inventory, leave, order, payroll, position, pricing, project, quotation,
+        stock, stock_count, timesheet, traceability, webhook,
+    },
+    webhooks,
 };
 use config::{
     app::{AppConfig, AppState},
     db::Db,
     file_session::FileSessionStore,
+    file_storage::{self, FileStorage},
     inertia::initialize_inertia,
     meilisearch::Meilisearch,
     vite::ASSETS_VERSION,
@@ -50,7 +59,8 @@ async fn main() -> std::io::Result<()> {
 
     for (index_name, p_key) in &config_app.meilisearch_indexes {
         let pk: Vec<&str> = p_key.iter().map(|s| s.as_str()).collect();
-        configure_index(&meili_client, index_name, &pk)
+        let filterable = config_app.filterable_attributes(index_name);
+        configure_index(&meili_client, index_name, &pk, &filterable)
             .await
             .unwrap_or_else(|_| panic!("Failed to configure '{index_name}' index"));
     }
@@ -62,8 +72,31 @@ async fn main() -> std::io::Result<()> {
         jwt_expires_in_seconds: 3600, // Default to 1 hour
         bcrypt_cost: bcrypt::DEFAULT_COST,
         jwt_algorithm: jsonwebtoken::Algorithm::HS256,
+        idempotency_window_seconds: env::var("IDEMPOTENCY_WINDOW_SECONDS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(86400), // Default to 24 hours
+        webhook_allow_private_hosts: env::var("WEBHOOK_ALLOW_PRIVATE_HOSTS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(false),
     };
 
+    // Documents indexed before companies existed are found by no search
+    for index_name in config_app.meilisearch_indexes.keys() {
+        let reindexed = api::reindex::reindex_documents_without_tenant(&app_state, index_name)
+            .await
+            .unwrap_or_else(|_| panic!("Failed to reindex '{index_name}' index"));
+        if reindexed {
+            println!("Reindexed '{index_name}' index");
+        }
+    }
+
+    // Sends queued webhook deliveries in the background
+    webhooks::spawn_dispatcher(app_state.db.clone());
+    // Applies employment records made ahead of time as they take effect
+    employee::services::spawn_employment_sync(app_state.clone());
+
     // starts a Inertia manager instance.
     let inertia = initialize_inertia().await?;
     let inertia = web::Data::new(inertia);
@@ -85,16 +118,50 @@ async fn main() -> std::io::Result<()> {
     let port = listener.local_addr().unwrap().port();
     println!("Server is listening on port {port}");
 
+    // Changes streamed from /v1/events, kept for clients that reconnect
+    let event_bus = web::Data::new(EventBus::default());
+
+    // Receipts, attachments and other uploaded files
+    let file_storage: web::Data<dyn FileStorage> = web::Data::from(file_storage::from_env());
+
     HttpServer::new(move || {
         App::new()
+            .wrap(EventsMiddleware::new())
+            .wrap(RequestIdMiddleware::new())
+            .app_data(json_config())
+            .app_data(query_config())
             .route("/healthcheck", web::get().to(healthcheck))
             // Config for api
             .service(Scalar::with_url("/scalar", ApiDoc::openapi()))
+            .configure(openapi::init_routes)
+            // Ahead of the scopes whose records take attachments
+            .configure(attachment::routes::init_routes)
             .configure(inventory::routes::init_routes)
             .configure(employee::routes::init_routes)
             .configure(order::routes::init_routes)
             .configure(auth::routes::init_routes)
+            .configure(stock::routes::init_routes)
+            .configure(traceability::routes::init_routes)
+            .configure(stock_count::routes::init_routes)
+            .configure(customer::routes::init_routes)
+            .configure(import::routes::init_routes)
+            .configure(audit::routes::init_routes)
+            .configure(company::routes::init_routes)
+            .configure(webhook::routes::init_routes)
+            .configure(events::routes::init_routes)
+            .configure(department::routes::init_routes)
+            .configure(position::routes::init_routes)
+            .configure(leave::routes::init_routes)
+            .configure(project::routes::init_routes)
+            .configure(attendance::routes::init_routes)
+            .configure(timesheet::routes::init_routes)
+            .configure(payroll::routes::init_routes)
+            .configure(expense::routes::init_routes)
+            .configure(quotation::routes::init_routes)
+            .configure(pricing::routes::init_routes)
             .app_data(web::Data::new(app_state.clone()))
+            .app_data(event_bus.clone())
+            .app_data(file_storage.clone())
             // Config for page
             .service(
                 web::scope("/page")
@@ -41,15 +41,104 @@ async fn test_internal_server_error_response_format() {
         reqwest::StatusCode::INTERNAL_SERVER_ERROR
     );
 
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/problem+json"
+    );
+    let request_id = response.headers()["x-request-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+
     let body: serde_json::Value = response
         .json()
         .await
         .expect("Failed to parse error response");
 
     // Verify the structure of the error response
-    assert_eq!(body["error"]["code"], 500);
-    assert!(body["error"]["message"].as_str().is_some());
-    assert_eq!(body["error"]["message"], "Database error");
+    assert_eq!(body["type"], "/problems/internal-server-error");
+    assert_eq!(body["title"], "Internal server error");
+    assert_eq!(body["status"], 500);
+    assert_eq!(body["detail"], "Database error");
+    assert_eq!(body["instance"], "/v1/inventory/create");
+    assert_eq!(body["request_id"], request_id);
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+#[tokio::test]
+async fn test_validation_errors_list_every_field() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+
+    let response = client
+        .post(format!("{server_url}/v1/inventory/create"))
+        .bearer_auth(&token)
+        .header("X-Request-Id", "test-request-42")
+        .json(&json!({
+            "name": "Broken item",
+            "quantity": -1,
+            "price": -5.0
+        }))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    assert_eq!(response.headers()["x-request-id"], "test-request-42");
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["type"], "/problems/validation-error");
+    assert_eq!(body["request_id"], "test-request-42");
+    assert!(body["errors"]["quantity"].is_array());
+    assert!(body["errors"]["price"].is_array());
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+#[tokio::test]
+async fn test_malformed_json_is_a_problem() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+
+    let response = client
+        .post(format!("{server_url}/v1/inventory/create"))
+        .bearer_auth(&token)
+        .header("Content-Type", "application/json")
+        .body(r#"{"name": "Unclosed", "quantity": "#)
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/problem+json"
+    );
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["type"], "/problems/validation-error");
+    assert_eq!(body["instance"], "/v1/inventory/create");
 
     server_handle.stop(true).await;
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
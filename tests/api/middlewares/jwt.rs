@@ -8,6 +8,7 @@ use crate::helper::TestAppBuilder;
 fn create_token(sub: &str, secret: &str, exp: usize) -> String {
     let claims = Claims {
         sub: sub.to_owned(),
+        tenant_id: "company1".to_owned(),
         exp,
     };
     encode(
@@ -1,3 +1,4 @@
 pub mod error;
 pub mod middlewares;
+pub mod openapi;
 pub mod v1;
@@ -0,0 +1,32 @@
+use reqwest::Client as HttpClient;
+use serde_json::Value;
+
+use crate::helper::TestAppBuilder;
+
+#[tokio::test]
+async fn test_openapi_json_is_served() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+
+    let client = HttpClient::new();
+
+    let response = client
+        .get(format!("{server_url}/openapi.json"))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let doc: Value = response.json().await.expect("Failed to parse response");
+    assert!(doc["paths"]["/v1/auth/login"]["post"].is_object());
+    assert!(doc["components"]["securitySchemes"]["bearerAuth"].is_object());
+    assert!(doc["components"]["schemas"]["Problem"].is_object());
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
@@ -0,0 +1,223 @@
+use reqwest::multipart::{Form, Part};
+use reqwest::{Client as HttpClient, StatusCode, header};
+use serde_json::json;
+
+use crate::helper::{TestAppBuilder, create_item, employee_with_login, get_auth_token};
+use api::v1::attachment::models::AttachmentWithUrls;
+
+/// A 2x2 red PNG.
+const PNG: &[u8] = &[
+    137, 80, 78, 71, 13, 10, 26, 10, 0, 0, 0, 13, 73, 72, 68, 82, 0, 0, 0, 2, 0, 0, 0, 2, 8, 2, 0,
+    0, 0, 253, 212, 154, 115, 0, 0, 0, 16, 73, 68, 65, 84, 120, 156, 99, 248, 207, 192, 0, 68, 12,
+    16, 10, 0, 31, 238, 3, 253, 139, 95, 20, 212, 0, 0, 0, 0, 73, 69, 78, 68, 174, 66, 96, 130,
+];
+
+async fn attach(
+    client: &HttpClient,
+    url: &str,
+    token: &str,
+    file_name: &str,
+    content_type: &str,
+    content: &[u8],
+) -> reqwest::Response {
+    let part = Part::bytes(content.to_vec())
+        .file_name(file_name.to_string())
+        .mime_str(content_type)
+        .unwrap();
+    client
+        .post(url)
+        .bearer_auth(token)
+        .multipart(Form::new().part("file", part))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_product_photos_are_deduplicated_and_downloaded_through_signed_links() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let chair = create_item(&client, server_url, &token, "Chair").await;
+    let stool = create_item(&client, server_url, &token, "Stool").await;
+    let chair_url = format!("{server_url}/v1/inventory/{}/attachments", chair.id);
+
+    let response = attach(&client, &chair_url, &token, "photo.png", "image/png", PNG).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let photo: AttachmentWithUrls = response.json().await.unwrap();
+    assert_eq!(photo.attachment.file_name, "photo.png");
+    assert_eq!(photo.attachment.size, PNG.len() as i32);
+    assert_eq!(photo.attachment.checksum.len(), 64);
+    assert!(photo.thumbnail_url.is_some());
+
+    // The same file again is the same attachment
+    let response = attach(&client, &chair_url, &token, "again.png", "image/png", PNG).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let again: AttachmentWithUrls = response.json().await.unwrap();
+    assert_eq!(again.attachment.id, photo.attachment.id);
+
+    let response = attach(
+        &client,
+        &format!("{server_url}/v1/inventory/{}/attachments", stool.id),
+        &token,
+        "stool.png",
+        "image/png",
+        PNG,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let shared: AttachmentWithUrls = response.json().await.unwrap();
+    assert_ne!(shared.attachment.id, photo.attachment.id);
+    assert_eq!(shared.attachment.checksum, photo.attachment.checksum);
+
+    let response = attach(
+        &client,
+        &chair_url,
+        &token,
+        "setup.exe",
+        "application/x-msdownload",
+        b"MZ",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = attach(
+        &client,
+        &chair_url,
+        &token,
+        "fake.png",
+        "image/png",
+        b"not a png",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = attach(
+        &client,
+        &format!("{server_url}/v1/inventory/missing/attachments"),
+        &token,
+        "photo.png",
+        "image/png",
+        PNG,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let listed: Vec<AttachmentWithUrls> = client
+        .get(&chair_url)
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+
+    // Links work without a token, and only as signed
+    let response = client.get(&photo.url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+    assert!(
+        response.headers()[header::CONTENT_DISPOSITION]
+            .to_str()
+            .unwrap()
+            .contains("photo.png")
+    );
+    assert_eq!(&response.bytes().await.unwrap()[..], PNG);
+
+    let response = client
+        .get(photo.thumbnail_url.as_ref().unwrap())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.bytes().await.unwrap().starts_with(b"\x89PNG"));
+
+    let tampered = photo.url.replace("/download?", "/thumbnail?");
+    let response = client.get(&tampered).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let (unsigned, _) = photo.url.split_once("&signature=").unwrap();
+    let response = client
+        .get(format!("{unsigned}&signature={}", "0".repeat(64)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // The stool still has the file after the chair lets go of it
+    let response = client
+        .delete(format!("{chair_url}/{}", photo.attachment.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = client.get(&photo.url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = client.get(&shared.url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_employee_documents_are_kept_from_colleagues() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let (employee, employee_token) =
+        employee_with_login(&client, server_url, &token, "Employee", json!({})).await;
+    let (_, colleague_token) =
+        employee_with_login(&client, server_url, &token, "Colleague", json!({})).await;
+    let documents_url = format!("{server_url}/v1/employee/{}/attachments", employee.id);
+
+    let response = attach(
+        &client,
+        &documents_url,
+        &employee_token,
+        "contract.pdf",
+        "application/pdf",
+        b"%PDF-1.4 contract",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let contract: AttachmentWithUrls = response.json().await.unwrap();
+    assert!(contract.thumbnail_url.is_none());
+
+    let response = client
+        .get(&documents_url)
+        .bearer_auth(&colleague_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = client
+        .get(format!("{documents_url}/{}", contract.attachment.id))
+        .bearer_auth(&colleague_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = client
+        .get(format!(
+            "{server_url}/v1/customer/{}/attachments",
+            employee.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    app.server_handle.stop(true).await;
+}
@@ -0,0 +1,314 @@
+use reqwest::{Client as HttpClient, StatusCode};
+use serde_json::{Value, json};
+
+use api::v1::attendance::models::{Attendance, AttendanceReport};
+use api::v1::department::models::Department;
+use api::v1::project::models::Project;
+use api::v1::timesheet::models::{Timesheet, TimesheetWithEntries};
+use entity::timesheet::TimesheetStatus;
+
+use crate::helper::{TestAppBuilder, employee_with_login, get_auth_token};
+
+async fn book(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    project_id: &str,
+    work_date: &str,
+    hours: f64,
+) -> reqwest::Response {
+    client
+        .post(format!("{server_url}/v1/timesheet/entry"))
+        .bearer_auth(token)
+        .json(&json!({ "project_id": project_id, "work_date": work_date, "hours": hours }))
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn act(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    timesheet_id: &str,
+    action: &str,
+) -> reqwest::Response {
+    client
+        .post(format!("{server_url}/v1/timesheet/{timesheet_id}/{action}"))
+        .bearer_auth(token)
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_clock_in_and_out() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+    let (employee, employee_token) =
+        employee_with_login(&client, server_url, &token, "Staff", json!({})).await;
+
+    let clock = |token: String, action: &'static str| {
+        let client = client.clone();
+        async move {
+            client
+                .post(format!("{server_url}/v1/attendance/{action}"))
+                .bearer_auth(token)
+                .json(&json!({}))
+                .send()
+                .await
+                .unwrap()
+        }
+    };
+
+    // Only logins linked to an employee clock in
+    let response = clock(token.clone(), "clock-in").await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = clock(employee_token.clone(), "clock-in").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let attendance: Attendance = response.json().await.unwrap();
+    assert_eq!(attendance.employee_id, employee.id);
+    assert!(attendance.clock_out.is_none());
+    let response = clock(employee_token.clone(), "clock-in").await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = clock(employee_token.clone(), "clock-out").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let attendance: Attendance = response.json().await.unwrap();
+    assert!(attendance.clock_out.is_some());
+    let response = clock(employee_token.clone(), "clock-out").await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let today = attendance.clock_in.date();
+    let report: Vec<AttendanceReport> = client
+        .get(format!(
+            "{server_url}/v1/attendance/report?from={today}&to={today}"
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let row = report
+        .iter()
+        .find(|r| r.employee_id == employee.id)
+        .unwrap();
+    assert_eq!(row.days_present, 1);
+
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_weekly_timesheet_approval_and_overtime() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let department: Department = client
+        .post(format!("{server_url}/v1/department"))
+        .bearer_auth(&token)
+        .json(&json!({ "name": "Engineering" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let (manager, manager_token) =
+        employee_with_login(&client, server_url, &token, "Manager", json!({})).await;
+    let (employee, employee_token) = employee_with_login(
+        &client,
+        server_url,
+        &token,
+        "Engineer",
+        json!({ "manager_id": manager.id, "department_id": department.id }),
+    )
+    .await;
+    let project: Project = client
+        .post(format!("{server_url}/v1/project"))
+        .bearer_auth(&token)
+        .json(&json!({ "code": "P-001", "name": "Warehouse rollout" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // Ten hours on Monday, eight on Tuesday and three on Saturday
+    for (date, hours) in [
+        ("2030-06-03", 10.0),
+        ("2030-06-04", 8.0),
+        ("2030-06-08", 3.0),
+    ] {
+        let response = book(
+            &client,
+            server_url,
+            &employee_token,
+            &project.id,
+            date,
+            hours,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+    let response = book(
+        &client,
+        server_url,
+        &employee_token,
+        &project.id,
+        "2030-06-04",
+        17.0,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let problem: Value = response.json().await.unwrap();
+    assert!(problem["errors"]["hours"].is_array());
+
+    let timesheets: Vec<Timesheet> = client
+        .get(format!(
+            "{server_url}/v1/timesheet?employee_id={}&week=2030-06-06",
+            employee.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(timesheets.len(), 1);
+    let timesheet_id = timesheets[0].id.clone();
+
+    let response = act(
+        &client,
+        server_url,
+        &employee_token,
+        &timesheet_id,
+        "submit",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let timesheet: Timesheet = response.json().await.unwrap();
+    assert_eq!(timesheet.status, TimesheetStatus::Submitted);
+    assert_eq!(timesheet.regular_hours, 16.0);
+    assert_eq!(timesheet.overtime_hours, 5.0);
+
+    // Submitted weeks are closed, and only the manager decides on them
+    let response = book(
+        &client,
+        server_url,
+        &employee_token,
+        &project.id,
+        "2030-06-05",
+        8.0,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = act(
+        &client,
+        server_url,
+        &employee_token,
+        &timesheet_id,
+        "approve",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // A rejected week is corrected and submitted again
+    let response = act(&client, server_url, &manager_token, &timesheet_id, "reject").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = book(
+        &client,
+        server_url,
+        &employee_token,
+        &project.id,
+        "2030-06-05",
+        8.0,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let timesheet: TimesheetWithEntries = client
+        .get(format!("{server_url}/v1/timesheet/{timesheet_id}"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(timesheet.timesheet.status, TimesheetStatus::Draft);
+    assert_eq!(timesheet.entries.len(), 4);
+    let response = act(
+        &client,
+        server_url,
+        &employee_token,
+        &timesheet_id,
+        "submit",
+    )
+    .await;
+    let timesheet: Timesheet = response.json().await.unwrap();
+    assert_eq!(timesheet.regular_hours, 24.0);
+    let response = act(
+        &client,
+        server_url,
+        &manager_token,
+        &timesheet_id,
+        "approve",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let report: Vec<AttendanceReport> = client
+        .get(format!(
+            "{server_url}/v1/attendance/report?from=2030-06-01&to=2030-06-30&department={}",
+            department.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].employee_id, employee.id);
+    assert_eq!(report[0].regular_hours, 24.0);
+    assert_eq!(report[0].overtime_hours, 5.0);
+    assert_eq!(report[0].projects.len(), 1);
+    assert_eq!(report[0].projects[0].hours, 29.0);
+
+    // Projects with time on them stay, and the policy is for administrators
+    let response = client
+        .delete(format!("{server_url}/v1/project/{}", project.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = client
+        .put(format!("{server_url}/v1/attendance/overtime-policy"))
+        .bearer_auth(&manager_token)
+        .json(&json!({
+            "daily_hours": 7, "weekly_hours": 35, "non_working_days": true, "multiplier": 2
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    app.server_handle.stop(true).await;
+}
@@ -0,0 +1,106 @@
+use fake::{Fake, faker::lorem::en::Sentence};
+use reqwest::Client as HttpClient;
+use serde_json::{Value, json};
+
+use api::v1::inventory::models::InventoryItem;
+
+use crate::helper::{TestAppBuilder, get_auth_token};
+
+#[tokio::test]
+async fn test_inventory_changes_are_audited() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let name: String = Sentence(1..3).fake();
+
+    let item: InventoryItem = client
+        .post(format!("{server_url}/v1/inventory/create"))
+        .bearer_auth(&token)
+        .header("X-Request-Id", "audit-test-create")
+        .json(&json!({ "name": name, "quantity": 5, "price": 10.0 }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let response = client
+        .put(format!("{server_url}/v1/inventory/{}", item.id))
+        .bearer_auth(&token)
+        .header("If-Match", "*")
+        .json(&json!({ "price": 12.5 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let response = client
+        .get(format!(
+            "{server_url}/v1/audit?entity=inventory&id={}&per_page=1",
+            item.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let page: Value = response.json().await.unwrap();
+    assert_eq!(page["total"], 2);
+    assert_eq!(page["total_pages"], 2);
+
+    // Newest first: the price change, with only the changed fields
+    let update = &page["items"][0];
+    assert_eq!(update["action"], "update");
+    assert!(update["actor"].is_string());
+    assert_eq!(update["before"]["price"], 10.0);
+    assert_eq!(update["after"]["price"], 12.5);
+    assert!(update["before"].get("name").is_none());
+
+    let response = client
+        .get(format!(
+            "{server_url}/v1/audit?entity=inventory&id={}&per_page=1&page=2",
+            item.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    let page: Value = response.json().await.unwrap();
+    let create = &page["items"][0];
+    assert_eq!(create["action"], "create");
+    assert_eq!(create["request_id"], "audit-test-create");
+    assert!(create["before"].is_null());
+    assert_eq!(create["after"]["name"], name);
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+#[tokio::test]
+async fn test_audit_log_requires_auth() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+
+    let client = HttpClient::new();
+    let response = client
+        .get(format!("{server_url}/v1/audit"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
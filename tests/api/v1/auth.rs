@@ -667,3 +667,32 @@ async fn test_login_malformed_hash() {
     server_handle.stop(true).await;
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 }
+
+#[tokio::test]
+async fn test_register_rejects_invalid_fields() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+
+    let client = HttpClient::new();
+
+    let response = client
+        .post(format!("{server_url}/v1/auth/register"))
+        .json(&json!({ "username": "ab", "password": "short" }))
+        .send()
+        .await
+        .expect("Failed to send registration request");
+
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["errors"]["username"].is_array());
+    assert!(body["errors"]["password"].is_array());
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
@@ -0,0 +1,216 @@
+use reqwest::{Client as HttpClient, StatusCode};
+use serde_json::{Value, json};
+
+use api::v1::auth::models::TokenResponse;
+use api::v1::company::models::Company;
+use api::v1::inventory::models::InventoryItem;
+
+use crate::helper::{TestAppBuilder, create_item, get_auth_token};
+
+async fn switch_company(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    company_id: &str,
+) -> reqwest::Response {
+    client
+        .post(format!("{server_url}/v1/auth/switch-company"))
+        .bearer_auth(token)
+        .json(&json!({ "company_id": company_id }))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_companies_cannot_see_each_others_data() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    // Every registration comes with its own company
+    let token_a = get_auth_token(&client, server_url, db_pool).await;
+    let token_b = get_auth_token(&client, server_url, db_pool).await;
+
+    let item = create_item(&client, server_url, &token_a, "Desk").await;
+
+    let response = client
+        .get(format!("{server_url}/v1/inventory/{}", item.id))
+        .bearer_auth(&token_b)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = client
+        .put(format!("{server_url}/v1/inventory/{}", item.id))
+        .bearer_auth(&token_b)
+        .header("If-Match", "*")
+        .json(&json!({ "price": 1.0 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let items: Vec<InventoryItem> = client
+        .get(format!("{server_url}/v1/inventory"))
+        .bearer_auth(&token_b)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(items.iter().all(|other| other.id != item.id));
+
+    let audit: Value = client
+        .get(format!(
+            "{server_url}/v1/audit?entity=inventory&id={}",
+            item.id
+        ))
+        .bearer_auth(&token_b)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(audit["total"], 0);
+
+    // The owner still sees it
+    let response = client
+        .get(format!("{server_url}/v1/inventory/{}", item.id))
+        .bearer_auth(&token_a)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_switch_between_companies() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let other_token = get_auth_token(&client, server_url, db_pool).await;
+
+    let second: Company = client
+        .post(format!("{server_url}/v1/company"))
+        .bearer_auth(&token)
+        .json(&json!({ "name": "Second company" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let companies: Vec<Company> = client
+        .get(format!("{server_url}/v1/company"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(companies.len(), 2);
+
+    let response = switch_company(&client, server_url, &token, &second.id).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let second_token = response.json::<TokenResponse>().await.unwrap().token;
+
+    let me: Value = client
+        .get(format!("{server_url}/v1/auth/me"))
+        .bearer_auth(&second_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(me["tenant_id"], second.id.as_str());
+    // Whoever creates a company administers it
+    assert_eq!(me["is_admin"], true);
+
+    // Data created in the second company stays there
+    let item = create_item(&client, server_url, &second_token, "Lamp").await;
+    assert_eq!(item.tenant_id, second.id);
+    let response = client
+        .get(format!("{server_url}/v1/inventory/{}", item.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Outsiders cannot switch in until they are added
+    let response = switch_company(&client, server_url, &other_token, &second.id).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let other: Value = client
+        .get(format!("{server_url}/v1/auth/me"))
+        .bearer_auth(&other_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let response = client
+        .post(format!("{server_url}/v1/company/{}/members", second.id))
+        .bearer_auth(&token)
+        .json(&json!({ "user_id": other["sub"] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = switch_company(&client, server_url, &other_token, &second.id).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let other_second_token = response.json::<TokenResponse>().await.unwrap().token;
+
+    // Administering their own company gives them no say in this one
+    let me: Value = client
+        .get(format!("{server_url}/v1/auth/me"))
+        .bearer_auth(&other_second_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(me["is_admin"], false);
+    let response = client
+        .post(format!("{server_url}/v1/company/{}/members", second.id))
+        .bearer_auth(&other_token)
+        .json(&json!({ "user_id": other["sub"], "role": "admin" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = client
+        .put(format!("{server_url}/v1/pricing/discount-policy"))
+        .bearer_auth(&other_second_token)
+        .json(&json!({ "line_limit_percent": 50.0 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    server_handle.stop(true).await;
+}
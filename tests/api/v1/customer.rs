@@ -0,0 +1,82 @@
+use fake::{
+    Fake,
+    faker::{internet::en::SafeEmail, name::en::Name},
+};
+use reqwest::Client as HttpClient;
+use serde_json::json;
+
+use api::v1::customer::models::Customer;
+
+use crate::helper::{TestAppBuilder, get_auth_token};
+
+#[tokio::test]
+async fn test_customer_crud() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let name: String = Name().fake();
+    let email: String = SafeEmail().fake();
+
+    let response = client
+        .post(format!("{server_url}/v1/customer"))
+        .bearer_auth(&token)
+        .json(&json!({ "name": name, "email": email }))
+        .send()
+        .await
+        .expect("Failed to create customer");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let customer: Customer = response.json().await.unwrap();
+    assert_eq!(customer.email, email);
+
+    // Emails are unique
+    let response = client
+        .post(format!("{server_url}/v1/customer"))
+        .bearer_auth(&token)
+        .json(&json!({ "name": name, "email": email }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CONFLICT);
+
+    let response = client
+        .put(format!("{server_url}/v1/customer/{}", customer.id))
+        .bearer_auth(&token)
+        .header("If-Match", "*")
+        .json(&json!({ "phone": "+62 21 555 0100" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let updated: Customer = response.json().await.unwrap();
+    assert_eq!(updated.phone.as_deref(), Some("+62 21 555 0100"));
+
+    let response = client
+        .delete(format!("{server_url}/v1/customer/{}", customer.id))
+        .bearer_auth(&token)
+        .header("If-Match", "*")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let response = client
+        .get(format!("{server_url}/v1/customer/{}", customer.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
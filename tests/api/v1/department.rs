@@ -0,0 +1,269 @@
+use fake::{Fake, faker::internet::en::SafeEmail};
+use reqwest::{Client as HttpClient, StatusCode};
+use serde_json::{Value, json};
+
+use api::v1::department::models::{Department, Headcount};
+use api::v1::employee::models::{Employee, ReportNode};
+use api::v1::position::models::Position;
+
+use crate::helper::{TestAppBuilder, get_auth_token};
+
+async fn create_department(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    name: &str,
+) -> Department {
+    let response = client
+        .post(format!("{server_url}/v1/department"))
+        .bearer_auth(token)
+        .json(&json!({ "name": name }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    response.json().await.unwrap()
+}
+
+async fn create_employee(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    name: &str,
+    placement: Value,
+) -> Employee {
+    let email: String = SafeEmail().fake();
+    let mut body = json!({ "name": name, "role": "Staff", "email": email });
+    body.as_object_mut()
+        .unwrap()
+        .extend(placement.as_object().unwrap().clone());
+    let response = client
+        .post(format!("{server_url}/v1/employee"))
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    response.json().await.unwrap()
+}
+
+async fn set_manager(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    employee_id: &str,
+    manager_id: Option<&str>,
+) -> reqwest::Response {
+    client
+        .put(format!("{server_url}/v1/employee/{employee_id}"))
+        .bearer_auth(token)
+        .header("If-Match", "*")
+        .json(&json!({ "manager_id": manager_id }))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_reports_are_returned_as_a_tree() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let ceo = create_employee(&client, server_url, &token, "Ceo", json!({})).await;
+    let cto = create_employee(
+        &client,
+        server_url,
+        &token,
+        "Cto",
+        json!({ "manager_id": ceo.id }),
+    )
+    .await;
+    let engineer = create_employee(
+        &client,
+        server_url,
+        &token,
+        "Engineer",
+        json!({ "manager_id": cto.id }),
+    )
+    .await;
+
+    let tree: ReportNode = client
+        .get(format!("{server_url}/v1/employee/{}/reports", ceo.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(tree.employee.id, ceo.id);
+    assert_eq!(tree.reports.len(), 1);
+    assert_eq!(tree.reports[0].employee.id, cto.id);
+    assert_eq!(tree.reports[0].reports[0].employee.id, engineer.id);
+    assert!(tree.reports[0].reports[0].reports.is_empty());
+
+    // A manager with reports cannot be deleted
+    let response = client
+        .delete(format!("{server_url}/v1/employee/{}", cto.id))
+        .bearer_auth(&token)
+        .header("If-Match", "*")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_manager_cycles_are_rejected() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let top = create_employee(&client, server_url, &token, "Top", json!({})).await;
+    let middle = create_employee(
+        &client,
+        server_url,
+        &token,
+        "Middle",
+        json!({ "manager_id": top.id }),
+    )
+    .await;
+    let bottom = create_employee(
+        &client,
+        server_url,
+        &token,
+        "Bottom",
+        json!({ "manager_id": middle.id }),
+    )
+    .await;
+
+    // Neither themselves nor someone below them can manage the top
+    for manager in [&top.id, &bottom.id] {
+        let response = set_manager(&client, server_url, &token, &top.id, Some(manager)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let problem: Value = response.json().await.unwrap();
+        assert!(problem["errors"]["manager_id"].is_array());
+    }
+
+    // Clearing the manager breaks the chain
+    let response = set_manager(&client, server_url, &token, &middle.id, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let middle: Employee = response.json().await.unwrap();
+    assert_eq!(middle.manager_id, None);
+    let response = set_manager(&client, server_url, &token, &top.id, Some(&bottom.id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_department_headcount() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let sales = create_department(&client, server_url, &token, "Sales").await;
+    let empty = create_department(&client, server_url, &token, "Archive").await;
+    let position: Position = client
+        .post(format!("{server_url}/v1/position"))
+        .bearer_auth(&token)
+        .json(&json!({ "title": "Account Manager", "department_id": sales.id }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    for name in ["Ana", "Budi"] {
+        create_employee(
+            &client,
+            server_url,
+            &token,
+            name,
+            json!({ "department_id": sales.id, "position_id": position.id }),
+        )
+        .await;
+    }
+    create_employee(&client, server_url, &token, "Citra", json!({})).await;
+
+    // Employees who left are no longer counted
+    let left = create_employee(
+        &client,
+        server_url,
+        &token,
+        "Eko",
+        json!({ "department_id": sales.id, "hire_date": "2024-01-01" }),
+    )
+    .await;
+    let response = client
+        .post(format!("{server_url}/v1/employee/{}/history", left.id))
+        .bearer_auth(&token)
+        .json(
+            &json!({ "kind": "termination", "effective_date": "2025-01-31", "reason": "Resigned" }),
+        )
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // The position belongs to Sales
+    let email: String = SafeEmail().fake();
+    let response = client
+        .post(format!("{server_url}/v1/employee"))
+        .bearer_auth(&token)
+        .json(&json!({
+            "name": "Dewi", "role": "Staff", "email": email,
+            "department_id": empty.id, "position_id": position.id
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let headcount: Vec<Headcount> = client
+        .get(format!("{server_url}/v1/department/headcount"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let count = |id: Option<&str>| {
+        headcount
+            .iter()
+            .find(|h| h.department_id.as_deref() == id)
+            .map(|h| h.headcount)
+    };
+    assert_eq!(count(Some(&sales.id)), Some(2));
+    assert_eq!(count(Some(&empty.id)), Some(0));
+    assert_eq!(count(None), Some(1));
+
+    // Departments in use are kept
+    let response = client
+        .delete(format!("{server_url}/v1/department/{}", sales.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    app.server_handle.stop(true).await;
+}
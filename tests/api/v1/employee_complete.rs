@@ -205,6 +205,7 @@ async fn test_update_employee() {
     let response = client
         .put(format!("{server_url}/v1/employee/{employee_id}"))
         .bearer_auth(&token)
+        .header("If-Match", "*")
         .json(&updated_data)
         .send()
         .await
@@ -246,6 +247,7 @@ async fn test_update_nonexistent_employee() {
     let response = client
         .put(format!("{server_url}/v1/employee/{nonexistent_id}"))
         .bearer_auth(&token)
+        .header("If-Match", "*")
         .json(&updated_data)
         .send()
         .await
@@ -301,6 +303,7 @@ async fn test_update_employee_invalid_email() {
     let response = client
         .put(format!("{server_url}/v1/employee/{employee_id}"))
         .bearer_auth(&token)
+        .header("If-Match", "*")
         .json(&updated_data)
         .send()
         .await
@@ -348,6 +351,7 @@ async fn test_delete_employee() {
     let response = client
         .delete(format!("{server_url}/v1/employee/{employee_id}"))
         .bearer_auth(&token)
+        .header("If-Match", "*")
         .send()
         .await
         .expect("Failed to send DELETE request");
@@ -388,6 +392,7 @@ async fn test_delete_nonexistent_employee() {
     let response = client
         .delete(format!("{server_url}/v1/employee/{nonexistent_id}"))
         .bearer_auth(&token)
+        .header("If-Match", "*")
         .send()
         .await
         .expect("Failed to send DELETE request");
@@ -0,0 +1,239 @@
+use fake::{Fake, faker::internet::en::SafeEmail};
+use reqwest::{Client as HttpClient, StatusCode};
+use serde_json::{Value, json};
+
+use api::v1::department::models::Department;
+use api::v1::employee::models::{Employee, EmployeeAsOf, EmploymentHistory};
+
+use crate::helper::{TestAppBuilder, employee_with_login, get_auth_token};
+
+async fn add_record(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    employee_id: &str,
+    record: Value,
+) -> reqwest::Response {
+    client
+        .post(format!("{server_url}/v1/employee/{employee_id}/history"))
+        .bearer_auth(token)
+        .json(&record)
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn employed_as_of(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    query: &str,
+) -> Vec<EmployeeAsOf> {
+    client
+        .get(format!("{server_url}/v1/employee/as-of?{query}"))
+        .bearer_auth(token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_history_answers_who_was_where() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let sales: Department = client
+        .post(format!("{server_url}/v1/department"))
+        .bearer_auth(&token)
+        .json(&json!({ "name": "Sales" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let email: String = SafeEmail().fake();
+    let employee: Employee = client
+        .post(format!("{server_url}/v1/employee"))
+        .bearer_auth(&token)
+        .json(&json!({
+            "name": "Timeline", "role": "Clerk", "email": email,
+            "hire_date": "2025-01-06", "salary": 5000000.0
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let response = add_record(
+        &client,
+        server_url,
+        &token,
+        &employee.id,
+        json!({ "kind": "transfer", "effective_date": "2025-02-15", "department_id": sales.id }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = add_record(
+        &client,
+        server_url,
+        &token,
+        &employee.id,
+        json!({ "kind": "termination", "effective_date": "2025-04-01", "reason": "Resigned" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // Past changes are copied onto the employee
+    let current: Employee = client
+        .get(format!("{server_url}/v1/employee/{}", employee.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(current.department_id.as_deref(), Some(sales.id.as_str()));
+
+    let in_sales = format!("department_id={}", sales.id);
+    let march = employed_as_of(
+        &client,
+        server_url,
+        &token,
+        &format!("date=2025-03-01&{in_sales}"),
+    )
+    .await;
+    assert_eq!(march.len(), 1);
+    assert_eq!(march[0].employee.id, employee.id);
+    assert_eq!(march[0].employment.salary, Some(5000000.0));
+    let january = employed_as_of(
+        &client,
+        server_url,
+        &token,
+        &format!("date=2025-01-31&{in_sales}"),
+    )
+    .await;
+    assert!(january.is_empty());
+    let april = employed_as_of(&client, server_url, &token, "date=2025-04-15").await;
+    assert!(april.iter().all(|e| e.employee.id != employee.id));
+
+    // Nothing can happen between the termination and a rehire
+    let response = add_record(
+        &client,
+        server_url,
+        &token,
+        &employee.id,
+        json!({ "kind": "salary_change", "effective_date": "2025-05-01", "salary": 6000000.0 }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let problem: Value = response.json().await.unwrap();
+    assert!(problem["errors"]["effective_date"].is_array());
+
+    let history: EmploymentHistory = client
+        .get(format!(
+            "{server_url}/v1/employee/{}/history?as_of=2025-03-01",
+            employee.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(history.entries.len(), 3);
+    assert!(history.state.employed);
+    assert_eq!(
+        history.state.department_id.as_deref(),
+        Some(sales.id.as_str())
+    );
+    let last = &history.entries[2].state;
+    assert!(!last.employed);
+    assert_eq!(last.termination_reason.as_deref(), Some("Resigned"));
+
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_termination_disables_the_login_until_rehire() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+    let (employee, staff_token) = employee_with_login(
+        &client,
+        server_url,
+        &token,
+        "Leaver",
+        json!({ "hire_date": "2025-01-06" }),
+    )
+    .await;
+    let login = || {
+        client
+            .post(format!("{server_url}/v1/auth/login"))
+            .json(&json!({
+                "username": format!("staff_{}", &employee.id[..8]),
+                "password": "password123"
+            }))
+            .send()
+    };
+
+    let response = add_record(
+        &client,
+        server_url,
+        &token,
+        &employee.id,
+        json!({ "kind": "termination", "effective_date": "2025-06-30", "reason": "Resigned" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = client
+        .get(format!("{server_url}/v1/auth/me"))
+        .bearer_auth(&staff_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(login().await.unwrap().status(), StatusCode::UNAUTHORIZED);
+
+    let response = add_record(
+        &client,
+        server_url,
+        &token,
+        &employee.id,
+        json!({ "kind": "hire", "effective_date": "2025-09-01" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(login().await.unwrap().status(), StatusCode::OK);
+
+    // A termination ahead of time leaves the login alone until then
+    let response = add_record(
+        &client,
+        server_url,
+        &token,
+        &employee.id,
+        json!({ "kind": "termination", "effective_date": "2999-12-31", "reason": "Retiring" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(login().await.unwrap().status(), StatusCode::OK);
+
+    app.server_handle.stop(true).await;
+}
@@ -0,0 +1,259 @@
+use std::time::Duration;
+
+use reqwest::{Client as HttpClient, Response, StatusCode};
+use serde_json::{Value, json};
+
+use crate::helper::{TestAppBuilder, create_item, employee_with_login, get_auth_token};
+
+/// One parsed Server-Sent Event.
+#[derive(Debug)]
+struct Event {
+    id: Option<String>,
+    event: String,
+    data: Value,
+}
+
+/// Reads events from an open stream.
+struct EventReader {
+    response: Response,
+    buffer: String,
+}
+
+impl EventReader {
+    async fn open(
+        client: &HttpClient,
+        url: &str,
+        token: &str,
+        last_event_id: Option<&str>,
+    ) -> EventReader {
+        let mut request = client.get(url).bearer_auth(token);
+        if let Some(id) = last_event_id {
+            request = request.header("Last-Event-ID", id);
+        }
+        let response = request.send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(
+            response.headers()["content-type"]
+                .to_str()
+                .unwrap()
+                .starts_with("text/event-stream")
+        );
+        EventReader {
+            response,
+            buffer: String::new(),
+        }
+    }
+
+    /// Next named event, or `None` when nothing arrives in time.
+    async fn next(&mut self, wait: Duration) -> Option<Event> {
+        let deadline = tokio::time::Instant::now() + wait;
+        loop {
+            while let Some(end) = self.buffer.find("\n\n") {
+                let frame: String = self.buffer.drain(..end + 2).collect();
+                if let Some(event) = parse(&frame) {
+                    return Some(event);
+                }
+            }
+            let chunk = tokio::time::timeout_at(deadline, self.response.chunk())
+                .await
+                .ok()?
+                .unwrap()?;
+            self.buffer.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    }
+
+    async fn expect(&mut self, name: &str) -> Event {
+        let event = self
+            .next(Duration::from_secs(10))
+            .await
+            .unwrap_or_else(|| panic!("No {name} event received"));
+        assert_eq!(event.event, name, "unexpected event {event:?}");
+        event
+    }
+}
+
+/// Frames without an `event` field (retry hints, keep-alives) are skipped.
+fn parse(frame: &str) -> Option<Event> {
+    let mut id = None;
+    let mut event = None;
+    let mut data = String::new();
+    for line in frame.lines() {
+        if let Some(value) = line.strip_prefix("id: ") {
+            id = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("event: ") {
+            event = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("data: ") {
+            data.push_str(value);
+        }
+    }
+    Some(Event {
+        id,
+        event: event?,
+        data: serde_json::from_str(&data).unwrap_or(Value::Null),
+    })
+}
+
+#[tokio::test]
+async fn test_changes_are_streamed_to_their_company() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+    let other_token = get_auth_token(&client, server_url, &app.db).await;
+    let events_url = format!("{server_url}/v1/events?types=inventory");
+
+    let mut stream = EventReader::open(&client, &events_url, &token, None).await;
+    let mut other_stream = EventReader::open(&client, &events_url, &other_token, None).await;
+
+    let item = create_item(&client, server_url, &token, "Streamed item").await;
+    let event = stream.expect("inventory.created").await;
+    assert!(event.id.is_some());
+    assert_eq!(event.data["entity"], "inventory");
+    assert_eq!(event.data["id"], item.id.as_str());
+    assert_eq!(event.data["data"]["name"], "Streamed item");
+
+    let response = client
+        .delete(format!("{server_url}/v1/inventory/{}", item.id))
+        .bearer_auth(&token)
+        .header("If-Match", "*")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    stream.expect("inventory.deleted").await;
+
+    // Another company sees none of it
+    assert!(other_stream.next(Duration::from_secs(1)).await.is_none());
+
+    // Open streams would hold up a graceful stop
+    drop(stream);
+    drop(other_stream);
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_stream_resumes_after_last_event_id() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+    let events_url = format!("{server_url}/v1/events");
+
+    let mut stream = EventReader::open(&client, &events_url, &token, None).await;
+    create_item(&client, server_url, &token, "First").await;
+    let first = stream.expect("inventory.created").await;
+    drop(stream);
+
+    // Changes made while disconnected are replayed on reconnect
+    create_item(&client, server_url, &token, "Second").await;
+    let mut stream = EventReader::open(&client, &events_url, &token, first.id.as_deref()).await;
+    let second = stream.expect("inventory.created").await;
+    assert_eq!(second.data["data"]["name"], "Second");
+    drop(stream);
+
+    // Ids the server cannot resume from ask the client to reload
+    let mut stream = EventReader::open(&client, &events_url, &token, Some("unknown-1")).await;
+    stream.expect("reset").await;
+
+    drop(stream);
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_failed_changes_are_not_streamed() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let mut stream =
+        EventReader::open(&client, &format!("{server_url}/v1/events"), &token, None).await;
+
+    // An atomic batch with a failing operation is rolled back entirely
+    let response = client
+        .post(format!("{server_url}/v1/inventory/batch"))
+        .bearer_auth(&token)
+        .json(&json!({
+            "operations": [
+                { "op": "create", "data": { "name": "Rolled back", "quantity": 1, "price": 1.0 } },
+                { "op": "delete", "id": "missing" }
+            ]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(stream.next(Duration::from_secs(1)).await.is_none());
+
+    let response = client
+        .get(format!("{server_url}/v1/events?types=webhook"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    drop(stream);
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_employee_changes_reach_only_who_may_read_them() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+    let (manager, manager_token) =
+        employee_with_login(&client, server_url, &token, "Manager", json!({})).await;
+    let (report, _) = employee_with_login(
+        &client,
+        server_url,
+        &token,
+        "Report",
+        json!({ "manager_id": manager.id }),
+    )
+    .await;
+    let (_, colleague_token) =
+        employee_with_login(&client, server_url, &token, "Colleague", json!({})).await;
+    let events_url = format!("{server_url}/v1/events?types=employee,inventory");
+
+    let mut admin_stream = EventReader::open(&client, &events_url, &token, None).await;
+    let mut manager_stream = EventReader::open(&client, &events_url, &manager_token, None).await;
+    let mut colleague_stream =
+        EventReader::open(&client, &events_url, &colleague_token, None).await;
+
+    let response = client
+        .put(format!("{server_url}/v1/employee/{}", report.id))
+        .bearer_auth(&token)
+        .header("If-Match", "*")
+        .json(&json!({ "role": "Senior Staff" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    create_item(&client, server_url, &token, "Shared item").await;
+
+    let event = admin_stream.expect("employee.updated").await;
+    assert_eq!(event.data["id"], report.id.as_str());
+    let event = manager_stream.expect("employee.updated").await;
+    assert_eq!(event.data["id"], report.id.as_str());
+    // Colleagues only see what everyone in the company may see
+    colleague_stream.expect("inventory.created").await;
+
+    drop(admin_stream);
+    drop(manager_stream);
+    drop(colleague_stream);
+    app.server_handle.stop(true).await;
+}
@@ -0,0 +1,529 @@
+use chrono::{Datelike, NaiveDate, Utc};
+use reqwest::{Client as HttpClient, StatusCode, header};
+use serde_json::{Value, json};
+
+use api::v1::expense::models::{ClaimWithLines, ExpenseCategory, ExpenseClaim, ExpenseLine};
+use api::v1::payroll::models::{PayrollRun, PayrollRunWithPayslips, PayslipWithLines};
+use entity::expense_claim::{ClaimStatus, ReimbursementMethod};
+
+use crate::helper::{TestAppBuilder, employee_with_login, get_auth_token};
+
+async fn add_category(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    name: &str,
+    account: &str,
+) -> ExpenseCategory {
+    let response = client
+        .post(format!("{server_url}/v1/expense/category"))
+        .bearer_auth(token)
+        .json(&json!({ "name": name, "account": account }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    response.json().await.unwrap()
+}
+
+async fn act(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    claim_id: &str,
+    action: &str,
+    body: Value,
+) -> reqwest::Response {
+    client
+        .post(format!("{server_url}/v1/expense/claim/{claim_id}/{action}"))
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn upload_receipt(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    line: &ExpenseLine,
+    content_type: &str,
+    content: &'static [u8],
+) -> reqwest::Response {
+    client
+        .put(format!(
+            "{server_url}/v1/expense/claim/{}/line/{}/receipt",
+            line.claim_id, line.id
+        ))
+        .bearer_auth(token)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(content)
+        .send()
+        .await
+        .unwrap()
+}
+
+/// A submitted claim of one receipted expense.
+async fn submitted_claim(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    category: &ExpenseCategory,
+    amount: f64,
+) -> ExpenseClaim {
+    let claim: ExpenseClaim = client
+        .post(format!("{server_url}/v1/expense/claim"))
+        .bearer_auth(token)
+        .json(&json!({ "title": "Client visit", "currency": "IDR" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let line: ExpenseLine = client
+        .post(format!("{server_url}/v1/expense/claim/{}/line", claim.id))
+        .bearer_auth(token)
+        .json(&json!({
+            "category_id": category.id, "expense_date": "2025-06-10",
+            "description": "Taxi", "amount": amount
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let response = upload_receipt(client, server_url, token, &line, "image/png", b"png").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = act(client, server_url, token, &claim.id, "submit", json!({})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_claim_is_approved_by_manager_then_finance() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let (manager, manager_token) =
+        employee_with_login(&client, server_url, &token, "Manager", json!({})).await;
+    let (employee, employee_token) = employee_with_login(
+        &client,
+        server_url,
+        &token,
+        "Employee",
+        json!({ "manager_id": manager.id }),
+    )
+    .await;
+    let travel = add_category(&client, server_url, &token, "Travel", "6300").await;
+
+    let response = client
+        .post(format!("{server_url}/v1/expense/category"))
+        .bearer_auth(&employee_token)
+        .json(&json!({ "name": "Meals", "account": "6310" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = client
+        .post(format!("{server_url}/v1/expense/claim"))
+        .bearer_auth(&employee_token)
+        .json(&json!({ "title": "Singapore trip", "currency": "IDR" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let claim: ExpenseClaim = response.json().await.unwrap();
+    assert_eq!(claim.employee_id, employee.id);
+    assert_eq!(claim.status, ClaimStatus::Draft);
+
+    // Foreign currency needs a rate
+    let hotel = json!({
+        "category_id": travel.id, "expense_date": "2025-06-10",
+        "description": "Hotel", "amount": 200.0, "currency": "SGD"
+    });
+    let response = client
+        .post(format!("{server_url}/v1/expense/claim/{}/line", claim.id))
+        .bearer_auth(&employee_token)
+        .json(&hotel)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["errors"]["exchange_rate"].is_array());
+
+    let mut hotel = hotel;
+    hotel["exchange_rate"] = json!(12000.0);
+    let response = client
+        .post(format!("{server_url}/v1/expense/claim/{}/line", claim.id))
+        .bearer_auth(&employee_token)
+        .json(&hotel)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let line: ExpenseLine = response.json().await.unwrap();
+    assert_eq!(line.claim_amount, 2400000.0);
+
+    let response = act(
+        &client,
+        server_url,
+        &employee_token,
+        &claim.id,
+        "submit",
+        json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["errors"]["lines"].is_array());
+
+    let response = upload_receipt(
+        &client,
+        server_url,
+        &employee_token,
+        &line,
+        "text/plain",
+        b"hotel",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let receipt = b"%PDF-1.4 hotel invoice";
+    let response = upload_receipt(
+        &client,
+        server_url,
+        &employee_token,
+        &line,
+        "application/pdf",
+        receipt,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let line: ExpenseLine = response.json().await.unwrap();
+    assert_eq!(line.receipt_size, Some(receipt.len() as i32));
+
+    let response = client
+        .get(format!(
+            "{server_url}/v1/expense/claim/{}/line/{}/receipt",
+            claim.id, line.id
+        ))
+        .bearer_auth(&manager_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/pdf");
+    assert_eq!(&response.bytes().await.unwrap()[..], receipt);
+
+    let response = act(
+        &client,
+        server_url,
+        &employee_token,
+        &claim.id,
+        "submit",
+        json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = client
+        .post(format!("{server_url}/v1/expense/claim/{}/line", claim.id))
+        .bearer_auth(&employee_token)
+        .json(&hotel)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Nobody approves their own claim, and finance waits for the manager
+    let response = act(
+        &client,
+        server_url,
+        &employee_token,
+        &claim.id,
+        "approve",
+        json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = act(&client, server_url, &token, &claim.id, "approve", json!({})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let approved: ExpenseClaim = response.json().await.unwrap();
+    assert_eq!(approved.status, ClaimStatus::ManagerApproved);
+    let response = act(
+        &client,
+        server_url,
+        &manager_token,
+        &claim.id,
+        "approve",
+        json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = act(
+        &client,
+        server_url,
+        &token,
+        &claim.id,
+        "reimburse",
+        json!({ "method": "payment", "reference": "TRF-001" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = act(&client, server_url, &token, &claim.id, "approve", json!({})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let approved: ExpenseClaim = response.json().await.unwrap();
+    assert_eq!(approved.status, ClaimStatus::Approved);
+    assert!(approved.finance_approved_by.is_some());
+
+    let response = act(
+        &client,
+        server_url,
+        &manager_token,
+        &claim.id,
+        "reimburse",
+        json!({ "method": "payment" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = act(
+        &client,
+        server_url,
+        &token,
+        &claim.id,
+        "reimburse",
+        json!({ "method": "payment", "reference": "TRF-001" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let reimbursed: ExpenseClaim = response.json().await.unwrap();
+    assert_eq!(reimbursed.status, ClaimStatus::Reimbursed);
+    assert_eq!(
+        reimbursed.reimbursement_method,
+        Some(ReimbursementMethod::Payment)
+    );
+
+    let detail: ClaimWithLines = client
+        .get(format!("{server_url}/v1/expense/claim/{}", claim.id))
+        .bearer_auth(&employee_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(detail.claim.total, 2400000.0);
+    assert_eq!(detail.lines.len(), 1);
+
+    // Claims in use keep their category
+    let response = client
+        .delete(format!("{server_url}/v1/expense/category/{}", travel.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = client
+        .get(format!(
+            "{server_url}/v1/expense/export?status=reimbursed&format=csv"
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let csv = response.text().await.unwrap();
+    assert!(csv.starts_with("id,employee_id,title"));
+    assert!(csv.contains("TRF-001"));
+
+    // Employees with claims are never purged
+    let employee_url = format!("{server_url}/v1/employee/{}", employee.id);
+    let response = client
+        .delete(&employee_url)
+        .bearer_auth(&token)
+        .header(header::IF_MATCH, "*")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = client
+        .delete(format!("{employee_url}/purge"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_rejected_claim_is_reopened_and_paid_through_payroll() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let (employee, employee_token) = employee_with_login(
+        &client,
+        server_url,
+        &token,
+        "Employee",
+        json!({ "hire_date": "2025-01-06" }),
+    )
+    .await;
+    let meals = add_category(&client, server_url, &token, "Meals", "6310").await;
+
+    let claim = submitted_claim(&client, server_url, &employee_token, &meals, 150000.0).await;
+    assert_eq!(claim.status, ClaimStatus::Submitted);
+    let response = act(
+        &client,
+        server_url,
+        &token,
+        &claim.id,
+        "reject",
+        json!({ "note": "Receipt is unreadable" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let rejected: ExpenseClaim = response.json().await.unwrap();
+    assert_eq!(rejected.status, ClaimStatus::Rejected);
+    assert_eq!(
+        rejected.decision_note.as_deref(),
+        Some("Receipt is unreadable")
+    );
+
+    // A new receipt reopens the claim for another submission
+    let detail: ClaimWithLines = client
+        .get(format!("{server_url}/v1/expense/claim/{}", claim.id))
+        .bearer_auth(&employee_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let response = upload_receipt(
+        &client,
+        server_url,
+        &employee_token,
+        &detail.lines[0],
+        "image/jpeg",
+        b"jpeg",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = act(
+        &client,
+        server_url,
+        &employee_token,
+        &claim.id,
+        "submit",
+        json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    for _ in 0..2 {
+        let response = act(&client, server_url, &token, &claim.id, "approve", json!({})).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = act(
+        &client,
+        server_url,
+        &token,
+        &claim.id,
+        "reimburse",
+        json!({ "method": "payroll" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let queued: ExpenseClaim = response.json().await.unwrap();
+    assert_eq!(queued.status, ClaimStatus::Approved);
+
+    let today = Utc::now().date_naive();
+    let period_start = today.with_day(1).unwrap();
+    let next_month = if today.month() == 12 {
+        NaiveDate::from_ymd_opt(today.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(today.year(), today.month() + 1, 1)
+    }
+    .unwrap();
+    let period_end = next_month.pred_opt().unwrap();
+    let response = client
+        .post(format!("{server_url}/v1/payroll/run"))
+        .bearer_auth(&token)
+        .json(&json!({ "period_start": period_start, "period_end": period_end }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let run: PayrollRun = response.json().await.unwrap();
+    assert_eq!(run.net, 150000.0);
+
+    let detail: PayrollRunWithPayslips = client
+        .get(format!("{server_url}/v1/payroll/run/{}", run.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let payslip = detail
+        .payslips
+        .iter()
+        .find(|p| p.employee_id == employee.id)
+        .unwrap();
+    let payslip: PayslipWithLines = client
+        .get(format!("{server_url}/v1/payroll/payslip/{}", payslip.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let expenses = payslip.lines.iter().find(|l| l.code == "EXPENSES").unwrap();
+    assert_eq!(expenses.name, "Expenses: Meals");
+    assert_eq!(expenses.account, "6310");
+
+    for (action, body) in [
+        ("approve", json!({})),
+        ("post", json!({ "net_pay_account": "2100" })),
+    ] {
+        let response = client
+            .post(format!("{server_url}/v1/payroll/run/{}/{action}", run.id))
+            .bearer_auth(&token)
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let paid: ClaimWithLines = client
+        .get(format!("{server_url}/v1/expense/claim/{}", claim.id))
+        .bearer_auth(&employee_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(paid.claim.status, ClaimStatus::Reimbursed);
+    assert_eq!(paid.claim.payroll_run_id.as_deref(), Some(run.id.as_str()));
+
+    app.server_handle.stop(true).await;
+}
@@ -0,0 +1,169 @@
+use reqwest::Client as HttpClient;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde_json::Value;
+use uuid::Uuid;
+
+use api::v1::import::models::ImportReport;
+use entity::{inventory, stock_movement};
+
+use crate::helper::{TestAppBuilder, get_auth_token};
+
+async fn post_csv(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    path: &str,
+    body: String,
+) -> reqwest::Response {
+    client
+        .post(format!("{server_url}/v1/import/{path}"))
+        .bearer_auth(token)
+        .header("Content-Type", "text/csv")
+        .body(body)
+        .send()
+        .await
+        .expect("Failed to send import")
+}
+
+#[tokio::test]
+async fn test_inventory_import_upserts_by_sku() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let sku_a = format!("A-{}", Uuid::new_v4());
+    let sku_b = format!("B-{}", Uuid::new_v4());
+
+    let csv = format!("sku,name,quantity,price\n{sku_a},Bolt,10,1.5\n{sku_b},Nut,4,0.5\n");
+
+    // A dry run reports without writing anything
+    let response = post_csv(
+        &client,
+        server_url,
+        &token,
+        "inventory?dry_run=true",
+        csv.clone(),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let report: ImportReport = response.json().await.unwrap();
+    assert_eq!(report.created, 2);
+    assert!(!report.applied);
+
+    let stored = inventory::Entity::find()
+        .filter(inventory::Column::Sku.eq(&sku_a))
+        .one(db_pool)
+        .await
+        .unwrap();
+    assert!(stored.is_none());
+
+    let response = post_csv(&client, server_url, &token, "inventory", csv).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let report: ImportReport = response.json().await.unwrap();
+    assert!(report.applied);
+    assert_eq!(report.created, 2);
+
+    // Re-importing the same SKU updates the item and books the quantity difference
+    let csv = format!("sku,name,quantity,price\n{sku_a},Hex bolt,12,1.75\n");
+    let response = post_csv(&client, server_url, &token, "inventory", csv).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let report: ImportReport = response.json().await.unwrap();
+    assert_eq!(report.updated, 1);
+    assert_eq!(report.created, 0);
+
+    let item = inventory::Entity::find()
+        .filter(inventory::Column::Sku.eq(&sku_a))
+        .one(db_pool)
+        .await
+        .unwrap()
+        .expect("Imported item not found");
+    assert_eq!(item.name, "Hex bolt");
+    assert_eq!(item.quantity, 12);
+
+    let adjustments = stock_movement::Entity::find()
+        .filter(stock_movement::Column::InventoryId.eq(&item.id))
+        .filter(stock_movement::Column::Reason.eq("import"))
+        .all(db_pool)
+        .await
+        .unwrap();
+    assert_eq!(adjustments.len(), 1);
+    assert_eq!(adjustments[0].quantity, 2);
+
+    // Both imports are audited, the dry run is not
+    let page: Value = client
+        .get(format!(
+            "{server_url}/v1/audit?entity=inventory&id={}",
+            item.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(page["total"], 2);
+    let update = &page["items"][0];
+    assert_eq!(update["action"], "update");
+    assert_eq!(update["before"]["price"], 1.5);
+    assert_eq!(update["after"]["price"], 1.75);
+    assert_eq!(update["before"]["quantity"], 10);
+    assert_eq!(update["after"]["quantity"], 12);
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+#[tokio::test]
+async fn test_import_row_errors_reject_batch() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let email = format!("{}@example.com", Uuid::new_v4());
+
+    let csv =
+        format!("name,email,phone\nGood Customer,{email},021555\nNo Email,,\nDuplicate,{email},\n");
+    let response = post_csv(&client, server_url, &token, "customer", csv).await;
+    assert_eq!(response.status(), reqwest::StatusCode::UNPROCESSABLE_ENTITY);
+
+    let report: ImportReport = response.json().await.unwrap();
+    assert!(!report.applied);
+    assert_eq!(report.total_rows, 3);
+    let rows: Vec<usize> = report.errors.iter().map(|e| e.row).collect();
+    assert_eq!(rows, vec![3, 4]);
+
+    let stored = entity::customer::Entity::find()
+        .filter(entity::customer::Column::Email.eq(&email))
+        .one(db_pool)
+        .await
+        .unwrap();
+    assert!(stored.is_none());
+
+    let response = post_csv(
+        &client,
+        server_url,
+        &token,
+        "supplier",
+        "name\nAcme\n".into(),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
@@ -3,11 +3,14 @@ use fake::{
     faker::lorem::en::{Sentence, Word},
 };
 use reqwest::Client as HttpClient;
+use sea_orm::EntityTrait;
 use serde_json::json;
 
+use api::batch::BatchResponse;
 use api::v1::inventory::models::InventoryItem;
+use entity::inventory;
 
-use crate::helper::{TestAppBuilder, get_auth_token};
+use crate::helper::{TestAppBuilder, employee_with_login, get_auth_token};
 use uuid::Uuid;
 
 #[tokio::test]
@@ -192,6 +195,7 @@ async fn test_update() {
     let response = client
         .put(format!("{}/v1/inventory/{}", server_url, created_item.id))
         .bearer_auth(token)
+        .header("If-Match", "*")
         .json(&updated_data)
         .send()
         .await
@@ -250,6 +254,7 @@ async fn test_update_negative_quantity() {
     let response = client
         .put(format!("{}/v1/inventory/{}", server_url, created_item.id))
         .bearer_auth(token)
+        .header("If-Match", "*")
         .json(&updated_data)
         .send()
         .await
@@ -302,6 +307,7 @@ async fn test_update_negative_price() {
     let response = client
         .put(format!("{}/v1/inventory/{}", server_url, created_item.id))
         .bearer_auth(token)
+        .header("If-Match", "*")
         .json(&updated_data)
         .send()
         .await
@@ -353,6 +359,7 @@ async fn test_delete() {
     let response = client
         .delete(format!("{}/v1/inventory/{}", server_url, created_item.id))
         .bearer_auth(token.clone())
+        .header("If-Match", "*")
         .send()
         .await
         .expect("Gagal mengirim request DELETE");
@@ -363,6 +370,7 @@ async fn test_delete() {
     let response = client
         .delete(format!("{}/v1/inventory/{}", server_url, created_item.id))
         .bearer_auth(token)
+        .header("If-Match", "*")
         .send()
         .await
         .expect("Gagal mengirim request DELETE");
@@ -440,6 +448,7 @@ async fn test_update_internal_server_error() {
     let response = client
         .put(format!("{server_url}/v1/inventory/{item_id}"))
         .bearer_auth(token)
+        .header("If-Match", "*")
         .json(&updated_data)
         .send()
         .await
@@ -494,6 +503,7 @@ async fn test_delete_internal_server_error() {
     let response = client
         .delete(format!("{}/v1/inventory/{}", server_url, created_item.id))
         .bearer_auth(token)
+        .header("If-Match", "*")
         .send()
         .await
         .expect("Gagal mengirim request DELETE");
@@ -557,6 +567,7 @@ async fn test_update_not_found() {
     let response = client
         .put(format!("{server_url}/v1/inventory/{non_existent_id}"))
         .bearer_auth(token)
+        .header("If-Match", "*")
         .json(&updated_data)
         .send()
         .await
@@ -567,3 +578,379 @@ async fn test_update_not_found() {
     server_handle.stop(true).await;
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 }
+
+#[tokio::test]
+async fn test_export_honours_filters() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let category = format!("CAT-{}", Uuid::new_v4());
+
+    for name in ["Export A", "Export B"] {
+        let response = client
+            .post(format!("{server_url}/v1/inventory/create"))
+            .bearer_auth(&token)
+            .json(&json!({
+                "name": name,
+                "quantity": 3,
+                "price": 2.5,
+                "category": category
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    let response = client
+        .get(format!(
+            "{server_url}/v1/inventory/export?format=csv&category={category}"
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to export inventory");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/csv")
+    );
+
+    let body = response.text().await.unwrap();
+    let lines: Vec<&str> = body.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("id,sku,name"));
+    assert!(lines[1].contains("Export A"));
+
+    let response = client
+        .get(format!(
+            "{server_url}/v1/inventory/export?format=jsonl&category={category}"
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    let body = response.text().await.unwrap();
+    let items: Vec<InventoryItem> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(items.len(), 2);
+    assert!(
+        items
+            .iter()
+            .all(|item| item.category.as_deref() == Some(category.as_str()))
+    );
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+#[tokio::test]
+async fn test_batch_atomic_and_best_effort() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let name: String = Sentence(1..3).fake();
+
+    let item: InventoryItem = client
+        .post(format!("{server_url}/v1/inventory/create"))
+        .bearer_auth(&token)
+        .json(&json!({ "name": name, "quantity": 5, "price": 10.0 }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let operations = json!([
+        { "op": "update", "id": item.id, "version": item.version, "data": { "price": 12.5 } },
+        { "op": "create", "data": { "name": "Batch item", "quantity": 1, "price": 1.0 } },
+        { "op": "delete", "id": Uuid::new_v4().to_string(), "version": 1 }
+    ]);
+
+    // The missing item fails the whole atomic batch
+    let response = client
+        .post(format!("{server_url}/v1/inventory/batch"))
+        .bearer_auth(&token)
+        .json(&json!({ "operations": operations }))
+        .send()
+        .await
+        .expect("Failed to send batch");
+    assert_eq!(response.status(), reqwest::StatusCode::UNPROCESSABLE_ENTITY);
+
+    let report: BatchResponse = response.json().await.unwrap();
+    assert!(!report.applied);
+    assert_eq!(report.failed, 1);
+    assert_eq!(report.results[2].status, 404);
+
+    let unchanged: InventoryItem = client
+        .get(format!("{server_url}/v1/inventory/{}", item.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(unchanged.price, 10.0);
+
+    // Best effort keeps the successful operations
+    let response = client
+        .post(format!("{server_url}/v1/inventory/batch"))
+        .bearer_auth(&token)
+        .json(&json!({ "mode": "best_effort", "operations": operations }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let report: BatchResponse = response.json().await.unwrap();
+    assert!(report.applied);
+    assert_eq!(report.succeeded, 2);
+    assert_eq!(report.failed, 1);
+
+    let updated: InventoryItem = client
+        .get(format!("{server_url}/v1/inventory/{}", item.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(updated.price, 12.5);
+
+    // Like PUT and DELETE, operations must name the version they replace
+    let response = client
+        .post(format!("{server_url}/v1/inventory/batch"))
+        .bearer_auth(&token)
+        .json(&json!({
+            "mode": "best_effort",
+            "operations": [{ "op": "update", "id": item.id, "data": { "price": 15.0 } }]
+        }))
+        .send()
+        .await
+        .unwrap();
+    let report: BatchResponse = response.json().await.unwrap();
+    assert_eq!(report.results[0].status, 428);
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+#[tokio::test]
+async fn test_optimistic_concurrency() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let name: String = Sentence(1..3).fake();
+
+    let item: InventoryItem = client
+        .post(format!("{server_url}/v1/inventory/create"))
+        .bearer_auth(&token)
+        .json(&json!({ "name": name, "quantity": 5, "price": 10.0 }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let item_url = format!("{server_url}/v1/inventory/{}", item.id);
+
+    let response = client
+        .get(&item_url)
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let etag = response.headers()["etag"].to_str().unwrap().to_string();
+    assert_eq!(etag, "\"1\"");
+
+    let response = client
+        .get(&item_url)
+        .bearer_auth(&token)
+        .header("If-None-Match", &etag)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_MODIFIED);
+
+    let response = client
+        .put(&item_url)
+        .bearer_auth(&token)
+        .json(&json!({ "price": 11.0 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.status(),
+        reqwest::StatusCode::PRECONDITION_REQUIRED
+    );
+
+    let response = client
+        .put(&item_url)
+        .bearer_auth(&token)
+        .header("If-Match", &etag)
+        .json(&json!({ "price": 11.0 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let new_etag = response.headers()["etag"].to_str().unwrap().to_string();
+    assert_eq!(new_etag, "\"2\"");
+
+    // A second writer still holding the first ETag is turned away
+    let response = client
+        .put(&item_url)
+        .bearer_auth(&token)
+        .header("If-Match", &etag)
+        .json(&json!({ "price": 12.0 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::PRECONDITION_FAILED);
+
+    let response = client
+        .delete(&item_url)
+        .bearer_auth(&token)
+        .header("If-Match", &new_etag)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+#[tokio::test]
+async fn test_soft_delete_restore_and_purge() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let name: String = Sentence(1..3).fake();
+
+    let item: InventoryItem = client
+        .post(format!("{server_url}/v1/inventory/create"))
+        .bearer_auth(&token)
+        .json(&json!({ "name": name, "quantity": 5, "price": 10.0 }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let item_url = format!("{server_url}/v1/inventory/{}", item.id);
+
+    let response = client
+        .delete(&item_url)
+        .bearer_auth(&token)
+        .header("If-Match", "*")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // Hidden from lookups but still stored
+    let response = client
+        .get(&item_url)
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    let stored = inventory::Entity::find_by_id(item.id.clone())
+        .one(db_pool)
+        .await
+        .unwrap()
+        .expect("soft-deleted item should be kept");
+    assert!(stored.deleted_at.is_some());
+
+    let response = client
+        .post(format!("{item_url}/restore"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let restored: InventoryItem = response.json().await.unwrap();
+    assert!(restored.deleted_at.is_none());
+
+    let response = client
+        .post(format!("{item_url}/restore"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CONFLICT);
+
+    client
+        .delete(&item_url)
+        .bearer_auth(&token)
+        .header("If-Match", "*")
+        .send()
+        .await
+        .unwrap();
+
+    // Purging is for administrators, as whoever registered the company is
+    let (_, staff_token) =
+        employee_with_login(&client, server_url, &token, "Staff", json!({})).await;
+    let response = client
+        .delete(format!("{item_url}/purge"))
+        .bearer_auth(&staff_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+
+    let response = client
+        .delete(format!("{item_url}/purge"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let stored = inventory::Entity::find_by_id(item.id.clone())
+        .one(db_pool)
+        .await
+        .unwrap();
+    assert!(stored.is_none());
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
@@ -0,0 +1,180 @@
+use fake::{Fake, faker::internet::en::SafeEmail};
+use reqwest::{Client as HttpClient, StatusCode};
+use serde_json::{Value, json};
+
+use api::v1::auth::models::TokenResponse;
+use api::v1::department::models::Department;
+use api::v1::employee::models::{Employee, Invite};
+
+use crate::helper::{TestAppBuilder, get_auth_token};
+
+async fn login(client: &HttpClient, server_url: &str, username: &str) -> reqwest::Response {
+    client
+        .post(format!("{server_url}/v1/auth/login"))
+        .json(&json!({ "username": username, "password": "password123" }))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_invited_employee_signs_in_until_deleted() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let department: Department = client
+        .post(format!("{server_url}/v1/department"))
+        .bearer_auth(&token)
+        .json(&json!({ "name": "Finance" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let email: String = SafeEmail().fake();
+    let employee: Employee = client
+        .post(format!("{server_url}/v1/employee"))
+        .bearer_auth(&token)
+        .json(&json!({
+            "name": "Invited Employee", "role": "Accountant", "email": email,
+            "department_id": department.id
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let response = client
+        .post(format!("{server_url}/v1/employee/{}/invite", employee.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let invite: Invite = response.json().await.unwrap();
+
+    let username = format!("invited_{}", &employee.id[..8]);
+    let accept = json!({ "token": invite.token, "username": username, "password": "password123" });
+    let response = client
+        .post(format!("{server_url}/v1/auth/accept-invite"))
+        .json(&accept)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let employee_token = response.json::<TokenResponse>().await.unwrap().token;
+
+    // The invitation is used up, and the employee has their login
+    let response = client
+        .post(format!("{server_url}/v1/auth/accept-invite"))
+        .json(&accept)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = client
+        .post(format!("{server_url}/v1/employee/{}/invite", employee.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let me: Value = client
+        .get(format!("{server_url}/v1/auth/me"))
+        .bearer_auth(&employee_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(me["username"], username.as_str());
+    assert_eq!(me["tenant_id"], employee.tenant_id.as_str());
+    assert_eq!(me["employee"]["name"], "Invited Employee");
+    assert_eq!(me["employee"]["department"]["name"], "Finance");
+    assert_eq!(me["roles"], json!(["employee"]));
+
+    // Deleting the employee disables the login, restoring enables it
+    let response = client
+        .delete(format!("{server_url}/v1/employee/{}", employee.id))
+        .bearer_auth(&token)
+        .header("If-Match", "*")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = login(&client, server_url, &username).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = client
+        .get(format!("{server_url}/v1/auth/me"))
+        .bearer_auth(&employee_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .post(format!("{server_url}/v1/employee/{}/restore", employee.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = login(&client, server_url, &username).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Only the company that let the employee go disables their login
+    let branch: Value = client
+        .post(format!("{server_url}/v1/company"))
+        .bearer_auth(&token)
+        .json(&json!({ "name": "Branch office" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let response = client
+        .post(format!(
+            "{server_url}/v1/company/{}/members",
+            branch["id"].as_str().unwrap()
+        ))
+        .bearer_auth(&token)
+        .json(&json!({ "user_id": me["sub"] }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let response = client
+        .delete(format!("{server_url}/v1/employee/{}", employee.id))
+        .bearer_auth(&token)
+        .header("If-Match", "*")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = login(&client, server_url, &username).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let branch_token = response.json::<TokenResponse>().await.unwrap().token;
+    let me: Value = client
+        .get(format!("{server_url}/v1/auth/me"))
+        .bearer_auth(&branch_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(me["tenant_id"], branch["id"]);
+
+    app.server_handle.stop(true).await;
+}
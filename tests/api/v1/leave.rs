@@ -0,0 +1,231 @@
+use reqwest::{Client as HttpClient, StatusCode};
+use serde_json::{Value, json};
+
+use api::v1::leave::models::{LeaveBalance, LeaveRequest, LeaveType};
+use entity::leave_request::LeaveStatus;
+
+use crate::helper::{TestAppBuilder, employee_with_login, get_auth_token};
+
+async fn submit(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    leave_type_id: &str,
+    start_date: &str,
+    end_date: &str,
+) -> reqwest::Response {
+    client
+        .post(format!("{server_url}/v1/leave/request"))
+        .bearer_auth(token)
+        .json(&json!({
+            "leave_type_id": leave_type_id,
+            "start_date": start_date,
+            "end_date": end_date
+        }))
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn decide(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    request_id: &str,
+    action: &str,
+) -> reqwest::Response {
+    client
+        .post(format!(
+            "{server_url}/v1/leave/request/{request_id}/{action}"
+        ))
+        .bearer_auth(token)
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn annual_balance(client: &HttpClient, server_url: &str, token: &str) -> LeaveBalance {
+    let balances: Vec<LeaveBalance> = client
+        .get(format!("{server_url}/v1/leave/balance?year=2030"))
+        .bearer_auth(token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    balances.into_iter().find(|b| b.name == "Annual").unwrap()
+}
+
+#[tokio::test]
+async fn test_leave_request_workflow() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let (manager, manager_token) =
+        employee_with_login(&client, server_url, &token, "Manager", json!({})).await;
+    let (_, employee_token) = employee_with_login(
+        &client,
+        server_url,
+        &token,
+        "Staff",
+        json!({ "manager_id": manager.id }),
+    )
+    .await;
+
+    let annual: LeaveType = client
+        .post(format!("{server_url}/v1/leave/type"))
+        .bearer_auth(&token)
+        .json(&json!({ "name": "Annual", "accrual": "upfront", "days_per_year": 12 }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let response = client
+        .post(format!("{server_url}/v1/leave/holiday"))
+        .bearer_auth(&token)
+        .json(&json!({ "date": "2030-06-05", "name": "Company day" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Monday to Friday with a holiday in between
+    let response = submit(
+        &client,
+        server_url,
+        &employee_token,
+        &annual.id,
+        "2030-06-03",
+        "2030-06-07",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let request: LeaveRequest = response.json().await.unwrap();
+    assert_eq!(request.days, 4.0);
+    assert_eq!(request.status, LeaveStatus::Pending);
+
+    let response = submit(
+        &client,
+        server_url,
+        &employee_token,
+        &annual.id,
+        "2030-06-07",
+        "2030-06-10",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Only the manager decides, or an administrator for them
+    let (_, colleague_token) =
+        employee_with_login(&client, server_url, &token, "Colleague", json!({})).await;
+    for token in [&employee_token, &colleague_token] {
+        let response = decide(&client, server_url, token, &request.id, "approve").await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+    let response = decide(&client, server_url, &manager_token, &request.id, "approve").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let request: LeaveRequest = response.json().await.unwrap();
+    assert_eq!(request.status, LeaveStatus::Approved);
+    let response = decide(&client, server_url, &manager_token, &request.id, "reject").await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let balance = annual_balance(&client, server_url, &employee_token).await;
+    assert_eq!(balance.entitled, Some(12.0));
+    assert_eq!(balance.taken, 4.0);
+    assert_eq!(balance.available, Some(8.0));
+
+    // Two full weeks is more than is left
+    let response = submit(
+        &client,
+        server_url,
+        &employee_token,
+        &annual.id,
+        "2030-06-10",
+        "2030-06-21",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Cancelling gives the days back
+    let response = client
+        .post(format!(
+            "{server_url}/v1/leave/request/{}/cancel",
+            request.id
+        ))
+        .bearer_auth(&employee_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let balance = annual_balance(&client, server_url, &employee_token).await;
+    assert_eq!(balance.available, Some(12.0));
+
+    // The leave type is in use
+    let response = client
+        .delete(format!("{server_url}/v1/leave/type/{}", annual.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_leave_dates_are_validated() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+    let (_, employee_token) =
+        employee_with_login(&client, server_url, &token, "Staff", json!({})).await;
+
+    let unpaid: LeaveType = client
+        .post(format!("{server_url}/v1/leave/type"))
+        .bearer_auth(&token)
+        .json(&json!({ "name": "Unpaid", "accrual": "none" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    for (start, end, field) in [
+        ("2030-06-07", "2030-06-03", "end_date"),
+        ("2030-12-30", "2031-01-03", "end_date"),
+        ("2030-06-08", "2030-06-09", "start_date"),
+    ] {
+        let response = submit(&client, server_url, &employee_token, &unpaid.id, start, end).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let problem: Value = response.json().await.unwrap();
+        assert!(problem["errors"][field].is_array());
+    }
+
+    // Unpaid leave has no balance to run out of
+    let response = submit(
+        &client,
+        server_url,
+        &employee_token,
+        &unpaid.id,
+        "2030-06-03",
+        "2030-07-31",
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    app.server_handle.stop(true).await;
+}
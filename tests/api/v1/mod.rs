@@ -1,7 +1,25 @@
+pub mod attachment;
+pub mod attendance;
+pub mod audit;
 pub mod auth;
 pub mod auth_complete;
+pub mod company;
+pub mod customer;
+pub mod department;
 pub mod employee;
 pub mod employee_complete;
+pub mod employment;
+pub mod events;
+pub mod expense;
+pub mod import;
 pub mod inventory;
+pub mod invite;
+pub mod leave;
 pub mod order;
 pub mod order_complete;
+pub mod payroll;
+pub mod pricing;
+pub mod quotation;
+pub mod stock;
+pub mod stock_count;
+pub mod webhook;
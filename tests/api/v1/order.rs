@@ -2,9 +2,8 @@ use api::v1::order::models::Order;
 use fake::Fake;
 use reqwest::Client as HttpClient;
 use serde_json::json;
-use uuid::Uuid;
 
-use crate::helper::{TestAppBuilder, get_auth_token};
+use crate::helper::{TestAppBuilder, customer_and_item, get_auth_token};
 
 #[tokio::test]
 async fn test_create_order() {
@@ -19,13 +18,13 @@ async fn test_create_order() {
 
     let client = HttpClient::new();
     let token = get_auth_token(&client, server_url, db_pool).await;
-    let customer_id = Uuid::new_v4().to_string();
-    let total_amount: f64 = (1.0..1000.0).fake();
+    let price: f64 = (1..1000).fake::<i32>().into();
+    let (customer, item) = customer_and_item(&client, server_url, &token, price).await;
 
     // Tes endpoint POST /v1/order
     let new_order = json!({
-        "customer_id": customer_id,
-        "total_amount": total_amount
+        "customer_id": customer.id,
+        "lines": [{ "inventory_id": item.id, "quantity": 3 }]
     });
 
     let response = client
@@ -40,8 +39,8 @@ async fn test_create_order() {
 
     let created_order: Order = response.json().await.expect("Gagal parse response JSON");
 
-    assert_eq!(created_order.customer_id, customer_id);
-    assert!((created_order.total_amount - total_amount).abs() < 1e-9);
+    assert_eq!(created_order.customer_id, customer.id);
+    assert!((created_order.total_amount - 3.0 * price).abs() < 1e-9);
 
     server_handle.stop(true).await;
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
@@ -60,12 +59,12 @@ async fn test_create_negative_amount() {
 
     let client = HttpClient::new();
     let token = get_auth_token(&client, server_url, db_pool).await;
-    let customer_id = Uuid::new_v4().to_string();
+    let (customer, item) = customer_and_item(&client, server_url, &token, 150.75).await;
 
     // Tes endpoint POST /v1/order dengan jumlah negatif
     let new_order = json!({
-        "customer_id": customer_id,
-        "total_amount": -150.75
+        "customer_id": customer.id,
+        "lines": [{ "inventory_id": item.id, "quantity": -2 }]
     });
 
     let response = client
@@ -95,15 +94,14 @@ async fn test_create_internal_server_error() {
 
     let client = HttpClient::new();
     let token = get_auth_token(&client, server_url, db_pool).await;
-    let customer_id = Uuid::new_v4().to_string();
-    let total_amount: f64 = (1.0..1000.0).fake();
+    let (customer, item) = customer_and_item(&client, server_url, &token, 99.0).await;
 
     // Simulate database connection error by closing the pool
     let _ = app.db.close().await;
 
     let new_order = json!({
-        "customer_id": customer_id,
-        "total_amount": total_amount
+        "customer_id": customer.id,
+        "lines": [{ "inventory_id": item.id, "quantity": 1 }]
     });
 
     let response = client
@@ -4,7 +4,7 @@ use reqwest::Client as HttpClient;
 use serde_json::json;
 use uuid::Uuid;
 
-use crate::helper::{TestAppBuilder, get_auth_token};
+use crate::helper::{TestAppBuilder, customer_and_item, get_auth_token};
 use entity::order::Entity as OrderEntity;
 use sea_orm::EntityTrait;
 
@@ -26,19 +26,17 @@ async fn test_get_all_orders() {
     let _ = OrderEntity::delete_many().exec(db_pool).await;
 
     // Create test orders
-    let customer_id1 = Uuid::new_v4().to_string();
-    let customer_id2 = Uuid::new_v4().to_string();
-    let total_amount1: f64 = (10.0..100.0).fake();
-    let total_amount2: f64 = (100.0..1000.0).fake();
+    let (customer1, item1) = customer_and_item(&client, server_url, &token, 40.0).await;
+    let (customer2, item2) = customer_and_item(&client, server_url, &token, 400.0).await;
 
     let order1 = json!({
-        "customer_id": customer_id1,
-        "total_amount": total_amount1
+        "customer_id": customer1.id,
+        "lines": [{ "inventory_id": item1.id, "quantity": (1..5).fake::<i32>() }]
     });
 
     let order2 = json!({
-        "customer_id": customer_id2,
-        "total_amount": total_amount2
+        "customer_id": customer2.id,
+        "lines": [{ "inventory_id": item2.id, "quantity": (1..5).fake::<i32>() }]
     });
 
     // Create first order
@@ -91,11 +89,11 @@ async fn test_get_order_by_id() {
     let token = get_auth_token(&client, server_url, db_pool).await;
 
     // Create test order
-    let customer_id = Uuid::new_v4().to_string();
-    let total_amount: f64 = (50.0..500.0).fake();
+    let price: f64 = (50..500).fake::<i32>().into();
+    let (customer, item) = customer_and_item(&client, server_url, &token, price).await;
     let new_order = json!({
-        "customer_id": customer_id.clone(),
-        "total_amount": total_amount
+        "customer_id": customer.id,
+        "lines": [{ "inventory_id": item.id, "quantity": 2 }]
     });
 
     let create_response = client
@@ -121,8 +119,8 @@ async fn test_get_order_by_id() {
 
     let order: Order = response.json().await.expect("Failed to parse response");
     assert_eq!(order.id, order_id);
-    assert_eq!(order.customer_id, customer_id);
-    assert!((order.total_amount - total_amount).abs() < 1e-9);
+    assert_eq!(order.customer_id, customer.id);
+    assert!((order.total_amount - 2.0 * price).abs() < 1e-9);
 
     server_handle.stop(true).await;
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
@@ -173,11 +171,10 @@ async fn test_update_order() {
     let token = get_auth_token(&client, server_url, db_pool).await;
 
     // Create test order
-    let customer_id = Uuid::new_v4().to_string();
-    let original_amount: f64 = 100.0;
+    let (customer, item) = customer_and_item(&client, server_url, &token, 50.0).await;
     let new_order = json!({
-        "customer_id": customer_id.clone(),
-        "total_amount": original_amount
+        "customer_id": customer.id,
+        "lines": [{ "inventory_id": item.id, "quantity": 2 }]
     });
 
     let create_response = client
@@ -189,19 +186,19 @@ async fn test_update_order() {
         .expect("Failed to create order");
 
     let created_order: Order = create_response.json().await.unwrap();
+    assert!((created_order.total_amount - 100.0).abs() < 1e-9);
     let order_id = created_order.id;
 
-    // Update order data
-    let updated_amount: f64 = 150.0;
+    // Update order data; the order is priced again
     let updated_data = json!({
-        "customer_id": customer_id,
-        "total_amount": updated_amount
+        "lines": [{ "inventory_id": item.id, "quantity": 3 }]
     });
 
     // Test PUT /v1/order/{id}
     let response = client
         .put(format!("{server_url}/v1/order/{order_id}"))
         .bearer_auth(&token)
+        .header("If-Match", "*")
         .json(&updated_data)
         .send()
         .await
@@ -211,7 +208,7 @@ async fn test_update_order() {
 
     let updated_order: Order = response.json().await.expect("Failed to parse response");
     assert_eq!(updated_order.id, order_id);
-    assert!((updated_order.total_amount - updated_amount).abs() < 1e-9);
+    assert!((updated_order.total_amount - 150.0).abs() < 1e-9);
 
     server_handle.stop(true).await;
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
@@ -233,14 +230,14 @@ async fn test_update_nonexistent_order() {
 
     let nonexistent_id = Uuid::new_v4().to_string();
     let updated_data = json!({
-        "customer_id": Uuid::new_v4().to_string(),
-        "total_amount": 200.0
+        "customer_id": Uuid::new_v4().to_string()
     });
 
     // Test PUT /v1/order/{nonexistent_id}
     let response = client
         .put(format!("{server_url}/v1/order/{nonexistent_id}"))
         .bearer_auth(&token)
+        .header("If-Match", "*")
         .json(&updated_data)
         .send()
         .await
@@ -267,11 +264,10 @@ async fn test_update_order_negative_amount() {
     let token = get_auth_token(&client, server_url, db_pool).await;
 
     // Create test order
-    let customer_id = Uuid::new_v4().to_string();
-    let original_amount: f64 = 100.0;
+    let (customer, item) = customer_and_item(&client, server_url, &token, 100.0).await;
     let new_order = json!({
-        "customer_id": customer_id,
-        "total_amount": original_amount
+        "customer_id": customer.id,
+        "lines": [{ "inventory_id": item.id, "quantity": 1 }]
     });
 
     let create_response = client
@@ -287,13 +283,13 @@ async fn test_update_order_negative_amount() {
 
     // Update with negative amount
     let updated_data = json!({
-        "customer_id": customer_id,
-        "total_amount": -50.0
+        "lines": [{ "inventory_id": item.id, "quantity": -1 }]
     });
 
     let response = client
         .put(format!("{server_url}/v1/order/{order_id}"))
         .bearer_auth(&token)
+        .header("If-Match", "*")
         .json(&updated_data)
         .send()
         .await
@@ -320,11 +316,10 @@ async fn test_delete_order() {
     let token = get_auth_token(&client, server_url, db_pool).await;
 
     // Create test order
-    let customer_id = Uuid::new_v4().to_string();
-    let total_amount: f64 = 75.0;
+    let (customer, item) = customer_and_item(&client, server_url, &token, 75.0).await;
     let new_order = json!({
-        "customer_id": customer_id,
-        "total_amount": total_amount
+        "customer_id": customer.id,
+        "lines": [{ "inventory_id": item.id, "quantity": 1 }]
     });
 
     let create_response = client
@@ -342,6 +337,7 @@ async fn test_delete_order() {
     let response = client
         .delete(format!("{server_url}/v1/order/{order_id}"))
         .bearer_auth(&token)
+        .header("If-Match", "*")
         .send()
         .await
         .expect("Failed to send DELETE request");
@@ -382,6 +378,7 @@ async fn test_delete_nonexistent_order() {
     let response = client
         .delete(format!("{server_url}/v1/order/{nonexistent_id}"))
         .bearer_auth(&token)
+        .header("If-Match", "*")
         .send()
         .await
         .expect("Failed to send DELETE request");
@@ -425,7 +422,7 @@ async fn test_order_unauthorized_access() {
     // Test PUT without token
     let response = client
         .put(format!("{server_url}/v1/order/123"))
-        .json(&json!({"customer_id": Uuid::new_v4().to_string(), "total_amount": 100.0}))
+        .json(&json!({"customer_id": Uuid::new_v4().to_string()}))
         .send()
         .await
         .expect("Failed to send PUT request");
@@ -459,10 +456,10 @@ async fn test_order_zero_amount() {
     let client = HttpClient::new();
     let token = get_auth_token(&client, server_url, db_pool).await;
 
-    let customer_id = Uuid::new_v4().to_string();
+    let (customer, item) = customer_and_item(&client, server_url, &token, 0.0).await;
     let new_order = json!({
-        "customer_id": customer_id,
-        "total_amount": 0.0
+        "customer_id": customer.id,
+        "lines": [{ "inventory_id": item.id, "quantity": 4 }]
     });
 
     let response = client
@@ -475,6 +472,97 @@ async fn test_order_zero_amount() {
 
     // Should accept zero amount
     assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let order: Order = response.json().await.expect("Failed to parse response");
+    assert!(order.total_amount.abs() < 1e-9);
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+#[tokio::test]
+async fn test_create_order_idempotency_key() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let idempotency_key = Uuid::new_v4().to_string();
+    let (customer, item) = customer_and_item(&client, server_url, &token, 150.0).await;
+
+    let order_data = json!({
+        "customer_id": customer.id,
+        "lines": [{ "inventory_id": item.id, "quantity": 1 }]
+    });
+
+    let first = client
+        .post(format!("{server_url}/v1/order"))
+        .bearer_auth(&token)
+        .header("Idempotency-Key", &idempotency_key)
+        .json(&order_data)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(first.status(), reqwest::StatusCode::OK);
+    assert!(first.headers().get("Idempotent-Replayed").is_none());
+    let first_order: Order = first.json().await.expect("Failed to parse response");
+
+    // A retry replays the stored response instead of creating a second order
+    let retry = client
+        .post(format!("{server_url}/v1/order"))
+        .bearer_auth(&token)
+        .header("Idempotency-Key", &idempotency_key)
+        .json(&order_data)
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(retry.status(), reqwest::StatusCode::OK);
+    assert_eq!(retry.headers()["Idempotent-Replayed"], "true");
+    let retried_order: Order = retry.json().await.expect("Failed to parse response");
+    assert_eq!(retried_order.id, first_order.id);
+
+    // Reusing the key for a different order is rejected
+    let response = client
+        .post(format!("{server_url}/v1/order"))
+        .bearer_auth(&token)
+        .header("Idempotency-Key", &idempotency_key)
+        .json(&json!({
+            "customer_id": customer.id,
+            "lines": [{ "inventory_id": item.id, "quantity": 2 }]
+        }))
+        .send()
+        .await
+        .expect("Failed to send POST request");
+    assert_eq!(response.status(), reqwest::StatusCode::UNPROCESSABLE_ENTITY);
+
+    // Replays carry the ETag of the first response
+    let response = client
+        .delete(format!("{server_url}/v1/order/{}", first_order.id))
+        .bearer_auth(&token)
+        .header("If-Match", "*")
+        .send()
+        .await
+        .expect("Failed to send DELETE request");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let restore_key = Uuid::new_v4().to_string();
+    let mut etags = Vec::new();
+    for _ in 0..2 {
+        let response = client
+            .post(format!("{server_url}/v1/order/{}/restore", first_order.id))
+            .bearer_auth(&token)
+            .header("Idempotency-Key", &restore_key)
+            .send()
+            .await
+            .expect("Failed to send POST request");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        etags.push(response.headers()["ETag"].clone());
+    }
+    assert_eq!(etags[0], etags[1]);
 
     server_handle.stop(true).await;
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
@@ -0,0 +1,389 @@
+use reqwest::{Client as HttpClient, StatusCode, header};
+use serde_json::{Value, json};
+
+use api::v1::payroll::models::{
+    JournalEntry, PayrollComponent, PayrollRun, PayrollRunWithPayslips, Payslip, PayslipWithLines,
+};
+use entity::payroll_run::PayrollStatus;
+
+use crate::helper::{TestAppBuilder, employee_with_login, get_auth_token};
+
+async fn add_component(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    component: Value,
+) -> reqwest::Response {
+    client
+        .post(format!("{server_url}/v1/payroll/component"))
+        .bearer_auth(token)
+        .json(&component)
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn act(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    run_id: &str,
+    action: &str,
+    body: Value,
+) -> reqwest::Response {
+    client
+        .post(format!("{server_url}/v1/payroll/run/{run_id}/{action}"))
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_components_are_checked() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+    let (_, staff_token) =
+        employee_with_login(&client, server_url, &token, "Staff", json!({})).await;
+
+    let basic = json!({
+        "code": "BASIC", "name": "Basic salary", "kind": "earning",
+        "base": "basic", "rate": 100.0, "account": "6100"
+    });
+    let response = add_component(&client, server_url, &staff_token, basic.clone()).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = add_component(&client, server_url, &token, basic.clone()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let component: PayrollComponent = response.json().await.unwrap();
+    assert!(component.active);
+    let response = add_component(&client, server_url, &token, basic).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Contributions are owed on an account of their own
+    let response = add_component(
+        &client,
+        server_url,
+        &token,
+        json!({
+            "code": "JKK", "name": "Work accident insurance", "kind": "employer_contribution",
+            "base": "basic", "rate": 0.24, "account": "6200"
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["errors"]["payable_account"].is_array());
+
+    let response = add_component(
+        &client,
+        server_url,
+        &token,
+        json!({
+            "code": "PPH21", "name": "Income tax", "kind": "deduction",
+            "base": "gross", "account": "2300",
+            "brackets": [{ "up_to": 60000000.0, "rate": 5.0 }, { "up_to": 50000000.0, "rate": 15.0 }]
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["errors"]["brackets"].is_array());
+
+    let response = client
+        .put(format!(
+            "{server_url}/v1/payroll/component/{}",
+            component.id
+        ))
+        .bearer_auth(&token)
+        .json(&json!({ "kind": "employer_contribution" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_run_is_locked_after_approval_and_posts_to_journal() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let (veteran, veteran_token) = employee_with_login(
+        &client,
+        server_url,
+        &token,
+        "Veteran",
+        json!({ "hire_date": "2025-01-06", "salary": 10000000.0 }),
+    )
+    .await;
+    // Joins after 10 of June's 21 working days
+    let (newcomer, _) = employee_with_login(
+        &client,
+        server_url,
+        &token,
+        "Newcomer",
+        json!({ "hire_date": "2025-06-16", "salary": 6300000.0 }),
+    )
+    .await;
+
+    for component in [
+        json!({
+            "code": "BASIC", "name": "Basic salary", "kind": "earning",
+            "base": "basic", "rate": 100.0, "account": "6100", "sequence": 1
+        }),
+        json!({
+            "code": "BPJS_KES_CO", "name": "Health insurance (company)",
+            "kind": "employer_contribution", "base": "basic", "rate": 4.0,
+            "base_cap": 12000000.0, "account": "6200", "payable_account": "2200", "sequence": 10
+        }),
+        json!({
+            "code": "BPJS_KES", "name": "Health insurance", "kind": "deduction",
+            "base": "basic", "rate": 1.0, "base_cap": 12000000.0, "account": "2200",
+            "sequence": 11
+        }),
+        json!({
+            "code": "PPH21", "name": "Income tax", "kind": "deduction",
+            "base": "gross_with_contributions", "account": "2300", "sequence": 20,
+            "brackets": [{ "up_to": 5400000.0, "rate": 0.0 }, { "up_to": null, "rate": 5.0 }]
+        }),
+    ] {
+        let response = add_component(&client, server_url, &token, component).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = client
+        .post(format!("{server_url}/v1/payroll/run"))
+        .bearer_auth(&token)
+        .json(&json!({ "period_start": "2025-06-01", "period_end": "2025-06-30" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let run: PayrollRun = response.json().await.unwrap();
+    assert_eq!(run.status, PayrollStatus::Draft);
+    assert_eq!(run.working_days, 21);
+    assert_eq!(run.employees, 2);
+    assert_eq!(run.gross, 13300000.0);
+    assert_eq!(run.deductions, 653000.0);
+    assert_eq!(run.net, 12647000.0);
+    assert_eq!(run.employer_contributions, 532000.0);
+
+    let response = client
+        .post(format!("{server_url}/v1/payroll/run"))
+        .bearer_auth(&token)
+        .json(&json!({ "period_start": "2025-06-15", "period_end": "2025-07-14" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let detail: PayrollRunWithPayslips = client
+        .get(format!("{server_url}/v1/payroll/run/{}", run.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let slip = |employee_id: &str| {
+        detail
+            .payslips
+            .iter()
+            .find(|p| p.employee_id == employee_id)
+            .unwrap()
+            .clone()
+    };
+    let newcomer_slip = slip(&newcomer.id);
+    assert_eq!(newcomer_slip.days_worked, 11);
+    assert_eq!(newcomer_slip.basic_salary, 3300000.0);
+    assert_eq!(newcomer_slip.deductions, 33000.0);
+    let veteran_slip = slip(&veteran.id);
+    assert_eq!(veteran_slip.net, 9380000.0);
+
+    // Drafts stay out of sight of employees
+    let payslips: Vec<Payslip> = client
+        .get(format!("{server_url}/v1/payroll/payslip"))
+        .bearer_auth(&veteran_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(payslips.is_empty());
+
+    let response = act(
+        &client,
+        server_url,
+        &token,
+        &run.id,
+        "post",
+        json!({ "net_pay_account": "2100" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = act(
+        &client,
+        server_url,
+        &token,
+        &run.id,
+        "recalculate",
+        json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = act(&client, server_url, &token, &run.id, "approve", json!({})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let approved: PayrollRun = response.json().await.unwrap();
+    assert_eq!(approved.status, PayrollStatus::Approved);
+    assert!(approved.approved_by.is_some());
+
+    let response = act(
+        &client,
+        server_url,
+        &token,
+        &run.id,
+        "recalculate",
+        json!({}),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = client
+        .delete(format!("{server_url}/v1/payroll/run/{}", run.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let payslips: Vec<Payslip> = client
+        .get(format!("{server_url}/v1/payroll/payslip"))
+        .bearer_auth(&veteran_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(payslips.len(), 1);
+    let payslip: PayslipWithLines = client
+        .get(format!(
+            "{server_url}/v1/payroll/payslip/{}",
+            payslips[0].id
+        ))
+        .bearer_auth(&veteran_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let tax = payslip.lines.iter().find(|l| l.code == "PPH21").unwrap();
+    assert_eq!(tax.amount, 520000.0);
+
+    let response = client
+        .get(format!(
+            "{server_url}/v1/payroll/payslip/{}",
+            newcomer_slip.id
+        ))
+        .bearer_auth(&veteran_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = client
+        .get(format!(
+            "{server_url}/v1/payroll/payslip/{}/pdf",
+            veteran_slip.id
+        ))
+        .bearer_auth(&veteran_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/pdf");
+    let pdf = response.bytes().await.unwrap();
+    assert!(pdf.starts_with(b"%PDF"));
+
+    let response = act(
+        &client,
+        server_url,
+        &token,
+        &run.id,
+        "post",
+        json!({ "net_pay_account": "2100" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let entries: Vec<JournalEntry> = response.json().await.unwrap();
+    let debits: f64 = entries.iter().map(|e| e.debit).sum();
+    let credits: f64 = entries.iter().map(|e| e.credit).sum();
+    assert!((debits - credits).abs() < 0.01);
+    let booked = |account: &str| -> (f64, f64) {
+        entries
+            .iter()
+            .filter(|e| e.account == account)
+            .fold((0.0, 0.0), |(d, c), e| (d + e.debit, c + e.credit))
+    };
+    assert_eq!(booked("6100"), (13300000.0, 0.0));
+    assert_eq!(booked("6200"), (532000.0, 0.0));
+    assert_eq!(booked("2200"), (0.0, 665000.0));
+    assert_eq!(booked("2300"), (0.0, 520000.0));
+    assert_eq!(booked("2100"), (0.0, 12647000.0));
+
+    let response = act(
+        &client,
+        server_url,
+        &token,
+        &run.id,
+        "post",
+        json!({ "net_pay_account": "2100" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let journal: Vec<JournalEntry> = client
+        .get(format!("{server_url}/v1/payroll/run/{}/journal", run.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(journal.len(), entries.len());
+
+    // Paid employees are never purged
+    let newcomer_url = format!("{server_url}/v1/employee/{}", newcomer.id);
+    let response = client
+        .delete(&newcomer_url)
+        .bearer_auth(&token)
+        .header(header::IF_MATCH, "*")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = client
+        .delete(format!("{newcomer_url}/purge"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    app.server_handle.stop(true).await;
+}
@@ -0,0 +1,341 @@
+use chrono::{Duration, Utc};
+use reqwest::{Client as HttpClient, StatusCode};
+use serde_json::{Value, json};
+
+use api::v1::customer::models::Customer;
+use api::v1::order::models::{Order, OrderLine};
+use api::v1::pricing::models::{PriceListWithItems, PricingQuote};
+use entity::order::DiscountStatus;
+
+use crate::helper::{TestAppBuilder, customer_and_item, employee_with_login, get_auth_token};
+
+async fn post(client: &HttpClient, url: &str, token: &str, body: Value) -> reqwest::Response {
+    client
+        .post(url)
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn unit_price(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    customer_id: &str,
+    inventory_id: &str,
+    quantity: i32,
+) -> f64 {
+    let response = post(
+        client,
+        &format!("{server_url}/v1/pricing/quote"),
+        token,
+        json!({
+            "customer_id": customer_id,
+            "lines": [{ "inventory_id": inventory_id, "quantity": quantity }]
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let quote: PricingQuote = response.json().await.unwrap();
+    quote.lines[0].unit_price
+}
+
+#[tokio::test]
+async fn test_price_lists_set_the_customer_price() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+    let price_lists_url = format!("{server_url}/v1/pricing/price-list");
+
+    let (retail, chair) = customer_and_item(&client, server_url, &token, 100.0).await;
+    let wholesale: Customer = post(
+        &client,
+        &format!("{server_url}/v1/customer"),
+        &token,
+        json!({
+            "name": "Bulk Buyers",
+            "email": "orders@bulk.test",
+            "customer_group": "wholesale"
+        }),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+
+    // Without price lists the item's own price applies
+    let price = unit_price(&client, server_url, &token, &retail.id, &chair.id, 5).await;
+    assert!((price - 100.0).abs() < 1e-9);
+
+    let response = post(
+        &client,
+        &price_lists_url,
+        &token,
+        json!({
+            "name": "Standard",
+            "items": [
+                { "inventory_id": chair.id, "unit_price": 95.0 },
+                { "inventory_id": chair.id, "min_quantity": 10, "unit_price": 90.0 }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let standard: PriceListWithItems = response.json().await.unwrap();
+    assert_eq!(standard.items.len(), 2);
+
+    let yesterday = (Utc::now() - Duration::days(1)).date_naive();
+    post(
+        &client,
+        &price_lists_url,
+        &token,
+        json!({
+            "name": "Wholesale",
+            "customer_group": "wholesale",
+            "items": [{ "inventory_id": chair.id, "unit_price": 80.0 }]
+        }),
+    )
+    .await;
+    post(
+        &client,
+        &price_lists_url,
+        &token,
+        json!({
+            "name": "Last year's promotion",
+            "valid_to": yesterday,
+            "items": [{ "inventory_id": chair.id, "unit_price": 50.0 }]
+        }),
+    )
+    .await;
+
+    let response = post(
+        &client,
+        &price_lists_url,
+        &token,
+        json!({
+            "name": "Standard",
+            "items": []
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Quantity breaks, then the customer's group
+    let price = unit_price(&client, server_url, &token, &retail.id, &chair.id, 5).await;
+    assert!((price - 95.0).abs() < 1e-9);
+    let price = unit_price(&client, server_url, &token, &retail.id, &chair.id, 12).await;
+    assert!((price - 90.0).abs() < 1e-9);
+    let price = unit_price(&client, server_url, &token, &wholesale.id, &chair.id, 12).await;
+    assert!((price - 80.0).abs() < 1e-9);
+
+    // Nothing is priced in another currency
+    let response = post(
+        &client,
+        &format!("{server_url}/v1/pricing/quote"),
+        &token,
+        json!({
+            "customer_id": retail.id,
+            "currency": "EUR",
+            "lines": [{ "inventory_id": chair.id, "quantity": 1 }]
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let problem: Value = response.json().await.unwrap();
+    assert!(problem.to_string().contains("lines[0].inventory_id"));
+
+    // Orders are priced by the server, whatever the client says they cost
+    let response = post(
+        &client,
+        &format!("{server_url}/v1/order"),
+        &token,
+        json!({
+            "customer_id": retail.id,
+            "total_amount": 1.0,
+            "lines": [{ "inventory_id": chair.id, "quantity": 12 }]
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let order: Order = response.json().await.unwrap();
+    assert!((order.total_amount - 1080.0).abs() < 1e-9);
+
+    let response = client
+        .put(format!("{price_lists_url}/{}", standard.price_list.id))
+        .bearer_auth(&token)
+        .json(&json!({ "items": [{ "inventory_id": chair.id, "unit_price": 97.0 }] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let price = unit_price(&client, server_url, &token, &retail.id, &chair.id, 12).await;
+    assert!((price - 97.0).abs() < 1e-9);
+
+    let response = client
+        .delete(format!("{price_lists_url}/{}", standard.price_list.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let price = unit_price(&client, server_url, &token, &retail.id, &chair.id, 12).await;
+    assert!((price - 100.0).abs() < 1e-9);
+
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_discounts_past_the_limits_wait_for_an_administrator() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+    let (_, staff_token) =
+        employee_with_login(&client, server_url, &token, "Sales", json!({})).await;
+    let (customer, desk) = customer_and_item(&client, server_url, &token, 200.0).await;
+    let policy_url = format!("{server_url}/v1/pricing/discount-policy");
+
+    let response = client
+        .put(&policy_url)
+        .bearer_auth(&staff_token)
+        .json(&json!({ "line_limit_percent": 50.0 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = client
+        .put(&policy_url)
+        .bearer_auth(&token)
+        .json(&json!({ "line_limit_percent": 10.0, "order_limit_percent": 15.0 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Within the limits
+    let response = post(
+        &client,
+        &format!("{server_url}/v1/order"),
+        &staff_token,
+        json!({
+            "customer_id": customer.id,
+            "lines": [{
+                "inventory_id": desk.id,
+                "quantity": 2,
+                "discount": { "kind": "percent", "value": 10.0 }
+            }]
+        }),
+    )
+    .await;
+    let order: Order = response.json().await.unwrap();
+    assert!((order.total_amount - 360.0).abs() < 1e-9);
+    assert!(order.discount_status.is_none());
+
+    // 10% off the line and 50 off the order is 17.5% overall
+    let response = post(
+        &client,
+        &format!("{server_url}/v1/pricing/quote"),
+        &staff_token,
+        json!({
+            "customer_id": customer.id,
+            "lines": [{
+                "inventory_id": desk.id,
+                "quantity": 2,
+                "discount": { "kind": "percent", "value": 10.0 }
+            }],
+            "discount": { "kind": "fixed", "value": 50.0 }
+        }),
+    )
+    .await;
+    let quote: PricingQuote = response.json().await.unwrap();
+    assert!((quote.subtotal - 400.0).abs() < 1e-9);
+    assert!((quote.discount_total - 70.0).abs() < 1e-9);
+    assert!((quote.total - 330.0).abs() < 1e-9);
+    assert!(quote.requires_approval);
+
+    let response = post(
+        &client,
+        &format!("{server_url}/v1/order"),
+        &staff_token,
+        json!({
+            "customer_id": customer.id,
+            "lines": [{
+                "inventory_id": desk.id,
+                "quantity": 2,
+                "discount": { "kind": "percent", "value": 10.0 }
+            }],
+            "discount": { "kind": "fixed", "value": 50.0 }
+        }),
+    )
+    .await;
+    let order: Order = response.json().await.unwrap();
+    assert!((order.total_amount - 330.0).abs() < 1e-9);
+    assert_eq!(order.discount_status, Some(DiscountStatus::Pending));
+    let lines: Vec<OrderLine> = client
+        .get(format!("{server_url}/v1/order/{}/lines", order.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!((lines[0].discount_amount - 40.0).abs() < 1e-9);
+
+    let approve_url = format!("{server_url}/v1/order/{}/approve-discount", order.id);
+    let response = post(&client, &approve_url, &staff_token, json!({})).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = post(&client, &approve_url, &token, json!({})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let approved: Order = response.json().await.unwrap();
+    assert_eq!(approved.discount_status, Some(DiscountStatus::Approved));
+    assert!(approved.discount_approved_by.is_some());
+    let response = post(&client, &approve_url, &token, json!({})).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // An administrator's own discounts need no one else's approval
+    let response = post(
+        &client,
+        &format!("{server_url}/v1/order"),
+        &token,
+        json!({
+            "customer_id": customer.id,
+            "lines": [{
+                "inventory_id": desk.id,
+                "quantity": 1,
+                "discount": { "kind": "fixed", "value": 100.0 }
+            }]
+        }),
+    )
+    .await;
+    let order: Order = response.json().await.unwrap();
+    assert_eq!(order.discount_status, Some(DiscountStatus::Approved));
+
+    // A discount is never more than what it is taken off
+    let response = post(
+        &client,
+        &format!("{server_url}/v1/order"),
+        &token,
+        json!({
+            "customer_id": customer.id,
+            "lines": [{
+                "inventory_id": desk.id,
+                "quantity": 1,
+                "discount": { "kind": "fixed", "value": 250.0 }
+            }]
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    app.server_handle.stop(true).await;
+}
@@ -0,0 +1,341 @@
+use chrono::{Duration, Utc};
+use reqwest::{Client as HttpClient, StatusCode, header};
+use serde_json::{Value, json};
+
+use api::v1::customer::models::Customer;
+use api::v1::inventory::models::InventoryItem;
+use api::v1::order::models::{Order, OrderLine};
+use api::v1::quotation::models::{
+    ConvertedQuotation, Quotation, QuotationRevision, QuotationWithLines,
+};
+use entity::quotation::QuoteStatus;
+
+use crate::helper::{TestAppBuilder, get_auth_token};
+
+async fn create_customer(client: &HttpClient, server_url: &str, token: &str) -> Customer {
+    client
+        .post(format!("{server_url}/v1/customer"))
+        .bearer_auth(token)
+        .json(&json!({ "name": "Acme", "email": "buyer@acme.test" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+async fn post(client: &HttpClient, url: &str, token: &str, body: Value) -> reqwest::Response {
+    client
+        .post(url)
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_accepted_quotation_becomes_an_order_at_the_quoted_prices() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let customer = create_customer(&client, server_url, &token).await;
+    let desk: InventoryItem = post(
+        &client,
+        &format!("{server_url}/v1/inventory/create"),
+        &token,
+        json!({ "name": "Desk", "quantity": 20, "price": 250.0 }),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    let valid_until = (Utc::now() + Duration::days(30)).date_naive();
+
+    let response = post(
+        &client,
+        &format!("{server_url}/v1/quotation"),
+        &token,
+        json!({
+            "customer_id": customer.id,
+            "valid_until": valid_until,
+            "lines": [
+                { "inventory_id": desk.id, "quantity": 4, "discount_percent": 10.0 },
+                { "description": "Delivery", "quantity": 1, "unit_price": 50.0 }
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let quote: QuotationWithLines = response.json().await.unwrap();
+    assert_eq!(quote.quotation.status, QuoteStatus::Draft);
+    assert_eq!(quote.lines[0].description, "Desk");
+    assert!((quote.lines[0].line_total - 900.0).abs() < 1e-9);
+    assert!((quote.quotation.subtotal - 1050.0).abs() < 1e-9);
+    assert!((quote.quotation.discount_total - 100.0).abs() < 1e-9);
+    assert!((quote.quotation.total - 950.0).abs() < 1e-9);
+    let quote_url = format!("{server_url}/v1/quotation/{}", quote.quotation.id);
+
+    // Only accepted quotes become orders
+    let response = post(&client, &format!("{quote_url}/convert"), &token, json!({})).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = post(&client, &format!("{quote_url}/send"), &token, json!({})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = client
+        .put(&quote_url)
+        .bearer_auth(&token)
+        .json(&json!({ "notes": "Too late" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = post(&client, &format!("{quote_url}/accept"), &token, json!({})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let accepted: Quotation = response.json().await.unwrap();
+    assert_eq!(accepted.status, QuoteStatus::Accepted);
+
+    let response = post(&client, &format!("{quote_url}/convert"), &token, json!({})).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let converted: ConvertedQuotation = response.json().await.unwrap();
+    assert_eq!(converted.order.customer_id, customer.id);
+    assert_eq!(
+        converted.order.quotation_id.as_deref(),
+        Some(quote.quotation.id.as_str())
+    );
+    assert!((converted.order.total_amount - 950.0).abs() < 1e-9);
+    assert_eq!(converted.lines.len(), 2);
+    assert!((converted.lines[0].discount_percent - 10.0).abs() < 1e-9);
+
+    let lines: Vec<OrderLine> = client
+        .get(format!(
+            "{server_url}/v1/order/{}/lines",
+            converted.order.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(lines.len(), 2);
+    let order: Order = client
+        .get(format!("{server_url}/v1/order/{}", converted.order.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(order.id, converted.order.id);
+
+    // Updating nothing the prices depend on keeps the quoted prices
+    let response = client
+        .put(format!("{server_url}/v1/order/{}", order.id))
+        .bearer_auth(&token)
+        .header(header::IF_MATCH, "*")
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let unchanged: Order = response.json().await.unwrap();
+    assert!((unchanged.total_amount - 950.0).abs() < 1e-9);
+    assert_eq!(unchanged.version, order.version);
+
+    // A discount on the whole order keeps the quoted lines, and `null`
+    // takes it off again
+    let put_order = |body: Value| {
+        client
+            .put(format!("{server_url}/v1/order/{}", order.id))
+            .bearer_auth(&token)
+            .header(header::IF_MATCH, "*")
+            .json(&body)
+            .send()
+    };
+    let response = put_order(json!({ "discount": { "kind": "fixed", "value": 50.0 } }))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let discounted: Order = response.json().await.unwrap();
+    assert!((discounted.total_amount - 900.0).abs() < 1e-9);
+    assert!((discounted.discount_total - 150.0).abs() < 1e-9);
+    let response = put_order(json!({ "discount": null })).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let undiscounted: Order = response.json().await.unwrap();
+    assert!((undiscounted.total_amount - 950.0).abs() < 1e-9);
+    assert!(undiscounted.discount_kind.is_none());
+    let lines: Vec<OrderLine> = client
+        .get(format!("{server_url}/v1/order/{}/lines", order.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(lines.len(), 2);
+    assert!((lines[1].unit_price - 50.0).abs() < 1e-9);
+
+    let quote: QuotationWithLines = client
+        .get(&quote_url)
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(quote.quotation.status, QuoteStatus::Converted);
+    assert_eq!(quote.quotation.order_id, Some(order.id));
+
+    // Once
+    let response = post(&client, &format!("{quote_url}/convert"), &token, json!({})).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = client
+        .get(format!("{quote_url}/pdf"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/pdf");
+    let pdf = response.bytes().await.unwrap();
+    assert!(pdf.starts_with(b"%PDF-"));
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains("Desk"));
+    assert!(text.contains("950.00"));
+
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_revising_a_rejected_quotation_keeps_what_was_sent() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let customer = create_customer(&client, server_url, &token).await;
+    let valid_until = (Utc::now() + Duration::days(14)).date_naive();
+    let quotations_url = format!("{server_url}/v1/quotation");
+
+    let response = post(
+        &client,
+        &quotations_url,
+        &token,
+        json!({
+            "customer_id": customer.id,
+            "valid_until": valid_until,
+            "lines": [{ "description": "Consulting day", "quantity": 3 }]
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let problem: Value = response.json().await.unwrap();
+    assert!(problem.to_string().contains("lines[0].unit_price"));
+
+    let response = post(
+        &client,
+        &quotations_url,
+        &token,
+        json!({
+            "customer_id": "missing",
+            "valid_until": valid_until,
+            "lines": [{ "description": "Consulting day", "quantity": 3, "unit_price": 800.0 }]
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let quote: QuotationWithLines = post(
+        &client,
+        &quotations_url,
+        &token,
+        json!({
+            "customer_id": customer.id,
+            "valid_until": valid_until,
+            "lines": [{ "description": "Consulting day", "quantity": 3, "unit_price": 800.0 }]
+        }),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    let quote_url = format!("{quotations_url}/{}", quote.quotation.id);
+
+    // Drafts are not revised, just changed
+    let response = post(&client, &format!("{quote_url}/revise"), &token, json!({})).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    post(&client, &format!("{quote_url}/send"), &token, json!({})).await;
+    let response = post(
+        &client,
+        &format!("{quote_url}/reject"),
+        &token,
+        json!({ "note": "Over budget" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let rejected: Quotation = response.json().await.unwrap();
+    assert_eq!(rejected.decision_note.as_deref(), Some("Over budget"));
+
+    let response = post(&client, &format!("{quote_url}/revise"), &token, json!({})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let revised: QuotationWithLines = response.json().await.unwrap();
+    assert_eq!(revised.quotation.revision, 2);
+    assert_eq!(revised.quotation.status, QuoteStatus::Draft);
+    assert!(revised.quotation.decision_note.is_none());
+    assert_eq!(revised.lines.len(), 1);
+
+    let response = client
+        .put(&quote_url)
+        .bearer_auth(&token)
+        .json(&json!({
+            "lines": [{ "description": "Consulting day", "quantity": 3, "unit_price": 800.0, "discount_percent": 15.0 }]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let updated: QuotationWithLines = response.json().await.unwrap();
+    assert!((updated.quotation.total - 2040.0).abs() < 1e-9);
+
+    let revisions: Vec<QuotationRevision> = client
+        .get(format!("{quote_url}/revisions"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(revisions.len(), 1);
+    assert_eq!(revisions[0].revision, 1);
+    assert_eq!(revisions[0].snapshot["quotation"]["status"], "rejected");
+    assert_eq!(revisions[0].snapshot["quotation"]["total"], 2400.0);
+
+    // A quote the customer saw is kept
+    let response = client
+        .delete(&quote_url)
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    app.server_handle.stop(true).await;
+}
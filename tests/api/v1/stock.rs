@@ -0,0 +1,312 @@
+use fake::{Fake, faker::lorem::en::Sentence};
+use reqwest::Client as HttpClient;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use api::v1::inventory::models::InventoryItem;
+use api::v1::stock::models::{PickList, StockMovement};
+
+use crate::helper::{TestAppBuilder, get_auth_token};
+
+async fn create_tracked_item(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    tracking: &str,
+) -> InventoryItem {
+    let name: String = Sentence(1..3).fake();
+    client
+        .post(format!("{server_url}/v1/inventory/create"))
+        .bearer_auth(token)
+        .json(&json!({
+            "name": name,
+            "quantity": 0,
+            "price": 10.0,
+            "tracking": tracking
+        }))
+        .send()
+        .await
+        .expect("Failed to create item")
+        .json()
+        .await
+        .expect("Failed to parse item")
+}
+
+#[tokio::test]
+async fn test_receipt_and_fefo_shipment() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let item = create_tracked_item(&client, server_url, &token, "lot").await;
+
+    let late_lot = format!("LATE-{}", Uuid::new_v4());
+    let early_lot = format!("EARLY-{}", Uuid::new_v4());
+
+    let response = client
+        .post(format!("{server_url}/v1/stock/receipt"))
+        .bearer_auth(&token)
+        .json(&json!({
+            "inventory_id": item.id,
+            "quantity": 15,
+            "supplier_id": Uuid::new_v4().to_string(),
+            "lots": [
+                { "lot_number": late_lot, "expiry_date": "2099-12-31", "quantity": 10 },
+                { "lot_number": early_lot, "expiry_date": "2098-01-31", "quantity": 5 }
+            ]
+        }))
+        .send()
+        .await
+        .expect("Failed to send receipt");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let response = client
+        .get(format!(
+            "{server_url}/v1/stock/pick?inventory_id={}&quantity=7",
+            item.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to get pick suggestion");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let pick: PickList = response.json().await.unwrap();
+    assert_eq!(pick.allocated, 7);
+    assert_eq!(pick.lines[0].lot_number, early_lot);
+    assert_eq!(pick.lines[0].quantity, 5);
+    assert_eq!(pick.lines[1].lot_number, late_lot);
+    assert_eq!(pick.lines[1].quantity, 2);
+
+    let customer_id = Uuid::new_v4().to_string();
+    let response = client
+        .post(format!("{server_url}/v1/stock/shipment"))
+        .bearer_auth(&token)
+        .json(&json!({
+            "inventory_id": item.id,
+            "quantity": 7,
+            "customer_id": customer_id
+        }))
+        .send()
+        .await
+        .expect("Failed to send shipment");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let movements: Vec<StockMovement> = response.json().await.unwrap();
+    assert_eq!(movements.len(), 2);
+    assert_eq!(movements.iter().map(|m| m.quantity).sum::<i32>(), -7);
+
+    let updated: InventoryItem = client
+        .get(format!("{server_url}/v1/inventory/{}", item.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(updated.quantity, 8);
+
+    // The early lot went to the customer and came from the supplier
+    let response = client
+        .get(format!("{server_url}/v1/traceability/{early_lot}"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to get trace");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let traces: Value = response.json().await.unwrap();
+    assert_eq!(traces[0]["backward"].as_array().unwrap().len(), 1);
+    assert_eq!(traces[0]["forward"][0]["partner_id"], customer_id);
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+#[tokio::test]
+async fn test_serial_receipt_validation() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let item = create_tracked_item(&client, server_url, &token, "serial").await;
+
+    // Serial numbers must be received one unit at a time
+    let response = client
+        .post(format!("{server_url}/v1/stock/receipt"))
+        .bearer_auth(&token)
+        .json(&json!({
+            "inventory_id": item.id,
+            "quantity": 2,
+            "lots": [{ "lot_number": "SN-1", "quantity": 2 }]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    // Tracked items require lots
+    let response = client
+        .post(format!("{server_url}/v1/stock/receipt"))
+        .bearer_auth(&token)
+        .json(&json!({
+            "inventory_id": item.id,
+            "quantity": 1
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let serial = format!("SN-{}", Uuid::new_v4());
+    let receipt = json!({
+        "inventory_id": item.id,
+        "quantity": 1,
+        "lots": [{ "lot_number": serial, "quantity": 1 }]
+    });
+
+    let response = client
+        .post(format!("{server_url}/v1/stock/receipt"))
+        .bearer_auth(&token)
+        .json(&receipt)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // The same serial cannot be in stock twice
+    let response = client
+        .post(format!("{server_url}/v1/stock/receipt"))
+        .bearer_auth(&token)
+        .json(&receipt)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CONFLICT);
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+#[tokio::test]
+async fn test_shipment_insufficient_stock() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let item = create_tracked_item(&client, server_url, &token, "none").await;
+
+    let response = client
+        .post(format!("{server_url}/v1/stock/shipment"))
+        .bearer_auth(&token)
+        .json(&json!({
+            "inventory_id": item.id,
+            "quantity": 1,
+            "customer_id": Uuid::new_v4().to_string()
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let response = client
+        .get(format!("{server_url}/v1/traceability/{}", Uuid::new_v4()))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+#[tokio::test]
+async fn test_item_with_movements_is_not_purged() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let item = create_tracked_item(&client, server_url, &token, "lot").await;
+
+    let response = client
+        .post(format!("{server_url}/v1/stock/receipt"))
+        .bearer_auth(&token)
+        .json(&json!({
+            "inventory_id": item.id,
+            "quantity": 3,
+            "lots": [{ "lot_number": format!("LOT-{}", Uuid::new_v4()), "quantity": 3 }]
+        }))
+        .send()
+        .await
+        .expect("Failed to send receipt");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // The receipt shows in the item's audit trail
+    let page: Value = client
+        .get(format!(
+            "{server_url}/v1/audit?entity=inventory&id={}",
+            item.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(page["items"][0]["action"], "update");
+    assert_eq!(page["items"][0]["before"]["quantity"], 0);
+    assert_eq!(page["items"][0]["after"]["quantity"], 3);
+
+    let item_url = format!("{server_url}/v1/inventory/{}", item.id);
+    let response = client
+        .delete(&item_url)
+        .bearer_auth(&token)
+        .header("If-Match", "*")
+        .send()
+        .await
+        .expect("Failed to delete item");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // The lot and its movements stay traceable
+    let response = client
+        .delete(format!("{item_url}/purge"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to purge item");
+    assert_eq!(response.status(), reqwest::StatusCode::CONFLICT);
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
@@ -0,0 +1,176 @@
+use fake::{Fake, faker::lorem::en::Sentence};
+use reqwest::Client as HttpClient;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde_json::json;
+use uuid::Uuid;
+
+use api::v1::inventory::models::InventoryItem;
+use api::v1::stock_count::models::{StockCountDetail, StockCountLineView};
+use entity::stock_movement;
+
+use crate::helper::{TestAppBuilder, get_auth_token};
+
+#[tokio::test]
+async fn test_count_approval_posts_adjustments() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let location = format!("BIN-{}", Uuid::new_v4());
+    let name: String = Sentence(1..3).fake();
+
+    let item: InventoryItem = client
+        .post(format!("{server_url}/v1/inventory/create"))
+        .bearer_auth(&token)
+        .json(&json!({
+            "name": name,
+            "quantity": 10,
+            "price": 5.0,
+            "location": location
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let response = client
+        .post(format!("{server_url}/v1/stock-count"))
+        .bearer_auth(&token)
+        .json(&json!({ "location": location }))
+        .send()
+        .await
+        .expect("Failed to open stock count");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let count: StockCountDetail = response.json().await.unwrap();
+    assert_eq!(count.lines.len(), 1);
+    assert_eq!(count.lines[0].line.expected_quantity, 10);
+    let line_id = count.lines[0].line.id.clone();
+    let count_id = count.count.id.clone();
+
+    // Two passes, the second one wins
+    for counted in [9, 8] {
+        let response = client
+            .post(format!("{server_url}/v1/stock-count/{count_id}/counts"))
+            .bearer_auth(&token)
+            .json(&json!({ "lines": [{ "line_id": line_id, "counted_quantity": counted }] }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    let variances: Vec<StockCountLineView> = client
+        .get(format!("{server_url}/v1/stock-count/{count_id}/variances"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(variances.len(), 1);
+    assert_eq!(variances[0].variance, Some(-2));
+    assert_eq!(variances[0].line.passes, 2);
+
+    let response = client
+        .post(format!("{server_url}/v1/stock-count/{count_id}/approve"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let updated: InventoryItem = client
+        .get(format!("{server_url}/v1/inventory/{}", item.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(updated.quantity, 8);
+
+    let adjustments = stock_movement::Entity::find()
+        .filter(stock_movement::Column::InventoryId.eq(&item.id))
+        .filter(stock_movement::Column::Reason.eq("count"))
+        .all(db_pool)
+        .await
+        .unwrap();
+    assert_eq!(adjustments.len(), 1);
+    assert_eq!(adjustments[0].quantity, -2);
+
+    // A closed count cannot be approved twice
+    let response = client
+        .post(format!("{server_url}/v1/stock-count/{count_id}/approve"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CONFLICT);
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+#[tokio::test]
+async fn test_manual_quantity_update_is_recorded() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+
+    let server_url = &app.server_url;
+    let server_handle = &app.server_handle;
+    let db_pool = &app.db;
+
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, db_pool).await;
+    let name: String = Sentence(1..3).fake();
+
+    let item: InventoryItem = client
+        .post(format!("{server_url}/v1/inventory/create"))
+        .bearer_auth(&token)
+        .json(&json!({ "name": name, "quantity": 4, "price": 1.0 }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let response = client
+        .put(format!("{server_url}/v1/inventory/{}", item.id))
+        .bearer_auth(&token)
+        .header("If-Match", "*")
+        .json(&json!({ "quantity": 7 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let updated: InventoryItem = response.json().await.unwrap();
+    assert_eq!(updated.quantity, 7);
+
+    let adjustments = stock_movement::Entity::find()
+        .filter(stock_movement::Column::InventoryId.eq(&item.id))
+        .all(db_pool)
+        .await
+        .unwrap();
+    assert_eq!(adjustments.len(), 1);
+    assert_eq!(adjustments[0].quantity, 3);
+    assert_eq!(adjustments[0].reason.as_deref(), Some("manual"));
+
+    server_handle.stop(true).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
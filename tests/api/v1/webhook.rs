@@ -0,0 +1,376 @@
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use actix_web::{App, HttpRequest, HttpResponse, HttpServer, web};
+use reqwest::{Client as HttpClient, StatusCode};
+use serde_json::{Value, json};
+
+use api::pagination::Page;
+use api::v1::inventory::models::InventoryItem;
+use api::v1::webhook::models::{Webhook, WebhookDelivery};
+use api::webhooks::{EVENT_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER, sign};
+use entity::webhook_delivery::DeliveryStatus;
+
+use crate::helper::{TestAppBuilder, employee_with_login, get_auth_token};
+
+const SECRET: &str = "a-test-secret-of-some-length";
+
+#[derive(Clone, Debug)]
+struct Received {
+    event: String,
+    timestamp: i64,
+    signature: String,
+    body: web::Bytes,
+}
+
+/// Stand-in for a subscriber: records every request and answers with the
+/// configured status.
+#[derive(Clone, Default)]
+struct Receiver {
+    received: Arc<Mutex<Vec<Received>>>,
+    status: Arc<AtomicU16>,
+}
+
+impl Receiver {
+    fn start() -> (Receiver, String) {
+        let receiver = Receiver::default();
+        receiver.answer(StatusCode::OK);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let state = receiver.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(web::Data::new(state.clone()))
+                .route("/hook", web::post().to(receive))
+        })
+        .listen(listener)
+        .unwrap()
+        .run();
+        tokio::spawn(server);
+
+        (receiver, url)
+    }
+
+    fn answer(&self, status: StatusCode) {
+        self.status.store(status.as_u16(), Ordering::SeqCst);
+    }
+
+    async fn wait_for(&self, event: &str) -> Received {
+        for _ in 0..100 {
+            let found = self
+                .received
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|r| r.event == event)
+                .cloned();
+            if let Some(found) = found {
+                return found;
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        panic!("No {event} delivery received");
+    }
+}
+
+async fn receive(
+    req: HttpRequest,
+    body: web::Bytes,
+    receiver: web::Data<Receiver>,
+) -> HttpResponse {
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+    receiver.received.lock().unwrap().push(Received {
+        event: header(EVENT_HEADER),
+        timestamp: header(TIMESTAMP_HEADER).parse().unwrap_or_default(),
+        signature: header(SIGNATURE_HEADER),
+        body,
+    });
+    let status = receiver.status.load(Ordering::SeqCst);
+    HttpResponse::build(actix_web::http::StatusCode::from_u16(status).unwrap()).finish()
+}
+
+async fn subscribe(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    url: &str,
+    events: &[&str],
+) -> Webhook {
+    let response = client
+        .post(format!("{server_url}/v1/webhook"))
+        .bearer_auth(token)
+        .json(&json!({ "url": url, "secret": SECRET, "events": events }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    response.json().await.unwrap()
+}
+
+async fn deliveries(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    webhook_id: &str,
+) -> Vec<WebhookDelivery> {
+    let page: Page<WebhookDelivery> = client
+        .get(format!("{server_url}/v1/webhook/{webhook_id}/deliveries"))
+        .bearer_auth(token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    page.items
+}
+
+#[tokio::test]
+async fn test_subscribed_events_are_delivered_signed() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+    let (receiver, url) = Receiver::start();
+
+    let webhook = subscribe(
+        &client,
+        server_url,
+        &token,
+        &url,
+        &["inventory.created", "inventory.low_stock"],
+    )
+    .await;
+    // The secret is write-only
+    let body: Value = client
+        .get(format!("{server_url}/v1/webhook/{}", webhook.id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(body.get("secret").is_none());
+
+    let item: InventoryItem = client
+        .post(format!("{server_url}/v1/inventory/create"))
+        .bearer_auth(&token)
+        .json(
+            &json!({ "name": "Webhook widget", "quantity": 10, "price": 2.5, "reorder_point": 5 }),
+        )
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let created = receiver.wait_for("inventory.created").await;
+    assert_eq!(
+        created.signature,
+        sign(SECRET, created.timestamp, &created.body)
+    );
+    let payload: Value = serde_json::from_slice(&created.body).unwrap();
+    assert_eq!(payload["event"], "inventory.created");
+    assert_eq!(payload["data"]["id"], item.id.as_str());
+
+    // Dropping to the reorder point raises a low stock event
+    let response = client
+        .put(format!("{server_url}/v1/inventory/{}", item.id))
+        .bearer_auth(&token)
+        .header("If-Match", "*")
+        .json(&json!({ "quantity": 4 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let low_stock = receiver.wait_for("inventory.low_stock").await;
+    let payload: Value = serde_json::from_slice(&low_stock.body).unwrap();
+    assert_eq!(payload["data"]["quantity"], 4);
+
+    // Events the webhook did not subscribe to are not sent
+    let received = receiver.received.lock().unwrap().clone();
+    assert!(received.iter().all(|r| r.event != "inventory.updated"));
+
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_failed_deliveries_are_retried_and_can_be_redelivered() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+    let (receiver, url) = Receiver::start();
+    receiver.answer(StatusCode::INTERNAL_SERVER_ERROR);
+
+    let webhook = subscribe(&client, server_url, &token, &url, &["customer.created"]).await;
+    let response = client
+        .post(format!("{server_url}/v1/customer"))
+        .bearer_auth(&token)
+        .json(&json!({ "name": "Hook customer", "email": "hook@example.com" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    receiver.wait_for("customer.created").await;
+
+    // The failure is recorded and the next attempt pushed back
+    let mut delivery = None;
+    for _ in 0..50 {
+        let found = deliveries(&client, server_url, &token, &webhook.id).await;
+        if let Some(d) = found.into_iter().find(|d| d.attempts > 0) {
+            delivery = Some(d);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    let delivery = delivery.expect("Delivery attempt was not recorded");
+    assert_eq!(delivery.status, DeliveryStatus::Pending);
+    assert_eq!(delivery.response_status, Some(500));
+    assert!(delivery.last_error.is_some());
+    assert!(delivery.next_attempt_at > delivery.created_at);
+
+    receiver.answer(StatusCode::OK);
+    let response = client
+        .post(format!(
+            "{server_url}/v1/webhook/deliveries/{}/redeliver",
+            delivery.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let mut delivered = false;
+    for _ in 0..50 {
+        let found = deliveries(&client, server_url, &token, &webhook.id).await;
+        if found
+            .iter()
+            .any(|d| d.id == delivery.id && d.status == DeliveryStatus::Delivered)
+        {
+            delivered = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    assert!(delivered, "Redelivery did not succeed");
+
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_unknown_events_are_rejected() {
+    let app = TestAppBuilder::new()
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    let response = client
+        .post(format!("{server_url}/v1/webhook"))
+        .bearer_auth(&token)
+        .json(&json!({
+            "url": "http://127.0.0.1:9/hook",
+            "secret": SECRET,
+            "events": ["order.created", "order.shipped"]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let problem: Value = response.json().await.unwrap();
+    assert!(problem["errors"]["events"].is_array());
+
+    app.server_handle.stop(true).await;
+}
+
+#[tokio::test]
+async fn test_webhooks_only_go_to_public_addresses() {
+    let app = TestAppBuilder::new()
+        .webhook_allow_private_hosts(false)
+        .build()
+        .await
+        .expect("Failed to build test app");
+    let server_url = &app.server_url;
+    let client = HttpClient::new();
+    let token = get_auth_token(&client, server_url, &app.db).await;
+
+    for url in [
+        "ftp://example.com/hook",
+        "http://localhost:8080/hook",
+        "http://127.0.0.1:9/hook",
+        "http://10.0.0.5/hook",
+        "http://169.254.169.254/latest/meta-data",
+        "http://[::1]/hook",
+        "http://[fd00::1]/hook",
+    ] {
+        let response = client
+            .post(format!("{server_url}/v1/webhook"))
+            .bearer_auth(&token)
+            .json(&json!({ "url": url, "secret": SECRET, "events": ["order.created"] }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{url}");
+        let problem: Value = response.json().await.unwrap();
+        assert!(problem["errors"]["url"].is_array(), "{url}");
+    }
+
+    let webhook = subscribe(
+        &client,
+        server_url,
+        &token,
+        "https://example.com/hook",
+        &["order.created"],
+    )
+    .await;
+    let response = client
+        .put(format!("{server_url}/v1/webhook/{}", webhook.id))
+        .bearer_auth(&token)
+        .json(&json!({ "url": "http://192.168.1.10/hook" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Subscriptions are managed by administrators
+    let (_, staff_token) =
+        employee_with_login(&client, server_url, &token, "Staff", json!({})).await;
+    let response = client
+        .get(format!("{server_url}/v1/webhook"))
+        .bearer_auth(&staff_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = client
+        .delete(format!("{server_url}/v1/webhook/{}", webhook.id))
+        .bearer_auth(&staff_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    app.server_handle.stop(true).await;
+}
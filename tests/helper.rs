@@ -4,18 +4,39 @@ use actix_web::{
     dev::{Server, ServerHandle},
     web,
 };
+use api::error::{json_config, query_config};
+use api::events::EventBus;
+use api::middlewares::events::EventsMiddleware;
+use api::middlewares::request_id::RequestIdMiddleware;
+use api::openapi;
 use api::v1::auth::models::TokenResponse;
-use api::v1::{auth, employee, inventory, order};
+use api::v1::customer::models::Customer;
+use api::v1::employee::models::{Employee, Invite};
+use api::v1::inventory::models::InventoryItem;
+use api::v1::{
+    attachment, attendance, audit, auth, company, customer, department, employee, events, expense,
+    import, inventory, leave, order, payroll, position, pricing, project, quotation, stock,
+    stock_count, timesheet, traceability, webhook,
+};
+use api::webhooks;
 use config::{
-    app::AppState, db::Db, file_session::FileSessionStore, inertia::initialize_inertia,
-    meilisearch::Meilisearch, vite::ASSETS_VERSION,
+    app::{AppConfig, AppState},
+    db::Db,
+    file_session::FileSessionStore,
+    file_storage::{FileStorage, LocalFileStorage},
+    inertia::initialize_inertia,
+    meilisearch::Meilisearch,
+    vite::ASSETS_VERSION,
 };
 use db::mysql::init_db_pool;
 use erp_api::healthcheck;
 use fake::{Fake, faker::internet::en::SafeEmail};
-use reqwest::Client as HttpClient;
+use reqwest::{Client as HttpClient, StatusCode};
 use sea_orm::DatabaseConnection;
-use search::{Client, meilisearch::init_meilisearch};
+use search::{
+    Client,
+    meilisearch::{configure_index, init_meilisearch},
+};
 use std::{env, net::TcpListener, sync::Arc, time::Duration};
 // Entity imports are moved to the test_db_utils module
 use actix_session::{SessionExt, SessionMiddleware};
@@ -199,6 +220,8 @@ pub struct TestAppBuilder {
     bcrypt_cost: Option<u32>,
     jwt_secret: Option<String>,
     jwt_algorithm: Option<jsonwebtoken::Algorithm>,
+    idempotency_window_seconds: Option<u64>,
+    webhook_allow_private_hosts: Option<bool>,
     clear_tables: bool,
     skip_app_state: bool,
     meili_host: Option<String>,
@@ -217,6 +240,8 @@ impl TestAppBuilder {
             bcrypt_cost: None,
             jwt_secret: None,
             jwt_algorithm: None,
+            idempotency_window_seconds: None,
+            webhook_allow_private_hosts: None,
             clear_tables: false,
             skip_app_state: false,
             meili_host: None,
@@ -243,6 +268,16 @@ impl TestAppBuilder {
         self
     }
 
+    pub fn idempotency_window_seconds(mut self, seconds: u64) -> Self {
+        self.idempotency_window_seconds = Some(seconds);
+        self
+    }
+
+    pub fn webhook_allow_private_hosts(mut self, allow: bool) -> Self {
+        self.webhook_allow_private_hosts = Some(allow);
+        self
+    }
+
     pub fn clear_tables(mut self) -> Self {
         self.clear_tables = true;
         self
@@ -284,6 +319,19 @@ impl TestAppBuilder {
             .await
             .map_err(|e| TestError::MeilisearchInit(e.to_string()))?;
 
+        // Searches filter on the tenant, which the indexes must allow. A
+        // custom host is only used to simulate an unreachable engine.
+        if self.meili_host.is_none() {
+            let app_config = AppConfig::new();
+            for (index_name, attributes) in &app_config.meilisearch_indexes {
+                let attributes: Vec<&str> = attributes.iter().map(String::as_str).collect();
+                let filterable = app_config.filterable_attributes(index_name);
+                configure_index(&meilisearch, index_name, &attributes, &filterable)
+                    .await
+                    .map_err(|e| TestError::MeilisearchInit(e.to_string()))?;
+            }
+        }
+
         // Start server
         let listener =
             TcpListener::bind("0.0.0.0:0").map_err(|e| TestError::ServerStartup(e.to_string()))?;
@@ -329,8 +377,13 @@ impl TestAppBuilder {
             jwt_expires_in_seconds: self.jwt_expires_in_seconds.unwrap_or(3600),
             bcrypt_cost: self.bcrypt_cost.unwrap_or(bcrypt::DEFAULT_COST),
             jwt_algorithm: self.jwt_algorithm.unwrap_or(jsonwebtoken::Algorithm::HS256),
+            idempotency_window_seconds: self.idempotency_window_seconds.unwrap_or(86400),
+            // Test receivers listen on 127.0.0.1
+            webhook_allow_private_hosts: self.webhook_allow_private_hosts.unwrap_or(true),
         };
 
+        webhooks::spawn_dispatcher(app_state.db.clone());
+
         run(app_state, listener)
             .await
             .map_err(|e| TestError::ServerStartup(e.to_string()))
@@ -342,11 +395,36 @@ impl TestAppBuilder {
     ) -> Result<Server, TestError> {
         let server = HttpServer::new(move || {
             App::new()
+                .wrap(RequestIdMiddleware::new())
+                .app_data(json_config())
+                .app_data(query_config())
                 .route("/healthcheck", web::get().to(healthcheck))
+                .configure(openapi::init_routes)
+                // Ahead of the scopes whose records take attachments
+                .configure(attachment::routes::init_routes)
                 .configure(inventory::routes::init_routes)
                 .configure(employee::routes::init_routes)
                 .configure(order::routes::init_routes)
                 .configure(auth::routes::init_routes)
+                .configure(stock::routes::init_routes)
+                .configure(traceability::routes::init_routes)
+                .configure(stock_count::routes::init_routes)
+                .configure(customer::routes::init_routes)
+                .configure(import::routes::init_routes)
+                .configure(audit::routes::init_routes)
+                .configure(company::routes::init_routes)
+                .configure(webhook::routes::init_routes)
+                .configure(events::routes::init_routes)
+                .configure(department::routes::init_routes)
+                .configure(position::routes::init_routes)
+                .configure(leave::routes::init_routes)
+                .configure(project::routes::init_routes)
+                .configure(attendance::routes::init_routes)
+                .configure(timesheet::routes::init_routes)
+                .configure(payroll::routes::init_routes)
+                .configure(expense::routes::init_routes)
+                .configure(quotation::routes::init_routes)
+                .configure(pricing::routes::init_routes)
         })
         .listen(listener)
         .map_err(|e| TestError::ServerStartup(e.to_string()))?
@@ -412,6 +490,103 @@ pub async fn get_auth_token(
     token_response.token
 }
 
+/// Creates an employee with a login of their own and returns its token.
+pub async fn employee_with_login(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    name: &str,
+    placement: Value,
+) -> (Employee, String) {
+    let email: String = SafeEmail().fake();
+    let mut body = json!({ "name": name, "role": "Staff", "email": email });
+    body.as_object_mut()
+        .unwrap()
+        .extend(placement.as_object().unwrap().clone());
+    let employee: Employee = client
+        .post(format!("{server_url}/v1/employee"))
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let invite: Invite = client
+        .post(format!("{server_url}/v1/employee/{}/invite", employee.id))
+        .bearer_auth(token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let response = client
+        .post(format!("{server_url}/v1/auth/accept-invite"))
+        .json(&json!({
+            "token": invite.token,
+            "username": format!("staff_{}", &employee.id[..8]),
+            "password": "password123"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let token = response.json::<TokenResponse>().await.unwrap().token;
+    (employee, token)
+}
+
+/// Creates an inventory item called `name`.
+pub async fn create_item(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    name: &str,
+) -> InventoryItem {
+    client
+        .post(format!("{server_url}/v1/inventory/create"))
+        .bearer_auth(token)
+        .json(&json!({ "name": name, "quantity": 5, "price": 10.0 }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+/// Creates a customer and an inventory item at `price` to order.
+pub async fn customer_and_item(
+    client: &HttpClient,
+    server_url: &str,
+    token: &str,
+    price: f64,
+) -> (Customer, InventoryItem) {
+    let email: String = SafeEmail().fake();
+    let customer: Customer = client
+        .post(format!("{server_url}/v1/customer"))
+        .bearer_auth(token)
+        .json(&json!({ "name": "Acme", "email": email }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let item: InventoryItem = client
+        .post(format!("{server_url}/v1/inventory/create"))
+        .bearer_auth(token)
+        .json(&json!({ "name": "Chair", "quantity": 100, "price": price }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    (customer, item)
+}
+
 async fn run(app_state: AppState, listener: TcpListener) -> std::io::Result<Server> {
     // starts a Inertia manager instance.
     let inertia = initialize_inertia().await?;
@@ -428,15 +603,49 @@ async fn run(app_state: AppState, listener: TcpListener) -> std::io::Result<Serv
     let rust_env = env::var("RUST_ENV").unwrap_or_else(|_| "production".to_string());
     let use_secure_cookie = rust_env.as_str() == "production";
 
+    let event_bus = web::Data::new(EventBus::default());
+
+    // Receipts and other uploaded files
+    let file_storage: web::Data<dyn FileStorage> =
+        web::Data::from(Arc::new(LocalFileStorage::default()) as Arc<dyn FileStorage>);
+
     let server = HttpServer::new(move || {
         App::new()
+            .wrap(EventsMiddleware::new())
+            .wrap(RequestIdMiddleware::new())
+            .app_data(json_config())
+            .app_data(query_config())
             .app_data(web::Data::new(app_state.clone()))
+            .app_data(event_bus.clone())
+            .app_data(file_storage.clone())
             // Register your routes here
             .route("/healthcheck", web::get().to(healthcheck))
+            .configure(openapi::init_routes)
+            // Ahead of the scopes whose records take attachments
+            .configure(attachment::routes::init_routes)
             .configure(inventory::routes::init_routes)
             .configure(employee::routes::init_routes)
             .configure(order::routes::init_routes)
             .configure(auth::routes::init_routes)
+            .configure(stock::routes::init_routes)
+            .configure(traceability::routes::init_routes)
+            .configure(stock_count::routes::init_routes)
+            .configure(customer::routes::init_routes)
+            .configure(import::routes::init_routes)
+            .configure(audit::routes::init_routes)
+            .configure(company::routes::init_routes)
+            .configure(webhook::routes::init_routes)
+            .configure(events::routes::init_routes)
+            .configure(department::routes::init_routes)
+            .configure(position::routes::init_routes)
+            .configure(leave::routes::init_routes)
+            .configure(project::routes::init_routes)
+            .configure(attendance::routes::init_routes)
+            .configure(timesheet::routes::init_routes)
+            .configure(payroll::routes::init_routes)
+            .configure(expense::routes::init_routes)
+            .configure(quotation::routes::init_routes)
+            .configure(pricing::routes::init_routes)
             .service(
                 web::scope("/page")
                     .wrap(GarbageCollectorMiddleware::new())